@@ -0,0 +1,28 @@
+#![no_main]
+
+use gb_core::bus::MemoryBus;
+use gb_core::cartridge::Cartridge;
+use libfuzzer_sys::fuzz_target;
+
+const HEADER_END: usize = 0x150;
+
+// Header (e ROM) aleatórios no Cartridge::load. Se carregar, o que vem depois do header
+// vira escritas e leituras no barramento, três bytes cada (endereço e valor): troca de
+// banco, RAM e registradores do MBC com valores quaisquer
+fuzz_target!(|data: &[u8]| {
+    let Ok(cartridge) = Cartridge::load(data.to_vec()) else {
+        return;
+    };
+    let _ = format!("{} {} {}", cartridge, cartridge.title(), cartridge.mapper());
+    let _ = cartridge.rom_size_bytes();
+
+    let mut bus = MemoryBus::new(cartridge);
+    for op in data[HEADER_END..].chunks_exact(3) {
+        let addr = u16::from_le_bytes([op[0], op[1]]);
+        if op[2] & 1 == 0 {
+            bus.write(addr, op[2]);
+        } else {
+            bus.read(addr);
+        }
+    }
+});
@@ -0,0 +1,41 @@
+#![no_main]
+
+use gb_core::bus::MemoryBus;
+use gb_core::cartridge::Cartridge;
+use gb_core::cpu::Cpu;
+use gb_core::machine::Model;
+use libfuzzer_sys::fuzz_target;
+
+// O bastante pro programa sair da ROM e cair no que ele mesmo escreveu em RAM e IO
+const STEPS: usize = 10_000;
+// Logo depois do header
+const PROGRAM_START: usize = 0x150;
+
+// Opcodes aleatórios rodando a partir de 0x150 numa ROM sem MBC. O primeiro byte escolhe
+// o modelo (DMG ou CGB), o resto é o programa. Opcode ilegal trava a CPU como no hardware
+// e o resto dos passos fica parado
+fuzz_target!(|data: &[u8]| {
+    let Some((&model, program)) = data.split_first() else {
+        return;
+    };
+    let mut rom = vec![0; 0x8000];
+    let len = program.len().min(rom.len() - PROGRAM_START);
+    rom[PROGRAM_START..PROGRAM_START + len].copy_from_slice(&program[..len]);
+    // JP $0150 no entry point; o resto do header zerado é ROM only sem RAM
+    rom[0x100..0x103].copy_from_slice(&[0xC3, 0x50, 0x01]);
+
+    let mut bus = MemoryBus::new(Cartridge::load(rom).unwrap());
+    bus.model = if model & 1 == 0 {
+        Model::Dmg
+    } else {
+        Model::Cgb
+    };
+    let mut cpu = Cpu::new();
+    cpu.reset();
+    bus.reset();
+
+    for _ in 0..STEPS {
+        let cycles = cpu.step(&mut bus) as u64;
+        bus.tick(cycles, cpu.stop);
+    }
+});
@@ -0,0 +1,136 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use crate::achievements::Trigger;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum State {
+    // Só arma depois de ver as condições falsas uma vez, pra não desbloquear ao carregar
+    // um state ou ligar no meio do jogo
+    Waiting,
+    Active,
+    Unlocked,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Achievement {
+    pub id: u32,
+    pub title: String,
+    pub description: String,
+    pub points: u32,
+    pub trigger: Trigger,
+    state: State,
+}
+
+impl Achievement {
+    pub fn unlocked(&self) -> bool {
+        self.state == State::Unlocked
+    }
+}
+
+// Conquistas de um jogo, avaliadas no fim de cada frame contra a memória (peek)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Achievements {
+    list: Vec<Achievement>,
+}
+
+impl Achievements {
+    #[cfg(feature = "std")]
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|erro| format!("erro ao ler '{}': {}", path.display(), erro))?;
+        Self::parse(&text).map_err(|erro| format!("{}: {}", path.display(), erro))
+    }
+
+    // Arquivo local de conquistas do RAIntegration (<id do jogo>-User.txt): versão, nome do
+    // jogo e uma conquista por linha, id:"condições":título:descrição::::autor:pontos:...
+    // Linhas que não começam com o id (leaderboards, rich presence) ficam de fora
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut list = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let fields = split_fields(line.trim());
+            let Some(id) = fields.first().and_then(|id| id.parse().ok()) else {
+                continue;
+            };
+            let invalid = |erro: String| format!("linha {}: {}", number + 1, erro);
+            let (Some(trigger), Some(title)) = (fields.get(1), fields.get(2)) else {
+                return Err(invalid("esperado id:\"condições\":título".to_string()));
+            };
+            list.push(Achievement {
+                id,
+                title: title.clone(),
+                description: fields.get(3).cloned().unwrap_or_default(),
+                points: fields
+                    .get(8)
+                    .and_then(|points| points.parse().ok())
+                    .unwrap_or(0),
+                trigger: Trigger::parse(trigger).map_err(invalid)?,
+                state: State::Waiting,
+            });
+        }
+        Ok(Self { list })
+    }
+
+    pub fn list(&self) -> &[Achievement] {
+        &self.list
+    }
+
+    // Pontos desbloqueados e o total
+    pub fn points(&self) -> (u32, u32) {
+        let unlocked = self.list.iter().filter(|a| a.unlocked()).map(|a| a.points);
+        (unlocked.sum(), self.list.iter().map(|a| a.points).sum())
+    }
+
+    // Fim de frame: as que desbloquearam agora
+    pub fn update(&mut self, peek: &dyn Fn(u16) -> u8) -> Vec<&Achievement> {
+        let mut unlocked = Vec::new();
+        for achievement in &mut self.list {
+            if achievement.state == State::Unlocked {
+                continue;
+            }
+            let triggered = achievement.trigger.test(peek);
+            match (achievement.state, triggered) {
+                (State::Waiting, false) => achievement.state = State::Active,
+                (State::Active, true) => {
+                    achievement.state = State::Unlocked;
+                    unlocked.push(&*achievement);
+                }
+                _ => {}
+            }
+        }
+        unlocked
+    }
+
+    // Reset do console: as que faltam voltam a esperar, do zero
+    pub fn reset(&mut self) {
+        for achievement in &mut self.list {
+            if achievement.state != State::Unlocked {
+                achievement.state = State::Waiting;
+                achievement.trigger.reset_hits();
+            }
+        }
+    }
+}
+
+// Campos separados por ':', que podem vir entre aspas (com \" e \\ dentro)
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' if quoted => field.extend(chars.next()),
+            ':' if !quoted => fields.push(core::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
@@ -0,0 +1,396 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+// Tamanho da leitura, pela letra depois do 0x: 0xH1234 é um byte, 0x1234 (sem letra) uma
+// word, 0xM..0xT um bit, 0xL/0xU um nibble, 0xK quantos bits ligados no byte
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Size {
+    Bit(u8),
+    Lower,
+    Upper,
+    Byte,
+    Word,
+    Tbyte,
+    Dword,
+    BitCount,
+}
+
+impl Size {
+    fn parse(letter: char) -> Option<Self> {
+        Some(match letter.to_ascii_uppercase() {
+            letter @ 'M'..='T' => Size::Bit(letter as u8 - b'M'),
+            'L' => Size::Lower,
+            'U' => Size::Upper,
+            'H' => Size::Byte,
+            ' ' => Size::Word,
+            'W' => Size::Tbyte,
+            'X' => Size::Dword,
+            'K' => Size::BitCount,
+            _ => return None,
+        })
+    }
+
+    fn read(self, address: u16, peek: &dyn Fn(u16) -> u8) -> u32 {
+        let little_endian = |bytes: u16| {
+            (0..bytes).fold(0u32, |value, i| {
+                value | ((peek(address.wrapping_add(i)) as u32) << (8 * i))
+            })
+        };
+        let byte = peek(address) as u32;
+        match self {
+            Size::Bit(bit) => (byte >> bit) & 1,
+            Size::Lower => byte & 0x0F,
+            Size::Upper => byte >> 4,
+            Size::Byte => byte,
+            Size::Word => little_endian(2),
+            Size::Tbyte => little_endian(3),
+            Size::Dword => little_endian(4),
+            Size::BitCount => byte.count_ones(),
+        }
+    }
+}
+
+// Valor de agora, do frame anterior (d) ou de antes da última mudança (p)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Kind {
+    Value,
+    Delta,
+    Prior,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Operand {
+    Constant(u32),
+    Memory {
+        size: Size,
+        address: u16,
+        kind: Kind,
+        current: u32,
+        previous: u32,
+        prior: u32,
+    },
+}
+
+impl Operand {
+    // 0xH1234, d0xH1234, p0xH1234, 42 ou h2A
+    fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("operando inválido: '{}'", text);
+        let (kind, rest) = match text.chars().next() {
+            Some('d' | 'D') => (Kind::Delta, &text[1..]),
+            Some('p' | 'P') => (Kind::Prior, &text[1..]),
+            _ => (Kind::Value, text),
+        };
+        let Some(memory) = rest.strip_prefix("0x").or(rest.strip_prefix("0X")) else {
+            let value = match text.strip_prefix(['h', 'H']) {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => text.parse(),
+            };
+            return value.map(Operand::Constant).map_err(|_| invalid());
+        };
+
+        // Sem letra é uma word, como "0x 1234"
+        let (size, hex) = match memory.chars().next() {
+            Some(c) if c.is_ascii_hexdigit() => (Size::Word, memory),
+            Some(letter) => (Size::parse(letter).ok_or_else(invalid)?, &memory[1..]),
+            None => return Err(invalid()),
+        };
+        let address = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+        // O mapa do GB no RetroAchievements passa de $FFFF nos bancos de WRAM do CGB
+        let address =
+            u16::try_from(address).map_err(|_| format!("endereço fora de 0-FFFF: '{}'", text))?;
+        Ok(Operand::Memory {
+            size,
+            address,
+            kind,
+            current: 0,
+            previous: 0,
+            prior: 0,
+        })
+    }
+
+    // Uma leitura por frame, antes de avaliar: é ela que anda o delta e o prior
+    fn refresh(&mut self, peek: &dyn Fn(u16) -> u8) {
+        if let Operand::Memory {
+            size,
+            address,
+            current,
+            previous,
+            prior,
+            ..
+        } = self
+        {
+            let value = size.read(*address, peek);
+            if value != *current {
+                *prior = *current;
+            }
+            *previous = *current;
+            *current = value;
+        }
+    }
+
+    fn value(&self) -> u32 {
+        match *self {
+            Operand::Constant(value) => value,
+            Operand::Memory {
+                kind,
+                current,
+                previous,
+                prior,
+                ..
+            } => match kind {
+                Kind::Value => current,
+                Kind::Delta => previous,
+                Kind::Prior => prior,
+            },
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Flag {
+    None,
+    // R: zera os hits do trigger inteiro
+    ResetIf,
+    // P: o grupo não conta nada enquanto for verdade
+    PauseIf,
+    // A:/B: soma (ou subtrai) o valor no lado esquerdo da próxima condição
+    AddSource,
+    SubSource,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl Comparison {
+    fn test(self, left: u32, right: u32) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterEqual => left >= right,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Condition {
+    flag: Flag,
+    left: Operand,
+    // A:/B: só têm o lado esquerdo
+    comparison: Option<(Comparison, Operand)>,
+    // 0 = vale só no frame em que for verdade
+    target_hits: u32,
+    hits: u32,
+}
+
+impl Condition {
+    // [flag:]esquerda<op>direita[.hits.]
+    fn parse(text: &str) -> Result<Self, String> {
+        let (flag, rest) = match text.split_once(':') {
+            Some((flag, rest)) => {
+                let flag = match flag {
+                    "R" | "r" => Flag::ResetIf,
+                    "P" | "p" => Flag::PauseIf,
+                    "A" | "a" => Flag::AddSource,
+                    "B" | "b" => Flag::SubSource,
+                    _ => return Err(format!("flag não suportada: '{}:'", flag)),
+                };
+                (flag, rest)
+            }
+            None => (Flag::None, text),
+        };
+
+        let (rest, target_hits) = match rest.strip_suffix('.') {
+            Some(rest) => {
+                let (rest, hits) = rest
+                    .rsplit_once('.')
+                    .ok_or_else(|| format!("hits inválidos: '{}'", text))?;
+                let hits = hits
+                    .parse()
+                    .map_err(|_| format!("hits inválidos: '{}'", text))?;
+                (rest, hits)
+            }
+            None => (rest, 0),
+        };
+
+        let Some(op) = rest.find(['=', '!', '<', '>']) else {
+            if !matches!(flag, Flag::AddSource | Flag::SubSource) {
+                return Err(format!("condição sem comparação: '{}'", text));
+            }
+            return Ok(Self {
+                flag,
+                left: Operand::parse(rest)?,
+                comparison: None,
+                target_hits,
+                hits: 0,
+            });
+        };
+        let (left, right) = rest.split_at(op);
+        let (comparison, right) = [
+            ("!=", Comparison::NotEqual),
+            ("<=", Comparison::LessEqual),
+            (">=", Comparison::GreaterEqual),
+            ("=", Comparison::Equal),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+        ]
+        .into_iter()
+        .find_map(|(symbol, comparison)| Some((comparison, right.strip_prefix(symbol)?)))
+        .ok_or_else(|| format!("comparação inválida: '{}'", text))?;
+
+        Ok(Self {
+            flag,
+            left: Operand::parse(left)?,
+            comparison: Some((comparison, Operand::parse(right)?)),
+            target_hits,
+            hits: 0,
+        })
+    }
+
+    // Verdade agora, somando o que os A:/B: de antes acumularam; com alvo de hits, conta
+    // mais um e fica verdade de vez quando chega nele
+    fn test(&mut self, source: u32) -> bool {
+        let left = source.wrapping_add(self.left.value());
+        let Some((comparison, right)) = &self.comparison else {
+            return false;
+        };
+        let now = comparison.test(left, right.value());
+        if self.target_hits == 0 {
+            return now;
+        }
+        if now && self.hits < self.target_hits {
+            self.hits += 1;
+        }
+        self.hits >= self.target_hits
+    }
+
+    fn operands(&mut self) -> impl Iterator<Item = &mut Operand> {
+        let right = self.comparison.as_mut().map(|(_, right)| right);
+        core::iter::once(&mut self.left).chain(right)
+    }
+}
+
+#[derive(Default)]
+struct GroupResult {
+    satisfied: bool,
+    reset: bool,
+}
+
+// Os grupos vão separados por 'S', que também é o tamanho do bit 6 logo depois do "0x"
+fn split_groups(text: &str) -> Vec<&str> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        let size = text[..i].ends_with("0x") || text[..i].ends_with("0X");
+        if c == 'S' && !size {
+            groups.push(&text[start..i]);
+            start = i + 1;
+        }
+    }
+    groups.push(&text[start..]);
+    groups
+}
+
+// Condições separadas por '_', todas precisando ser verdade
+fn parse_group(text: &str) -> Result<Vec<Condition>, String> {
+    text.split('_').map(Condition::parse).collect()
+}
+
+fn test_group(group: &mut [Condition]) -> GroupResult {
+    // Pausado, o grupo não conta hits nem reseta
+    let mut source = 0u32;
+    let mut paused = false;
+    for condition in group.iter_mut() {
+        match condition.flag {
+            Flag::AddSource => source = source.wrapping_add(condition.left.value()),
+            Flag::SubSource => source = source.wrapping_sub(condition.left.value()),
+            Flag::PauseIf => paused |= condition.test(core::mem::take(&mut source)),
+            _ => source = 0,
+        }
+    }
+    if paused {
+        return GroupResult::default();
+    }
+
+    let mut result = GroupResult {
+        satisfied: true,
+        reset: false,
+    };
+    let mut source = 0u32;
+    for condition in group.iter_mut() {
+        match condition.flag {
+            Flag::AddSource => source = source.wrapping_add(condition.left.value()),
+            Flag::SubSource => source = source.wrapping_sub(condition.left.value()),
+            Flag::PauseIf => source = 0,
+            Flag::ResetIf => result.reset |= condition.test(core::mem::take(&mut source)),
+            Flag::None => result.satisfied &= condition.test(core::mem::take(&mut source)),
+        }
+    }
+    result.satisfied &= !result.reset;
+    result
+}
+
+// Condições de uma conquista no formato MemAddr do rcheevos: o grupo principal e as
+// alternativas depois de cada 'S' ("0xH1234=5_d0xH1234=4S0xH2000=1S0xH2001=1").
+// Dispara com o principal verdadeiro e pelo menos uma alternativa (se houver)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Trigger {
+    pub text: String,
+    core: Vec<Condition>,
+    alternatives: Vec<Vec<Condition>>,
+}
+
+impl Trigger {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let mut groups = split_groups(text).into_iter().map(parse_group);
+        let core = groups
+            .next()
+            .unwrap_or_else(|| Err("trigger vazio".to_string()))?;
+        let alternatives = groups.collect::<Result<_, _>>()?;
+        Ok(Self {
+            text: text.to_string(),
+            core,
+            alternatives,
+        })
+    }
+
+    // Uma vez por frame: lê a memória, conta hits e diz se as condições batem
+    pub fn test(&mut self, peek: &dyn Fn(u16) -> u8) -> bool {
+        for group in core::iter::once(&mut self.core).chain(&mut self.alternatives) {
+            for condition in group.iter_mut() {
+                condition
+                    .operands()
+                    .for_each(|operand| operand.refresh(peek));
+            }
+        }
+
+        let core = test_group(&mut self.core);
+        let alternatives: Vec<GroupResult> = self
+            .alternatives
+            .iter_mut()
+            .map(|group| test_group(group))
+            .collect();
+        if core.reset || alternatives.iter().any(|alt| alt.reset) {
+            self.reset_hits();
+            return false;
+        }
+        core.satisfied && (alternatives.is_empty() || alternatives.iter().any(|alt| alt.satisfied))
+    }
+
+    pub fn reset_hits(&mut self) {
+        for group in core::iter::once(&mut self.core).chain(&mut self.alternatives) {
+            group.iter_mut().for_each(|condition| condition.hits = 0);
+        }
+    }
+}
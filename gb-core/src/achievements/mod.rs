@@ -0,0 +1,10 @@
+pub mod achievements;
+pub mod condition;
+pub mod md5;
+
+pub use achievements::*;
+pub use condition::*;
+pub use md5::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,78 @@
+use core::cell::RefCell;
+
+use crate::achievements::{Achievements, Trigger, rom_hash};
+
+#[test]
+fn md5_da_rom() {
+    assert_eq!(rom_hash(b""), "d41d8cd98f00b204e9800998ecf8427e");
+    assert_eq!(rom_hash(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+    let long = [b'a'; 1000];
+    assert_eq!(rom_hash(&long), "cabe45dcc9ae5b66ba86600cca6b8ba8");
+}
+
+#[test]
+fn trigger_com_delta_hits_e_reset() {
+    let memory = RefCell::new([0u8; 0x10000]);
+    let peek = |addr: u16| memory.borrow()[addr as usize];
+    let set = |addr: usize, value| memory.borrow_mut()[addr] = value;
+
+    // Vidas subindo de 2 pra 3 duas vezes, sem o flag de game over no meio
+    let mut trigger = Trigger::parse("0xHC000=3_d0xHC000=2.2._R:0xHC001=1").unwrap();
+    let mut frame = |lives, game_over| {
+        set(0xC000, lives);
+        set(0xC001, game_over);
+        trigger.test(&peek)
+    };
+    assert!(!frame(2, 0));
+    assert!(!frame(3, 0));
+    assert!(!frame(2, 0));
+    assert!(frame(3, 0));
+
+    assert!(!frame(2, 1));
+    assert!(!frame(3, 0));
+    assert!(!frame(2, 0));
+    assert!(frame(3, 0));
+}
+
+#[test]
+fn trigger_com_alternativas_e_tamanhos() {
+    let memory = RefCell::new([0u8; 0x10000]);
+    let peek = |addr: u16| memory.borrow()[addr as usize];
+
+    // 0xS é o bit 6, não separador; 0x sem letra lê uma word
+    let mut trigger = Trigger::parse("0xSC000=1_0xC002=h1234S0xHC004=5S0xHC005=6").unwrap();
+    memory.borrow_mut()[0xC000] = 0x40;
+    memory.borrow_mut()[0xC002] = 0x34;
+    memory.borrow_mut()[0xC003] = 0x12;
+    assert!(!trigger.test(&peek));
+    memory.borrow_mut()[0xC005] = 6;
+    assert!(trigger.test(&peek));
+
+    assert!(Trigger::parse("0xHC000").is_err());
+    assert!(Trigger::parse("0xH10000=1").is_err());
+    assert!(Trigger::parse("Z:0xHC000=1").is_err());
+}
+
+#[test]
+fn conquista_so_desbloqueia_depois_de_armar() {
+    let text = "0.078\nJogo\n\
+        101:\"0xHC000=1\":Primeira:\"Pegue a: moeda\"::::autor:5:0:0:0:0:00001\n\
+        L102:\"0xHC000=1\":placar\n";
+    let mut achievements = Achievements::parse(text).unwrap();
+    assert_eq!(achievements.list().len(), 1);
+    assert_eq!(achievements.list()[0].description, "Pegue a: moeda");
+
+    let memory = RefCell::new([0u8; 0x10000]);
+    let peek = |addr: u16| memory.borrow()[addr as usize];
+    // Já verdade no primeiro frame (state carregado): espera ficar falso antes
+    memory.borrow_mut()[0xC000] = 1;
+    assert!(achievements.update(&peek).is_empty());
+    memory.borrow_mut()[0xC000] = 0;
+    assert!(achievements.update(&peek).is_empty());
+    memory.borrow_mut()[0xC000] = 1;
+    let unlocked = achievements.update(&peek);
+    assert_eq!(unlocked.len(), 1);
+    assert_eq!(unlocked[0].id, 101);
+    assert!(achievements.update(&peek).is_empty());
+    assert_eq!(achievements.points(), (5, 5));
+}
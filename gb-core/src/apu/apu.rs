@@ -0,0 +1,486 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::apu::{Noise, Square, Sweep, SweepStep, Wave};
+use crate::bus::{ClockDomain, Clocked, InterruptFlags};
+use crate::state::{Savestate, StateReader, StateWriter};
+
+// Registros de som: FF10-FF26, e a wave RAM em FF30-FF3F
+pub const NR10: u16 = 0xFF10;
+const NR13: u16 = 0xFF13;
+const NR14: u16 = 0xFF14;
+const NR21: u16 = 0xFF16;
+const NR23: u16 = 0xFF18;
+const NR24: u16 = 0xFF19;
+const NR32: u16 = 0xFF1C;
+const NR33: u16 = 0xFF1D;
+const NR34: u16 = 0xFF1E;
+const NR43: u16 = 0xFF22;
+const NR50: u16 = 0xFF24;
+const NR51: u16 = 0xFF25;
+pub const NR52: u16 = 0xFF26;
+pub const WAVE_RAM: u16 = 0xFF30;
+pub const APU_END: u16 = 0xFF3F;
+
+const NR52_POWER: u8 = 0x80;
+
+// Registros de debug do CGB com a saída digital dos canais: 1 e 2 em PCM12, 3 e 4 em PCM34
+pub const PCM12: u16 = 0xFF76;
+pub const PCM34: u16 = 0xFF77;
+
+// Saída estéreo intercalada (esquerda, direita) em 16 bits
+pub const SAMPLE_RATE: u32 = 48000;
+const CPU_HZ: u64 = 4_194_304;
+
+// Capacitor do passa-alta na saída: fração da carga que sobra a cada ciclo de CPU (o do
+// CGB descarrega mais rápido)
+const DMG_CHARGE_PER_CYCLE: f64 = 0.999958;
+const CGB_CHARGE_PER_CYCLE: f64 = 0.998943;
+
+// Bits que voltam sempre 1 na leitura (só escrita ou sem uso), de FF10 a FF2F
+const READ_MASKS: [u8; 0x20] = [
+    0x80, 0x3F, 0x00, 0xFF, 0xBF, // NR10-NR14
+    0xFF, 0x3F, 0x00, 0xFF, 0xBF, // FF15, NR21-NR24
+    0x7F, 0xFF, 0x9F, 0xFF, 0xBF, // NR30-NR34
+    0xFF, 0xFF, 0x00, 0x00, 0xBF, // FF1F, NR41-NR44
+    0x00, 0x00, 0x70, // NR50-NR52
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // FF27-FF2F
+];
+
+// Frame sequencer a 512 Hz; os passos pares clockam os contadores de duração, os passos
+// 2 e 6 o sweep e o passo 7 os envelopes
+const FRAME_SEQUENCER_CYCLES: u64 = 8192;
+
+const LENGTH_ENABLE: u8 = 0x40;
+const TRIGGER: u8 = 0x80;
+
+// Por canal: NRx1 (duração), NRx2 (DAC), NRx4 (trigger e enable da duração), e o máximo
+// do contador (o canal de wave conta 256)
+const LENGTH_REGS: [u16; 4] = [0xFF11, 0xFF16, 0xFF1B, 0xFF20];
+const DAC_REGS: [u16; 4] = [0xFF12, 0xFF17, 0xFF1A, 0xFF21];
+const CONTROL_REGS: [u16; 4] = [0xFF14, 0xFF19, 0xFF1E, 0xFF23];
+const MAX_LENGTH: [u16; 4] = [64, 64, 256, 64];
+
+pub struct Apu {
+    regs: [u8; 0x30],
+    powered: bool,
+    enabled: [bool; 4],
+    length: [u16; 4],
+    sequencer_cycles: u64,
+    // Próximo passo do frame sequencer (0-7)
+    step: u8,
+    square1: Square,
+    sweep: Sweep,
+    square2: Square,
+    wave: Wave,
+    noise: Noise,
+    // Ciclos × SAMPLE_RATE acumulados; cada CPU_HZ vira uma amostra
+    sample_clock: u64,
+    // Amostras do frame ainda não entregues ao frontend
+    samples: Vec<i16>,
+    // Passa-alta: fator por amostra e carga de cada lado. Tira o offset DC dos DACs ligados,
+    // e ligar ou desligar um DAC vira um clique que decai
+    charge: f32,
+    capacitor: [f32; 2],
+    // Canais silenciados na mixagem (mute/solo do frontend); não mexe no estado do canal
+    pub muted: [bool; 4],
+    // Saída digital de cada canal por amostra, só com o scope aberto
+    scope: Option<[Vec<u8>; 4]>,
+}
+
+fn charge_per_sample(per_cycle: f64) -> f32 {
+    powf(per_cycle, CPU_HZ as f64 / SAMPLE_RATE as f64) as f32
+}
+
+#[cfg(feature = "std")]
+fn powf(base: f64, exponent: f64) -> f64 {
+    base.powf(exponent)
+}
+
+// Sem std não há libm: com a base perto de 1 (as cargas por ciclo), ln e exp por série
+// já dão a precisão do f32
+#[cfg(not(feature = "std"))]
+fn powf(base: f64, exponent: f64) -> f64 {
+    let d = base - 1.0;
+    let t = exponent * (d - d * d / 2.0 + d * d * d / 3.0);
+    1.0 + t + t * t / 2.0 + t * t * t / 6.0 + t * t * t * t / 24.0
+}
+
+impl Apu {
+    pub fn new() -> Self {
+        Self {
+            regs: [0; 0x30],
+            powered: false,
+            enabled: [false; 4],
+            length: [0; 4],
+            sequencer_cycles: 0,
+            step: 0,
+            square1: Square::new(),
+            sweep: Sweep::new(),
+            square2: Square::new(),
+            wave: Wave::new(),
+            noise: Noise::new(),
+            sample_clock: 0,
+            samples: Vec::new(),
+            charge: charge_per_sample(DMG_CHARGE_PER_CYCLE),
+            capacitor: [0.0; 2],
+            muted: [false; 4],
+            scope: None,
+        }
+    }
+
+    // Valores pós-bootrom (DMG): som ligado, canal 1 tocou o "ding" do logo. A wave RAM
+    // não é tocada pelo boot
+    pub fn reset(&mut self, cgb: bool) {
+        let wave = self.regs[(WAVE_RAM - NR10) as usize..].to_vec();
+        let samples = core::mem::take(&mut self.samples);
+        let muted = self.muted;
+        let scope = self.scope.take();
+        *self = Self::new();
+        self.regs[(WAVE_RAM - NR10) as usize..].copy_from_slice(&wave);
+        self.samples = samples;
+        self.muted = muted;
+        self.scope = scope;
+        if cgb {
+            self.charge = charge_per_sample(CGB_CHARGE_PER_CYCLE);
+        }
+        self.powered = true;
+        self.regs[0x00] = 0x80;
+        self.regs[0x01] = 0xBF;
+        self.regs[0x02] = 0xF3;
+        self.regs[0x04] = 0xBF;
+        self.regs[0x06] = 0x3F;
+        self.regs[0x09] = 0xBF;
+        self.regs[0x0A] = 0x7F;
+        self.regs[0x0B] = 0xFF;
+        self.regs[0x0C] = 0x9F;
+        self.regs[0x0E] = 0xBF;
+        self.regs[0x10] = 0xFF;
+        self.regs[0x13] = 0xBF;
+        self.regs[0x14] = 0x77;
+        self.regs[0x15] = 0xF3;
+        self.enabled[0] = true;
+    }
+
+    // Desligar o console apaga a wave RAM; o resto volta no reset
+    pub fn power_cycle(&mut self) {
+        self.regs[(WAVE_RAM - NR10) as usize..].fill(0);
+    }
+
+    fn clock_sequencer(&mut self) {
+        if self.step.is_multiple_of(2) {
+            for channel in 0..4 {
+                self.clock_length(channel);
+            }
+        }
+        if self.step == 2 || self.step == 6 {
+            match self.sweep.clock(self.reg(NR10)) {
+                SweepStep::Idle => {}
+                SweepStep::Frequency(frequency) => {
+                    self.regs[(NR13 - NR10) as usize] = frequency as u8;
+                    let nr14 = &mut self.regs[(NR14 - NR10) as usize];
+                    *nr14 = (*nr14 & 0xF8) | (frequency >> 8) as u8;
+                }
+                SweepStep::Overflow => self.enabled[0] = false,
+            }
+        }
+        if self.step == 7 {
+            self.square1.envelope.clock(self.reg(DAC_REGS[0]));
+            self.square2.envelope.clock(self.reg(DAC_REGS[1]));
+            self.noise.envelope.clock(self.reg(DAC_REGS[3]));
+        }
+        self.step = (self.step + 1) % 8;
+    }
+
+    // Frequência de 11 bits: NRx3 e os bits 0-2 do NRx4
+    fn frequency(&self, low: u16, high: u16) -> u16 {
+        ((self.reg(high) as u16 & 0x07) << 8) | self.reg(low) as u16
+    }
+
+    // Valor digital (0-15) de cada canal, o que o CGB expõe em PCM12/PCM34
+    fn output(&self, channel: usize) -> u8 {
+        if !self.enabled[channel] {
+            return 0;
+        }
+        match channel {
+            0 => self.square1.output(self.reg(LENGTH_REGS[0])),
+            1 => self.square2.output(self.reg(NR21)),
+            2 => self
+                .wave
+                .output(self.reg(NR32), &self.regs[(WAVE_RAM - NR10) as usize..]),
+            _ => self.noise.output(),
+        }
+    }
+
+    // DAC de cada canal (-1.0 a 1.0) somado nos lados ligados no NR51, escalado pelo
+    // volume master do NR50 (1 a 8 oitavos) e passado pelo passa-alta
+    fn mix(&mut self) -> (i16, i16) {
+        let nr50 = self.reg(NR50);
+        let nr51 = self.reg(NR51);
+        let (mut left, mut right) = (0.0, 0.0);
+        let mut any_dac = false;
+        for channel in 0..4 {
+            if !self.dac_on(channel) {
+                continue;
+            }
+            any_dac = true;
+            if self.muted[channel] {
+                continue;
+            }
+            let analog = self.output(channel) as f32 / 7.5 - 1.0;
+            if nr51 & (0x10 << channel) != 0 {
+                left += analog;
+            }
+            if nr51 & (0x01 << channel) != 0 {
+                right += analog;
+            }
+        }
+
+        let volume = |bits: u8| ((bits & 0x07) + 1) as f32 / 8.0;
+        let left = self.high_pass(0, left / 4.0 * volume(nr50 >> 4), any_dac);
+        let right = self.high_pass(1, right / 4.0 * volume(nr50), any_dac);
+        let scale = |value: f32| (value.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        (scale(left), scale(right))
+    }
+
+    // Com todos os DACs desligados a saída fica em zero e o capacitor parado
+    fn high_pass(&mut self, side: usize, input: f32, any_dac: bool) -> f32 {
+        if !any_dac {
+            return 0.0;
+        }
+        let output = input - self.capacitor[side];
+        self.capacitor[side] = input - output * self.charge;
+        output
+    }
+
+    pub fn set_scope(&mut self, enabled: bool) {
+        self.scope = enabled.then(Default::default);
+    }
+
+    // Formas de onda desde a última chamada, com o scope aberto
+    pub fn take_scope(&mut self) -> Option<[Vec<u8>; 4]> {
+        self.scope.as_mut().map(core::mem::take)
+    }
+
+    pub fn take_samples(&mut self) -> Vec<i16> {
+        core::mem::take(&mut self.samples)
+    }
+
+    pub fn read_pcm(&self, addr: u16) -> u8 {
+        let (low, high) = if addr == PCM12 { (0, 1) } else { (2, 3) };
+        self.output(low) | self.output(high) << 4
+    }
+
+    fn clock_length(&mut self, channel: usize) {
+        if self.reg(CONTROL_REGS[channel]) & LENGTH_ENABLE != 0 && self.length[channel] > 0 {
+            self.length[channel] -= 1;
+            if self.length[channel] == 0 {
+                self.enabled[channel] = false;
+            }
+        }
+    }
+
+    fn reg(&self, addr: u16) -> u8 {
+        self.regs[(addr - NR10) as usize]
+    }
+
+    fn dac_on(&self, channel: usize) -> bool {
+        let value = self.reg(DAC_REGS[channel]);
+        if channel == 2 {
+            value & 0x80 != 0
+        } else {
+            value & 0xF8 != 0
+        }
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            NR52 => {
+                let status = self
+                    .enabled
+                    .iter()
+                    .enumerate()
+                    .fold(0, |bits, (channel, &on)| bits | (on as u8) << channel);
+                0x70 | if self.powered { NR52_POWER } else { 0 } | status
+            }
+            NR10..WAVE_RAM => self.reg(addr) | READ_MASKS[(addr - NR10) as usize],
+            WAVE_RAM..=APU_END => self.reg(addr),
+            _ => 0xFF,
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, data: u8, cgb: bool) {
+        match addr {
+            NR52 => self.set_power(data & NR52_POWER != 0, cgb),
+            WAVE_RAM..=APU_END => self.regs[(addr - NR10) as usize] = data,
+            // Desligado só a duração aceita escrita, e só no DMG (o duty fica zerado)
+            _ if !self.powered => {
+                if !cgb && let Some(channel) = LENGTH_REGS.iter().position(|&reg| reg == addr) {
+                    self.load_length(channel, data);
+                }
+            }
+            NR10..WAVE_RAM => {
+                let old = self.reg(addr);
+                self.regs[(addr - NR10) as usize] = data;
+                if let Some(channel) = LENGTH_REGS.iter().position(|&reg| reg == addr) {
+                    self.load_length(channel, data);
+                } else if let Some(channel) = DAC_REGS.iter().position(|&reg| reg == addr) {
+                    if !self.dac_on(channel) {
+                        self.enabled[channel] = false;
+                    }
+                } else if let Some(channel) = CONTROL_REGS.iter().position(|&reg| reg == addr) {
+                    self.write_control(channel, old, data);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn load_length(&mut self, channel: usize, data: u8) {
+        let bits = if channel == 2 {
+            data as u16
+        } else {
+            (data & 0x3F) as u16
+        };
+        self.length[channel] = MAX_LENGTH[channel] - bits;
+    }
+
+    fn write_control(&mut self, channel: usize, old: u8, data: u8) {
+        let length_enabled = data & LENGTH_ENABLE != 0;
+        // Próximo passo ímpar: o sequencer acabou de clockar a duração
+        let first_half = self.step % 2 == 1;
+
+        // Ligar a duração na primeira metade do período dá um clock extra
+        if first_half && old & LENGTH_ENABLE == 0 && length_enabled && self.length[channel] > 0 {
+            self.length[channel] -= 1;
+            if self.length[channel] == 0 && data & TRIGGER == 0 {
+                self.enabled[channel] = false;
+            }
+        }
+
+        if data & TRIGGER != 0 {
+            self.enabled[channel] = self.dac_on(channel);
+            if self.length[channel] == 0 {
+                self.length[channel] = MAX_LENGTH[channel];
+                if length_enabled && first_half {
+                    self.length[channel] -= 1;
+                }
+            }
+            self.trigger(channel);
+        }
+    }
+
+    fn trigger(&mut self, channel: usize) {
+        match channel {
+            0 => {
+                let frequency = self.frequency(NR13, NR14);
+                self.square1.trigger(self.reg(DAC_REGS[0]), frequency);
+                if self.sweep.trigger(self.reg(NR10), frequency) {
+                    self.enabled[0] = false;
+                }
+            }
+            1 => {
+                let frequency = self.frequency(NR23, NR24);
+                self.square2.trigger(self.reg(DAC_REGS[1]), frequency);
+            }
+            2 => self.wave.trigger(self.frequency(NR33, NR34)),
+            _ => self.noise.trigger(self.reg(DAC_REGS[3]), self.reg(NR43)),
+        }
+    }
+
+    fn set_power(&mut self, on: bool, cgb: bool) {
+        if on == self.powered {
+            return;
+        }
+
+        if on {
+            // Sequencer e posição do duty recomeçam do zero
+            self.step = 0;
+            self.sequencer_cycles = 0;
+            self.square1 = Square::new();
+            self.square2 = Square::new();
+        } else {
+            // Tudo zerado menos a wave RAM; no DMG os contadores de duração sobrevivem
+            let length = self.length;
+            self.regs[..(WAVE_RAM - NR10) as usize].fill(0);
+            self.enabled = [false; 4];
+            self.length = if cgb { [0; 4] } else { length };
+        }
+        self.powered = on;
+    }
+}
+
+impl Clocked for Apu {
+    fn domain(&self) -> ClockDomain {
+        ClockDomain::System
+    }
+
+    fn tick(&mut self, t_cycles: u64) -> InterruptFlags {
+        if self.powered {
+            let cycles = t_cycles as u32;
+            self.square1.step(cycles, self.frequency(NR13, NR14));
+            self.square2.step(cycles, self.frequency(NR23, NR24));
+            self.wave.step(cycles, self.frequency(NR33, NR34));
+            self.noise.step(cycles, self.reg(NR43));
+
+            self.sequencer_cycles += t_cycles;
+            while self.sequencer_cycles >= FRAME_SEQUENCER_CYCLES {
+                self.sequencer_cycles -= FRAME_SEQUENCER_CYCLES;
+                self.clock_sequencer();
+            }
+        }
+
+        // Desligado continua gerando silêncio, pro áudio não engasgar
+        self.sample_clock += t_cycles * SAMPLE_RATE as u64;
+        while self.sample_clock >= CPU_HZ {
+            self.sample_clock -= CPU_HZ;
+            if let Some(mut scope) = self.scope.take() {
+                for (channel, samples) in scope.iter_mut().enumerate() {
+                    samples.push(self.output(channel));
+                }
+                self.scope = Some(scope);
+            }
+            let (left, right) = self.mix();
+            self.samples.push(left);
+            self.samples.push(right);
+        }
+        InterruptFlags::empty()
+    }
+}
+
+impl Savestate for Apu {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bytes(&self.regs);
+        w.write_bool(self.powered);
+        for channel in 0..4 {
+            w.write_bool(self.enabled[channel]);
+            w.write_u16(self.length[channel]);
+        }
+        w.write_u32(self.sequencer_cycles as u32);
+        w.write_u8(self.step);
+        self.square1.save_state(w);
+        self.sweep.save_state(w);
+        self.square2.save_state(w);
+        self.wave.save_state(w);
+        self.noise.save_state(w);
+        w.write_u32(self.sample_clock as u32);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        r.read_bytes(&mut self.regs)?;
+        self.powered = r.read_bool()?;
+        for channel in 0..4 {
+            self.enabled[channel] = r.read_bool()?;
+            self.length[channel] = r.read_u16()?;
+        }
+        self.sequencer_cycles = r.read_u32()? as u64;
+        self.step = r.read_u8()?;
+        self.square1.load_state(r)?;
+        self.sweep.load_state(r)?;
+        self.square2.load_state(r)?;
+        self.wave.load_state(r)?;
+        self.noise.load_state(r)?;
+        self.sample_clock = r.read_u32()? as u64;
+        Ok(())
+    }
+}
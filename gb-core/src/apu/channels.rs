@@ -0,0 +1,340 @@
+use alloc::string::String;
+
+use crate::state::{Savestate, StateReader, StateWriter};
+
+// Formas de onda do duty (NRx1 bits 6-7): 12,5%, 25%, 50% e 75%
+const DUTY_PATTERNS: [u8; 4] = [0b0000_0001, 0b1000_0001, 0b1000_0111, 0b0111_1110];
+
+// Volume com envelope (NRx2: volume inicial, direção e período), clockado a 64 Hz
+pub struct Envelope {
+    pub volume: u8,
+    timer: u8,
+}
+
+impl Envelope {
+    pub fn new() -> Self {
+        Self {
+            volume: 0,
+            timer: 0,
+        }
+    }
+
+    pub fn trigger(&mut self, nrx2: u8) {
+        self.volume = nrx2 >> 4;
+        self.timer = nrx2 & 0x07;
+    }
+
+    pub fn clock(&mut self, nrx2: u8) {
+        let period = nrx2 & 0x07;
+        if period == 0 {
+            return;
+        }
+
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.timer = period;
+            if nrx2 & 0x08 != 0 {
+                self.volume = (self.volume + 1).min(15);
+            } else {
+                self.volume = self.volume.saturating_sub(1);
+            }
+        }
+    }
+}
+
+impl Savestate for Envelope {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.volume);
+        w.write_u8(self.timer);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.volume = r.read_u8()?;
+        self.timer = r.read_u8()?;
+        Ok(())
+    }
+}
+
+// Canais 1 e 2: onda quadrada, 8 passos de duty por período
+pub struct Square {
+    timer: u32,
+    duty_step: u8,
+    pub envelope: Envelope,
+}
+
+fn square_period(frequency: u16) -> u32 {
+    (2048 - frequency as u32) * 4
+}
+
+impl Square {
+    pub fn new() -> Self {
+        Self {
+            timer: 0,
+            duty_step: 0,
+            envelope: Envelope::new(),
+        }
+    }
+
+    pub fn trigger(&mut self, nrx2: u8, frequency: u16) {
+        self.timer = square_period(frequency);
+        self.envelope.trigger(nrx2);
+    }
+
+    pub fn step(&mut self, mut cycles: u32, frequency: u16) {
+        while cycles >= self.timer {
+            cycles -= self.timer;
+            self.timer = square_period(frequency);
+            self.duty_step = (self.duty_step + 1) % 8;
+        }
+        self.timer -= cycles;
+    }
+
+    pub fn output(&self, nrx1: u8) -> u8 {
+        let pattern = DUTY_PATTERNS[(nrx1 >> 6) as usize];
+        if (pattern >> self.duty_step) & 1 != 0 {
+            self.envelope.volume
+        } else {
+            0
+        }
+    }
+}
+
+impl Savestate for Square {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u32(self.timer);
+        w.write_u8(self.duty_step);
+        self.envelope.save_state(w);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.timer = r.read_u32()?;
+        self.duty_step = r.read_u8()?;
+        self.envelope.load_state(r)
+    }
+}
+
+// Resultado de um clock do sweep do canal 1
+pub enum SweepStep {
+    Idle,
+    Frequency(u16),
+    // Passou de 2047: o canal desliga
+    Overflow,
+}
+
+// Sweep de frequência do canal 1 (NR10: período, direção e shift), clockado a 128 Hz
+pub struct Sweep {
+    shadow: u16,
+    timer: u8,
+    enabled: bool,
+}
+
+// Período 0 conta como 8
+fn sweep_period(nr10: u8) -> u8 {
+    match (nr10 >> 4) & 0x07 {
+        0 => 8,
+        period => period,
+    }
+}
+
+impl Sweep {
+    pub fn new() -> Self {
+        Self {
+            shadow: 0,
+            timer: 0,
+            enabled: false,
+        }
+    }
+
+    fn next(&self, nr10: u8) -> u16 {
+        let delta = self.shadow >> (nr10 & 0x07);
+        if nr10 & 0x08 != 0 {
+            self.shadow - delta
+        } else {
+            self.shadow + delta
+        }
+    }
+
+    // Retorna true se o cálculo imediato do trigger já estoura (canal não liga)
+    pub fn trigger(&mut self, nr10: u8, frequency: u16) -> bool {
+        self.shadow = frequency;
+        self.timer = sweep_period(nr10);
+        self.enabled = nr10 & 0x77 != 0;
+        nr10 & 0x07 != 0 && self.next(nr10) > 2047
+    }
+
+    pub fn clock(&mut self, nr10: u8) -> SweepStep {
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer != 0 {
+            return SweepStep::Idle;
+        }
+
+        self.timer = sweep_period(nr10);
+        if !self.enabled || nr10 & 0x70 == 0 {
+            return SweepStep::Idle;
+        }
+
+        let frequency = self.next(nr10);
+        if frequency > 2047 {
+            return SweepStep::Overflow;
+        }
+        if nr10 & 0x07 == 0 {
+            return SweepStep::Idle;
+        }
+
+        // A frequência nova é checada de novo na hora, sem ser aplicada
+        self.shadow = frequency;
+        if self.next(nr10) > 2047 {
+            return SweepStep::Overflow;
+        }
+        SweepStep::Frequency(frequency)
+    }
+}
+
+impl Savestate for Sweep {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u16(self.shadow);
+        w.write_u8(self.timer);
+        w.write_bool(self.enabled);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.shadow = r.read_u16()?;
+        self.timer = r.read_u8()?;
+        self.enabled = r.read_bool()?;
+        Ok(())
+    }
+}
+
+// Canal 3: 32 amostras de 4 bits da wave RAM, nibble alto primeiro
+pub struct Wave {
+    timer: u32,
+    position: u8,
+}
+
+fn wave_period(frequency: u16) -> u32 {
+    (2048 - frequency as u32) * 2
+}
+
+impl Wave {
+    pub fn new() -> Self {
+        Self {
+            timer: 0,
+            position: 0,
+        }
+    }
+
+    pub fn trigger(&mut self, frequency: u16) {
+        self.timer = wave_period(frequency);
+        self.position = 0;
+    }
+
+    pub fn step(&mut self, mut cycles: u32, frequency: u16) {
+        while cycles >= self.timer {
+            cycles -= self.timer;
+            self.timer = wave_period(frequency);
+            self.position = (self.position + 1) % 32;
+        }
+        self.timer -= cycles;
+    }
+
+    // NR32 bits 5-6: mudo, 100%, 50% ou 25%
+    pub fn output(&self, nr32: u8, wave_ram: &[u8]) -> u8 {
+        let byte = wave_ram[(self.position / 2) as usize];
+        let sample = if self.position.is_multiple_of(2) {
+            byte >> 4
+        } else {
+            byte & 0x0F
+        };
+        match (nr32 >> 5) & 0x03 {
+            0 => 0,
+            1 => sample,
+            2 => sample >> 1,
+            _ => sample >> 2,
+        }
+    }
+}
+
+impl Savestate for Wave {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u32(self.timer);
+        w.write_u8(self.position);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.timer = r.read_u32()?;
+        self.position = r.read_u8()?;
+        Ok(())
+    }
+}
+
+// Canal 4: ruído de um LFSR de 15 bits (ou 7 bits com NR43 bit 3)
+pub struct Noise {
+    timer: u32,
+    lfsr: u16,
+    pub envelope: Envelope,
+}
+
+// NR43: divisor (bits 0-2, 0 vale 8) deslocado pelo shift (bits 4-7)
+fn noise_period(nr43: u8) -> u32 {
+    let divisor = match nr43 & 0x07 {
+        0 => 8,
+        code => code as u32 * 16,
+    };
+    divisor << (nr43 >> 4)
+}
+
+impl Noise {
+    pub fn new() -> Self {
+        Self {
+            timer: 0,
+            lfsr: 0x7FFF,
+            envelope: Envelope::new(),
+        }
+    }
+
+    pub fn trigger(&mut self, nr42: u8, nr43: u8) {
+        self.timer = noise_period(nr43);
+        self.lfsr = 0x7FFF;
+        self.envelope.trigger(nr42);
+    }
+
+    pub fn step(&mut self, mut cycles: u32, nr43: u8) {
+        // Shift 14 e 15 não clockam o LFSR
+        if nr43 >> 4 >= 14 {
+            return;
+        }
+
+        while cycles >= self.timer {
+            cycles -= self.timer;
+            self.timer = noise_period(nr43);
+            let bit = (self.lfsr ^ (self.lfsr >> 1)) & 1;
+            self.lfsr = (self.lfsr >> 1) | (bit << 14);
+            if nr43 & 0x08 != 0 {
+                self.lfsr = (self.lfsr & !0x40) | (bit << 6);
+            }
+        }
+        self.timer -= cycles;
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.lfsr & 1 == 0 {
+            self.envelope.volume
+        } else {
+            0
+        }
+    }
+}
+
+impl Savestate for Noise {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u32(self.timer);
+        w.write_u16(self.lfsr);
+        self.envelope.save_state(w);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.timer = r.read_u32()?;
+        self.lfsr = r.read_u16()?;
+        self.envelope.load_state(r)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod apu;
+pub mod channels;
+
+pub use apu::*;
+pub use channels::*;
@@ -0,0 +1,7 @@
+pub mod inflate;
+#[cfg(feature = "std")]
+pub mod rom;
+pub mod zip;
+
+#[cfg(feature = "std")]
+pub use rom::*;
@@ -0,0 +1,14 @@
+use std::fs;
+use std::path::Path;
+
+use super::zip::{extract_rom, is_zip};
+
+// Lê a ROM direto ou de dentro de um .zip
+pub fn read_rom(path: &Path) -> Result<Vec<u8>, String> {
+    let data = fs::read(path)
+        .map_err(|erro| format!("Error ao ler o arquivo '{}': {}", path.display(), erro))?;
+    if !is_zip(&data) {
+        return Ok(data);
+    }
+    extract_rom(&data).map_err(|erro| format!("'{}': {}", path.display(), erro))
+}
@@ -0,0 +1,111 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use super::inflate::inflate;
+use crate::png;
+
+const EOCD_SIGNATURE: u32 = 0x0605_4B50;
+const CENTRAL_SIGNATURE: u32 = 0x0201_4B50;
+const LOCAL_SIGNATURE: u32 = 0x0403_4B50;
+const EOCD_SIZE: usize = 22;
+const CENTRAL_SIZE: usize = 46;
+const LOCAL_SIZE: usize = 30;
+// O EOCD pode ter um comentário de até 64 KB depois dele
+const MAX_COMMENT: usize = 0xFFFF;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+
+const ROM_EXTENSIONS: [&str; 2] = [".gb", ".gbc"];
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| "zip truncado".to_string())
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| "zip truncado".to_string())
+}
+
+pub fn is_zip(data: &[u8]) -> bool {
+    data.len() >= 4 && u32_at(data, 0) == Ok(LOCAL_SIGNATURE)
+}
+
+// Procura o EOCD de trás pra frente (o comentário no fim tem tamanho variável)
+fn find_eocd(data: &[u8]) -> Result<usize, String> {
+    if data.len() < EOCD_SIZE {
+        return Err("zip truncado".to_string());
+    }
+    let last = data.len() - EOCD_SIZE;
+    let first = last.saturating_sub(MAX_COMMENT);
+    (first..=last)
+        .rev()
+        .find(|&offset| u32_at(data, offset) == Ok(EOCD_SIGNATURE))
+        .ok_or_else(|| "zip sem diretório central".to_string())
+}
+
+// Primeiro .gb/.gbc do arquivo, descompactado
+pub fn extract_rom(data: &[u8]) -> Result<Vec<u8>, String> {
+    let eocd = find_eocd(data)?;
+    let entries = u16_at(data, eocd + 10)?;
+    let mut offset = u32_at(data, eocd + 16)? as usize;
+
+    for _ in 0..entries {
+        if u32_at(data, offset)? != CENTRAL_SIGNATURE {
+            return Err("diretório central do zip corrompido".to_string());
+        }
+        let name_len = u16_at(data, offset + 28)? as usize;
+        let extra_len = u16_at(data, offset + 30)? as usize;
+        let comment_len = u16_at(data, offset + 32)? as usize;
+        let name = data
+            .get(offset + CENTRAL_SIZE..offset + CENTRAL_SIZE + name_len)
+            .ok_or("zip truncado")?;
+        let name = String::from_utf8_lossy(name).to_lowercase();
+
+        if ROM_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+            return extract_entry(data, offset);
+        }
+        offset += CENTRAL_SIZE + name_len + extra_len + comment_len;
+    }
+
+    Err("nenhuma ROM (.gb/.gbc) dentro do zip".to_string())
+}
+
+// Tamanhos e CRC vêm do diretório central: no header local podem estar zerados
+fn extract_entry(data: &[u8], central: usize) -> Result<Vec<u8>, String> {
+    let flags = u16_at(data, central + 8)?;
+    let method = u16_at(data, central + 10)?;
+    let crc = u32_at(data, central + 16)?;
+    let compressed_size = u32_at(data, central + 20)? as usize;
+    let size = u32_at(data, central + 24)? as usize;
+    let local = u32_at(data, central + 42)? as usize;
+
+    if flags & 1 != 0 {
+        return Err("zip criptografado não é suportado".to_string());
+    }
+    if u32_at(data, local)? != LOCAL_SIGNATURE {
+        return Err("entrada do zip corrompida".to_string());
+    }
+    let start = local
+        + LOCAL_SIZE
+        + u16_at(data, local + 26)? as usize
+        + u16_at(data, local + 28)? as usize;
+    let compressed = data
+        .get(start..start + compressed_size)
+        .ok_or("zip truncado")?;
+
+    let rom = match method {
+        METHOD_STORED => compressed.to_vec(),
+        METHOD_DEFLATE => inflate(compressed)?,
+        other => return Err(format!("método de compressão não suportado: {}", other)),
+    };
+
+    if rom.len() != size || png::crc32(&rom) != crc {
+        return Err("CRC da ROM no zip não confere".to_string());
+    }
+    Ok(rom)
+}
@@ -0,0 +1,55 @@
+use crate::bus::InterruptFlags;
+
+// De qual clock o componente depende
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ClockDomain {
+    // Segue a CPU: dobra em double speed
+    Cpu,
+    // Clock normal do sistema, mesmo em double speed
+    System,
+    // Cristal próprio (RTC do cartucho): não para nem no STOP
+    Rtc,
+}
+
+// Componente avançado pelo bus a cada instrução; as interrupções pedidas voltam pro IF
+pub trait Clocked {
+    fn domain(&self) -> ClockDomain;
+    fn tick(&mut self, t_cycles: u64) -> InterruptFlags;
+}
+
+// Tempo gasto no tick de cada dispositivo, pro overlay de estatísticas. Sem std o core
+// não tem relógio: quem liga passa uma função que devolve nanossegundos
+pub struct DeviceTimes {
+    clock: fn() -> u64,
+    // Na ordem do MemoryBus::tick: timer, serial, DMA, PPU, APU, cartucho
+    spent: [u64; 6],
+}
+
+impl DeviceTimes {
+    pub fn new(clock: fn() -> u64) -> Self {
+        Self {
+            clock,
+            spent: [0; 6],
+        }
+    }
+
+    pub fn now(&self) -> u64 {
+        (self.clock)()
+    }
+
+    pub fn add(&mut self, device: usize, nanos: u64) {
+        self.spent[device] += nanos;
+    }
+
+    pub fn ppu(&self) -> u64 {
+        self.spent[3]
+    }
+
+    pub fn apu(&self) -> u64 {
+        self.spent[4]
+    }
+
+    pub fn clear(&mut self) {
+        self.spent = [0; 6];
+    }
+}
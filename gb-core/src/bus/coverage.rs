@@ -0,0 +1,97 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use bitflags::bitflags;
+
+bitflags! {
+    // Como um byte da ROM já foi acessado. Busca de instrução também conta como leitura:
+    // dado é o que foi lido e nunca executado
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    pub struct CoverageFlags: u8 {
+        const EXECUTED = 1 << 0;
+        const READ = 1 << 1;
+        // Escritas na faixa da ROM são os registros do MBC
+        const WRITTEN = 1 << 2;
+    }
+}
+
+const BANK_SIZE: usize = 0x4000;
+
+// Mapa de cobertura da ROM: um byte de flags por byte do arquivo, na mesma posição
+pub struct Coverage {
+    map: Vec<u8>,
+}
+
+impl Coverage {
+    pub fn new(rom_len: usize) -> Self {
+        Self {
+            map: vec![0; rom_len],
+        }
+    }
+
+    pub fn mark(&mut self, offset: usize, flags: CoverageFlags) {
+        if let Some(byte) = self.map.get_mut(offset) {
+            *byte |= flags.bits();
+        }
+    }
+
+    // Formato binário: o mapa cru, do tamanho da ROM
+    pub fn map(&self) -> &[u8] {
+        &self.map
+    }
+
+    // Totais (geral e por banco) e as faixas contíguas com as mesmas flags
+    pub fn to_json(&self) -> String {
+        let count = |bytes: &[u8], flag: CoverageFlags| {
+            bytes.iter().filter(|&&byte| byte & flag.bits() != 0).count()
+        };
+        let totals = |bytes: &[u8]| {
+            format!(
+                "\"executed\": {}, \"read\": {}, \"written\": {}",
+                count(bytes, CoverageFlags::EXECUTED),
+                count(bytes, CoverageFlags::READ),
+                count(bytes, CoverageFlags::WRITTEN)
+            )
+        };
+
+        let banks: Vec<String> = self
+            .map
+            .chunks(BANK_SIZE)
+            .enumerate()
+            .map(|(bank, bytes)| format!("    {{\"bank\": {}, {}}}", bank, totals(bytes)))
+            .collect();
+
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        while start < self.map.len() {
+            let flags = self.map[start];
+            let length = self.map[start..]
+                .iter()
+                .take_while(|&&byte| byte == flags)
+                .count();
+            if flags != 0 {
+                let flags = CoverageFlags::from_bits_truncate(flags);
+                ranges.push(format!(
+                    "    {{\"offset\": {}, \"length\": {}, \"executed\": {}, \"read\": {}, \
+                     \"written\": {}}}",
+                    start,
+                    length,
+                    flags.contains(CoverageFlags::EXECUTED),
+                    flags.contains(CoverageFlags::READ),
+                    flags.contains(CoverageFlags::WRITTEN)
+                ));
+            }
+            start += length;
+        }
+
+        format!(
+            "{{\n  \"rom_size\": {}, {},\n  \"banks\": [\n{}\n  ],\n  \"ranges\": [\n{}\n  ]\n}}\n",
+            self.map.len(),
+            totals(&self.map),
+            banks.join(",\n"),
+            ranges.join(",\n")
+        )
+    }
+}
@@ -0,0 +1,36 @@
+use crate::bus::{AccessKind, OamBugAccess};
+
+// O que a CPU usa do barramento. O MemoryBus é o do emulador; nos testes a CPU roda num
+// TestBus com 64KB de RAM plana, sem PPU nem MBC no caminho
+pub trait CpuBus {
+    fn read(&mut self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, data: u8);
+
+    // Little-endian, um byte de cada vez
+    fn read_u16(&mut self, addr: u16) -> u16 {
+        let low = self.read(addr) as u16;
+        let high = self.read(addr.wrapping_add(1)) as u16;
+        (high << 8) | low
+    }
+
+    fn write_u16(&mut self, addr: u16, value: u16) {
+        self.write(addr, value as u8);
+        self.write(addr.wrapping_add(1), (value >> 8) as u8);
+    }
+
+    // Todo acesso da CPU à memória passa aqui, pros watchpoints
+    fn record_access(&mut self, _kind: AccessKind, _addr: u16, _value: u8) {}
+
+    // Valor de 16 bits no barramento de endereço (INC/DEC rr, PUSH/POP, LDI/LDD)
+    fn oam_bug(&mut self, _addr: u16, _access: OamBugAccess) {}
+
+    // Instrução STOP: true se a CPU para de fato
+    fn stop(&mut self) -> bool {
+        true
+    }
+
+    // Em STOP: true quando um botão acorda a CPU
+    fn stop_wake(&self) -> bool {
+        false
+    }
+}
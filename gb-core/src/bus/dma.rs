@@ -0,0 +1,62 @@
+use alloc::string::String;
+
+use crate::bus::{ClockDomain, Clocked, InterruptFlags};
+use crate::state::{Savestate, StateReader, StateWriter};
+
+pub const DMA: u16 = 0xFF46;
+
+// 160 bytes, um por M-cycle
+const DMA_CYCLES: u64 = 0xA0 * 4;
+
+// OAM DMA: o bus copia os 160 bytes na escrita do FF46; aqui ficam o registrador e a
+// janela em que a transferência ainda estaria rodando no hardware (OAM inacessível)
+pub struct OamDma {
+    source: u8,
+    remaining: u64,
+}
+
+impl OamDma {
+    pub fn new() -> Self {
+        Self {
+            source: 0xFF,
+            remaining: 0,
+        }
+    }
+
+    pub fn start(&mut self, source: u8) {
+        self.source = source;
+        self.remaining = DMA_CYCLES;
+    }
+
+    pub fn source(&self) -> u8 {
+        self.source
+    }
+
+    pub fn active(&self) -> bool {
+        self.remaining > 0
+    }
+}
+
+impl Clocked for OamDma {
+    fn domain(&self) -> ClockDomain {
+        ClockDomain::Cpu
+    }
+
+    fn tick(&mut self, t_cycles: u64) -> InterruptFlags {
+        self.remaining = self.remaining.saturating_sub(t_cycles);
+        InterruptFlags::empty()
+    }
+}
+
+impl Savestate for OamDma {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.source);
+        w.write_u32(self.remaining as u32);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.source = r.read_u8()?;
+        self.remaining = r.read_u32()? as u64;
+        Ok(())
+    }
+}
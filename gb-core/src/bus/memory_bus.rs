@@ -0,0 +1,729 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use bitflags::bitflags;
+use crate::apu::{APU_END, Apu, NR10, PCM12, PCM34};
+use crate::bus::oam_bug::{self, OamBugAccess};
+use crate::bus::dma::DMA;
+use crate::bus::serial::{SB, SC};
+use crate::bus::{
+    AccessKind, ClockDomain, Clocked, Coverage, CoverageFlags, CpuBus, DeviceTimes, OamDma,
+    SerialPort, Watchpoints,
+};
+use crate::cartridge::{Cartridge, IrDevice, NoIr};
+use crate::cheats::Cheats;
+use crate::cpu::instruction_length;
+use crate::joypad::{Joypad, JoypadInput, P1};
+use crate::machine::{Model, Rng};
+use crate::ppu::{LCD_END, LCDC, OAM_BASE, OAM_END, OPRI, Ppu, VRAM_BASE, VRAM_END};
+use crate::sgb::{Sgb, TRANSFER_SIZE};
+use crate::state::{BlockId, Savestate, StateBlocks, StateReader, StateWriter};
+use crate::timer::Timer;
+
+bitflags! {
+    #[derive(Copy, Clone, Default)]
+    pub struct InterruptFlags: u8 {
+        const VBLANK  =  1 << 0;
+        const LCDSTAT =  1 << 1;
+        const TIMER   =  1 << 2;
+        const SERIAL  =  1 << 3;
+        const JOYPAD  =  1 << 4;
+    }
+}
+
+// Porta infravermelha do CGB: bit 0 acende o LED, bits 6-7 = 11 habilitam a leitura
+const RP: u16 = 0xFF56;
+const RP_LED: u8 = 0x01;
+const RP_READ_ENABLE: u8 = 0xC0;
+
+// Modo do CGB, que só o boot ROM escreve: o flag do header (0x143) pra jogos de CGB ou
+// bit 2 = modo compatível pra jogos de DMG
+const KEY0: u16 = 0xFF4C;
+const KEY0_DMG_COMPAT: u8 = 0x04;
+
+// Blocos do bus no save state e a versão do layout de cada um: o STATE_VERSION em que o
+// save_state do componente mudou por último
+const CART_BLOCK: BlockId = (b"CART", 7);
+const TIMER_BLOCK: BlockId = (b"TIMR", 7);
+const APU_BLOCK: BlockId = (b"APU ", 7);
+const JOYPAD_BLOCK: BlockId = (b"JOYP", 7);
+const SERIAL_BLOCK: BlockId = (b"SERL", 7);
+const DMA_BLOCK: BlockId = (b"DMA ", 7);
+const PPU_BLOCK: BlockId = (b"PPU ", 7);
+const MEMORY_BLOCK: BlockId = (b"MEM ", 7);
+const SGB_BLOCK: BlockId = (b"SGB ", 7);
+
+// Troca de velocidade do CGB: bit 7 = double speed, bit 0 = troca armada pro próximo STOP
+const KEY1: u16 = 0xFF4D;
+const KEY1_DOUBLE: u8 = 0x80;
+const KEY1_ARMED: u8 = 0x01;
+
+// Transferência do SGB: os 4 KB de tiles na ordem em que aparecem no mapa do BG (20 por
+// linha), que é como o SGB os lê da tela
+fn screen_transfer(vram: &[u8], lcdc: u8) -> Vec<u8> {
+    let map = if lcdc & 0x08 != 0 { 0x1C00 } else { 0x1800 };
+    let mut data = Vec::with_capacity(TRANSFER_SIZE);
+    for i in 0..TRANSFER_SIZE / 16 {
+        let tile = vram[map + (i / 20) * 32 + i % 20];
+        let start = if lcdc & 0x10 != 0 {
+            tile as usize * 16
+        } else {
+            (0x1000 + tile as i8 as isize * 16) as usize
+        };
+        data.extend_from_slice(&vram[start..start + 16]);
+    }
+    data
+}
+
+pub struct MemoryBus {
+    pub cartridge: Cartridge,
+    pub timer: Timer,
+    pub ppu: Ppu,
+    pub apu: Apu,
+    pub joypad: Joypad,
+    pub serial: SerialPort,
+    pub dma: OamDma,
+    pub cheats: Cheats,
+    pub watch: Watchpoints,
+    wram: [u8; 0x2000],
+    hram: [u8; 0x7F],
+    io: [u8; 0x80],
+    if_reg: u8,
+    ie_reg: u8,
+    pub model: Model,
+    // Porta infravermelha do CGB (RP, 0xFF56)
+    pub infrared: Box<dyn IrDevice>,
+    // Luz forçada pelo script; None = a do dispositivo
+    pub ir_light: Option<bool>,
+    // Só com o modelo SGB e cartucho com suporte
+    pub sgb: Option<Sgb>,
+    // Tempo por dispositivo, com o overlay de estatísticas aberto
+    pub device_times: Option<DeviceTimes>,
+    // Quais bytes da ROM já foram executados, lidos ou escritos; None = desligado
+    pub coverage: Option<Coverage>,
+    // Overclock: a CPU roda `overclock` vezes mais rápido que o resto (1 = normal)
+    pub overclock: u8,
+    // Ciclos da CPU que ainda não somaram um ciclo dos dispositivos com overclock
+    overclock_cycles: u64,
+}
+
+impl MemoryBus {
+    pub fn new(cartridge: Cartridge) -> Self {
+        Self {
+            cartridge,
+            timer: Timer::new(),
+            ppu: Ppu::new(),
+            apu: Apu::new(),
+            joypad: Joypad::new(),
+            serial: SerialPort::new(),
+            dma: OamDma::new(),
+            cheats: Cheats::new(),
+            watch: Watchpoints::new(),
+            wram: [0; 0x2000],
+            hram: [0; 0x7F],
+            io: [0; 0x80],
+            if_reg: 0x00,
+            ie_reg: 0x00,
+            model: Model::Dmg,
+            infrared: Box::new(NoIr),
+            ir_light: None,
+            sgb: None,
+            device_times: None,
+            coverage: None,
+            overclock: 1,
+            overclock_cycles: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.if_reg = 0xE1;
+        self.ie_reg = 0x00;
+        self.timer.reset();
+        self.ppu.reset();
+        // O boot ROM do CGB põe jogos sem o bit 7 do 0x143 em modo compatível, com a
+        // prioridade de objetos pelo x como no DMG
+        let cgb_flag = self.cartridge.cgb_flag;
+        self.io[(KEY0 - 0xFF00) as usize] = match self.model {
+            Model::Cgb if cgb_flag & 0x80 != 0 => cgb_flag,
+            Model::Cgb => KEY0_DMG_COMPAT,
+            _ => 0,
+        };
+        self.ppu.set_opri(!self.cgb_mode() as u8);
+        self.apu.reset(self.model == Model::Cgb);
+        self.io[(KEY1 - 0xFF00) as usize] = 0;
+    }
+
+    // Desligar e ligar: memória interna zerada e mapper de volta ao estado inicial (a RAM
+    // do cartucho tem bateria ou some junto, como no hardware)
+    pub fn power_cycle(&mut self) {
+        self.wram = [0; 0x2000];
+        self.ppu = Ppu::new();
+        self.hram = [0; 0x7F];
+        self.io = [0; 0x80];
+        self.apu.power_cycle();
+        self.serial.power_cycle();
+        self.dma = OamDma::new();
+        self.infrared.set_led(false);
+        self.cartridge.reset();
+    }
+
+    // No hardware WRAM e HRAM ligam com lixo; o boot ROM não limpa
+    pub fn randomize_ram(&mut self, rng: &mut Rng) {
+        rng.fill(&mut self.wram);
+        rng.fill(&mut self.hram);
+    }
+
+    pub fn ir_led(&self) -> bool {
+        self.io[(RP - 0xFF00) as usize] & RP_LED != 0
+    }
+
+    // Bit 1 = 0 quando está chegando luz, mas só com a leitura habilitada (bits 6-7);
+    // bits 2-5 sempre 1
+    fn read_rp(&self) -> u8 {
+        let rp = self.io[(RP - 0xFF00) as usize];
+        let receiving = rp & RP_READ_ENABLE == RP_READ_ENABLE
+            && self.ir_light.unwrap_or_else(|| self.infrared.light());
+        rp | 0x3C | if receiving { 0x00 } else { 0x02 }
+    }
+
+    // Chamado pela CPU quando um valor de 16 bits passa pelo barramento de endereço
+    pub fn oam_bug(&mut self, addr: u16, access: OamBugAccess) {
+        if self.model == Model::Cgb || !(0xFE00..=0xFEFF).contains(&addr) {
+            return;
+        }
+
+        if let Some(row) = self.ppu.oam_scan_row() {
+            oam_bug::corrupt(self.ppu.oam_mut(), row as usize, access);
+        }
+    }
+
+    // Avança os dispositivos pelos ciclos que a CPU gastou e retorna quanto andou o clock
+    // normal (metade em double speed). Em STOP só o que tem cristal próprio continua
+    pub fn tick(&mut self, cpu_cycles: u64, stopped: bool) -> u64 {
+        // Com overclock até o timer fica no ritmo normal: só a CPU ganha ciclos
+        let cpu_cycles = if self.overclock > 1 {
+            let factor = self.overclock as u64;
+            self.overclock_cycles += cpu_cycles;
+            let cycles = self.overclock_cycles / factor;
+            self.overclock_cycles %= factor;
+            cycles
+        } else {
+            cpu_cycles
+        };
+
+        let system = if self.double_speed() {
+            cpu_cycles / 2
+        } else {
+            cpu_cycles
+        };
+
+        let devices: [&mut dyn Clocked; 6] = [
+            &mut self.timer,
+            &mut self.serial,
+            &mut self.dma,
+            &mut self.ppu,
+            &mut self.apu,
+            &mut self.cartridge,
+        ];
+        let mut interrupts = InterruptFlags::empty();
+        for (index, device) in devices.into_iter().enumerate() {
+            let cycles = match device.domain() {
+                ClockDomain::Rtc => system,
+                _ if stopped => continue,
+                ClockDomain::Cpu => cpu_cycles,
+                ClockDomain::System => system,
+            };
+            match &mut self.device_times {
+                Some(times) => {
+                    let start = times.now();
+                    interrupts |= device.tick(cycles);
+                    times.add(index, times.now() - start);
+                }
+                None => interrupts |= device.tick(cycles),
+            }
+        }
+        self.request_interrupt(interrupts);
+        system
+    }
+
+    // A cópia sai inteira na escrita do FF46; fontes de 0xE0 pra cima caem na WRAM
+    // (0xFE e 0xFF viram 0xDE e 0xDF)
+    fn start_dma(&mut self, source: u8) {
+        let base = match source {
+            0xE0..=0xFF => ((source - 0x20) as u16) << 8,
+            _ => (source as u16) << 8,
+        };
+        self.copy_block(base, OAM_BASE, 0xA0);
+        self.dma.start(source);
+        self.ppu.log_dma(source);
+    }
+
+    // Little-endian, com o endereço dando a volta em 0xFFFF
+    pub fn read_u16(&mut self, addr: u16) -> u16 {
+        let low = self.read(addr) as u16;
+        let high = self.read(addr.wrapping_add(1)) as u16;
+        (high << 8) | low
+    }
+
+    pub fn write_u16(&mut self, addr: u16, value: u16) {
+        self.write(addr, value as u8);
+        self.write(addr.wrapping_add(1), (value >> 8) as u8);
+    }
+
+    // Copia `len` bytes pelo mapa de memória, então espelhos e registros se comportam como
+    // em acessos avulsos. Da WRAM (ou do eco) pra VRAM/OAM, sem atravessar nenhuma borda,
+    // vira uma cópia direta entre os slices
+    pub fn copy_block(&mut self, src: u16, dst: u16, len: u16) {
+        let size = len as usize;
+        // (início na WRAM, fim da região): o eco acaba em 0xFDFF
+        let source = match src {
+            0xC000..=0xDFFF => Some(((src - 0xC000) as usize, 0x2000)),
+            0xE000..=0xFDFF => Some(((src - 0xE000) as usize, 0x1E00)),
+            _ => None,
+        }
+        .filter(|(start, end)| start + size <= *end)
+        .map(|(start, _)| start);
+        let target = match dst {
+            VRAM_BASE..=VRAM_END => Some((self.ppu.vram_mut(), (dst - VRAM_BASE) as usize)),
+            OAM_BASE..=OAM_END => Some((self.ppu.oam_mut(), (dst - OAM_BASE) as usize)),
+            _ => None,
+        };
+
+        if let (Some(start), Some((target, offset))) = (source, target)
+            && offset + size <= target.len()
+        {
+            target[offset..offset + size].copy_from_slice(&self.wram[start..start + size]);
+            return;
+        }
+
+        for i in 0..len {
+            let value = self.read(src.wrapping_add(i));
+            self.write(dst.wrapping_add(i), value);
+        }
+    }
+
+    // CGB rodando jogo de CGB. No modo compatível os registros exclusivos do CGB (KEY1, RP,
+    // OPRI, clock rápido do serial) ficam travados como num DMG
+    pub fn cgb_mode(&self) -> bool {
+        self.model == Model::Cgb && !self.dmg_compat()
+    }
+
+    pub fn dmg_compat(&self) -> bool {
+        self.model == Model::Cgb && self.io[(KEY0 - 0xFF00) as usize] & KEY0_DMG_COMPAT != 0
+    }
+
+    // Executado pela instrução STOP; retorna true se a CPU para de fato. No CGB com a troca
+    // armada no KEY1 a velocidade muda e a execução segue. O DIV zera nos dois casos
+    pub fn stop(&mut self) -> bool {
+        self.timer.write(0xFF04, 0);
+        let key1 = self.io[(KEY1 - 0xFF00) as usize];
+        if self.cgb_mode() && key1 & KEY1_ARMED != 0 {
+            self.io[(KEY1 - 0xFF00) as usize] = (key1 ^ KEY1_DOUBLE) & !KEY1_ARMED;
+            return false;
+        }
+        true
+    }
+
+    // Sai do STOP quando alguma linha selecionada do P1 vai pra 0
+    pub fn stop_wake(&self) -> bool {
+        self.joypad.any_low()
+    }
+
+    pub fn double_speed(&self) -> bool {
+        self.io[(KEY1 - 0xFF00) as usize] & KEY1_DOUBLE != 0
+    }
+
+    pub fn set_input(&mut self, input: JoypadInput) {
+        if self.joypad.set_input(input) {
+            self.request_interrupt(InterruptFlags::JOYPAD);
+        }
+    }
+
+    // WRAM seguida da SRAM (todos os bancos), na ordem usada pela busca de cheats
+    pub fn search_ram(&self) -> Vec<u8> {
+        let mut ram = self.wram.to_vec();
+        ram.extend_from_slice(self.cartridge.ram());
+        ram
+    }
+
+    // Chamado uma vez por VBlank; SRAM só é alterada se o jogo deixou a RAM habilitada
+    pub fn apply_ram_cheats(&mut self) {
+        let bank = self.cartridge.ram_bank();
+        let writes: Vec<(u16, u8)> = self.cheats.ram_writes(bank).collect();
+        for (addr, value) in writes {
+            self.write(addr, value);
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, data: u8) {
+        // Endereço congelado: a escrita acontece, mas com o valor preso
+        let data = self
+            .cheats
+            .frozen(addr, self.cartridge.ram_bank())
+            .unwrap_or(data);
+        if addr < 0x8000 {
+            self.cover(addr, CoverageFlags::WRITTEN);
+        }
+        match addr {
+            0x0000..=0x7FFF => {
+                // println!("Write Cartridge addr: 0x{:04X}", addr);
+                self.cartridge.write(addr, data);
+            }
+
+            VRAM_BASE..=VRAM_END => {
+                // println!("Write VRAM addr: 0x{:04X}", addr);
+                self.ppu.write(addr, data);
+            }
+
+            0xA000..=0xBFFF => {
+                // println!("Write ERAM addr: 0x{:04X}", addr);
+                self.cartridge.write(addr, data);
+            }
+
+            0xC000..=0xDFFF => {
+                // println!("Write WRAM addr: 0x{:04X}", addr);
+                self.wram[(addr - 0xC000) as usize] = data;
+            }
+
+            0xE000..=0xFDFF => {
+                // println!("Write ECHO RAM addr: 0x{:04X}", addr);
+                let echo = addr - 0xE000;
+                self.wram[echo as usize] = data;
+            }
+
+            OAM_BASE..=OAM_END => {
+                // println!("Write OAM addr: 0x{:04X}", addr);
+                if !self.dma.active() {
+                    self.ppu.write(addr, data);
+                }
+            }
+
+            0xFEA0..=0xFEFF => {}
+
+            0xFF00..=0xFF7F => {
+                // println!("Write I/O addr: 0x{:04X}", addr);
+                if addr == 0xFF0F {
+                    self.if_reg = data & 0x1F;
+                } else if addr == P1 {
+                    if let Some(sgb) = &mut self.sgb {
+                        let lcdc = self.ppu.read(LCDC);
+                        sgb.write_joypad(data, || screen_transfer(self.ppu.vram(), lcdc));
+                    }
+                    if self.joypad.write(data) {
+                        self.request_interrupt(InterruptFlags::JOYPAD);
+                    }
+                } else if (0xFF04..=0xFF07).contains(&addr) {
+                    self.timer.write(addr, data);
+                } else if (NR10..=APU_END).contains(&addr) {
+                    self.apu.write(addr, data, self.model == Model::Cgb);
+                } else if addr == KEY0 {
+                    // Só o boot ROM escreve
+                } else if addr == KEY1 && self.cgb_mode() {
+                    let key1 = &mut self.io[(addr - 0xFF00) as usize];
+                    *key1 = (*key1 & KEY1_DOUBLE) | (data & KEY1_ARMED);
+                } else if addr == RP && self.cgb_mode() {
+                    self.io[(addr - 0xFF00) as usize] = data & (RP_LED | RP_READ_ENABLE);
+                    self.infrared.set_led(data & RP_LED != 0);
+                } else if addr == SB || addr == SC {
+                    let cgb_mode = self.cgb_mode();
+                    self.serial.write(addr, data, cgb_mode);
+                } else if addr == DMA {
+                    self.start_dma(data);
+                } else if (LCDC..=LCD_END).contains(&addr) {
+                    self.ppu.write(addr, data);
+                } else if addr == OPRI {
+                    if self.cgb_mode() {
+                        self.ppu.set_opri(data);
+                    }
+                } else if addr == KEY1 || addr == RP {
+                    // Travados fora do modo CGB
+                } else {
+                    self.io[(addr - 0xFF00) as usize] = data;
+                }
+            }
+
+            0xFF80..=0xFFFE => {
+                // println!("Write HRAM addr: 0x{:04X}", addr);
+                self.hram[(addr - 0xFF80) as usize] = data;
+            }
+
+            0xFFFF => {
+                // println!("Write IE addr: 0x{:04X}", addr);
+                self.ie_reg = data;
+            }
+        }
+    }
+
+    pub fn request_interrupt(&mut self, flag: InterruptFlags) {
+        self.if_reg |= flag.bits() & 0x1F;
+        // println!(
+            // " request if |= 0x{:02X} -> if=0x{:02X}",
+            // flag_bit & 0x1F,
+            // self.if_reg
+        // );
+    }
+
+    // Leitura da CPU: além do mapa de memória, vê os conflitos de barramento (OAM
+    // inacessível durante o DMA)
+    pub fn read(&mut self, addr: u16) -> u8 {
+        if (OAM_BASE..=OAM_END).contains(&addr) && self.dma.active() {
+            return 0xFF;
+        }
+        if addr < 0x8000 {
+            self.cover(addr, CoverageFlags::READ);
+        }
+        self.peek(addr)
+    }
+
+    // Marca os bytes da instrução em `pc` como executados, antes da CPU rodar ela
+    pub fn cover_instruction(&mut self, pc: u16) {
+        if self.coverage.is_none() {
+            return;
+        }
+        for index in 0..instruction_length(self.peek(pc)) {
+            let addr = pc.wrapping_add(index);
+            if addr < 0x8000 {
+                self.cover(addr, CoverageFlags::EXECUTED);
+            }
+        }
+    }
+
+    fn cover(&mut self, addr: u16, flags: CoverageFlags) {
+        if let Some(coverage) = &mut self.coverage {
+            coverage.mark(self.cartridge.rom_offset(addr), flags);
+        }
+    }
+
+    // Valor no endereço sem efeito nenhum no estado, pro debugger, scripts e ferramentas
+    pub fn peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x7FFF => {
+                // println!("Read Cartridge addr: 0x{:04X}", addr);
+                let value = self.cartridge.read(addr);
+                self.cheats.patch_rom(addr, value)
+            }
+
+            VRAM_BASE..=VRAM_END => {
+                // println!("Read VRAM addr: 0x{:04X}", addr);
+                self.ppu.read(addr)
+            }
+
+            0xA000..=0xBFFF => {
+                // println!("Read ERAM addr: 0x{:04X}", addr);
+                self.cartridge.read(addr)
+            }
+
+            0xC000..=0xDFFF => {
+                // println!("Read WRAM addr: 0x{:04X}", addr);
+                self.wram[(addr - 0xC000) as usize]
+            }
+
+            0xE000..=0xFDFF => {
+                // println!("Read ECHO RAM addr: 0x{:04X}", addr);
+                self.wram[(addr - 0xE000) as usize]
+            }
+
+            OAM_BASE..=OAM_END => {
+                // println!("Read OAM addr: 0x{:04X}", addr);
+                self.ppu.read(addr)
+            }
+
+            0xFEA0..=0xFEFF => {
+                // println!("Read not usable addr: 0x{:04X}", addr);
+                0xFF
+            }
+
+            0xFF00..=0xFF7F => {
+                // println!("Read I/O registers addr: 0x{:04X}", addr);
+                if addr == 0xFF0F {
+                    self.if_reg
+                } else if addr == P1 {
+                    let value = self.joypad.read();
+                    match &self.sgb {
+                        Some(sgb) => sgb.read_joypad(value),
+                        None => value,
+                    }
+                } else if (0xFF04..=0xFF07).contains(&addr) {
+                    self.timer.read(addr)
+                } else if (NR10..=APU_END).contains(&addr) {
+                    self.apu.read(addr)
+                } else if (addr == PCM12 || addr == PCM34) && self.model == Model::Cgb {
+                    self.apu.read_pcm(addr)
+                } else if addr == SB || addr == SC {
+                    self.serial.read(addr)
+                } else if addr == DMA {
+                    self.dma.source()
+                } else if (LCDC..=LCD_END).contains(&addr) {
+                    self.ppu.read(addr)
+                } else if addr == KEY0 {
+                    match self.model {
+                        Model::Cgb => self.io[(addr - 0xFF00) as usize],
+                        _ => 0xFF,
+                    }
+                } else if addr == KEY1 {
+                    if self.cgb_mode() {
+                        self.io[(addr - 0xFF00) as usize] | 0x7E
+                    } else {
+                        0xFF
+                    }
+                } else if addr == RP {
+                    if self.cgb_mode() {
+                        self.read_rp()
+                    } else {
+                        0xFF
+                    }
+                } else if addr == OPRI {
+                    match self.model {
+                        Model::Cgb => self.ppu.opri() | 0xFE,
+                        _ => 0xFF,
+                    }
+                } else {
+                    self.io[(addr - 0xFF00) as usize]
+                }
+            }
+
+            0xFF80..=0xFFFE => {
+                // println!("Read HRAM addr: 0x{:04X}", addr);
+                self.hram[(addr - 0xFF80) as usize]
+            }
+
+            0xFFFF => {
+                // println!("Read IE addr: 0x{:04X}", addr);
+                self.ie_reg
+            }
+        }
+    }
+
+    // `len` bytes a partir de `addr`, dando a volta em 0xFFFF
+    pub fn peek_range(&self, addr: u16, len: usize) -> Vec<u8> {
+        (0..len)
+            .map(|offset| self.peek(addr.wrapping_add(offset as u16)))
+            .collect()
+    }
+}
+
+impl CpuBus for MemoryBus {
+    fn read(&mut self, addr: u16) -> u8 {
+        MemoryBus::read(self, addr)
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        MemoryBus::write(self, addr, data);
+    }
+
+    fn read_u16(&mut self, addr: u16) -> u16 {
+        MemoryBus::read_u16(self, addr)
+    }
+
+    fn write_u16(&mut self, addr: u16, value: u16) {
+        MemoryBus::write_u16(self, addr, value);
+    }
+
+    fn record_access(&mut self, kind: AccessKind, addr: u16, value: u8) {
+        self.watch.record(kind, addr, value);
+    }
+
+    fn oam_bug(&mut self, addr: u16, access: OamBugAccess) {
+        MemoryBus::oam_bug(self, addr, access);
+    }
+
+    fn stop(&mut self) -> bool {
+        MemoryBus::stop(self)
+    }
+
+    fn stop_wake(&self) -> bool {
+        MemoryBus::stop_wake(self)
+    }
+}
+
+impl Savestate for MemoryBus {
+    fn save_state(&self, w: &mut StateWriter) {
+        self.cartridge.save_state(w);
+        self.timer.save_state(w);
+        self.apu.save_state(w);
+        self.joypad.save_state(w);
+        self.serial.save_state(w);
+        self.dma.save_state(w);
+        self.ppu.save_state(w);
+        self.save_memory(w);
+        if let Some(sgb) = &self.sgb {
+            sgb.save_state(w);
+        }
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.cartridge.load_state(r)?;
+        self.timer.load_state(r)?;
+        self.apu.load_state(r)?;
+        self.joypad.load_state(r)?;
+        self.serial.load_state(r)?;
+        self.dma.load_state(r)?;
+        self.ppu.load_state(r)?;
+        self.load_memory(r)?;
+        if let Some(sgb) = &mut self.sgb {
+            sgb.load_state(r)?;
+        }
+        Ok(())
+    }
+}
+
+impl MemoryBus {
+    // Um bloco por componente, pra um state sem algum deles (gravado antes de ele existir)
+    // ainda carregar
+    pub fn save_blocks(&self, w: &mut StateWriter) {
+        w.write_block(CART_BLOCK, |w| self.cartridge.save_state(w));
+        w.write_block(TIMER_BLOCK, |w| self.timer.save_state(w));
+        w.write_block(APU_BLOCK, |w| self.apu.save_state(w));
+        w.write_block(JOYPAD_BLOCK, |w| self.joypad.save_state(w));
+        w.write_block(SERIAL_BLOCK, |w| self.serial.save_state(w));
+        w.write_block(DMA_BLOCK, |w| self.dma.save_state(w));
+        w.write_block(PPU_BLOCK, |w| self.ppu.save_state(w));
+        w.write_block(MEMORY_BLOCK, |w| self.save_memory(w));
+        if let Some(sgb) = &self.sgb {
+            w.write_block(SGB_BLOCK, |w| sgb.save_state(w));
+        }
+    }
+
+    // Som, serial, DMA e SGB podem faltar: voltam pro estado do power-on
+    pub fn load_blocks(&mut self, blocks: &mut StateBlocks) -> Result<(), String> {
+        blocks.require(CART_BLOCK, |r| self.cartridge.load_state(r))?;
+        blocks.require(TIMER_BLOCK, |r| self.timer.load_state(r))?;
+        if !blocks.optional(APU_BLOCK, |r| self.apu.load_state(r))? {
+            self.apu.reset(self.model == Model::Cgb);
+        }
+        blocks.require(JOYPAD_BLOCK, |r| self.joypad.load_state(r))?;
+        if !blocks.optional(SERIAL_BLOCK, |r| self.serial.load_state(r))? {
+            self.serial.power_cycle();
+        }
+        if !blocks.optional(DMA_BLOCK, |r| self.dma.load_state(r))? {
+            self.dma = OamDma::new();
+        }
+        blocks.require(PPU_BLOCK, |r| self.ppu.load_state(r))?;
+        blocks.require(MEMORY_BLOCK, |r| self.load_memory(r))?;
+        if let Some(sgb) = &mut self.sgb
+            && !blocks.optional(SGB_BLOCK, |r| sgb.load_state(r))?
+        {
+            *sgb = Sgb::new();
+        }
+        Ok(())
+    }
+
+    fn save_memory(&self, w: &mut StateWriter) {
+        w.write_bytes(&self.wram);
+        w.write_bytes(&self.hram);
+        w.write_bytes(&self.io);
+        w.write_u8(self.if_reg);
+        w.write_u8(self.ie_reg);
+    }
+
+    fn load_memory(&mut self, r: &mut StateReader) -> Result<(), String> {
+        r.read_bytes(&mut self.wram)?;
+        r.read_bytes(&mut self.hram)?;
+        r.read_bytes(&mut self.io)?;
+        self.if_reg = r.read_u8()?;
+        self.ie_reg = r.read_u8()?;
+        self.infrared.set_led(self.ir_led());
+        Ok(())
+    }
+}
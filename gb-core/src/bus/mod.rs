@@ -0,0 +1,35 @@
+pub mod clocked;
+pub mod coverage;
+pub mod cpu_bus;
+pub mod dma;
+pub mod memory_bus;
+pub mod oam_bug;
+#[cfg(feature = "std")]
+pub mod printer;
+pub mod serial;
+pub mod serial_device;
+#[cfg(feature = "std")]
+pub mod tcp_link;
+#[cfg(test)]
+pub mod test_bus;
+#[cfg(test)]
+mod tests;
+pub mod watch;
+
+pub use clocked::*;
+pub use coverage::*;
+pub use cpu_bus::CpuBus;
+pub use dma::OamDma;
+pub use memory_bus::{MemoryBus, InterruptFlags};
+pub use oam_bug::OamBugAccess;
+#[cfg(feature = "std")]
+pub use printer::GbPrinter;
+#[cfg(feature = "std")]
+pub use serial::LinkPort;
+pub use serial::SerialPort;
+pub use serial_device::*;
+#[cfg(feature = "std")]
+pub use tcp_link::TcpLink;
+#[cfg(test)]
+pub use test_bus::TestBus;
+pub use watch::*;
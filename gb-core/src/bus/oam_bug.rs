@@ -0,0 +1,68 @@
+// Bug de corrupção da OAM do DMG: com a PPU no modo 2, colocar um endereço FE00-FEFF
+// no barramento de 16 bits (INC/DEC rr, LD [HL+/-], PUSH/POP) estraga a linha que a
+// PPU está lendo. A OAM é vista como 20 linhas de 8 bytes (4 words).
+
+const ROW_BYTES: usize = 8;
+const ROWS: usize = 20;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum OamBugAccess {
+    Write,
+    // Leitura junto com incremento/decremento do mesmo registro (LD A,[HL+], POP)
+    ReadIncrement,
+}
+
+fn word(oam: &[u8], row: usize, index: usize) -> u16 {
+    let offset = row * ROW_BYTES + index * 2;
+    u16::from_le_bytes([oam[offset], oam[offset + 1]])
+}
+
+fn set_word(oam: &mut [u8], row: usize, index: usize, value: u16) {
+    let offset = row * ROW_BYTES + index * 2;
+    oam[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+// Copia as 3 últimas words da linha anterior pra linha atual
+fn copy_tail_from_previous(oam: &mut [u8], row: usize) {
+    let start = row * ROW_BYTES;
+    oam.copy_within(start - ROW_BYTES + 2..start, start + 2);
+}
+
+pub fn corrupt(oam: &mut [u8], row: usize, access: OamBugAccess) {
+    // A linha 0 nunca é afetada
+    if row == 0 || row >= ROWS {
+        return;
+    }
+
+    match access {
+        OamBugAccess::Write => {
+            let a = word(oam, row, 0);
+            let b = word(oam, row - 1, 0);
+            let c = word(oam, row - 1, 2);
+            set_word(oam, row, 0, ((a ^ c) & (b ^ c)) ^ c);
+            copy_tail_from_previous(oam, row);
+        }
+        OamBugAccess::ReadIncrement => {
+            // Só acontece fora das 4 primeiras linhas e da última
+            if (4..ROWS - 1).contains(&row) {
+                let a = word(oam, row - 2, 0);
+                let b = word(oam, row - 1, 0);
+                let c = word(oam, row, 0);
+                let d = word(oam, row - 1, 2);
+                set_word(oam, row - 1, 0, (b & (a | c | d)) | (a & c & d));
+
+                // A linha anterior (já corrompida) é copiada pra atual e pra duas antes
+                let previous = (row - 1) * ROW_BYTES;
+                oam.copy_within(previous..previous + ROW_BYTES, row * ROW_BYTES);
+                oam.copy_within(previous..previous + ROW_BYTES, (row - 2) * ROW_BYTES);
+            }
+
+            // Em seguida vale a corrupção normal de leitura
+            let a = word(oam, row, 0);
+            let b = word(oam, row - 1, 0);
+            let c = word(oam, row - 1, 2);
+            set_word(oam, row, 0, b | (a & c));
+            copy_tail_from_previous(oam, row);
+        }
+    }
+}
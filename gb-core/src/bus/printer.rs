@@ -0,0 +1,253 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use crate::bus::SerialDevice;
+use crate::png;
+
+const MAGIC: [u8; 2] = [0x88, 0x33];
+// Resposta do penúltimo byte de cada pacote: "tem uma impressora aqui"
+const ALIVE: u8 = 0x81;
+
+const CMD_INIT: u8 = 0x01;
+const CMD_PRINT: u8 = 0x02;
+const CMD_DATA: u8 = 0x04;
+
+const STATUS_CHECKSUM: u8 = 0x01;
+const STATUS_BUSY: u8 = 0x02;
+const STATUS_FULL: u8 = 0x04;
+const STATUS_UNPROCESSED: u8 = 0x08;
+
+// 160 pixels de largura: 20 tiles de 16 bytes por linha de tiles
+const WIDTH: usize = 160;
+const TILE_ROW_BYTES: usize = 20 * 16;
+// Memória da impressora (9 faixas de 2 linhas de tiles); o resto é ignorado
+const BUFFER_SIZE: usize = 0x2000;
+// Pacotes respondidos como ocupada depois de um print, pro jogo ver a impressão andando
+const BUSY_PACKETS: u8 = 4;
+const SHADES: [u8; 4] = [0xFF, 0xAA, 0x55, 0x00];
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Stage {
+    Magic(usize),
+    Command,
+    Compression,
+    Length(usize),
+    Data,
+    Checksum(usize),
+    Alive,
+    Status,
+}
+
+// Game Boy Printer: recebe os pacotes (88 33, comando, compressão, tamanho, dados, checksum)
+// que o jogo manda com clock interno e grava cada impressão num PNG em `dir`
+pub struct GbPrinter {
+    dir: PathBuf,
+    stage: Stage,
+    command: u8,
+    compressed: bool,
+    length: usize,
+    packet: Vec<u8>,
+    sum: u16,
+    checksum: u16,
+    // Dados de imagem (tiles) desde o último init/print
+    buffer: Vec<u8>,
+    status: u8,
+    busy: u8,
+    printed: usize,
+    messages: VecDeque<String>,
+}
+
+impl GbPrinter {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            stage: Stage::Magic(0),
+            command: 0,
+            compressed: false,
+            length: 0,
+            packet: Vec::new(),
+            sum: 0,
+            checksum: 0,
+            buffer: Vec::new(),
+            status: 0,
+            busy: 0,
+            printed: 0,
+            messages: VecDeque::new(),
+        }
+    }
+
+    // Pacote completo, antes de responder o status
+    fn process(&mut self) {
+        if self.sum != self.checksum {
+            self.status |= STATUS_CHECKSUM;
+            return;
+        }
+        self.status &= !STATUS_CHECKSUM;
+
+        let data = if self.compressed {
+            decompress(&self.packet)
+        } else {
+            core::mem::take(&mut self.packet)
+        };
+        match self.command {
+            CMD_INIT => {
+                self.buffer.clear();
+                self.status = 0;
+                self.busy = 0;
+            }
+            CMD_DATA => {
+                let room = BUFFER_SIZE - self.buffer.len();
+                self.buffer.extend_from_slice(&data[..data.len().min(room)]);
+                if !self.buffer.is_empty() {
+                    self.status |= STATUS_UNPROCESSED;
+                }
+            }
+            // Folhas, margens, paleta e exposição; só a paleta muda a imagem
+            CMD_PRINT if data.len() >= 3 => {
+                self.print(data[2]);
+                self.status = STATUS_BUSY | STATUS_FULL;
+                self.busy = BUSY_PACKETS;
+            }
+            _ => {}
+        }
+    }
+
+    fn print(&mut self, palette: u8) {
+        let buffer = core::mem::take(&mut self.buffer);
+        let height = buffer.len() / TILE_ROW_BYTES * 8;
+        if height == 0 {
+            return;
+        }
+
+        let mut rgba = vec![0xFF; WIDTH * height * 4];
+        for (tile, bytes) in buffer.chunks_exact(16).enumerate() {
+            let (tile_x, tile_y) = (tile % 20 * 8, tile / 20 * 8);
+            for row in 0..8 {
+                let (low, high) = (bytes[row * 2], bytes[row * 2 + 1]);
+                for col in 0..8 {
+                    let bit = 7 - col;
+                    let color = ((high >> bit) & 1) << 1 | ((low >> bit) & 1);
+                    let shade = SHADES[(palette >> (color * 2)) as usize & 3];
+                    let pixel = ((tile_y + row) * WIDTH + tile_x + col) * 4;
+                    rgba[pixel..pixel + 3].fill(shade);
+                }
+            }
+        }
+
+        // Não sobrescreve impressões de outras sessões
+        let path = loop {
+            self.printed += 1;
+            let path = self.dir.join(format!("print-{:03}.png", self.printed));
+            if !path.exists() {
+                break path;
+            }
+        };
+        let message = match png::write_png(&path, WIDTH, height, &rgba) {
+            Ok(()) => format!("impressora: {}", path.display()),
+            Err(erro) => format!("impressora: erro ao gravar '{}': {}", path.display(), erro),
+        };
+        self.messages.push_back(message);
+    }
+
+    // Status do fim do pacote; a impressão "termina" depois de alguns pacotes
+    fn reply_status(&mut self) -> u8 {
+        let status = self.status;
+        if self.busy > 0 {
+            self.busy -= 1;
+            if self.busy == 0 {
+                self.status &= !STATUS_BUSY;
+            }
+        } else {
+            self.status &= !STATUS_FULL;
+        }
+        status
+    }
+}
+
+impl SerialDevice for GbPrinter {
+    fn transfer(&mut self, sent: u8) -> u8 {
+        let mut reply = 0x00;
+        self.stage = match self.stage {
+            Stage::Magic(index) if sent == MAGIC[index] => {
+                if index == 0 {
+                    Stage::Magic(1)
+                } else {
+                    Stage::Command
+                }
+            }
+            Stage::Magic(_) => Stage::Magic(0),
+            Stage::Command => {
+                self.command = sent;
+                self.sum = sent as u16;
+                Stage::Compression
+            }
+            Stage::Compression => {
+                self.compressed = sent & 1 != 0;
+                self.sum = self.sum.wrapping_add(sent as u16);
+                Stage::Length(0)
+            }
+            Stage::Length(0) => {
+                self.length = sent as usize;
+                self.sum = self.sum.wrapping_add(sent as u16);
+                Stage::Length(1)
+            }
+            Stage::Length(_) => {
+                self.length |= (sent as usize) << 8;
+                self.sum = self.sum.wrapping_add(sent as u16);
+                self.packet.clear();
+                if self.length == 0 {
+                    Stage::Checksum(0)
+                } else {
+                    Stage::Data
+                }
+            }
+            Stage::Data => {
+                self.packet.push(sent);
+                self.sum = self.sum.wrapping_add(sent as u16);
+                if self.packet.len() == self.length {
+                    Stage::Checksum(0)
+                } else {
+                    Stage::Data
+                }
+            }
+            Stage::Checksum(0) => {
+                self.checksum = sent as u16;
+                Stage::Checksum(1)
+            }
+            Stage::Checksum(_) => {
+                self.checksum |= (sent as u16) << 8;
+                Stage::Alive
+            }
+            Stage::Alive => {
+                reply = ALIVE;
+                self.process();
+                Stage::Status
+            }
+            Stage::Status => {
+                reply = self.reply_status();
+                Stage::Magic(0)
+            }
+        };
+        reply
+    }
+
+    fn take_message(&mut self) -> Option<String> {
+        self.messages.pop_front()
+    }
+}
+
+// RLE do pacote de dados: com o bit 7, o próximo byte repetido (n & 0x7F) + 2 vezes; sem
+// ele, os n + 1 bytes seguintes como estão
+fn decompress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut bytes = data.iter().copied();
+    while let Some(control) = bytes.next() {
+        if control & 0x80 != 0 {
+            let Some(value) = bytes.next() else { break };
+            out.extend(core::iter::repeat_n(value, (control & 0x7F) as usize + 2));
+        } else {
+            out.extend(bytes.by_ref().take(control as usize + 1));
+        }
+    }
+    out
+}
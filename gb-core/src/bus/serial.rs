@@ -0,0 +1,266 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex};
+
+use crate::bus::{ClockDomain, Clocked, InterruptFlags, SerialDevice, SerialRegistry};
+use crate::state::{Savestate, StateReader, StateWriter};
+
+pub const SB: u16 = 0xFF01;
+pub const SC: u16 = 0xFF02;
+const SC_START: u8 = 0x80;
+const SC_FAST: u8 = 0x02;
+const SC_INTERNAL_CLOCK: u8 = 0x01;
+
+// Ciclos de uma transferência de 8 bits com clock interno: 8192 Hz, ou 262144 Hz no modo
+// rápido do CGB (bit 1 do SC)
+pub const TRANSFER_CYCLES: u64 = 4096;
+pub const FAST_TRANSFER_CYCLES: u64 = 128;
+
+// Os dois lados do cabo: o SB de cada um e o byte que chegou e ainda não foi lido
+#[cfg(feature = "std")]
+struct Cable {
+    sb: [u8; 2],
+    incoming: [Option<u8>; 2],
+}
+
+// Uma ponta do cabo de link entre duas instâncias no mesmo processo
+#[cfg(feature = "std")]
+pub struct LinkPort {
+    cable: Arc<Mutex<Cable>>,
+    side: usize,
+}
+
+#[cfg(feature = "std")]
+impl LinkPort {
+    pub fn pair() -> (LinkPort, LinkPort) {
+        let cable = Arc::new(Mutex::new(Cable {
+            sb: [0xFF; 2],
+            incoming: [None; 2],
+        }));
+        (
+            LinkPort {
+                cable: cable.clone(),
+                side: 0,
+            },
+            LinkPort { cable, side: 1 },
+        )
+    }
+
+    pub fn set_sb(&self, value: u8) {
+        self.cable.lock().unwrap().sb[self.side] = value;
+    }
+
+    // Lado com clock interno terminou os 8 bits: os dois SBs trocam de lugar. O outro lado
+    // recebe mesmo sem ter armado a transferência, como no hardware
+    pub fn exchange(&self, value: u8) -> u8 {
+        let mut cable = self.cable.lock().unwrap();
+        let peer = 1 - self.side;
+        let received = cable.sb[peer];
+        cable.sb[peer] = value;
+        cable.sb[self.side] = received;
+        cable.incoming[peer] = Some(value);
+        received
+    }
+
+    pub fn take_incoming(&self) -> Option<u8> {
+        self.cable.lock().unwrap().incoming[self.side].take()
+    }
+}
+
+// A ponta que dá o clock troca os SBs no fim da transferência; a outra recebe pelo poll
+#[cfg(feature = "std")]
+impl SerialDevice for LinkPort {
+    fn transfer(&mut self, sent: u8) -> u8 {
+        self.exchange(sent)
+    }
+
+    fn poll(&mut self) -> Option<u8> {
+        self.take_incoming()
+    }
+
+    fn set_sb(&mut self, value: u8) {
+        LinkPort::set_sb(self, value);
+    }
+
+    fn drives_clock(&self) -> bool {
+        true
+    }
+}
+
+// SB e SC. O que está do outro lado do cabo é um SerialDevice; sem nada ligado o SB vai
+// pro stdout (saída das ROMs de teste) e a transferência termina com 0xFF, a linha de
+// entrada solta
+pub struct SerialPort {
+    sb: u8,
+    sc: u8,
+    device: Box<dyn SerialDevice>,
+    // Dispositivos que dá pra ligar pelo nome (attach_named)
+    pub devices: SerialRegistry,
+    // Clock externo com um dispositivo que não dá clock: no hardware a transferência
+    // espera pra sempre (None). Com um valor, termina com 0xFF depois desses ciclos, pra
+    // jogo que espera o outro lado seguir
+    pub external_timeout: Option<u64>,
+    // Ciclos até terminar a transferência em andamento
+    cycles: u64,
+    // Interrupção de um byte que chegou por clock_in, entregue no próximo tick
+    clocked_in: InterruptFlags,
+    // Bytes enviados desde a última chamada de take_sent
+    sent: Vec<u8>,
+}
+
+impl SerialPort {
+    pub fn new() -> Self {
+        #[cfg(feature = "std")]
+        let device = Box::new(crate::bus::ConsoleSink);
+        #[cfg(not(feature = "std"))]
+        let device = Box::new(crate::bus::Disconnected);
+        Self {
+            sb: 0,
+            sc: 0,
+            device,
+            devices: SerialRegistry::new(),
+            external_timeout: None,
+            cycles: 0,
+            clocked_in: InterruptFlags::empty(),
+            sent: Vec::new(),
+        }
+    }
+
+    pub fn power_cycle(&mut self) {
+        self.sb = 0;
+        self.sc = 0;
+        self.cycles = 0;
+        self.clocked_in = InterruptFlags::empty();
+        self.device.set_sb(self.sb);
+    }
+
+    // Troca o que está ligado na porta; uma transferência com clock externo já armada
+    // passa a esperar pelo dispositivo novo
+    pub fn attach(&mut self, mut device: Box<dyn SerialDevice>) {
+        device.set_sb(self.sb);
+        self.device = device;
+        if self.sc & (SC_START | SC_INTERNAL_CLOCK) == SC_START {
+            self.cycles = self.external_cycles();
+        }
+    }
+
+    // Troca o dispositivo sem mexer na transferência em andamento e devolve o anterior:
+    // os frames do run-ahead rodam com um Disconnected, longe da impressora e do cabo
+    pub fn swap_device(&mut self, device: Box<dyn SerialDevice>) -> Box<dyn SerialDevice> {
+        core::mem::replace(&mut self.device, device)
+    }
+
+    // Pelo nome no registro: "printer", "tcp:localhost:5000"
+    pub fn attach_named(&mut self, spec: &str) -> Result<(), String> {
+        let device = self.devices.create(spec)?;
+        self.attach(device);
+        Ok(())
+    }
+
+    pub fn take_sent(&mut self) -> Vec<u8> {
+        core::mem::take(&mut self.sent)
+    }
+
+    pub fn take_message(&mut self) -> Option<String> {
+        self.device.take_message()
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        if addr == SB { self.sb } else { self.sc }
+    }
+
+    pub fn write(&mut self, addr: u16, data: u8, cgb: bool) {
+        if addr == SB {
+            self.sb = data;
+            self.device.set_sb(data);
+            return;
+        }
+
+        self.sc = data;
+        self.cycles = if data & SC_START == 0 {
+            0
+        } else if data & SC_INTERNAL_CLOCK == 0 {
+            self.external_cycles()
+        } else if cgb && data & SC_FAST != 0 {
+            FAST_TRANSFER_CYCLES
+        } else {
+            TRANSFER_CYCLES
+        };
+    }
+
+    // Clock externo espera o dispositivo (poll ou clock_in); se ele não dá clock, só o
+    // timeout termina a transferência (0 = nunca)
+    fn external_cycles(&self) -> u64 {
+        if self.device.drives_clock() {
+            0
+        } else {
+            self.external_timeout.unwrap_or(0)
+        }
+    }
+
+    // Gancho pra quem dá o clock de fora: 8 pulsos com `value` na linha de entrada. Devolve
+    // o que estava no SB, que sai pela linha de saída. O byte entra mesmo sem transferência
+    // armada, como no hardware; a interrupção só vem com o SC esperando clock externo
+    pub fn clock_in(&mut self, value: u8) -> u8 {
+        let sent = self.sb;
+        self.sent.push(sent);
+        self.sb = value;
+        if self.sc & (SC_START | SC_INTERNAL_CLOCK) == SC_START {
+            self.cycles = 0;
+            let interrupt = self.finish_transfer();
+            self.clocked_in |= interrupt;
+        }
+        sent
+    }
+
+    fn finish_transfer(&mut self) -> InterruptFlags {
+        self.sc &= !SC_START;
+        InterruptFlags::SERIAL
+    }
+}
+
+impl Clocked for SerialPort {
+    fn domain(&self) -> ClockDomain {
+        ClockDomain::Cpu
+    }
+
+    fn tick(&mut self, t_cycles: u64) -> InterruptFlags {
+        if let Some(value) = self.device.poll() {
+            self.clock_in(value);
+        }
+        let mut interrupts = core::mem::take(&mut self.clocked_in);
+
+        if self.cycles > 0 {
+            self.cycles = self.cycles.saturating_sub(t_cycles);
+            if self.cycles == 0 {
+                // Com clock externo, chegar aqui é o timeout: ninguém mandou nada
+                self.sb = if self.sc & SC_INTERNAL_CLOCK != 0 {
+                    self.sent.push(self.sb);
+                    self.device.transfer(self.sb)
+                } else {
+                    0xFF
+                };
+                interrupts |= self.finish_transfer();
+            }
+        }
+        interrupts
+    }
+}
+
+impl Savestate for SerialPort {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.sb);
+        w.write_u8(self.sc);
+        w.write_u32(self.cycles as u32);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.sb = r.read_u8()?;
+        self.sc = r.read_u8()?;
+        self.cycles = r.read_u32()? as u64;
+        self.device.set_sb(self.sb);
+        Ok(())
+    }
+}
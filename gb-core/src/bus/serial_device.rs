@@ -0,0 +1,125 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::Write;
+
+// O que está do outro lado do cabo de link: outra instância, a impressora, a rede, um
+// script. O SerialPort cuida do SB/SC e do tempo; o dispositivo só troca bytes
+pub trait SerialDevice: Send {
+    // Fim de uma transferência com clock interno: `sent` saiu pela linha de saída; devolve
+    // o que entrou pela de entrada
+    fn transfer(&mut self, sent: u8) -> u8;
+
+    // Byte que o dispositivo mandou dando o clock ele mesmo (a ponta daqui em clock externo)
+    fn poll(&mut self) -> Option<u8> {
+        None
+    }
+
+    // O jogo escreveu no SB: é o que o dispositivo recebe quando der o clock
+    fn set_sb(&mut self, _value: u8) {}
+
+    // Dispositivo que dá clock: transferências com clock externo esperam por ele em vez de
+    // terminar pelo timeout
+    fn drives_clock(&self) -> bool {
+        false
+    }
+
+    // Aviso pro usuário (impressão salva, conexão perdida), entregue uma vez só
+    fn take_message(&mut self) -> Option<String> {
+        None
+    }
+}
+
+// Cabo solto: a linha de entrada fica em 1
+pub struct Disconnected;
+
+impl SerialDevice for Disconnected {
+    fn transfer(&mut self, _sent: u8) -> u8 {
+        0xFF
+    }
+}
+
+// Saída das ROMs de teste: cada byte vai pro stdout, e do outro lado não tem ninguém
+#[cfg(feature = "std")]
+pub struct ConsoleSink;
+
+#[cfg(feature = "std")]
+impl SerialDevice for ConsoleSink {
+    fn transfer(&mut self, sent: u8) -> u8 {
+        print!("{}", sent as char);
+        std::io::stdout().flush().ok();
+        0xFF
+    }
+}
+
+// Cria o dispositivo a partir do que vem depois do ':' no nome ("" sem argumento)
+pub type SerialFactory = Box<dyn Fn(&str) -> Result<Box<dyn SerialDevice>, String> + Send>;
+
+// Dispositivos que dá pra ligar pelo nome, como em "--serial printer" ou no console com
+// "serial tcp:localhost:5000". Frontends e scripts registram os seus
+pub struct SerialRegistry {
+    devices: Vec<(String, SerialFactory)>,
+}
+
+impl SerialRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self {
+            devices: Vec::new(),
+        };
+        registry.register("none", |_| Ok(Box::new(Disconnected)));
+        #[cfg(feature = "std")]
+        {
+            use crate::bus::{GbPrinter, TcpLink};
+
+            registry.register("console", |_| Ok(Box::new(ConsoleSink)));
+            registry.register("printer", |dir| {
+                Ok(Box::new(GbPrinter::new(if dir.is_empty() {
+                    "."
+                } else {
+                    dir
+                })))
+            });
+            registry.register("tcp-host", |port| {
+                let port = port
+                    .parse()
+                    .map_err(|_| format!("tcp-host espera a porta: 'tcp-host:{}'", port))?;
+                Ok(Box::new(TcpLink::host(port)?))
+            });
+            registry.register("tcp", |addr| Ok(Box::new(TcpLink::connect(addr)?)));
+        }
+        registry
+    }
+
+    // Um nome já registrado é substituído
+    pub fn register(
+        &mut self,
+        name: &str,
+        factory: impl Fn(&str) -> Result<Box<dyn SerialDevice>, String> + Send + 'static,
+    ) {
+        self.devices.retain(|(registered, _)| registered != name);
+        self.devices.push((name.to_string(), Box::new(factory)));
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.devices.iter().map(|(name, _)| name.as_str())
+    }
+
+    // "nome" ou "nome:argumento"
+    pub fn create(&self, spec: &str) -> Result<Box<dyn SerialDevice>, String> {
+        let (name, arg) = spec.split_once(':').unwrap_or((spec, ""));
+        match self
+            .devices
+            .iter()
+            .find(|(registered, _)| registered == name)
+        {
+            Some((_, factory)) => factory(arg),
+            None => Err(format!(
+                "dispositivo serial desconhecido: '{}' ({})",
+                name,
+                self.names().collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+}
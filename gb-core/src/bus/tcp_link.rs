@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::bus::SerialDevice;
+
+// Mensagens de 2 bytes: tipo e valor
+const MSG_SB: u8 = 0;
+const MSG_CLOCK: u8 = 1;
+
+#[derive(Default)]
+struct Shared {
+    // None até o outro lado conectar (host) ou depois da conexão cair
+    stream: Option<TcpStream>,
+    sb: u8,
+    peer_sb: u8,
+    // Bytes que o outro lado mandou dando o clock, ainda não entregues
+    clocked: VecDeque<u8>,
+    messages: VecDeque<String>,
+}
+
+// Cabo de link pela rede, com a mesma troca do LinkPort: cada lado manda o SB quando ele
+// muda, e quem dá o clock usa o último SB que recebeu e manda o próprio byte pro outro
+// lado entrar pelo poll. Sem sincronia de ciclos, então protocolos que respondem byte a
+// byte veem o SB de uma transferência atrás se a latência for maior que a transferência
+pub struct TcpLink {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl TcpLink {
+    // Não bloqueia: até alguém conectar a porta se comporta como cabo solto
+    pub fn host(port: u16) -> Result<Self, String> {
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .map_err(|erro| format!("link: erro ao abrir a porta {}: {}", port, erro))?;
+        let shared = Arc::new(Mutex::new(Shared::default()));
+
+        let accepted = shared.clone();
+        thread::spawn(move || match listener.accept() {
+            Ok((stream, addr)) => {
+                let message = format!("link: {} conectado", addr);
+                Self::connected(&accepted, stream, message);
+            }
+            Err(erro) => {
+                let message = format!("link: {}", erro);
+                accepted.lock().unwrap().messages.push_back(message);
+            }
+        });
+
+        Ok(Self { shared })
+    }
+
+    pub fn connect(addr: &str) -> Result<Self, String> {
+        let stream = TcpStream::connect(addr)
+            .map_err(|erro| format!("link: erro ao conectar em '{}': {}", addr, erro))?;
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        Self::connected(&shared, stream, format!("link: conectado em {}", addr));
+        Ok(Self { shared })
+    }
+
+    // Manda o SB atual e começa a ler o outro lado numa thread
+    fn connected(shared: &Arc<Mutex<Shared>>, stream: TcpStream, message: String) {
+        let reader = match stream.set_nodelay(true).and_then(|()| stream.try_clone()) {
+            Ok(reader) => reader,
+            Err(erro) => {
+                let message = format!("link: {}", erro);
+                shared.lock().unwrap().messages.push_back(message);
+                return;
+            }
+        };
+        {
+            let mut state = shared.lock().unwrap();
+            state.stream = Some(stream);
+            state.peer_sb = 0xFF;
+            let sb = state.sb;
+            state.send(MSG_SB, sb);
+            state.messages.push_back(message);
+        }
+
+        let shared = shared.clone();
+        thread::spawn(move || {
+            let mut reader = reader;
+            let mut msg = [0u8; 2];
+            while reader.read_exact(&mut msg).is_ok() {
+                let mut state = shared.lock().unwrap();
+                match msg {
+                    [MSG_SB, value] => state.peer_sb = value,
+                    [MSG_CLOCK, value] => state.clocked.push_back(value),
+                    _ => {}
+                }
+            }
+            let mut state = shared.lock().unwrap();
+            state.stream = None;
+            state
+                .messages
+                .push_back("link: conexão perdida".to_string());
+        });
+    }
+}
+
+impl Shared {
+    fn send(&mut self, kind: u8, value: u8) {
+        if let Some(stream) = &mut self.stream
+            && stream.write_all(&[kind, value]).is_err()
+        {
+            self.stream = None;
+        }
+    }
+}
+
+impl SerialDevice for TcpLink {
+    fn transfer(&mut self, sent: u8) -> u8 {
+        let mut state = self.shared.lock().unwrap();
+        if state.stream.is_none() {
+            return 0xFF;
+        }
+        // Os SBs trocam de lugar, como no LinkPort::exchange
+        let received = state.peer_sb;
+        state.peer_sb = sent;
+        state.sb = received;
+        state.send(MSG_CLOCK, sent);
+        received
+    }
+
+    fn poll(&mut self) -> Option<u8> {
+        let mut state = self.shared.lock().unwrap();
+        let value = state.clocked.pop_front()?;
+        state.sb = value;
+        Some(value)
+    }
+
+    fn set_sb(&mut self, value: u8) {
+        let mut state = self.shared.lock().unwrap();
+        state.sb = value;
+        state.send(MSG_SB, value);
+    }
+
+    fn drives_clock(&self) -> bool {
+        self.shared.lock().unwrap().stream.is_some()
+    }
+
+    fn take_message(&mut self) -> Option<String> {
+        self.shared.lock().unwrap().messages.pop_front()
+    }
+}
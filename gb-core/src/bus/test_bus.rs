@@ -0,0 +1,34 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::bus::CpuBus;
+
+// Bus dos testes da CPU: 64KB de RAM plana, sem mapa de memória nem dispositivos. IF
+// (FF0F) e IE (FFFF) são bytes comuns, então os testes de interrupção escrevem direto neles
+pub struct TestBus {
+    pub memory: Vec<u8>,
+}
+
+impl TestBus {
+    pub fn new() -> Self {
+        Self {
+            memory: vec![0; 0x10000],
+        }
+    }
+
+    // Copia `bytes` a partir de `addr`
+    pub fn load(&mut self, addr: u16, bytes: &[u8]) {
+        let start = addr as usize;
+        self.memory[start..start + bytes.len()].copy_from_slice(bytes);
+    }
+}
+
+impl CpuBus for TestBus {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.memory[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.memory[addr as usize] = data;
+    }
+}
@@ -0,0 +1,405 @@
+use crate::bus::serial::{SB, SC, TRANSFER_CYCLES};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::bus::{
+    Clocked, Disconnected, GbPrinter, InterruptFlags, LinkPort, MemoryBus, SerialDevice, SerialPort,
+};
+use crate::cartridge::Cartridge;
+use crate::cheats::Freeze;
+use crate::machine::Model;
+use crate::ppu::OAM_BASE;
+
+const SERIAL_BIT: u8 = InterruptFlags::SERIAL.bits();
+
+#[test]
+fn serial_sem_cabo_termina_com_ff() {
+    let mut serial = SerialPort::new();
+    serial.write(SB, 0x42, false);
+    serial.write(SC, 0x81, false);
+    assert_eq!(serial.tick(TRANSFER_CYCLES - 1).bits(), 0);
+    assert_eq!(serial.read(SC) & 0x80, 0x80);
+
+    assert_eq!(serial.tick(1).bits(), SERIAL_BIT);
+    assert_eq!(serial.read(SB), 0xFF);
+    assert_eq!(serial.read(SC) & 0x80, 0);
+    assert_eq!(serial.take_sent(), [0x42]);
+}
+
+#[test]
+fn clock_externo_sem_cabo_espera_sem_timeout() {
+    let mut serial = SerialPort::new();
+    serial.write(SB, 0x42, false);
+    serial.write(SC, 0x80, false);
+    for _ in 0..100 {
+        assert_eq!(serial.tick(TRANSFER_CYCLES).bits(), 0);
+    }
+    assert_eq!(serial.read(SC), 0x80);
+    assert_eq!(serial.read(SB), 0x42);
+}
+
+#[test]
+fn clock_externo_sem_cabo_com_timeout() {
+    let mut serial = SerialPort::new();
+    serial.external_timeout = Some(1000);
+    serial.write(SB, 0x42, false);
+    serial.write(SC, 0x80, false);
+    assert_eq!(serial.tick(999).bits(), 0);
+    assert_eq!(serial.tick(1).bits(), SERIAL_BIT);
+    assert_eq!(serial.read(SB), 0xFF);
+    assert_eq!(serial.read(SC) & 0x80, 0);
+}
+
+#[test]
+fn clock_in_completa_a_transferencia_externa() {
+    let mut serial = SerialPort::new();
+    serial.write(SB, 0x42, false);
+    // Sem transferência armada o byte entra, mas sem interrupção
+    assert_eq!(serial.clock_in(0x10), 0x42);
+    assert_eq!(serial.tick(4).bits(), 0);
+
+    serial.write(SC, 0x80, false);
+    assert_eq!(serial.clock_in(0x99), 0x10);
+    assert_eq!(serial.read(SB), 0x99);
+    assert_eq!(serial.tick(4).bits(), SERIAL_BIT);
+    assert_eq!(serial.read(SC) & 0x80, 0);
+}
+
+#[test]
+fn cabo_troca_os_bytes_entre_as_pontas() {
+    let (port, peer_port) = LinkPort::pair();
+    let (mut master, mut slave) = (SerialPort::new(), SerialPort::new());
+    master.attach(Box::new(port));
+    slave.attach(Box::new(peer_port));
+
+    slave.write(SB, 0x22, false);
+    slave.write(SC, 0x80, false);
+    master.write(SB, 0x11, false);
+    master.write(SC, 0x81, false);
+    // O lado com clock externo não termina sozinho, mesmo com o timeout
+    slave.external_timeout = Some(1);
+    assert_eq!(slave.tick(TRANSFER_CYCLES).bits(), 0);
+
+    assert_eq!(master.tick(TRANSFER_CYCLES).bits(), SERIAL_BIT);
+    assert_eq!(slave.tick(4).bits(), SERIAL_BIT);
+    assert_eq!((master.read(SB), slave.read(SB)), (0x22, 0x11));
+}
+
+// Dispositivo de teste: responde sempre o mesmo byte e guarda o que recebeu
+struct Echo(u8, Vec<u8>);
+
+impl SerialDevice for Echo {
+    fn transfer(&mut self, sent: u8) -> u8 {
+        self.1.push(sent);
+        self.0
+    }
+}
+
+#[test]
+fn swap_device_desvia_a_transferencia_e_devolve_o_dispositivo() {
+    let mut serial = SerialPort::new();
+    serial.attach(Box::new(Echo(0x55, Vec::new())));
+
+    // Como nos frames do run-ahead: o outro lado não vê nada
+    let device = serial.swap_device(Box::new(Disconnected));
+    serial.write(SB, 0x42, false);
+    serial.write(SC, 0x81, false);
+    serial.tick(TRANSFER_CYCLES);
+    assert_eq!(serial.read(SB), 0xFF);
+
+    serial.swap_device(device);
+    serial.write(SB, 0x42, false);
+    serial.write(SC, 0x81, false);
+    serial.tick(TRANSFER_CYCLES);
+    assert_eq!(serial.read(SB), 0x55);
+}
+
+#[test]
+fn registro_cria_dispositivos_pelo_nome() {
+    let mut serial = SerialPort::new();
+    serial.devices.register("echo", |arg| {
+        Ok(Box::new(Echo(arg.parse().unwrap(), Vec::new())))
+    });
+    assert!(serial.devices.names().any(|name| name == "printer"));
+    assert!(serial.attach_named("impressora").is_err());
+
+    serial.attach_named("echo:66").unwrap();
+    serial.write(SB, 0x01, false);
+    serial.write(SC, 0x81, false);
+    assert_eq!(serial.tick(TRANSFER_CYCLES).bits(), SERIAL_BIT);
+    assert_eq!(serial.read(SB), 66);
+
+    serial.attach(Box::new(Disconnected));
+    serial.write(SC, 0x81, false);
+    serial.tick(TRANSFER_CYCLES);
+    assert_eq!(serial.read(SB), 0xFF);
+}
+
+// Manda um pacote inteiro e devolve as respostas dos dois últimos bytes (alive, status)
+fn printer_packet(printer: &mut GbPrinter, command: u8, data: &[u8]) -> (u8, u8) {
+    let len = (data.len() as u16).to_le_bytes();
+    let mut packet = vec![0x88, 0x33, command, 0x00, len[0], len[1]];
+    packet.extend_from_slice(data);
+    let sum = packet[2..]
+        .iter()
+        .fold(0u16, |sum, &byte| sum.wrapping_add(byte as u16));
+    packet.extend_from_slice(&sum.to_le_bytes());
+    for &byte in &packet {
+        assert_eq!(printer.transfer(byte), 0x00);
+    }
+    (printer.transfer(0x00), printer.transfer(0x00))
+}
+
+#[test]
+fn impressora_grava_a_imagem_impressa() {
+    let dir = std::env::temp_dir().join(format!("gb-printer-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut printer = GbPrinter::new(&dir);
+
+    assert_eq!(printer_packet(&mut printer, 0x01, &[]), (0x81, 0x00));
+    // Uma linha de tiles toda na cor 3
+    assert_eq!(
+        printer_packet(&mut printer, 0x04, &[0xFF; 320]),
+        (0x81, 0x08)
+    );
+    assert_eq!(
+        printer_packet(&mut printer, 0x02, &[1, 0x13, 0xE4, 0x40]),
+        (0x81, 0x06)
+    );
+    assert_eq!(printer_packet(&mut printer, 0x0F, &[]), (0x81, 0x06));
+
+    let message = printer.take_message().unwrap();
+    let path = dir.join("print-001.png");
+    assert!(message.contains("print-001.png"), "{}", message);
+    let (width, height, rgba) = crate::png::read_png(&path).unwrap();
+    assert_eq!((width, height), (160, 8));
+    assert!(rgba.chunks_exact(4).all(|pixel| pixel == [0, 0, 0, 0xFF]));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn impressora_recusa_checksum_errado() {
+    let mut printer = GbPrinter::new(".");
+    for byte in [0x88, 0x33, 0x0F, 0x00, 0x00, 0x00, 0x12, 0x34] {
+        printer.transfer(byte);
+    }
+    assert_eq!(printer.transfer(0x00), 0x81);
+    assert_eq!(printer.transfer(0x00), 0x01);
+}
+
+fn cgb_bus(cgb_flag: u8) -> MemoryBus {
+    let mut rom = vec![0; 0x8000];
+    rom[0x143] = cgb_flag;
+    let mut bus = MemoryBus::new(Cartridge::load(rom).unwrap());
+    bus.model = Model::Cgb;
+    bus.reset();
+    bus
+}
+
+#[test]
+fn key0_depois_do_boot() {
+    let mut cgb = cgb_bus(0x80);
+    assert_eq!(cgb.read(0xFF4C), 0x80);
+    assert!(cgb.cgb_mode());
+
+    let mut dmg_game = cgb_bus(0x00);
+    assert_eq!(dmg_game.read(0xFF4C), 0x04);
+    assert!(dmg_game.dmg_compat());
+    assert!(!dmg_game.cgb_mode());
+}
+
+#[test]
+fn modo_compativel_trava_registros_do_cgb() {
+    let mut bus = cgb_bus(0x00);
+    bus.write(0xFF4C, 0x80);
+    assert!(bus.dmg_compat());
+
+    // Sem troca de velocidade: o STOP para a CPU
+    bus.write(0xFF4D, 0x01);
+    assert_eq!(bus.read(0xFF4D), 0xFF);
+    assert!(bus.stop());
+
+    bus.write(0xFF56, 0xC1);
+    assert_eq!(bus.read(0xFF56), 0xFF);
+
+    bus.write(0xFF6C, 0x00);
+    assert_eq!(bus.read(0xFF6C), 0xFF);
+
+    let mut cgb = cgb_bus(0x80);
+    cgb.write(0xFF4D, 0x01);
+    assert!(!cgb.stop());
+    assert_eq!(cgb.read(0xFF4D), 0xFE);
+}
+
+#[test]
+fn escrita_da_cpu_nao_muda_endereco_congelado() {
+    let mut bus = MemoryBus::new(Cartridge::load(vec![0; 0x8000]).unwrap());
+    bus.cheats
+        .freeze(Freeze::new(0xC010, 0x63).unwrap())
+        .unwrap();
+    bus.cheats.add("0105C1C0").unwrap();
+
+    bus.write(0xC010, 0x00);
+    // Pelo eco da WRAM também
+    bus.write(0xE010, 0x01);
+    bus.write(0xC0C1, 0x00);
+    bus.write(0xC011, 0x22);
+    assert_eq!(bus.read(0xC010), 0x63);
+    assert_eq!(bus.read(0xC0C1), 0x05);
+    assert_eq!(bus.read(0xC011), 0x22);
+
+    bus.cheats.enabled = false;
+    bus.write(0xC010, 0x00);
+    assert_eq!(bus.read(0xC010), 0x00);
+    bus.cheats.enabled = true;
+
+    assert!(bus.cheats.unfreeze(0xC010));
+    bus.cheats.set_enabled("0105C1C0", false);
+    bus.write(0xC010, 0x01);
+    bus.write(0xC0C1, 0x02);
+    assert_eq!(bus.read(0xC010), 0x01);
+    assert_eq!(bus.read(0xC0C1), 0x02);
+
+    assert!(Freeze::new(0xFF40, 0).is_err());
+}
+
+#[test]
+fn congelamento_fora_da_ram_e_recusado() {
+    let mut bus = MemoryBus::new(Cartridge::load(vec![0; 0x8000]).unwrap());
+    // Montado à mão, sem passar pelo Freeze::new
+    for address in [0x2000, 0x7FFF, 0xFE00, 0xFF00, 0xFF40, 0xFFFF] {
+        assert!(bus.cheats.freeze(Freeze { address, value: 1 }).is_err());
+    }
+    assert!(bus.cheats.freezes().is_empty());
+    assert_eq!(bus.cheats.ram_writes(0).count(), 0);
+
+    // Eco vira o endereço da WRAM; HRAM e SRAM passam
+    bus.cheats
+        .freeze(Freeze {
+            address: 0xE020,
+            value: 7,
+        })
+        .unwrap();
+    bus.cheats.freeze(Freeze::new(0xFF90, 8).unwrap()).unwrap();
+    bus.cheats.freeze(Freeze::new(0xA000, 9).unwrap()).unwrap();
+    assert_eq!(bus.cheats.freezes()[0].address, 0xC020);
+    bus.write(0xFF90, 0);
+    assert_eq!(bus.read(0xFF90), 8);
+
+    // Registros e o MBC continuam recebendo o valor escrito
+    bus.write(0xFF42, 0x33);
+    assert_eq!(bus.read(0xFF42), 0x33);
+    assert_eq!(bus.cheats.frozen(0x2000, 0), None);
+}
+
+#[test]
+fn gameshark_com_banco_so_vale_no_banco_de_sram_dele() {
+    // MBC5 com 32KB de SRAM (4 bancos)
+    let mut rom = vec![0; 0x8000];
+    rom[0x147] = 0x1A;
+    rom[0x149] = 0x03;
+    let mut bus = MemoryBus::new(Cartridge::load(rom).unwrap());
+    bus.write(0x0000, 0x0A);
+    // 82 = banco 2, valor 55 em A000
+    bus.cheats.add("825500A0").unwrap();
+
+    bus.write(0x4000, 0x00);
+    bus.write(0xA000, 0x11);
+    bus.apply_ram_cheats();
+    assert_eq!(bus.read(0xA000), 0x11);
+
+    bus.write(0x4000, 0x02);
+    bus.write(0xA000, 0x22);
+    assert_eq!(bus.read(0xA000), 0x55);
+    bus.write(0xA000, 0x00);
+    bus.apply_ram_cheats();
+    assert_eq!(bus.read(0xA000), 0x55);
+
+    // O banco 0 ficou com o que o jogo escreveu
+    bus.write(0x4000, 0x00);
+    assert_eq!(bus.read(0xA000), 0x11);
+}
+
+// ROM com o byte baixo do endereço em cada posição, pra saber de onde veio cada valor;
+// o tipo e os tamanhos do cabeçalho ficam zerados (ROM only)
+fn counting_bus() -> MemoryBus {
+    let mut rom: Vec<u8> = (0..0x8000).map(|i| i as u8).collect();
+    rom[0x147..0x14A].fill(0);
+    MemoryBus::new(Cartridge::load(rom).unwrap())
+}
+
+#[test]
+fn acessos_de_16_bits_sao_little_endian() {
+    let mut bus = counting_bus();
+    bus.write_u16(0xC000, 0xBEEF);
+    assert_eq!(bus.read(0xC000), 0xEF);
+    assert_eq!(bus.read(0xC001), 0xBE);
+    assert_eq!(bus.read_u16(0xC000), 0xBEEF);
+    assert_eq!(bus.read_u16(0x0102), 0x0302);
+}
+
+#[test]
+fn eco_da_wram_espelha_os_dois_sentidos() {
+    let mut bus = counting_bus();
+    bus.write_u16(0xC123, 0x1234);
+    assert_eq!(bus.read_u16(0xE123), 0x1234);
+
+    // Última word do eco: cai no fim da WRAM
+    bus.write_u16(0xFDFC, 0xABCD);
+    assert_eq!(bus.read_u16(0xDDFC), 0xABCD);
+    // A word que começa em FDFF já pega o primeiro byte da OAM
+    bus.write(0xFE00, 0x77);
+    assert_eq!(bus.read_u16(0xFDFF), 0x7700 | bus.read(0xDDFF) as u16);
+}
+
+#[test]
+fn word_em_ffff_da_a_volta_e_hram_para_no_ie() {
+    let mut bus = counting_bus();
+    bus.write_u16(0xFFFE, 0x1F80);
+    assert_eq!(bus.read(0xFFFE), 0x80);
+    assert_eq!(bus.read(0xFFFF), 0x1F);
+
+    // O byte alto vai pro 0x0000, que no ROM only não muda nada
+    bus.write_u16(0xFFFF, 0x5A05);
+    assert_eq!(bus.read(0xFFFF), 0x05);
+    assert_eq!(bus.read(0x0000), 0x00);
+    assert_eq!(bus.read_u16(0xFFFF), 0x0005);
+}
+
+#[test]
+fn copy_block_pra_oam_pela_rom_e_pela_wram() {
+    let mut bus = counting_bus();
+    bus.copy_block(0x0210, OAM_BASE, 0xA0);
+    assert!((0..0xA0).all(|i| bus.peek(OAM_BASE + i) == 0x10 + i as u8));
+
+    for i in 0..0x2000 {
+        bus.write(0xC000 + i, (i as u8) ^ 0x5A);
+    }
+    bus.copy_block(0xC100, OAM_BASE, 0xA0);
+    assert!((0..0xA0).all(|i| bus.peek(OAM_BASE + i) == bus.peek(0xC100 + i)));
+
+    // Atravessa o fim da WRAM pro eco, que recomeça no C000
+    bus.copy_block(0xDFF0, OAM_BASE, 0x20);
+    for i in 0..0x10 {
+        assert_eq!(bus.peek(OAM_BASE + i), bus.peek(0xDFF0 + i));
+        assert_eq!(bus.peek(OAM_BASE + 0x10 + i), bus.peek(0xC000 + i));
+    }
+}
+
+#[test]
+fn timer_anda_no_ritmo_normal_com_overclock() {
+    // DIV depois de 4096 NOPs (4 ciclos da CPU cada), com o contador começando do 0
+    let div = |overclock: u8| {
+        let mut bus = MemoryBus::new(Cartridge::load(vec![0; 0x8000]).unwrap());
+        bus.overclock = overclock;
+        for _ in 0..4096 {
+            bus.tick(4, false);
+        }
+        bus.read(0xFF04)
+    };
+    assert_eq!(div(1), 64);
+    // A CPU roda 2x ou 3x mais instruções no mesmo tempo do timer
+    assert_eq!(div(2), 32);
+    assert_eq!(div(3), 21);
+}
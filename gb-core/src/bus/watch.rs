@@ -0,0 +1,54 @@
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct MemoryAccess {
+    pub kind: AccessKind,
+    pub addr: u16,
+    pub value: u8,
+}
+
+// Endereços observados (hooks de script) e os acessos da CPU a eles ainda não entregues
+pub struct Watchpoints {
+    watched: BTreeSet<(AccessKind, u16)>,
+    hits: Vec<MemoryAccess>,
+}
+
+impl Watchpoints {
+    pub fn new() -> Self {
+        Self {
+            watched: BTreeSet::new(),
+            hits: Vec::new(),
+        }
+    }
+
+    pub fn set(&mut self, watched: impl IntoIterator<Item = (AccessKind, u16)>) {
+        self.watched = watched.into_iter().collect();
+        self.hits.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.set([]);
+    }
+
+    // Chamado em todo acesso da CPU; sem nada observado custa só o is_empty
+    pub fn record(&mut self, kind: AccessKind, addr: u16, value: u8) {
+        if !self.watched.is_empty() && self.watched.contains(&(kind, addr)) {
+            self.hits.push(MemoryAccess { kind, addr, value });
+        }
+    }
+
+    pub fn has_hits(&self) -> bool {
+        !self.hits.is_empty()
+    }
+
+    pub fn take_hits(&mut self) -> Vec<MemoryAccess> {
+        core::mem::take(&mut self.hits)
+    }
+}
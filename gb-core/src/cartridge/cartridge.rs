@@ -0,0 +1,409 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::cartridge_type::CartridgeType;
+use super::destination::Destination;
+use super::mbc::{Huc1, Huc3, IrDevice, Mbc, Mbc1, Mbc3, Mbc5, Mbc7, MbcOps, NoMbc, PocketCamera};
+use crate::bus::{ClockDomain, Clocked, InterruptFlags};
+use crate::joypad::Tilt;
+use crate::png;
+use crate::state::{Savestate, StateReader, StateWriter};
+
+// Fim do header (0x0100..0x014F)
+const HEADER_END: usize = 0x150;
+
+// Formato de um save trocado com flashcarts e outros emuladores: `Raw` é só a RAM (o que
+// os flashcarts gravam, às vezes completada com lixo), `Vba` é a RAM com o rodapé do
+// relógio, igual ao .sav daqui
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SaveLayout {
+    Raw,
+    Vba,
+}
+
+impl SaveLayout {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "raw" => Some(Self::Raw),
+            "vba" => Some(Self::Vba),
+            _ => None,
+        }
+    }
+}
+
+pub struct Cartridge {
+    pub mbc: Mbc,
+    pub game_title: String,
+    pub manufacturer_code: String,
+    pub cgb_flag: u8,
+    pub licensee_code: String,
+    pub sgb_flag: u8,
+    pub cartridge_type: CartridgeType,
+    pub rom_size: u8,
+    pub ram_size: u8,
+    pub destination_code: Destination,
+    pub old_licensee_code: u8,
+    pub mask_rom_version_number: u8,
+    pub header_checksum: u8,
+    pub global_checksum: u16,
+    // CRC32 da ROM inteira, pra conferir se um movie foi gravado com ela
+    pub rom_crc: u32,
+    // Checksums do header conferidos com a ROM (o boot ROM só verifica o do header)
+    pub header_checksum_valid: bool,
+    pub global_checksum_valid: bool,
+    // Escrita na RAM externa desde o último flush do .sav
+    pub ram_dirty: bool,
+}
+
+impl Cartridge {
+    pub fn read(&self, addr: u16) -> u8 {
+        self.mbc.read(addr)
+    }
+
+    pub fn write(&mut self, addr: u16, data: u8) {
+        if let 0xA000..=0xBFFF = addr {
+            self.ram_dirty = true;
+        }
+        self.mbc.write(addr, data);
+    }
+
+    pub fn ram(&self) -> &[u8] {
+        self.mbc.ram()
+    }
+
+    pub fn rom(&self) -> &[u8] {
+        self.mbc.rom()
+    }
+
+    pub fn rom_bank(&self, addr: u16) -> usize {
+        self.mbc.rom_bank(addr)
+    }
+
+    pub fn ram_bank(&self) -> usize {
+        self.mbc.ram_bank()
+    }
+
+    // Posição no arquivo da ROM do byte mapeado em `addr` (0x0000-0x7FFF)
+    pub fn rom_offset(&self, addr: u16) -> usize {
+        self.rom_bank(addr) * 0x4000 + (addr as usize & 0x3FFF)
+    }
+
+    pub fn reset(&mut self) {
+        self.mbc.reset();
+    }
+
+    pub fn rumble(&self) -> bool {
+        self.mbc.rumble()
+    }
+
+    pub fn set_tilt(&mut self, tilt: Tilt) {
+        self.mbc.set_tilt(tilt);
+    }
+
+    pub fn take_tone(&mut self) -> Option<u8> {
+        self.mbc.take_tone()
+    }
+
+    pub fn set_camera_image(&mut self, image: &[u8]) {
+        self.mbc.set_camera_image(image);
+    }
+
+    pub fn set_ir_device(&mut self, device: Box<dyn IrDevice>) {
+        self.mbc.set_ir_device(device);
+    }
+
+    pub fn take_ir_device(&mut self) -> Option<Box<dyn IrDevice>> {
+        self.mbc.take_ir_device()
+    }
+
+    // O SGB só libera as funções dele com a flag 0x03 e o licensee antigo 0x33
+    pub fn supports_sgb(&self) -> bool {
+        self.sgb_flag == 0x03 && self.old_licensee_code == 0x33
+    }
+
+    pub fn has_battery(&self) -> bool {
+        self.cartridge_type.has_battery()
+    }
+
+    pub fn has_rtc(&self) -> bool {
+        self.mbc.clock().is_some()
+    }
+
+    // Conteúdo do .sav: RAM externa e, se tiver relógio, o rodapé dele
+    pub fn battery_data(&self, timestamp: u64) -> Vec<u8> {
+        let mut data = self.mbc.ram().to_vec();
+        if let Some(clock) = self.mbc.clock() {
+            clock.write_footer(&mut data, timestamp);
+        }
+        data
+    }
+
+    // `now` = hora atual (UNIX); o RTC anda o tempo que passou desde que o .sav foi
+    // gravado. None mantém o relógio como estava no arquivo
+    pub fn load_battery_data(&mut self, data: &[u8], now: Option<u64>) -> Result<(), String> {
+        let ram_len = self.mbc.ram().len();
+        if data.len() < ram_len {
+            return Err(format!(
+                "save com {} bytes, esperado {}",
+                data.len(),
+                ram_len
+            ));
+        }
+        let (ram, footer) = data.split_at(ram_len);
+
+        // Sem rodapé (save de outro emulador ou de antes do RTC) o relógio fica zerado;
+        // bytes sobrando em cartucho sem RTC são ignorados
+        if !footer.is_empty()
+            && let Some(clock) = self.mbc.clock_mut()
+        {
+            let saved_at = clock.read_footer(footer)?;
+            if let Some(now) = now {
+                clock.advance(now.saturating_sub(saved_at));
+            }
+        }
+
+        self.mbc.ram_mut().copy_from_slice(ram);
+        Ok(())
+    }
+
+    pub fn export_battery(&self, layout: SaveLayout, timestamp: u64) -> Vec<u8> {
+        match layout {
+            SaveLayout::Raw => self.mbc.ram().to_vec(),
+            SaveLayout::Vba => self.battery_data(timestamp),
+        }
+    }
+
+    // Sem formato, tenta o do VBA e cai pro bruto se o que sobra depois da RAM não for um
+    // rodapé de relógio. No bruto o relógio fica como estava
+    pub fn import_battery(
+        &mut self,
+        data: &[u8],
+        layout: Option<SaveLayout>,
+        now: Option<u64>,
+    ) -> Result<(), String> {
+        match layout {
+            Some(SaveLayout::Vba) => self.load_battery_data(data, now),
+            Some(SaveLayout::Raw) => {
+                let ram_len = self.mbc.ram().len();
+                if data.len() < ram_len {
+                    return Err(format!(
+                        "save com {} bytes, esperado {}",
+                        data.len(),
+                        ram_len
+                    ));
+                }
+                self.mbc.ram_mut().copy_from_slice(&data[..ram_len]);
+                Ok(())
+            }
+            None => self
+                .load_battery_data(data, now)
+                .or_else(|_| self.import_battery(data, Some(SaveLayout::Raw), now)),
+        }
+    }
+
+    // Título do header sem o preenchimento com zeros
+    pub fn title(&self) -> String {
+        let title = &self.game_title;
+        title.split('\0').next().unwrap_or("GB").to_string()
+    }
+
+    pub fn load(value: Vec<u8>) -> Result<Self, String> {
+        if value.len() < HEADER_END {
+            return Err(format!("ROM pequena demais: {} bytes", value.len()));
+        }
+
+        // Parse do header (usa slices/cópias — não consome `value`)
+        let game_title = String::from_utf8_lossy(&value[308..324]).to_string();
+        let manufacturer_code = String::from_utf8_lossy(&value[319..323]).to_string();
+        let cgb_flag = value[323];
+        let licensee_code = format!("{}{}", value[324] as char, value[325] as char);
+        let sgb_flag = value[326];
+        let cartridge_type = CartridgeType::from(value[327]);
+        let rom_size = value[328];
+        let ram_size = value[329];
+        let destination_code = Destination::from(value[330]);
+        let old_licensee_code = value[331];
+        let mask_rom_version_number = value[332];
+        let header_checksum = value[333];
+        let global_checksum = u16::from_be_bytes([value[334], value[335]]);
+
+        let rom_crc = png::crc32(&value);
+        let header_checksum_valid = compute_header_checksum(&value) == header_checksum;
+        let global_checksum_valid = compute_global_checksum(&value) == global_checksum;
+        let ram_size_bytes = ram_size_from_byte(ram_size);
+
+        // Construção da variante (consome `value` movendo-o pra dentro do MBC)
+        let mbc: Mbc = match &cartridge_type {
+            CartridgeType::RomOnly => NoMbc::new(value).into(),
+
+            CartridgeType::Mbc1
+            | CartridgeType::Mbc1Ram
+            | CartridgeType::Mbc1RamBattery => Mbc1::new(value, ram_size_bytes).into(),
+
+            CartridgeType::Mbc3
+            | CartridgeType::Mbc3Ram
+            | CartridgeType::Mbc3RamBattery
+            | CartridgeType::Mbc3TimerBattery
+            | CartridgeType::Mbc3TimerRamBattery => {
+                Mbc3::new(value, ram_size_bytes, cartridge_type.has_rtc()).into()
+            }
+
+            CartridgeType::Mbc5
+            | CartridgeType::Mbc5Ram
+            | CartridgeType::Mbc5RamBattery
+            | CartridgeType::Mbc5Rumble
+            | CartridgeType::Mbc5RumbleRam
+            | CartridgeType::Mbc5RumbleRamBattery => {
+                Mbc5::new(value, ram_size_bytes, cartridge_type.has_rumble()).into()
+            }
+
+            CartridgeType::Mbc7SensorRumbleRamBattery => Mbc7::new(value).into(),
+
+            CartridgeType::Huc1RamBattery => Huc1::new(value, ram_size_bytes).into(),
+
+            CartridgeType::Huc3 => Huc3::new(value, ram_size_bytes).into(),
+
+            CartridgeType::PocketCamera => PocketCamera::new(value, ram_size_bytes).into(),
+
+            other => return Err(format!("MBC type não suportado ainda: {}", other)),
+        };
+
+        Ok(Self {
+            mbc,
+            game_title,
+            manufacturer_code,
+            cgb_flag,
+            licensee_code,
+            sgb_flag,
+            cartridge_type,
+            rom_size,
+            ram_size,
+            destination_code,
+            old_licensee_code,
+            mask_rom_version_number,
+            header_checksum,
+            global_checksum,
+            rom_crc,
+            header_checksum_valid,
+            global_checksum_valid,
+            ram_dirty: false,
+        })
+    }
+
+    // Tamanhos decodificados do header, em bytes
+    pub fn rom_size_bytes(&self) -> Option<usize> {
+        rom_size_from_byte(self.rom_size)
+    }
+
+    pub fn ram_size_bytes(&self) -> usize {
+        ram_size_from_byte(self.ram_size)
+    }
+
+    // Mapper que o emulador escolheu pra esse cartucho
+    pub fn mapper(&self) -> &'static str {
+        match self.mbc {
+            Mbc::NoMbc(_) => "nenhum",
+            Mbc::Mbc1(_) => "MBC1",
+            Mbc::Mbc3(_) if self.has_rtc() => "MBC3 + RTC",
+            Mbc::Mbc3(_) => "MBC3",
+            Mbc::Mbc5(_) if self.cartridge_type.has_rumble() => "MBC5 + Rumble",
+            Mbc::Mbc5(_) => "MBC5",
+            Mbc::Mbc7(_) => "MBC7",
+            Mbc::Huc1(_) => "HuC1",
+            Mbc::Huc3(_) => "HuC3",
+            Mbc::PocketCamera(_) => "Pocket Camera",
+        }
+    }
+}
+
+// Só o RTC anda com o tempo, e ele tem cristal próprio
+impl Clocked for Cartridge {
+    fn domain(&self) -> ClockDomain {
+        ClockDomain::Rtc
+    }
+
+    fn tick(&mut self, t_cycles: u64) -> InterruptFlags {
+        self.mbc.tick(t_cycles);
+        InterruptFlags::empty()
+    }
+}
+
+// x = x - rom[i] - 1 sobre 0x0134..=0x014C
+fn compute_header_checksum(rom: &[u8]) -> u8 {
+    rom[0x134..=0x14C]
+        .iter()
+        .fold(0u8, |x, &byte| x.wrapping_sub(byte).wrapping_sub(1))
+}
+
+// Soma de todos os bytes da ROM menos os dois do próprio checksum
+fn compute_global_checksum(rom: &[u8]) -> u16 {
+    rom.iter()
+        .enumerate()
+        .filter(|&(addr, _)| addr != 0x14E && addr != 0x14F)
+        .fold(0u16, |sum, (_, &byte)| sum.wrapping_add(byte as u16))
+}
+
+fn rom_size_from_byte(b: u8) -> Option<usize> {
+    match b {
+        0x00..=0x08 => Some((32 * 1024) << b),  // 32 KB até 8 MB
+        0x52 => Some(72 * 16 * 1024),           // 1.1 MB (valores não oficiais)
+        0x53 => Some(80 * 16 * 1024),           // 1.2 MB
+        0x54 => Some(96 * 16 * 1024),           // 1.5 MB
+        _ => None,
+    }
+}
+
+fn ram_size_from_byte(b: u8) -> usize {
+    match b {
+        0x00 => 0,
+        0x01 => 2 * 1024,    // 2 KB (raro)
+        0x02 => 8 * 1024,    // 8 KB
+        0x03 => 32 * 1024,   // 32 KB (4 banks de 8 KB)
+        0x04 => 128 * 1024,  // 128 KB (16 banks)
+        0x05 => 64 * 1024,   // 64 KB (8 banks)
+        _ => 0,
+    }
+}
+
+impl fmt::Display for Cartridge {
+    fn fmt(&self, format: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(format, "=== Game Boy Cartridge Info ===")?;
+        writeln!(format, "Title:               {}", self.game_title)?;
+        writeln!(format, "Manufacturer Code:   {}", self.manufacturer_code)?;
+        writeln!(format, "Licensee Code:       {}", self.licensee_code)?;
+        writeln!(format, "CGB Flag:            {:#04X}", self.cgb_flag)?;
+        writeln!(format, "SGB Flag:            {:#04X}", self.sgb_flag)?;
+        writeln!(format, "Cartridge Type:      {}", self.cartridge_type)?;
+        writeln!(format, "ROM Size:            {:#04X}", self.rom_size)?;
+        writeln!(format, "RAM Size:            {:#04X}", self.ram_size)?;
+        writeln!(format, "Destination Code:    {}", self.destination_code)?;
+        writeln!(
+            format,
+            "Old Licensee Code:   {:#04X}",
+            self.old_licensee_code
+        )?;
+        writeln!(
+            format,
+            "Mask ROM Version:    {:#04X}",
+            self.mask_rom_version_number
+        )?;
+        writeln!(format, "Header Checksum:     {:#04X}", self.header_checksum)?;
+        writeln!(format, "Global Checksum:     {:#06X}", self.global_checksum)?;
+        Ok(())
+    }
+}
+
+// Só o estado do MBC (registros e RAM); a ROM não entra no save state
+impl Savestate for Cartridge {
+    fn save_state(&self, w: &mut StateWriter) {
+        self.mbc.save_state(w);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ram_dirty = true;
+        self.mbc.load_state(r)
+    }
+}
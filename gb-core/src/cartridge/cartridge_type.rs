@@ -1,5 +1,5 @@
-use std::fmt;
-use std::u8;
+use core::fmt;
+use core::u8;
 
 pub enum CartridgeType {
     RomOnly,
@@ -30,6 +30,47 @@ pub enum CartridgeType {
     BandaiTama5,
     Huc3,
     Huc1RamBattery,
+    Unknown(u8),
+}
+
+impl CartridgeType {
+    // RAM (e RTC) mantidos por bateria: vão pro .sav
+    pub fn has_battery(&self) -> bool {
+        matches!(
+            self,
+            CartridgeType::Mbc1RamBattery
+                | CartridgeType::Mbc2Battery
+                | CartridgeType::RomRamBattery
+                | CartridgeType::Mmm01RamBattery
+                | CartridgeType::Mbc3TimerBattery
+                | CartridgeType::Mbc3TimerRamBattery
+                | CartridgeType::Mbc3RamBattery
+                | CartridgeType::Mbc5RamBattery
+                | CartridgeType::Mbc5RumbleRamBattery
+                | CartridgeType::Mbc7SensorRumbleRamBattery
+                | CartridgeType::PocketCamera
+                | CartridgeType::Huc3
+                | CartridgeType::Huc1RamBattery
+        )
+    }
+
+    pub fn has_rumble(&self) -> bool {
+        matches!(
+            self,
+            CartridgeType::Mbc5Rumble
+                | CartridgeType::Mbc5RumbleRam
+                | CartridgeType::Mbc5RumbleRamBattery
+        )
+    }
+
+    pub fn has_rtc(&self) -> bool {
+        matches!(
+            self,
+            CartridgeType::Mbc3TimerBattery
+                | CartridgeType::Mbc3TimerRamBattery
+                | CartridgeType::Huc3
+        )
+    }
 }
 
 impl From<u8> for CartridgeType {
@@ -63,7 +104,7 @@ impl From<u8> for CartridgeType {
             0xFD => CartridgeType::BandaiTama5,
             0xFE => CartridgeType::Huc3,
             0xFF => CartridgeType::Huc1RamBattery,
-            other => panic!("Cartridge Type invalid: 0x{:02X}", other),
+            other => CartridgeType::Unknown(other),
         }
     }
 }
@@ -101,6 +142,7 @@ impl fmt::Display for CartridgeType {
             CartridgeType::BandaiTama5 => write!(f, "Bandai TAMA5"),
             CartridgeType::Huc3 => write!(f, "HuC3"),
             CartridgeType::Huc1RamBattery => write!(f, "HuC1 + RAM + Battery"),
+            CartridgeType::Unknown(x) => write!(f, "Unknown (0x{:02X})", x),
         }
     }
 }
@@ -0,0 +1,309 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::MbcOps;
+use crate::state::{StateReader, StateWriter};
+
+// Sensor M64282FP: 128x112 pixels, lidos na RAM bank 0 a partir de 0x0100 como tiles 2bpp
+pub const SENSOR_W: usize = 128;
+pub const SENSOR_H: usize = 112;
+const IMAGE_START: usize = 0x0100;
+const REGISTERS: usize = 0x36;
+// Registros da câmera aparecem em A000-BFFF quando o bit 4 do RAM bank está ligado
+const REGISTER_SELECT: u8 = 0x10;
+
+const CAPTURE_START: u8 = 0x01;
+// A001: bit 7 = N (sem o atraso extra da captura), bits 5-6 = realce de borda
+const FLAG_N: u8 = 0x80;
+const EDGE_2D: u8 = 0x60;
+// A004: bit 3 inverte a saída, bits 4-6 = intensidade do realce de borda
+const INVERT: u8 = 0x08;
+const EDGE_RATIOS: [i32; 8] = [2, 3, 4, 5, 8, 12, 16, 20]; // em quartos
+// Ciclos da captura (em T-cycles): base, atraso sem o N e por passo de exposição
+const CAPTURE_CYCLES: u64 = 129_792;
+const CAPTURE_N_CYCLES: u64 = 2048;
+const CAPTURE_EXPOSURE_CYCLES: u64 = 64;
+// Exposição em que a entrada passa sem ganho
+const NEUTRAL_EXPOSURE: u32 = 0x0800;
+// Matriz de dithering 4x4 com três limiares por posição
+const MATRIX_START: usize = 0x06;
+
+// Cinza (0 = preto) em 128x112 a partir de uma imagem RGBA qualquer: corta o centro
+// na proporção do sensor e amostra o pixel mais próximo
+pub fn sensor_image(width: usize, height: usize, rgba: &[u8]) -> Vec<u8> {
+    let (crop_w, crop_h) = if width * SENSOR_H > height * SENSOR_W {
+        (height * SENSOR_W / SENSOR_H, height)
+    } else {
+        (width, width * SENSOR_H / SENSOR_W)
+    };
+    let (left, top) = ((width - crop_w) / 2, (height - crop_h) / 2);
+
+    let mut image = Vec::with_capacity(SENSOR_W * SENSOR_H);
+    for y in 0..SENSOR_H {
+        for x in 0..SENSOR_W {
+            let sx = left + x * crop_w / SENSOR_W;
+            let sy = top + y * crop_h / SENSOR_H;
+            let pixel = &rgba[(sy * width + sx) * 4..][..3];
+            let luma =
+                (pixel[0] as u32 * 299 + pixel[1] as u32 * 587 + pixel[2] as u32 * 114) / 1000;
+            image.push(luma as u8);
+        }
+    }
+    image
+}
+
+// Sem imagem do host o sensor vê chuvisco (fixo, pra não quebrar runs reproduzíveis)
+fn noise_image() -> Vec<u8> {
+    (0..SENSOR_W * SENSOR_H)
+        .map(|i| {
+            let mut v = (i as u32).wrapping_mul(0x9E37_79B9);
+            v ^= v >> 15;
+            v = v.wrapping_mul(0x85EB_CA6B);
+            (v >> 24) as u8
+        })
+        .collect()
+}
+
+pub struct PocketCamera {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    rom_bank: u8, // 6 bits, bank 0 pode ser mapeado em 0x4000
+    ram_bank: u8, // 4 bits
+    ram_enabled: bool,
+    registers_selected: bool,
+    registers: [u8; REGISTERS],
+    // Ciclos até a captura em andamento terminar
+    countdown: u64,
+    image: Vec<u8>,
+}
+
+impl PocketCamera {
+    pub fn new(rom: Vec<u8>, ram_size: usize) -> Self {
+        Self {
+            rom,
+            ram: vec![0; ram_size],
+            rom_bank: 1,
+            ram_bank: 0,
+            ram_enabled: false,
+            registers_selected: false,
+            registers: [0; REGISTERS],
+            countdown: 0,
+            image: noise_image(),
+        }
+    }
+
+    fn rom_byte(&self, bank: usize, addr: u16) -> u8 {
+        let banks = (self.rom.len() / 0x4000).max(1);
+        let offset = (bank % banks) * 0x4000 + (addr as usize & 0x3FFF);
+        self.rom.get(offset).copied().unwrap_or(0xFF)
+    }
+
+    fn ram_offset(&self, addr: u16) -> Option<usize> {
+        if self.ram.is_empty() {
+            return None;
+        }
+        let offset = self.ram_bank as usize * 0x2000 + (addr as usize - 0xA000);
+        Some(offset % self.ram.len())
+    }
+
+    fn exposure(&self) -> u32 {
+        u16::from_be_bytes([self.registers[2], self.registers[3]]) as u32
+    }
+
+    fn start_capture(&mut self) {
+        let mut cycles = CAPTURE_CYCLES + self.exposure() as u64 * CAPTURE_EXPOSURE_CYCLES;
+        if self.registers[1] & FLAG_N == 0 {
+            cycles += CAPTURE_N_CYCLES;
+        }
+        self.countdown = cycles;
+    }
+
+    // Pixel do sensor depois da exposição (0 = preto), com a borda repetida
+    fn exposed(&self, x: isize, y: isize) -> i32 {
+        let x = x.clamp(0, SENSOR_W as isize - 1) as usize;
+        let y = y.clamp(0, SENSOR_H as isize - 1) as usize;
+        (self.image[y * SENSOR_W + x] as u32 * self.exposure() / NEUTRAL_EXPOSURE) as i32
+    }
+
+    // Exposição, realce de borda 2D (os outros modos passam direto) e quantização pela
+    // matriz; o resultado vai pra RAM no formato de tiles
+    fn capture(&mut self) {
+        let edge = self.registers[1] & EDGE_2D == EDGE_2D;
+        let ratio = EDGE_RATIOS[(self.registers[4] >> 4) as usize & 0x07];
+        let invert = self.registers[4] & INVERT != 0;
+
+        for y in 0..SENSOR_H {
+            for x in 0..SENSOR_W {
+                let (sx, sy) = (x as isize, y as isize);
+                let mut value = self.exposed(sx, sy);
+                if edge {
+                    let neighbours = self.exposed(sx - 1, sy)
+                        + self.exposed(sx + 1, sy)
+                        + self.exposed(sx, sy - 1)
+                        + self.exposed(sx, sy + 1);
+                    value += (value * 4 - neighbours) * ratio / 16;
+                }
+                let mut value = value.clamp(0, 255) as u8;
+                if invert {
+                    value = 255 - value;
+                }
+
+                let matrix = MATRIX_START + ((y & 3) * 4 + (x & 3)) * 3;
+                let thresholds = &self.registers[matrix..matrix + 3];
+                let color = if value < thresholds[0] {
+                    3
+                } else if value < thresholds[1] {
+                    2
+                } else if value < thresholds[2] {
+                    1
+                } else {
+                    0
+                };
+
+                let tile = (y / 8) * (SENSOR_W / 8) + x / 8;
+                let offset = IMAGE_START + tile * 16 + (y % 8) * 2;
+                let bit = 0x80 >> (x % 8);
+                for (plane, mask) in [(0, 1), (1, 2)] {
+                    if let Some(byte) = self.ram.get_mut(offset + plane) {
+                        if color & mask != 0 {
+                            *byte |= bit;
+                        } else {
+                            *byte &= !bit;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl MbcOps for PocketCamera {
+    fn reset(&mut self) {
+        self.rom_bank = 1;
+        self.ram_bank = 0;
+        self.ram_enabled = false;
+        self.registers_selected = false;
+        self.registers = [0; REGISTERS];
+        self.countdown = 0;
+    }
+
+    fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom_byte(0, addr),
+            0x4000..=0x7FFF => self.rom_byte(self.rom_bank as usize, addr),
+            0xA000..=0xBFFF => {
+                // Dos registros só o A000 (bit 0 = capturando) é legível
+                if self.registers_selected {
+                    return if addr & 0x7F == 0 {
+                        self.registers[0]
+                    } else {
+                        0x00
+                    };
+                }
+                // A RAM é legível mesmo sem o enable
+                match self.ram_offset(addr) {
+                    Some(offset) => self.ram[offset],
+                    None => 0xFF,
+                }
+            }
+            _ => 0xFF,
+        }
+    }
+
+    fn rom_bank(&self, addr: u16) -> usize {
+        match addr {
+            0x0000..=0x3FFF => 0,
+            _ => self.rom_bank as usize % (self.rom.len() / 0x4000).max(1),
+        }
+    }
+
+    fn ram_bank(&self) -> usize {
+        self.ram_bank as usize
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x0000..=0x1FFF => {
+                self.ram_enabled = (data & 0x0F) == 0x0A;
+            }
+            0x2000..=0x3FFF => {
+                self.rom_bank = data & 0x3F;
+            }
+            0x4000..=0x5FFF => {
+                self.registers_selected = data & REGISTER_SELECT != 0;
+                self.ram_bank = data & 0x0F;
+            }
+            0xA000..=0xBFFF => {
+                if self.registers_selected {
+                    // Registros espelhados a cada 0x80 bytes
+                    let register = addr as usize & 0x7F;
+                    if register == 0 {
+                        self.registers[0] = data & 0x07;
+                        if data & CAPTURE_START != 0 {
+                            self.start_capture();
+                        } else {
+                            self.countdown = 0;
+                        }
+                    } else if register < REGISTERS {
+                        self.registers[register] = data;
+                    }
+                } else if self.ram_enabled
+                    && let Some(offset) = self.ram_offset(addr)
+                {
+                    self.ram[offset] = data;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn tick(&mut self, t_cycles: u64) {
+        if self.countdown == 0 {
+            return;
+        }
+        self.countdown = self.countdown.saturating_sub(t_cycles);
+        if self.countdown == 0 {
+            self.capture();
+            self.registers[0] &= !CAPTURE_START;
+        }
+    }
+
+    fn set_camera_image(&mut self, image: &[u8]) {
+        if image.len() == SENSOR_W * SENSOR_H {
+            self.image = image.to_vec();
+        }
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.rom_bank);
+        w.write_u8(self.ram_bank);
+        w.write_bool(self.ram_enabled);
+        w.write_bool(self.registers_selected);
+        w.write_bytes(&self.registers);
+        w.write_u32(self.countdown as u32);
+        w.write_bytes(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.rom_bank = r.read_u8()?;
+        self.ram_bank = r.read_u8()?;
+        self.ram_enabled = r.read_bool()?;
+        self.registers_selected = r.read_bool()?;
+        r.read_bytes(&mut self.registers)?;
+        self.countdown = r.read_u32()? as u64;
+        r.read_bytes(&mut self.ram)
+    }
+}
@@ -0,0 +1,155 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::MbcOps;
+use super::ir::{IrDevice, NoIr};
+use crate::state::{StateReader, StateWriter};
+
+// Escrever 0x0E em 0x0000-0x1FFF troca a RAM pela porta infravermelha
+const IR_MODE: u8 = 0x0E;
+
+pub struct Huc1 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    rom_bank: u8, // 6 bits, 0 vira 1
+    ram_bank: u8, // 2 bits
+    ir_mode: bool,
+    led: bool,
+    ir: Box<dyn IrDevice>,
+}
+
+impl Huc1 {
+    pub fn new(rom: Vec<u8>, ram_size: usize) -> Self {
+        Self {
+            rom,
+            ram: vec![0; ram_size],
+            rom_bank: 1,
+            ram_bank: 0,
+            ir_mode: false,
+            led: false,
+            ir: Box::new(NoIr),
+        }
+    }
+
+    fn rom_byte(&self, bank: usize, addr: u16) -> u8 {
+        let banks = (self.rom.len() / 0x4000).max(1);
+        let offset = (bank % banks) * 0x4000 + (addr as usize & 0x3FFF);
+        self.rom.get(offset).copied().unwrap_or(0xFF)
+    }
+
+    fn ram_offset(&self, addr: u16) -> Option<usize> {
+        if self.ram.is_empty() {
+            return None;
+        }
+        let offset = self.ram_bank as usize * 0x2000 + (addr as usize - 0xA000);
+        Some(offset % self.ram.len())
+    }
+
+    fn set_led(&mut self, on: bool) {
+        self.led = on;
+        self.ir.set_led(on);
+    }
+}
+
+impl MbcOps for Huc1 {
+    fn reset(&mut self) {
+        self.rom_bank = 1;
+        self.ram_bank = 0;
+        self.ir_mode = false;
+        self.set_led(false);
+    }
+
+    fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom_byte(0, addr),
+            0x4000..=0x7FFF => self.rom_byte(self.rom_bank as usize, addr),
+            0xA000..=0xBFFF => {
+                // Bit 0 = sensor vendo luz, os de cima sempre 1
+                if self.ir_mode {
+                    return 0xC0 | self.ir.light() as u8;
+                }
+                match self.ram_offset(addr) {
+                    Some(offset) => self.ram[offset],
+                    None => 0xFF,
+                }
+            }
+            _ => 0xFF,
+        }
+    }
+
+    fn rom_bank(&self, addr: u16) -> usize {
+        match addr {
+            0x0000..=0x3FFF => 0,
+            _ => self.rom_bank as usize % (self.rom.len() / 0x4000).max(1),
+        }
+    }
+
+    fn ram_bank(&self) -> usize {
+        self.ram_bank as usize
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x0000..=0x1FFF => {
+                // Sem enable: fora do modo IR a RAM está sempre acessível
+                self.ir_mode = (data & 0x0F) == IR_MODE;
+            }
+            0x2000..=0x3FFF => {
+                let bank = data & 0x3F;
+                self.rom_bank = if bank == 0 { 1 } else { bank };
+            }
+            0x4000..=0x5FFF => {
+                self.ram_bank = data & 0x03;
+            }
+            0xA000..=0xBFFF => {
+                if self.ir_mode {
+                    self.set_led(data & 0x01 != 0);
+                } else if let Some(offset) = self.ram_offset(addr) {
+                    self.ram[offset] = data;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn set_ir_device(&mut self, device: Box<dyn IrDevice>) {
+        self.ir = device;
+        self.ir.set_led(self.led);
+    }
+
+    fn take_ir_device(&mut self) -> Option<Box<dyn IrDevice>> {
+        self.ir.set_led(false);
+        Some(core::mem::replace(&mut self.ir, Box::new(NoIr)))
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.rom_bank);
+        w.write_u8(self.ram_bank);
+        w.write_bool(self.ir_mode);
+        w.write_bool(self.led);
+        w.write_bytes(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.rom_bank = r.read_u8()?;
+        self.ram_bank = r.read_u8()?;
+        self.ir_mode = r.read_bool()?;
+        let led = r.read_bool()?;
+        self.set_led(led);
+        r.read_bytes(&mut self.ram)
+    }
+}
@@ -0,0 +1,322 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::MbcOps;
+use super::ir::{IrDevice, NoIr};
+use super::rtc::{CYCLES_PER_SECOND, Clock};
+use crate::state::{StateReader, StateWriter};
+
+const MINUTES_PER_DAY: u64 = 24 * 60;
+const CYCLES_PER_MINUTE: u64 = 60 * CYCLES_PER_SECOND;
+// Memória do chip do relógio: 256 nibbles, endereçados pelos comandos 4 e 5. Os
+// nibbles 0x00-0x06 recebem a hora no comando de leitura (minutos e dias, 3 cada)
+const MEMORY_SIZE: usize = 256;
+// 0x26 = 1 liga o piezo, 0x27 = qual melodia
+const TONE_ENABLE: usize = 0x26;
+const TONE_SELECT: usize = 0x27;
+// Rodapé do .sav: minutos e dias (u16 LE), memória com dois nibbles por byte e
+// timestamp UNIX (u64 LE)
+const FOOTER_SIZE: usize = 4 + MEMORY_SIZE / 2 + 8;
+
+// Modos selecionados em 0x0000-0x1FFF (nibble de baixo)
+const MODE_RAM_READ: u8 = 0x0;
+const MODE_RAM: u8 = 0xA;
+const MODE_COMMAND: u8 = 0xB;
+const MODE_RESULT: u8 = 0xC;
+const MODE_SEMAPHORE: u8 = 0xD;
+const MODE_IR: u8 = 0xE;
+
+pub struct Huc3 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    rom_bank: u8, // 7 bits, 0 vira 1
+    ram_bank: u8, // 2 bits
+    mode: u8,
+    // Relógio: minutos do dia e dias (12 bits), sem segundos
+    minutes: u16,
+    days: u16,
+    cycles: u64, // ciclos desde o último minuto
+    memory: Vec<u8>,
+    address: u8,
+    // Resposta do último comando, lida no modo 0xC
+    result: u8,
+    // Melodia pedida pelo jogo e ainda não entregue pro frontend
+    tone: Option<u8>,
+    led: bool,
+    ir: Box<dyn IrDevice>,
+}
+
+impl Huc3 {
+    pub fn new(rom: Vec<u8>, ram_size: usize) -> Self {
+        Self {
+            rom,
+            ram: vec![0; ram_size],
+            rom_bank: 1,
+            ram_bank: 0,
+            mode: MODE_RAM_READ,
+            minutes: 0,
+            days: 0,
+            cycles: 0,
+            memory: vec![0; MEMORY_SIZE],
+            address: 0,
+            result: 0,
+            tone: None,
+            led: false,
+            ir: Box::new(NoIr),
+        }
+    }
+
+    fn rom_byte(&self, bank: usize, addr: u16) -> u8 {
+        let banks = (self.rom.len() / 0x4000).max(1);
+        let offset = (bank % banks) * 0x4000 + (addr as usize & 0x3FFF);
+        self.rom.get(offset).copied().unwrap_or(0xFF)
+    }
+
+    fn ram_offset(&self, addr: u16) -> Option<usize> {
+        if self.ram.is_empty() {
+            return None;
+        }
+        let offset = self.ram_bank as usize * 0x2000 + (addr as usize - 0xA000);
+        Some(offset % self.ram.len())
+    }
+
+    fn set_led(&mut self, on: bool) {
+        self.led = on;
+        self.ir.set_led(on);
+    }
+
+    fn add_minutes(&mut self, minutes: u64) {
+        let total = self.minutes as u64 + minutes;
+        self.minutes = (total % MINUTES_PER_DAY) as u16;
+        self.days = ((self.days as u64 + total / MINUTES_PER_DAY) & 0xFFF) as u16;
+    }
+
+    // Bits 4-6 = comando, bits 0-3 = argumento
+    fn command(&mut self, data: u8) {
+        let arg = data & 0x0F;
+        match (data >> 4) & 0x07 {
+            // Lê o nibble do endereço atual e avança
+            0x1 => {
+                self.result = self.memory[self.address as usize];
+                self.address = self.address.wrapping_add(1);
+            }
+            // Escreve no endereço atual (0x3 avança depois)
+            0x2 => self.memory[self.address as usize] = arg,
+            0x3 => {
+                self.memory[self.address as usize] = arg;
+                self.address = self.address.wrapping_add(1);
+            }
+            0x4 => self.address = (self.address & 0xF0) | arg,
+            0x5 => self.address = (self.address & 0x0F) | (arg << 4),
+            0x6 => self.extended(arg),
+            _ => {}
+        }
+    }
+
+    fn extended(&mut self, arg: u8) {
+        match arg {
+            // Hora atual pra memória
+            0x0 => {
+                for i in 0..3 {
+                    self.memory[i] = (self.minutes >> (i * 4)) as u8 & 0x0F;
+                    self.memory[3 + i] = (self.days >> (i * 4)) as u8 & 0x0F;
+                }
+            }
+            // Memória pra hora atual
+            0x1 => {
+                let nibbles = |start: usize| {
+                    (0..3).fold(0u16, |value, i| {
+                        value | ((self.memory[start + i] as u16) << (i * 4))
+                    })
+                };
+                self.minutes = nibbles(0) % MINUTES_PER_DAY as u16;
+                self.days = nibbles(3);
+                self.cycles = 0;
+            }
+            // Pedido de status: o jogo espera 1
+            0x2 => self.result = 0x01,
+            // Piezo: por enquanto só avisa qual melodia tocaria
+            0xE if self.memory[TONE_ENABLE] == 1 => {
+                self.tone = Some(self.memory[TONE_SELECT]);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl MbcOps for Huc3 {
+    fn reset(&mut self) {
+        self.rom_bank = 1;
+        self.ram_bank = 0;
+        self.mode = MODE_RAM_READ;
+        self.address = 0;
+        self.result = 0;
+        self.tone = None;
+        self.set_led(false);
+    }
+
+    fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom_byte(0, addr),
+            0x4000..=0x7FFF => self.rom_byte(self.rom_bank as usize, addr),
+            0xA000..=0xBFFF => match self.mode {
+                MODE_RAM_READ | MODE_RAM => match self.ram_offset(addr) {
+                    Some(offset) => self.ram[offset],
+                    None => 0xFF,
+                },
+                MODE_RESULT => self.result,
+                // Comando sempre pronto
+                MODE_SEMAPHORE => 0x01,
+                MODE_IR => 0xC0 | self.ir.light() as u8,
+                _ => 0xFF,
+            },
+            _ => 0xFF,
+        }
+    }
+
+    fn rom_bank(&self, addr: u16) -> usize {
+        match addr {
+            0x0000..=0x3FFF => 0,
+            _ => self.rom_bank as usize % (self.rom.len() / 0x4000).max(1),
+        }
+    }
+
+    fn ram_bank(&self) -> usize {
+        self.ram_bank as usize
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x0000..=0x1FFF => {
+                self.mode = data & 0x0F;
+            }
+            0x2000..=0x3FFF => {
+                let bank = data & 0x7F;
+                self.rom_bank = if bank == 0 { 1 } else { bank };
+            }
+            0x4000..=0x5FFF => {
+                self.ram_bank = data & 0x03;
+            }
+            0xA000..=0xBFFF => match self.mode {
+                MODE_RAM => {
+                    if let Some(offset) = self.ram_offset(addr) {
+                        self.ram[offset] = data;
+                    }
+                }
+                MODE_COMMAND => self.command(data),
+                MODE_IR => self.set_led(data & 0x01 != 0),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn tick(&mut self, t_cycles: u64) {
+        self.cycles += t_cycles;
+        if self.cycles >= CYCLES_PER_MINUTE {
+            self.add_minutes(self.cycles / CYCLES_PER_MINUTE);
+            self.cycles %= CYCLES_PER_MINUTE;
+        }
+    }
+
+    fn clock(&self) -> Option<&dyn Clock> {
+        Some(self)
+    }
+
+    fn clock_mut(&mut self) -> Option<&mut dyn Clock> {
+        Some(self)
+    }
+
+    fn take_tone(&mut self) -> Option<u8> {
+        self.tone.take()
+    }
+
+    fn set_ir_device(&mut self, device: Box<dyn IrDevice>) {
+        self.ir = device;
+        self.ir.set_led(self.led);
+    }
+
+    fn take_ir_device(&mut self) -> Option<Box<dyn IrDevice>> {
+        self.ir.set_led(false);
+        Some(core::mem::replace(&mut self.ir, Box::new(NoIr)))
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.rom_bank);
+        w.write_u8(self.ram_bank);
+        w.write_u8(self.mode);
+        w.write_u16(self.minutes);
+        w.write_u16(self.days);
+        w.write_u32(self.cycles as u32);
+        w.write_bytes(&self.memory);
+        w.write_u8(self.address);
+        w.write_u8(self.result);
+        w.write_bool(self.led);
+        w.write_bytes(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.rom_bank = r.read_u8()?;
+        self.ram_bank = r.read_u8()?;
+        self.mode = r.read_u8()?;
+        self.minutes = r.read_u16()?;
+        self.days = r.read_u16()?;
+        self.cycles = r.read_u32()? as u64;
+        r.read_bytes(&mut self.memory)?;
+        self.address = r.read_u8()?;
+        self.result = r.read_u8()?;
+        let led = r.read_bool()?;
+        self.set_led(led);
+        self.tone = None;
+        r.read_bytes(&mut self.ram)
+    }
+}
+
+impl Clock for Huc3 {
+    fn write_footer(&self, out: &mut Vec<u8>, timestamp: u64) {
+        out.extend_from_slice(&self.minutes.to_le_bytes());
+        out.extend_from_slice(&self.days.to_le_bytes());
+        for pair in self.memory.chunks(2) {
+            out.push(pair[0] | (pair[1] << 4));
+        }
+        out.extend_from_slice(&timestamp.to_le_bytes());
+    }
+
+    fn read_footer(&mut self, footer: &[u8]) -> Result<u64, String> {
+        if footer.len() != FOOTER_SIZE {
+            return Err(format!(
+                "rodapé de RTC do HuC3 com tamanho inválido: {} bytes",
+                footer.len()
+            ));
+        }
+        let (time, rest) = footer.split_at(4);
+        let (memory, timestamp) = rest.split_at(MEMORY_SIZE / 2);
+        self.minutes = u16::from_le_bytes([time[0], time[1]]) % MINUTES_PER_DAY as u16;
+        self.days = u16::from_le_bytes([time[2], time[3]]) & 0xFFF;
+        for (i, &byte) in memory.iter().enumerate() {
+            self.memory[i * 2] = byte & 0x0F;
+            self.memory[i * 2 + 1] = byte >> 4;
+        }
+        self.cycles = 0;
+        Ok(u64::from_le_bytes(timestamp.try_into().unwrap()))
+    }
+
+    fn advance(&mut self, seconds: u64) {
+        self.tick(seconds * CYCLES_PER_SECOND);
+    }
+}
@@ -0,0 +1,17 @@
+// Porta infravermelha dos cartuchos HuC: o LED que o jogo acende e o sensor que
+// enxerga a luz vinda do outro lado
+pub trait IrDevice: Send {
+    fn set_led(&mut self, on: bool);
+    fn light(&self) -> bool;
+}
+
+// Ninguém do outro lado: o sensor nunca vê luz
+pub struct NoIr;
+
+impl IrDevice for NoIr {
+    fn set_led(&mut self, _on: bool) {}
+
+    fn light(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,69 @@
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use super::ir::IrDevice;
+
+// Duas instâncias na mesma máquina trocando o estado do LED por UDP no localhost: o LED
+// de uma acende o sensor da outra. Sem sincronia de ciclos, então só serve pros
+// protocolos que toleram atraso
+pub struct IrSocket {
+    socket: UdpSocket,
+    led: bool,
+    light: Arc<AtomicBool>,
+}
+
+impl IrSocket {
+    pub fn bind(port: u16, peer: u16) -> Result<Self, String> {
+        let socket = UdpSocket::bind(("127.0.0.1", port))
+            .map_err(|e| format!("não foi possível abrir a porta IR {}: {}", port, e))?;
+        socket
+            .connect(("127.0.0.1", peer))
+            .map_err(|e| format!("não foi possível ligar a porta IR em {}: {}", peer, e))?;
+        let receiver = socket.try_clone().map_err(|e| e.to_string())?;
+
+        let light = Arc::new(AtomicBool::new(false));
+        let seen = light.clone();
+        thread::spawn(move || {
+            let mut buf = [0u8; 1];
+            // Erro de envio pro outro lado (ainda fechado) também cai aqui; só ignora
+            loop {
+                if let Ok(1) = receiver.recv(&mut buf) {
+                    seen.store(buf[0] != 0, Ordering::Relaxed);
+                }
+            }
+        });
+
+        Ok(Self {
+            socket,
+            led: false,
+            light,
+        })
+    }
+}
+
+impl IrSocket {
+    // Outra ponta local no mesmo socket (porta do CGB e do cartucho HuC): cada uma acende
+    // o próprio LED e as duas veem a mesma luz
+    pub fn try_clone(&self) -> Result<Self, String> {
+        Ok(Self {
+            socket: self.socket.try_clone().map_err(|e| e.to_string())?,
+            led: false,
+            light: self.light.clone(),
+        })
+    }
+}
+
+impl IrDevice for IrSocket {
+    fn set_led(&mut self, on: bool) {
+        if on != self.led {
+            self.led = on;
+            let _ = self.socket.send(&[on as u8]);
+        }
+    }
+
+    fn light(&self) -> bool {
+        self.light.load(Ordering::Relaxed)
+    }
+}
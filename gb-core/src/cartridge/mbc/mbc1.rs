@@ -1,4 +1,9 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
 use super::MbcOps;
+use crate::state::{StateReader, StateWriter};
 
 pub struct Mbc1 {
     rom: Vec<u8>,
@@ -32,26 +37,36 @@ impl Mbc1 {
         }
         bank
     }
+
+    // Banco além do tamanho da ROM dá a volta, como nos pinos de endereço que não existem
+    fn rom_byte(&self, bank: usize, addr: u16) -> u8 {
+        let banks = (self.rom.len() / 0x4000).max(1);
+        let offset = (bank % banks) * 0x4000 + (addr as usize & 0x3FFF);
+        self.rom.get(offset).copied().unwrap_or(0xFF)
+    }
 }
 
 impl MbcOps for Mbc1 {
+    fn reset(&mut self) {
+        self.rom_bank = 1;
+        self.ram_bank_or_upper = 0;
+        self.ram_enabled = false;
+        self.mode = 0;
+    }
+
     fn read(&self, addr: u16) -> u8 {
         match addr {
             0x0000..=0x3FFF => {
                 // Modo 0: bank 0 fixo
                 // Modo 1 (ROMs > 512KB): upper bits aplicados
-                let offset = if self.mode == 0 {
-                    addr as usize
+                let bank = if self.mode == 0 {
+                    0
                 } else {
-                    ((self.ram_bank_or_upper as usize) << 5) * 0x4000 + addr as usize
+                    (self.ram_bank_or_upper as usize) << 5
                 };
-                self.rom[offset]
-            }
-            0x4000..=0x7FFF => {
-                let bank = self.effective_rom_bank();
-                let offset = bank * 0x4000 + (addr as usize - 0x4000);
-                self.rom[offset]
+                self.rom_byte(bank, addr)
             }
+            0x4000..=0x7FFF => self.rom_byte(self.effective_rom_bank(), addr),
             0xA000..=0xBFFF => {
                 if !self.ram_enabled || self.ram.is_empty() {
                     return 0xFF;
@@ -62,12 +77,28 @@ impl MbcOps for Mbc1 {
                     0
                 };
                 let offset = bank * 0x2000 + (addr as usize - 0xA000);
-                self.ram[offset]
+                self.ram.get(offset).copied().unwrap_or(0xFF)
             }
             _ => 0xFF,
         }
     }
 
+    fn rom_bank(&self, addr: u16) -> usize {
+        match addr {
+            0x0000..=0x3FFF if self.mode == 0 => 0,
+            0x0000..=0x3FFF => (self.ram_bank_or_upper as usize) << 5,
+            _ => self.effective_rom_bank(),
+        }
+    }
+
+    fn ram_bank(&self) -> usize {
+        if self.mode == 1 {
+            self.ram_bank_or_upper as usize
+        } else {
+            0
+        }
+    }
+
     fn write(&mut self, addr: u16, data: u8) {
         match addr {
             0x0000..=0x1FFF => {
@@ -104,4 +135,32 @@ impl MbcOps for Mbc1 {
             _ => {}
         }
     }
+
+    fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.rom_bank);
+        w.write_u8(self.ram_bank_or_upper);
+        w.write_bool(self.ram_enabled);
+        w.write_u8(self.mode);
+        w.write_bytes(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.rom_bank = r.read_u8()?;
+        self.ram_bank_or_upper = r.read_u8()?;
+        self.ram_enabled = r.read_bool()?;
+        self.mode = r.read_u8()?;
+        r.read_bytes(&mut self.ram)
+    }
 }
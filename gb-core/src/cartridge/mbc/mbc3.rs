@@ -0,0 +1,173 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::MbcOps;
+use super::rtc::{Clock, Rtc};
+use crate::state::{StateReader, StateWriter};
+
+pub struct Mbc3 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    rom_bank: u8,      // 7 bits, 0 vira 1
+    ram_select: u8,    // 0x00-0x03 = RAM bank, 0x08-0x0C = registro do RTC
+    ram_enabled: bool, // também habilita o RTC
+    latch: u8,         // último valor escrito em 0x6000-0x7FFF
+    rtc: Option<Rtc>,
+}
+
+impl Mbc3 {
+    pub fn new(rom: Vec<u8>, ram_size: usize, has_rtc: bool) -> Self {
+        Self {
+            rom,
+            ram: vec![0; ram_size],
+            rom_bank: 1,
+            ram_select: 0,
+            ram_enabled: false,
+            latch: 0xFF,
+            rtc: has_rtc.then(Rtc::new),
+        }
+    }
+
+    fn rom_byte(&self, bank: usize, addr: u16) -> u8 {
+        let banks = (self.rom.len() / 0x4000).max(1);
+        let offset = (bank % banks) * 0x4000 + (addr as usize & 0x3FFF);
+        self.rom.get(offset).copied().unwrap_or(0xFF)
+    }
+
+    fn ram_offset(&self, addr: u16) -> Option<usize> {
+        let offset = self.ram_select as usize * 0x2000 + (addr as usize - 0xA000);
+        (offset < self.ram.len()).then_some(offset)
+    }
+}
+
+impl MbcOps for Mbc3 {
+    fn reset(&mut self) {
+        self.rom_bank = 1;
+        self.ram_select = 0;
+        self.ram_enabled = false;
+        self.latch = 0xFF;
+    }
+
+    fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom_byte(0, addr),
+            0x4000..=0x7FFF => self.rom_byte(self.rom_bank as usize, addr),
+            0xA000..=0xBFFF => {
+                if !self.ram_enabled {
+                    return 0xFF;
+                }
+                match (self.ram_select, &self.rtc) {
+                    (0x08..=0x0C, Some(rtc)) => rtc.read(self.ram_select),
+                    (0x00..=0x03, _) => match self.ram_offset(addr) {
+                        Some(offset) => self.ram[offset],
+                        None => 0xFF,
+                    },
+                    _ => 0xFF,
+                }
+            }
+            _ => 0xFF,
+        }
+    }
+
+    fn rom_bank(&self, addr: u16) -> usize {
+        match addr {
+            0x0000..=0x3FFF => 0,
+            _ => self.rom_bank as usize % (self.rom.len() / 0x4000).max(1),
+        }
+    }
+
+    // 08-0C: registro do RTC mapeado no lugar da RAM
+    fn ram_bank(&self) -> usize {
+        self.ram_select as usize
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x0000..=0x1FFF => {
+                self.ram_enabled = (data & 0x0F) == 0x0A;
+            }
+            0x2000..=0x3FFF => {
+                let bank = data & 0x7F;
+                self.rom_bank = if bank == 0 { 1 } else { bank };
+            }
+            0x4000..=0x5FFF => {
+                self.ram_select = data;
+            }
+            0x6000..=0x7FFF => {
+                // Latch na transição 0x00 -> 0x01
+                if self.latch == 0x00
+                    && data == 0x01
+                    && let Some(rtc) = &mut self.rtc
+                {
+                    rtc.latch();
+                }
+                self.latch = data;
+            }
+            0xA000..=0xBFFF => {
+                if !self.ram_enabled {
+                    return;
+                }
+                match (self.ram_select, &mut self.rtc) {
+                    (0x08..=0x0C, Some(rtc)) => rtc.write(self.ram_select, data),
+                    (0x00..=0x03, _) => {
+                        if let Some(offset) = self.ram_offset(addr) {
+                            self.ram[offset] = data;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn tick(&mut self, t_cycles: u64) {
+        if let Some(rtc) = &mut self.rtc {
+            rtc.tick(t_cycles);
+        }
+    }
+
+    fn clock(&self) -> Option<&dyn Clock> {
+        self.rtc.as_ref().map(|rtc| rtc as &dyn Clock)
+    }
+
+    fn clock_mut(&mut self) -> Option<&mut dyn Clock> {
+        self.rtc.as_mut().map(|rtc| rtc as &mut dyn Clock)
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.rom_bank);
+        w.write_u8(self.ram_select);
+        w.write_bool(self.ram_enabled);
+        w.write_u8(self.latch);
+        w.write_bytes(&self.ram);
+        if let Some(rtc) = &self.rtc {
+            rtc.save_state(w);
+        }
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.rom_bank = r.read_u8()?;
+        self.ram_select = r.read_u8()?;
+        self.ram_enabled = r.read_bool()?;
+        self.latch = r.read_u8()?;
+        r.read_bytes(&mut self.ram)?;
+        if let Some(rtc) = &mut self.rtc {
+            rtc.load_state(r)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::MbcOps;
+use crate::state::{StateReader, StateWriter};
+
+pub struct Mbc5 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    rom_bank: u16, // 9 bits, bank 0 pode ser mapeado em 0x4000
+    ram_bank: u8,  // 4 bits (3 nos cartuchos com rumble)
+    ram_enabled: bool,
+    // Nos cartuchos com rumble o bit 3 do RAM bank liga o motor
+    has_rumble: bool,
+    motor: bool,
+}
+
+impl Mbc5 {
+    pub fn new(rom: Vec<u8>, ram_size: usize, has_rumble: bool) -> Self {
+        Self {
+            rom,
+            ram: vec![0; ram_size],
+            rom_bank: 1,
+            ram_bank: 0,
+            ram_enabled: false,
+            has_rumble,
+            motor: false,
+        }
+    }
+
+    fn rom_byte(&self, bank: usize, addr: u16) -> u8 {
+        let banks = (self.rom.len() / 0x4000).max(1);
+        let offset = (bank % banks) * 0x4000 + (addr as usize & 0x3FFF);
+        self.rom.get(offset).copied().unwrap_or(0xFF)
+    }
+
+    fn ram_offset(&self, addr: u16) -> Option<usize> {
+        if self.ram.is_empty() {
+            return None;
+        }
+        let offset = self.ram_bank as usize * 0x2000 + (addr as usize - 0xA000);
+        Some(offset % self.ram.len())
+    }
+}
+
+impl MbcOps for Mbc5 {
+    fn reset(&mut self) {
+        self.rom_bank = 1;
+        self.ram_bank = 0;
+        self.ram_enabled = false;
+        self.motor = false;
+    }
+
+    fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom_byte(0, addr),
+            0x4000..=0x7FFF => self.rom_byte(self.rom_bank as usize, addr),
+            0xA000..=0xBFFF => match self.ram_offset(addr) {
+                Some(offset) if self.ram_enabled => self.ram[offset],
+                _ => 0xFF,
+            },
+            _ => 0xFF,
+        }
+    }
+
+    fn rom_bank(&self, addr: u16) -> usize {
+        match addr {
+            0x0000..=0x3FFF => 0,
+            _ => self.rom_bank as usize % (self.rom.len() / 0x4000).max(1),
+        }
+    }
+
+    fn ram_bank(&self) -> usize {
+        self.ram_bank as usize
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x0000..=0x1FFF => {
+                // No MBC5 só 0x0A exato habilita
+                self.ram_enabled = data == 0x0A;
+            }
+            0x2000..=0x2FFF => {
+                self.rom_bank = (self.rom_bank & 0x100) | data as u16;
+            }
+            0x3000..=0x3FFF => {
+                self.rom_bank = (self.rom_bank & 0xFF) | (((data & 0x01) as u16) << 8);
+            }
+            0x4000..=0x5FFF => {
+                if self.has_rumble {
+                    self.motor = data & 0x08 != 0;
+                    self.ram_bank = data & 0x07;
+                } else {
+                    self.ram_bank = data & 0x0F;
+                }
+            }
+            0xA000..=0xBFFF => {
+                if self.ram_enabled
+                    && let Some(offset) = self.ram_offset(addr)
+                {
+                    self.ram[offset] = data;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn rumble(&self) -> bool {
+        self.motor
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u16(self.rom_bank);
+        w.write_u8(self.ram_bank);
+        w.write_bool(self.ram_enabled);
+        w.write_bool(self.motor);
+        w.write_bytes(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.rom_bank = r.read_u16()?;
+        self.ram_bank = r.read_u8()?;
+        self.ram_enabled = r.read_bool()?;
+        self.motor = r.read_bool()?;
+        r.read_bytes(&mut self.ram)
+    }
+}
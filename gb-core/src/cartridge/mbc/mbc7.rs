@@ -0,0 +1,410 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::MbcOps;
+use crate::joypad::Tilt;
+use crate::state::{StateReader, StateWriter};
+
+// EEPROM 93LC56: 128 palavras de 16 bits
+const EEPROM_SIZE: usize = 256;
+
+// Acelerômetro: centro e variação pra 1 g
+const ACCEL_CENTER: f32 = 0x81D0 as f32;
+const ACCEL_RANGE: f32 = 0x70 as f32;
+
+// Pinos do registro da EEPROM (0xA080-0xA08F)
+const EEPROM_CS: u8 = 0x80;
+const EEPROM_CLK: u8 = 0x40;
+const EEPROM_DI: u8 = 0x02;
+const EEPROM_DO: u8 = 0x01;
+
+// Protocolo serial da EEPROM: bit de início, opcode de 2 bits, endereço de 8 e, na
+// escrita, 16 bits de dado; a leitura devolve um 0 e depois a palavra, MSB primeiro
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum EepromState {
+    Idle,
+    Command {
+        value: u16,
+        bits: u8,
+    },
+    Write {
+        addr: Option<u8>,
+        value: u16,
+        bits: u8,
+    },
+    Read {
+        value: u16,
+        bits: u8,
+    },
+}
+
+struct Eeprom {
+    data: Vec<u8>,
+    cs: bool,
+    clk: bool,
+    di: bool,
+    out: bool,
+    write_enabled: bool,
+    state: EepromState,
+}
+
+impl Eeprom {
+    fn new() -> Self {
+        Self {
+            data: vec![0xFF; EEPROM_SIZE],
+            cs: false,
+            clk: false,
+            di: false,
+            out: true,
+            write_enabled: false,
+            state: EepromState::Idle,
+        }
+    }
+
+    fn word(&self, addr: u8) -> u16 {
+        let index = (addr as usize & 0x7F) * 2;
+        u16::from_le_bytes([self.data[index], self.data[index + 1]])
+    }
+
+    fn set_word(&mut self, addr: u8, value: u16) {
+        let index = (addr as usize & 0x7F) * 2;
+        self.data[index..index + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn read(&self) -> u8 {
+        let mut value = 0;
+        if self.cs {
+            value |= EEPROM_CS;
+        }
+        if self.clk {
+            value |= EEPROM_CLK;
+        }
+        if self.di {
+            value |= EEPROM_DI;
+        }
+        if self.out {
+            value |= EEPROM_DO;
+        }
+        value
+    }
+
+    fn write(&mut self, data: u8) {
+        let cs = data & EEPROM_CS != 0;
+        let clk = data & EEPROM_CLK != 0;
+        self.di = data & EEPROM_DI != 0;
+
+        // CS baixo cancela o comando em andamento
+        if !cs {
+            self.state = EepromState::Idle;
+        } else if !self.clk && clk {
+            self.clock();
+        }
+        self.cs = cs;
+        self.clk = clk;
+    }
+
+    // Borda de subida do clock
+    fn clock(&mut self) {
+        let di = self.di as u16;
+        self.state = match self.state {
+            EepromState::Idle if di == 1 => EepromState::Command { value: 0, bits: 0 },
+            EepromState::Idle => EepromState::Idle,
+            EepromState::Command { value, bits } => {
+                let value = (value << 1) | di;
+                if bits + 1 < 10 {
+                    EepromState::Command {
+                        value,
+                        bits: bits + 1,
+                    }
+                } else {
+                    self.command((value >> 8) as u8 & 3, value as u8)
+                }
+            }
+            EepromState::Write { addr, value, bits } => {
+                let value = (value << 1) | di;
+                if bits + 1 < 16 {
+                    EepromState::Write {
+                        addr,
+                        value,
+                        bits: bits + 1,
+                    }
+                } else {
+                    if self.write_enabled {
+                        match addr {
+                            Some(addr) => self.set_word(addr, value),
+                            None => (0..128).for_each(|addr| self.set_word(addr, value)),
+                        }
+                    }
+                    // Escrita instantânea: DO alto = pronto
+                    self.out = true;
+                    EepromState::Idle
+                }
+            }
+            EepromState::Read { value, bits } => {
+                self.out = value & 0x8000 != 0;
+                if bits > 1 {
+                    EepromState::Read {
+                        value: value << 1,
+                        bits: bits - 1,
+                    }
+                } else {
+                    EepromState::Idle
+                }
+            }
+        };
+    }
+
+    fn command(&mut self, opcode: u8, addr: u8) -> EepromState {
+        match opcode {
+            // READ
+            0b10 => {
+                self.out = false;
+                EepromState::Read {
+                    value: self.word(addr),
+                    bits: 16,
+                }
+            }
+            // WRITE
+            0b01 => EepromState::Write {
+                addr: Some(addr),
+                value: 0,
+                bits: 0,
+            },
+            // ERASE
+            0b11 => {
+                if self.write_enabled {
+                    self.set_word(addr, 0xFFFF);
+                }
+                self.out = true;
+                EepromState::Idle
+            }
+            // Os dois bits altos do endereço escolhem o comando estendido
+            _ => match addr >> 6 {
+                // EWDS
+                0b00 => {
+                    self.write_enabled = false;
+                    EepromState::Idle
+                }
+                // WRAL
+                0b01 => EepromState::Write {
+                    addr: None,
+                    value: 0,
+                    bits: 0,
+                },
+                // ERAL
+                0b10 => {
+                    if self.write_enabled {
+                        self.data.fill(0xFF);
+                    }
+                    self.out = true;
+                    EepromState::Idle
+                }
+                // EWEN
+                _ => {
+                    self.write_enabled = true;
+                    EepromState::Idle
+                }
+            },
+        }
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bytes(&self.data);
+        w.write_bool(self.cs);
+        w.write_bool(self.clk);
+        w.write_bool(self.di);
+        w.write_bool(self.out);
+        w.write_bool(self.write_enabled);
+        let (kind, addr, value, bits) = match self.state {
+            EepromState::Idle => (0, 0, 0, 0),
+            EepromState::Command { value, bits } => (1, 0, value, bits),
+            EepromState::Write {
+                addr: Some(addr),
+                value,
+                bits,
+            } => (2, addr, value, bits),
+            EepromState::Write {
+                addr: None,
+                value,
+                bits,
+            } => (3, 0, value, bits),
+            EepromState::Read { value, bits } => (4, 0, value, bits),
+        };
+        w.write_u8(kind);
+        w.write_u8(addr);
+        w.write_u16(value);
+        w.write_u8(bits);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        r.read_bytes(&mut self.data)?;
+        self.cs = r.read_bool()?;
+        self.clk = r.read_bool()?;
+        self.di = r.read_bool()?;
+        self.out = r.read_bool()?;
+        self.write_enabled = r.read_bool()?;
+        let kind = r.read_u8()?;
+        let addr = r.read_u8()?;
+        let value = r.read_u16()?;
+        let bits = r.read_u8()?;
+        self.state = match kind {
+            0 => EepromState::Idle,
+            1 => EepromState::Command { value, bits },
+            2 => EepromState::Write {
+                addr: Some(addr),
+                value,
+                bits,
+            },
+            3 => EepromState::Write {
+                addr: None,
+                value,
+                bits,
+            },
+            4 => EepromState::Read { value, bits },
+            other => return Err(format!("estado da EEPROM inválido: {}", other)),
+        };
+        Ok(())
+    }
+}
+
+pub struct Mbc7 {
+    rom: Vec<u8>,
+    rom_bank: u8,
+    // A RAM (registros) só responde com os dois enables ligados
+    ram_enabled_1: bool,
+    ram_enabled_2: bool,
+    tilt: Tilt,
+    // Leitura travada do acelerômetro; 0x8000 depois do erase
+    accel_x: u16,
+    accel_y: u16,
+    latch_ready: bool,
+    eeprom: Eeprom,
+}
+
+impl Mbc7 {
+    pub fn new(rom: Vec<u8>) -> Self {
+        Self {
+            rom,
+            rom_bank: 1,
+            ram_enabled_1: false,
+            ram_enabled_2: false,
+            tilt: Tilt::default(),
+            accel_x: 0x8000,
+            accel_y: 0x8000,
+            latch_ready: false,
+            eeprom: Eeprom::new(),
+        }
+    }
+
+    fn rom_byte(&self, bank: usize, addr: u16) -> u8 {
+        let banks = (self.rom.len() / 0x4000).max(1);
+        let offset = (bank % banks) * 0x4000 + (addr as usize & 0x3FFF);
+        self.rom.get(offset).copied().unwrap_or(0xFF)
+    }
+
+    fn registers_enabled(&self) -> bool {
+        self.ram_enabled_1 && self.ram_enabled_2
+    }
+
+    fn latch_accelerometer(&mut self) {
+        self.accel_x = (ACCEL_CENTER + self.tilt.x * ACCEL_RANGE) as u16;
+        self.accel_y = (ACCEL_CENTER + self.tilt.y * ACCEL_RANGE) as u16;
+    }
+}
+
+impl MbcOps for Mbc7 {
+    fn reset(&mut self) {
+        self.rom_bank = 1;
+        self.ram_enabled_1 = false;
+        self.ram_enabled_2 = false;
+        self.accel_x = 0x8000;
+        self.accel_y = 0x8000;
+        self.latch_ready = false;
+    }
+
+    fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom_byte(0, addr),
+            0x4000..=0x7FFF => self.rom_byte(self.rom_bank as usize, addr),
+            0xA000..=0xAFFF if self.registers_enabled() => match (addr >> 4) & 0x0F {
+                0x2 => self.accel_x as u8,
+                0x3 => (self.accel_x >> 8) as u8,
+                0x4 => self.accel_y as u8,
+                0x5 => (self.accel_y >> 8) as u8,
+                0x6 => 0x00,
+                0x8 => self.eeprom.read(),
+                _ => 0xFF,
+            },
+            _ => 0xFF,
+        }
+    }
+
+    fn rom_bank(&self, addr: u16) -> usize {
+        match addr {
+            0x0000..=0x3FFF => 0,
+            _ => self.rom_bank as usize % (self.rom.len() / 0x4000).max(1),
+        }
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x0000..=0x1FFF => self.ram_enabled_1 = data == 0x0A,
+            0x2000..=0x3FFF => self.rom_bank = data,
+            0x4000..=0x5FFF => self.ram_enabled_2 = data == 0x40,
+            0xA000..=0xAFFF if self.registers_enabled() => match (addr >> 4) & 0x0F {
+                // 0x55 apaga a leitura anterior; 0xAA trava uma nova
+                0x0 if data == 0x55 => {
+                    self.accel_x = 0x8000;
+                    self.accel_y = 0x8000;
+                    self.latch_ready = true;
+                }
+                0x1 if data == 0xAA && self.latch_ready => {
+                    self.latch_accelerometer();
+                    self.latch_ready = false;
+                }
+                0x8 => self.eeprom.write(data),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // O .sav do MBC7 é a EEPROM
+    fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.eeprom.data
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.eeprom.data
+    }
+
+    fn set_tilt(&mut self, tilt: Tilt) {
+        self.tilt = tilt;
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.rom_bank);
+        w.write_bool(self.ram_enabled_1);
+        w.write_bool(self.ram_enabled_2);
+        w.write_u16(self.accel_x);
+        w.write_u16(self.accel_y);
+        w.write_bool(self.latch_ready);
+        self.eeprom.save_state(w);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.rom_bank = r.read_u8()?;
+        self.ram_enabled_1 = r.read_bool()?;
+        self.ram_enabled_2 = r.read_bool()?;
+        self.accel_x = r.read_u16()?;
+        self.accel_y = r.read_u16()?;
+        self.latch_ready = r.read_bool()?;
+        self.eeprom.load_state(r)
+    }
+}
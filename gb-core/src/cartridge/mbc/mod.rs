@@ -0,0 +1,94 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+
+use enum_dispatch::enum_dispatch;
+
+use crate::joypad::Tilt;
+use crate::state::{StateReader, StateWriter};
+
+mod camera;
+mod huc1;
+mod huc3;
+mod ir;
+#[cfg(feature = "std")]
+mod ir_socket;
+mod mbc1;
+mod mbc3;
+mod mbc5;
+mod mbc7;
+mod no_mbc;
+mod rtc;
+
+pub use camera::{PocketCamera, sensor_image};
+pub use huc1::Huc1;
+pub use huc3::Huc3;
+pub use ir::{IrDevice, NoIr};
+#[cfg(feature = "std")]
+pub use ir_socket::IrSocket;
+pub use mbc1::Mbc1;
+pub use mbc3::Mbc3;
+pub use mbc5::Mbc5;
+pub use mbc7::Mbc7;
+pub use no_mbc::NoMbc;
+pub use rtc::Clock;
+
+#[enum_dispatch]
+pub trait MbcOps {
+    fn read(&self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, data: u8);
+    // Banco da ROM mapeado em `addr` (0x0000-0x7FFF), pro profiler e o debugger
+    fn rom_bank(&self, addr: u16) -> usize {
+        if addr < 0x4000 { 0 } else { 1 }
+    }
+    // Banco da RAM externa mapeado em 0xA000-0xBFFF
+    fn ram_bank(&self) -> usize {
+        0
+    }
+    // ROM inteira, sem passar pelo mapeamento
+    fn rom(&self) -> &[u8];
+    // RAM externa inteira (todos os bancos em sequência), sem passar pelo mapeamento
+    fn ram(&self) -> &[u8];
+    fn ram_mut(&mut self) -> &mut [u8];
+    // Registros de banco no estado do power-on (RAM e RTC continuam)
+    fn reset(&mut self) {}
+    // Ciclos da CPU pra quem tem relógio no cartucho
+    fn tick(&mut self, _t_cycles: u64) {}
+    // Relógio com bateria (MBC3 com RTC, HuC3)
+    fn clock(&self) -> Option<&dyn Clock> {
+        None
+    }
+    fn clock_mut(&mut self) -> Option<&mut dyn Clock> {
+        None
+    }
+    // Motor de vibração ligado (MBC5 com rumble)
+    fn rumble(&self) -> bool {
+        false
+    }
+    // Sensor de inclinação (só o MBC7 tem)
+    fn set_tilt(&mut self, _tilt: Tilt) {}
+    // Melodia pedida ao piezo (HuC3), entregue uma vez só
+    fn take_tone(&mut self) -> Option<u8> {
+        None
+    }
+    // Imagem do host pro sensor da câmera, em cinza 128x112
+    fn set_camera_image(&mut self, _image: &[u8]) {}
+    // Porta infravermelha (HuC1/HuC3); os outros ignoram
+    fn set_ir_device(&mut self, _device: Box<dyn IrDevice>) {}
+    fn take_ir_device(&mut self) -> Option<Box<dyn IrDevice>> {
+        None
+    }
+    fn save_state(&self, w: &mut StateWriter);
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String>;
+}
+
+#[enum_dispatch(MbcOps)]
+pub enum Mbc {
+    NoMbc,
+    Mbc1,
+    Mbc3,
+    Mbc5,
+    Mbc7,
+    Huc1,
+    Huc3,
+    PocketCamera,
+}
@@ -0,0 +1,47 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::MbcOps;
+use crate::state::{StateReader, StateWriter};
+
+pub struct NoMbc {
+    rom: Vec<u8>,
+}
+
+impl NoMbc {
+    pub fn new(rom: Vec<u8>) -> Self {
+        Self { rom }
+    }
+}
+
+impl MbcOps for NoMbc {
+    fn read(&self, addr: u16) -> u8 {
+        match addr {
+            // ROM menor que 32KB: o resto lê como barramento aberto
+            0x0000..=0x7FFF => self.rom.get(addr as usize).copied().unwrap_or(0xFF),
+            _ => 0xFF, // sem RAM externa
+        }
+    }
+
+    fn write(&mut self, _addr: u16, _data: u8) {
+        // ROM read-only: writes silenciosamente ignorados
+    }
+
+    fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    fn ram(&self) -> &[u8] {
+        &[]
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut []
+    }
+
+    fn save_state(&self, _w: &mut StateWriter) {}
+
+    fn load_state(&mut self, _r: &mut StateReader) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::state::{StateReader, StateWriter};
+
+// Clock da CPU: o RTC conta segundos em ciclos emulados, não no relógio do sistema
+pub const CYCLES_PER_SECOND: u64 = 4_194_304;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const MAX_DAYS: u64 = 512;
+
+// Rodapé do .sav (VBA-M/BGB/mGBA/SameBoy): registros atuais e travados como u32 LE,
+// mais o timestamp UNIX de quando foi salvo (u64; versões antigas do VBA usam u32)
+const RTC_FOOTER_SIZE: usize = 48;
+const RTC_FOOTER_SIZE_OLD: usize = 44;
+
+const DAY_HIGH: u8 = 0x01;
+const HALT: u8 = 0x40;
+const DAY_CARRY: u8 = 0x80;
+
+// Registros 0x08..=0x0C do MBC3
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct RtcRegisters {
+    pub seconds: u8,
+    pub minutes: u8,
+    pub hours: u8,
+    pub days: u16,
+    pub halt: bool,
+    pub carry: bool,
+}
+
+impl RtcRegisters {
+    pub fn read(&self, register: u8) -> u8 {
+        match register {
+            0x08 => self.seconds,
+            0x09 => self.minutes,
+            0x0A => self.hours,
+            0x0B => self.days as u8,
+            0x0C => self.control(),
+            _ => 0xFF,
+        }
+    }
+
+    fn control(&self) -> u8 {
+        let mut value = (self.days >> 8) as u8 & DAY_HIGH;
+        if self.halt {
+            value |= HALT;
+        }
+        if self.carry {
+            value |= DAY_CARRY;
+        }
+        value
+    }
+
+    fn set_control(&mut self, value: u8) {
+        self.days = (self.days & 0xFF) | (((value & DAY_HIGH) as u16) << 8);
+        self.halt = value & HALT != 0;
+        self.carry = value & DAY_CARRY != 0;
+    }
+
+    // Os bits que não existem no chip somem na escrita
+    fn write(&mut self, register: u8, value: u8) {
+        match register {
+            0x08 => self.seconds = value & 0x3F,
+            0x09 => self.minutes = value & 0x3F,
+            0x0A => self.hours = value & 0x1F,
+            0x0B => self.days = (self.days & 0x100) | value as u16,
+            0x0C => self.set_control(value),
+            _ => {}
+        }
+    }
+
+    // Valores fora da faixa (escritos pelo jogo) dão a volta no limite dos bits sem
+    // propagar, como no chip
+    fn tick_second(&mut self) {
+        self.seconds = (self.seconds + 1) & 0x3F;
+        if self.seconds != 60 {
+            return;
+        }
+        self.seconds = 0;
+        self.minutes = (self.minutes + 1) & 0x3F;
+        if self.minutes != 60 {
+            return;
+        }
+        self.minutes = 0;
+        self.hours = (self.hours + 1) & 0x1F;
+        if self.hours != 24 {
+            return;
+        }
+        self.hours = 0;
+        self.days += 1;
+        if self.days as u64 == MAX_DAYS {
+            self.days = 0;
+            self.carry = true;
+        }
+    }
+
+    fn is_normal(&self) -> bool {
+        self.seconds < 60 && self.minutes < 60 && self.hours < 24
+    }
+
+    fn advance(&mut self, mut seconds: u64) {
+        // Primeiro sai de qualquer valor inválido segundo a segundo
+        while seconds > 0 && !self.is_normal() {
+            self.tick_second();
+            seconds -= 1;
+        }
+        if seconds == 0 {
+            return;
+        }
+
+        let total = self.seconds as u64
+            + self.minutes as u64 * 60
+            + self.hours as u64 * 3600
+            + self.days as u64 * SECONDS_PER_DAY
+            + seconds;
+        let days = total / SECONDS_PER_DAY;
+        if days >= MAX_DAYS {
+            self.carry = true;
+        }
+        self.days = (days % MAX_DAYS) as u16;
+        self.hours = (total % SECONDS_PER_DAY / 3600) as u8;
+        self.minutes = (total % 3600 / 60) as u8;
+        self.seconds = (total % 60) as u8;
+    }
+
+    fn write_footer(&self, out: &mut Vec<u8>) {
+        for value in [
+            self.seconds,
+            self.minutes,
+            self.hours,
+            self.days as u8,
+            self.control(),
+        ] {
+            out.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+    }
+
+    fn read_footer(data: &[u8]) -> Self {
+        let value = |index: usize| data[index * 4];
+        let mut registers = Self::default();
+        registers.write(0x08, value(0));
+        registers.write(0x09, value(1));
+        registers.write(0x0A, value(2));
+        registers.write(0x0B, value(3));
+        registers.write(0x0C, value(4));
+        registers
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.seconds);
+        w.write_u8(self.minutes);
+        w.write_u8(self.hours);
+        w.write_u8(self.days as u8);
+        w.write_u8(self.control());
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.seconds = r.read_u8()?;
+        self.minutes = r.read_u8()?;
+        self.hours = r.read_u8()?;
+        self.days = r.read_u8()? as u16;
+        self.set_control(r.read_u8()?);
+        Ok(())
+    }
+}
+
+// Relógio de cartucho com bateria (MBC3, HuC3): o estado vai num rodapé depois da RAM
+// no .sav
+pub trait Clock {
+    fn write_footer(&self, out: &mut Vec<u8>, timestamp: u64);
+    // Devolve o timestamp gravado no rodapé
+    fn read_footer(&mut self, footer: &[u8]) -> Result<u64, String>;
+    // Tempo que passou com o emulador fechado
+    fn advance(&mut self, seconds: u64);
+}
+
+pub struct Rtc {
+    pub current: RtcRegisters,
+    // Cópia congelada pelo latch (escrever 0x00 e depois 0x01 em 0x6000-0x7FFF); é ela
+    // que o jogo lê
+    pub latched: RtcRegisters,
+    // Ciclos desde o último segundo
+    cycles: u64,
+}
+
+impl Rtc {
+    pub fn new() -> Self {
+        Self {
+            current: RtcRegisters::default(),
+            latched: RtcRegisters::default(),
+            cycles: 0,
+        }
+    }
+
+    pub fn tick(&mut self, t_cycles: u64) {
+        if self.current.halt {
+            return;
+        }
+        self.cycles += t_cycles;
+        while self.cycles >= CYCLES_PER_SECOND {
+            self.cycles -= CYCLES_PER_SECOND;
+            self.current.tick_second();
+        }
+    }
+
+    pub fn latch(&mut self) {
+        self.latched = self.current;
+    }
+
+    pub fn read(&self, register: u8) -> u8 {
+        self.latched.read(register)
+    }
+
+    // Escrever nos segundos zera o divisor interno
+    pub fn write(&mut self, register: u8, value: u8) {
+        if register == 0x08 {
+            self.cycles = 0;
+        }
+        self.current.write(register, value);
+        self.latched.write(register, value);
+    }
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        self.current.save_state(w);
+        self.latched.save_state(w);
+        w.write_u32(self.cycles as u32);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.current.load_state(r)?;
+        self.latched.load_state(r)?;
+        self.cycles = r.read_u32()? as u64;
+        Ok(())
+    }
+}
+
+impl Clock for Rtc {
+    fn write_footer(&self, out: &mut Vec<u8>, timestamp: u64) {
+        self.current.write_footer(out);
+        self.latched.write_footer(out);
+        out.extend_from_slice(&timestamp.to_le_bytes());
+    }
+
+    fn read_footer(&mut self, footer: &[u8]) -> Result<u64, String> {
+        let timestamp = match footer.len() {
+            RTC_FOOTER_SIZE => u64::from_le_bytes(footer[40..48].try_into().unwrap()),
+            RTC_FOOTER_SIZE_OLD => u32::from_le_bytes(footer[40..44].try_into().unwrap()) as u64,
+            len => return Err(format!("rodapé de RTC com tamanho inválido: {} bytes", len)),
+        };
+        self.current = RtcRegisters::read_footer(&footer[..20]);
+        self.latched = RtcRegisters::read_footer(&footer[20..40]);
+        self.cycles = 0;
+        Ok(timestamp)
+    }
+
+    fn advance(&mut self, seconds: u64) {
+        if !self.current.halt {
+            self.current.advance(seconds);
+        }
+    }
+}
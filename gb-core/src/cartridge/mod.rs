@@ -0,0 +1,11 @@
+pub mod cartridge;
+pub mod cartridge_type;
+pub mod destination;
+mod mbc;
+#[cfg(test)]
+mod tests;
+
+pub use cartridge::*;
+#[cfg(feature = "std")]
+pub use mbc::IrSocket;
+pub use mbc::{IrDevice, NoIr, sensor_image};
@@ -0,0 +1,59 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::cartridge::{Cartridge, SaveLayout};
+
+// MBC3 com relógio, 8 KB de RAM e bateria
+fn mbc3_timer() -> Cartridge {
+    let mut rom = vec![0; 0x8000];
+    rom[0x147] = 0x10;
+    rom[0x149] = 0x02;
+    Cartridge::load(rom).unwrap()
+}
+
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7) as u8).collect()
+}
+
+#[test]
+fn importa_save_bruto_completado_de_flashcart() {
+    let mut cartridge = mbc3_timer();
+    let mut data = pattern(0x2000);
+    data.resize(0x8000, 0xFF);
+    cartridge
+        .import_battery(&data, Some(SaveLayout::Raw), None)
+        .unwrap();
+    assert_eq!(cartridge.ram(), &data[..0x2000]);
+    assert_eq!(
+        cartridge.export_battery(SaveLayout::Raw, 0),
+        &data[..0x2000]
+    );
+
+    let short = pattern(0x1000);
+    assert!(
+        cartridge
+            .import_battery(&short, Some(SaveLayout::Raw), None)
+            .is_err()
+    );
+}
+
+#[test]
+fn detecta_o_formato_do_vba_e_o_bruto() {
+    let mut source = mbc3_timer();
+    source
+        .import_battery(&pattern(0x2000), Some(SaveLayout::Raw), None)
+        .unwrap();
+    let vba = source.export_battery(SaveLayout::Vba, 1_000_000);
+    assert_eq!(vba.len(), 0x2000 + 48);
+
+    let mut cartridge = mbc3_timer();
+    cartridge.import_battery(&vba, None, None).unwrap();
+    assert_eq!(cartridge.battery_data(1_000_000), vba);
+
+    // Sobra que não é rodapé de relógio: lido como bruto
+    let mut padded = pattern(0x2000);
+    padded.resize(0x8000, 0x00);
+    let mut cartridge = mbc3_timer();
+    cartridge.import_battery(&padded, None, None).unwrap();
+    assert_eq!(cartridge.ram(), &padded[..0x2000]);
+}
@@ -0,0 +1,194 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::cheats::{Freeze, GameGenieCode, GameSharkCode};
+use crate::config::Config;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CheatKind {
+    GameGenie(GameGenieCode),
+    GameShark(GameSharkCode),
+}
+
+pub struct Cheat {
+    // Texto original, usado pra identificar o código nos comandos
+    pub code: String,
+    pub kind: CheatKind,
+    pub enabled: bool,
+}
+
+// Lista de cheats ativos, consultada pelo bus
+pub struct Cheats {
+    cheats: Vec<Cheat>,
+    // Congelados à mão (endereço e valor), fora da lista de códigos
+    freezes: Vec<Freeze>,
+    // Endereço -> valor (e o banco exigido, nos códigos 8x/9x) de todos os congelamentos
+    // ligados, refeito a cada mudança: o bus consulta em toda escrita
+    frozen: BTreeMap<u16, (u8, Option<usize>)>,
+    // Liga/desliga todos de uma vez sem perder a lista
+    pub enabled: bool,
+}
+
+impl Cheats {
+    pub fn new() -> Self {
+        Self {
+            cheats: Vec::new(),
+            freezes: Vec::new(),
+            frozen: BTreeMap::new(),
+            enabled: true,
+        }
+    }
+
+    // Game Genie tem traços (ABC-DEF-GHI); GameShark são 8 dígitos seguidos
+    fn parse(code: &str) -> Result<CheatKind, String> {
+        let code = code.trim();
+        if code.contains('-') {
+            Ok(CheatKind::GameGenie(GameGenieCode::parse(code)?))
+        } else {
+            Ok(CheatKind::GameShark(GameSharkCode::parse(code)?))
+        }
+    }
+
+    // Adicionar de novo um código existente substitui o anterior
+    pub fn add(&mut self, code: &str) -> Result<(), String> {
+        let kind = Self::parse(code)?;
+        self.remove(code);
+        self.cheats.push(Cheat {
+            code: code.trim().to_uppercase(),
+            kind,
+            enabled: true,
+        });
+        self.refresh();
+        Ok(())
+    }
+
+    pub fn remove(&mut self, code: &str) {
+        self.cheats
+            .retain(|cheat| !cheat.code.eq_ignore_ascii_case(code.trim()));
+        self.refresh();
+    }
+
+    pub fn set_enabled(&mut self, code: &str, enabled: bool) -> bool {
+        match self
+            .cheats
+            .iter_mut()
+            .find(|cheat| cheat.code.eq_ignore_ascii_case(code.trim()))
+        {
+            Some(cheat) => {
+                cheat.enabled = enabled;
+                self.refresh();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Cheat> {
+        self.cheats.iter()
+    }
+
+    // [cheats] on = <código> / off = <código> (fica na lista, desligado)
+    pub fn load_config(&mut self, config: &Config) -> Result<(), String> {
+        for (key, code) in config.section("cheats") {
+            let enabled = match key {
+                "on" => true,
+                "off" => false,
+                other => {
+                    return Err(format!(
+                        "[cheats]: esperado 'on' ou 'off', veio '{}'",
+                        other
+                    ));
+                }
+            };
+            self.add(code)?;
+            self.set_enabled(code, enabled);
+        }
+        Ok(())
+    }
+
+    // Leitura da ROM (0000-7FFF) passando pelos códigos Game Genie
+    pub fn patch_rom(&self, addr: u16, original: u8) -> u8 {
+        if !self.enabled {
+            return original;
+        }
+
+        self.cheats
+            .iter()
+            .filter(|cheat| cheat.enabled)
+            .find_map(|cheat| match cheat.kind {
+                CheatKind::GameGenie(code) if code.address == addr => Some(code.apply(original)),
+                _ => None,
+            })
+            .unwrap_or(original)
+    }
+
+    // Congelar de novo o mesmo endereço troca o valor. Os campos do Freeze são públicos,
+    // então o endereço passa pela mesma checagem do Freeze::new
+    pub fn freeze(&mut self, freeze: Freeze) -> Result<(), String> {
+        let freeze = Freeze::new(freeze.address, freeze.value)?;
+        self.freezes.retain(|other| other.address != freeze.address);
+        self.freezes.push(freeze);
+        self.refresh();
+        Ok(())
+    }
+
+    pub fn unfreeze(&mut self, address: u16) -> bool {
+        let count = self.freezes.len();
+        self.freezes.retain(|freeze| freeze.address != address);
+        self.refresh();
+        self.freezes.len() != count
+    }
+
+    pub fn freezes(&self) -> &[Freeze] {
+        &self.freezes
+    }
+
+    // Os congelados à mão valem sobre um código GameShark no mesmo endereço
+    fn refresh(&mut self) {
+        let codes = self.cheats.iter().filter(|cheat| cheat.enabled);
+        let codes = codes.filter_map(|cheat| match cheat.kind {
+            CheatKind::GameShark(code) => Some((code.freeze(), code.ram_bank())),
+            _ => None,
+        });
+        self.frozen = codes
+            .chain(self.freezes.iter().map(|&freeze| (freeze, None)))
+            .filter_map(|(freeze, bank)| {
+                Some((Freeze::ram_address(freeze.address)?, (freeze.value, bank)))
+            })
+            .collect();
+    }
+
+    // Valor preso no endereço, consultado pelo bus a cada escrita com o banco de SRAM
+    // mapeado agora
+    pub fn frozen(&self, addr: u16, sram_bank: usize) -> Option<u8> {
+        if !self.enabled || self.frozen.is_empty() {
+            return None;
+        }
+        let addr = Freeze::ram_address(addr)?;
+        let &(value, bank) = self.frozen.get(&addr)?;
+        bank_mapped(addr, bank, sram_bank).then_some(value)
+    }
+
+    // Congelamentos ativos: (endereço, valor) a escrever neste VBlank, pra RAM que a CPU
+    // não escreve também ficar com o valor
+    pub fn ram_writes(&self, sram_bank: usize) -> impl Iterator<Item = (u16, u8)> + '_ {
+        self.frozen
+            .iter()
+            .filter(|_| self.enabled)
+            .filter(move |&(&address, &(_, bank))| bank_mapped(address, bank, sram_bank))
+            .map(|(&address, &(value, _))| (address, value))
+    }
+}
+
+// Banco pedido por um código GameShark contra o mapeado no endereço. Só a SRAM troca de
+// banco; a WRAM do DMG tem o banco 1 fixo em D000 (o 0 também vale, como no SVBK do CGB)
+fn bank_mapped(address: u16, bank: Option<usize>, sram_bank: usize) -> bool {
+    match (bank, address) {
+        (None, _) => true,
+        (Some(bank), 0xA000..=0xBFFF) => bank == sram_bank,
+        (Some(bank), 0xD000..=0xDFFF) => bank <= 1,
+        _ => true,
+    }
+}
@@ -0,0 +1,33 @@
+use alloc::format;
+use alloc::string::String;
+
+// Endereço preso num valor: a CPU até escreve, mas a memória fica com o valor congelado.
+// Os códigos GameShark e os congelamentos da busca de cheats viram isso
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Freeze {
+    pub address: u16,
+    pub value: u8,
+}
+
+impl Freeze {
+    // Só RAM: SRAM, WRAM (e o eco dela) e HRAM
+    pub fn new(address: u16, value: u8) -> Result<Self, String> {
+        let address = Self::ram_address(address).ok_or_else(|| {
+            format!(
+                "só dá pra congelar RAM (A000-DFFF, FF80-FFFE): ${:04X}",
+                address
+            )
+        })?;
+        Ok(Self { address, value })
+    }
+
+    // Endereço na RAM, com o eco trocado pela WRAM; None no resto do mapa, onde uma escrita
+    // troca banco do MBC ou mexe num registro
+    pub fn ram_address(address: u16) -> Option<u16> {
+        match address {
+            0xA000..=0xDFFF | 0xFF80..=0xFFFE => Some(address),
+            0xE000..=0xFDFF => Some(address - 0x2000),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// Game Genie: patch de um byte da ROM, opcionalmente só quando o byte original bate
+// Formato ABC-DEF-GHI (ou ABC-DEF sem compare), em hexadecimal
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct GameGenieCode {
+    pub address: u16,
+    pub value: u8,
+    pub compare: Option<u8>,
+}
+
+impl GameGenieCode {
+    pub fn parse(code: &str) -> Result<Self, String> {
+        let digits: Vec<u8> = code
+            .chars()
+            .filter(|c| *c != '-')
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()
+            .ok_or(format!("código Game Genie inválido: '{}'", code))?;
+
+        if digits.len() != 6 && digits.len() != 9 {
+            return Err(format!(
+                "código Game Genie deve ter 6 ou 9 dígitos: '{}'",
+                code
+            ));
+        }
+
+        let value = (digits[0] << 4) | digits[1];
+        // O nibble alto do endereço vem invertido
+        let address = (((digits[5] ^ 0xF) as u16) << 12)
+            | ((digits[2] as u16) << 8)
+            | ((digits[3] as u16) << 4)
+            | digits[4] as u16;
+
+        if address >= 0x8000 {
+            return Err(format!("código Game Genie fora da ROM: '{}'", code));
+        }
+
+        // G e I formam o compare (rotacionado 2 bits e com XOR 0xBA); H é só verificação
+        let compare =
+            (digits.len() == 9).then(|| ((digits[6] << 4) | digits[8]).rotate_right(2) ^ 0xBA);
+
+        Ok(Self {
+            address,
+            value,
+            compare,
+        })
+    }
+
+    // Byte que o CPU enxerga no endereço do código
+    pub fn apply(&self, original: u8) -> u8 {
+        match self.compare {
+            Some(compare) if compare != original => original,
+            _ => self.value,
+        }
+    }
+}
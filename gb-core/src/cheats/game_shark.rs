@@ -0,0 +1,60 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::cheats::Freeze;
+
+// GameShark: escreve um valor na RAM (WRAM/SRAM) uma vez por VBlank
+// Formato TTVVLLHH em hexadecimal: TT = tipo/banco, VV = valor, HHLL = endereço
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct GameSharkCode {
+    pub bank: u8,
+    pub value: u8,
+    pub address: u16,
+}
+
+impl GameSharkCode {
+    pub fn parse(code: &str) -> Result<Self, String> {
+        if code.len() != 8 {
+            return Err(format!("código GameShark deve ter 8 dígitos: '{}'", code));
+        }
+
+        let byte = |index: usize| {
+            u8::from_str_radix(&code[index * 2..index * 2 + 2], 16)
+                .map_err(|_| format!("código GameShark inválido: '{}'", code))
+        };
+
+        let bank = byte(0)?;
+        let value = byte(1)?;
+        let address = u16::from_le_bytes([byte(2)?, byte(3)?]);
+
+        // 01 = banco atual; 8x/9x = banco x (o DMG só tem um banco de WRAM)
+        if bank != 0x01 && !(0x80..=0x9F).contains(&bank) {
+            return Err(format!(
+                "tipo de código GameShark não suportado: '{}'",
+                code
+            ));
+        }
+        if !(0xA000..=0xDFFF).contains(&address) {
+            return Err(format!("código GameShark fora da RAM: '{}'", code));
+        }
+
+        Ok(Self {
+            bank,
+            value,
+            address,
+        })
+    }
+
+    // Banco exigido pelo código: None no 01 (vale o que estiver mapeado)
+    pub fn ram_bank(self) -> Option<usize> {
+        (self.bank != 0x01).then_some((self.bank & 0x1F) as usize)
+    }
+
+    // O banco fica de fora: quem compara com o mapeado é o Cheats
+    pub fn freeze(self) -> Freeze {
+        Freeze {
+            address: self.address,
+            value: self.value,
+        }
+    }
+}
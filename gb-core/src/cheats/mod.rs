@@ -0,0 +1,11 @@
+pub mod cheats;
+pub mod freeze;
+pub mod game_genie;
+pub mod game_shark;
+pub mod search;
+
+pub use cheats::*;
+pub use freeze::*;
+pub use game_genie::*;
+pub use game_shark::*;
+pub use search::*;
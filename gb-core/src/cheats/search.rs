@@ -0,0 +1,124 @@
+// Busca de endereços pra cheats: compara a RAM atual com a da busca anterior
+// e vai eliminando candidatos. Índices seguem MemoryBus::search_ram (WRAM, depois SRAM)
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+const WRAM_SIZE: usize = 0x2000;
+const SRAM_BANK_SIZE: usize = 0x2000;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SearchFilter {
+    Equal(u8),
+    Greater,
+    Less,
+    Changed,
+    Unchanged,
+    // Diferença exata desde a busca anterior (pode ser negativa)
+    ChangedBy(i16),
+}
+
+impl SearchFilter {
+    fn matches(self, previous: u8, current: u8) -> bool {
+        match self {
+            SearchFilter::Equal(value) => current == value,
+            SearchFilter::Greater => current > previous,
+            SearchFilter::Less => current < previous,
+            SearchFilter::Changed => current != previous,
+            SearchFilter::Unchanged => current == previous,
+            SearchFilter::ChangedBy(delta) => current as i16 - previous as i16 == delta,
+        }
+    }
+}
+
+// Endereço como o jogo vê: WRAM direto, SRAM com o banco
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RamAddress {
+    Wram(u16),
+    Sram { bank: u8, address: u16 },
+}
+
+impl RamAddress {
+    fn from_index(index: usize) -> Self {
+        if index < WRAM_SIZE {
+            RamAddress::Wram(0xC000 + index as u16)
+        } else {
+            let offset = index - WRAM_SIZE;
+            RamAddress::Sram {
+                bank: (offset / SRAM_BANK_SIZE) as u8,
+                address: 0xA000 + (offset % SRAM_BANK_SIZE) as u16,
+            }
+        }
+    }
+
+    // Código GameShark que congela o valor nesse endereço
+    pub fn freeze_code(self, value: u8) -> String {
+        let (bank, address) = match self {
+            RamAddress::Wram(address) => (0x01, address),
+            RamAddress::Sram { bank, address } => (0x80 | bank, address),
+        };
+        let [low, high] = address.to_le_bytes();
+        format!("{:02X}{:02X}{:02X}{:02X}", bank, value, low, high)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SearchResult {
+    pub address: RamAddress,
+    pub value: u8,
+}
+
+pub struct CheatSearch {
+    previous: Vec<u8>,
+    candidates: Vec<usize>,
+}
+
+impl CheatSearch {
+    pub fn new() -> Self {
+        Self {
+            previous: Vec::new(),
+            candidates: Vec::new(),
+        }
+    }
+
+    // Nova busca: todo byte da RAM vira candidato
+    pub fn start(&mut self, ram: Vec<u8>) {
+        self.candidates = (0..ram.len()).collect();
+        self.previous = ram;
+    }
+
+    pub fn filter(&mut self, ram: Vec<u8>, filter: SearchFilter) {
+        let previous = &self.previous;
+        self.candidates
+            .retain(|&index| index < ram.len() && filter.matches(previous[index], ram[index]));
+        self.previous = ram;
+    }
+
+    pub fn len(&self) -> usize {
+        self.candidates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+
+    pub fn results(&self, limit: usize) -> Vec<SearchResult> {
+        self.candidates
+            .iter()
+            .take(limit)
+            .map(|&index| SearchResult {
+                address: RamAddress::from_index(index),
+                value: self.previous[index],
+            })
+            .collect()
+    }
+
+    pub fn result(&self, position: usize) -> Option<SearchResult> {
+        let &index = self.candidates.get(position)?;
+        Some(SearchResult {
+            address: RamAddress::from_index(index),
+            value: self.previous[index],
+        })
+    }
+}
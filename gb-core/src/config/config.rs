@@ -0,0 +1,80 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+pub const DEFAULT_CONFIG_PATH: &str = "gb-emu.ini";
+
+// Arquivo INI simples: [seção], chave = valor, comentários com # ou ;
+#[derive(Default)]
+pub struct Config {
+    sections: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|erro| format!("erro ao ler '{}': {}", path.display(), erro))?;
+        Self::parse(&text).map_err(|erro| format!("{}: {}", path.display(), erro))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut config = Self::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name
+                    .strip_suffix(']')
+                    .ok_or(format!("linha {}: seção sem ']'", number + 1))?;
+                config.sections.push((name.trim().to_string(), Vec::new()));
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(format!("linha {}: esperado 'chave = valor'", number + 1))?;
+
+            // Chaves antes de qualquer seção ficam na seção global ""
+            if config.sections.is_empty() {
+                config.sections.push((String::new(), Vec::new()));
+            }
+            let (_, entries) = config.sections.last_mut().unwrap();
+            entries.push((key.trim().to_lowercase(), value.trim().to_string()));
+        }
+
+        Ok(config)
+    }
+
+    pub fn section(&self, name: &str) -> impl Iterator<Item = (&str, &str)> {
+        self.sections
+            .iter()
+            .filter(move |(section, _)| section.eq_ignore_ascii_case(name))
+            .flat_map(|(_, entries)| entries.iter())
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn section_names(&self) -> impl Iterator<Item = &str> {
+        self.sections.iter().map(|(name, _)| name.as_str())
+    }
+
+    // Em caso de chave repetida vale a última
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.section(section)
+            .filter(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+            .last()
+    }
+}
@@ -1,6 +1,9 @@
-use bitflags::{Flags, bitflags};
+use alloc::string::String;
 
-use crate::bus::{MemoryBus, InterruptFlags};
+use bitflags::bitflags;
+
+use crate::bus::{AccessKind, CpuBus, InterruptFlags, OamBugAccess};
+use crate::state::{Savestate, StateReader, StateWriter};
 
 bitflags! {
     pub struct FFlags: u8 {
@@ -11,6 +14,15 @@ bitflags! {
     }
 }
 
+// O que fazer ao encontrar um opcode inexistente (0xD3, 0xE3, ...)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum IllegalOpcodePolicy {
+    // Trava a CPU como no hardware real (só o reset tira dali)
+    Lock,
+    // Loga e pula o byte, útil pra depurar ROMs em desenvolvimento
+    Skip,
+}
+
 pub struct Cpu {
     // 8-bit regs
     pub register_a: u8,
@@ -31,6 +43,13 @@ pub struct Cpu {
     pub stop: bool,
     pub interruption: bool,
     pub ime_pending: bool,
+    pub locked: bool,
+
+    pub illegal_opcode_policy: IllegalOpcodePolicy,
+    // (pc, opcode) do último opcode ilegal executado, consumido pelo Emulator
+    pub illegal_opcode: Option<(u16, u8)>,
+    // Bit do IF atendido no último step (em vez de uma instrução), consumido pelo Emulator
+    pub interrupt_dispatched: Option<u8>,
 
     pub opcode: u8,
     pub cycles: u8,
@@ -56,6 +75,11 @@ impl Cpu {
             stop: false,
             interruption: false,
             ime_pending: false,
+            locked: false,
+
+            illegal_opcode_policy: IllegalOpcodePolicy::Lock,
+            illegal_opcode: None,
+            interrupt_dispatched: None,
 
             opcode: 0,
             cycles: 0,
@@ -79,9 +103,73 @@ impl Cpu {
 
         self.interruption = false;
         self.ime_pending = false;
+        self.halt = false;
+        self.stop = false;
+        self.locked = false;
+        self.illegal_opcode = None;
+        self.interrupt_dispatched = None;
+    }
+
+    // Pares de 16 bits (high, low)
+    pub fn af(&self) -> u16 {
+        ((self.register_a as u16) << 8) | (self.register_f.bits() as u16)
+    }
+
+    pub fn set_af(&mut self, value: u16) {
+        self.register_a = (value >> 8) as u8;
+        // Os 4 bits baixos de F não existem no hardware
+        self.register_f = FFlags::from_bits_truncate(value as u8 & 0xF0);
+    }
+
+    pub fn bc(&self) -> u16 {
+        self.register_concat(self.register_b, self.register_c)
+    }
+
+    pub fn set_bc(&mut self, value: u16) {
+        self.register_b = (value >> 8) as u8;
+        self.register_c = value as u8;
+    }
+
+    pub fn de(&self) -> u16 {
+        self.register_concat(self.register_d, self.register_e)
+    }
+
+    pub fn set_de(&mut self, value: u16) {
+        self.register_d = (value >> 8) as u8;
+        self.register_e = value as u8;
+    }
+
+    pub fn hl(&self) -> u16 {
+        self.register_concat(self.register_h, self.register_l)
+    }
+
+    pub fn set_hl(&mut self, value: u16) {
+        self.register_h = (value >> 8) as u8;
+        self.register_l = value as u8;
     }
 
-    pub fn step(&mut self, bus: &mut MemoryBus) -> u8 {
+    pub fn flag(&self, flag: FFlags) -> bool {
+        self.register_f.contains(flag)
+    }
+
+    pub fn set_flag(&mut self, flag: FFlags, value: bool) {
+        self.register_f.set(flag, value);
+    }
+
+    pub fn step(&mut self, bus: &mut impl CpuBus) -> u8 {
+        // CPU travada não atende nem interrupções
+        if self.locked {
+            return 4;
+        }
+
+        // Em STOP os clocks estão parados; só um botão apertado numa linha selecionada acorda
+        if self.stop {
+            if !bus.stop_wake() {
+                return 4;
+            }
+            self.stop = false;
+        }
+
         let if_reg = InterruptFlags::from_bits_truncate(bus.read(0xFF0F));
         let ie_reg = InterruptFlags::from_bits_truncate(bus.read(0xFFFF));
         let pending = if_reg & ie_reg;
@@ -97,13 +185,13 @@ impl Cpu {
 
             self.interruption = false;
             bus.write(0xFF0F, (if_reg - serviced).bits());
-            self.push_u16(self.program_counter, bus);
+            self.push16(self.program_counter, bus);
             self.program_counter = vector;
+            self.interrupt_dispatched = Some(bit);
 
             return 20;
         }
 
-        if self.stop { return 4; }
         if self.halt { return 4; }
 
         let promote_at_end = self.ime_pending;
@@ -121,12 +209,12 @@ impl Cpu {
         self.cycles
     }
 
-    fn process(&mut self, inst: u8, bus: &mut MemoryBus) {
+    fn process(&mut self, inst: u8, bus: &mut impl CpuBus) {
         match inst {
             0x00 => self.nop(),
             0x01 => self.ld_bc_u16(bus),
             0x02 => self.ld_bc_a(bus),
-            0x03 => self.inc_bc(),
+            0x03 => self.inc_bc(bus),
             0x04 => self.inc_b(),
             0x05 => self.dec_b(),
             0x06 => self.ld_b_u8(bus),
@@ -134,7 +222,7 @@ impl Cpu {
             0x08 => self.ld_u16_sp(bus),
             0x09 => self.add_hl_bc(),
             0x0A => self.ld_a_bc(bus),
-            0x0B => self.dec_bc(),
+            0x0B => self.dec_bc(bus),
             0x0C => self.inc_c(),
             0x0D => self.dec_c(),
             0x0E => self.ld_c_u8(bus),
@@ -143,7 +231,7 @@ impl Cpu {
             0x10 => self.stop_inst(bus),
             0x11 => self.ld_de_u16(bus),
             0x12 => self.ld_de_a(bus),
-            0x13 => self.inc_de(),
+            0x13 => self.inc_de(bus),
             0x14 => self.inc_d(),
             0x15 => self.dec_d(),
             0x16 => self.ld_d_u8(bus),
@@ -151,7 +239,7 @@ impl Cpu {
             0x18 => self.jr_i8(bus),
             0x19 => self.add_hl_de(),
             0x1A => self.ld_a_de(bus),
-            0x1B => self.dec_de(),
+            0x1B => self.dec_de(bus),
             0x1C => self.inc_e(),
             0x1D => self.dec_e(),
             0x1E => self.ld_e_u8(bus),
@@ -160,7 +248,7 @@ impl Cpu {
             0x20 => self.jr_nz_i8(bus),
             0x21 => self.ld_hl_u16(bus),
             0x22 => self.ldi_hl_a(bus),
-            0x23 => self.inc_hl(),
+            0x23 => self.inc_hl(bus),
             0x24 => self.inc_h(),
             0x25 => self.dec_h(),
             0x26 => self.ld_h_u8(bus),
@@ -168,7 +256,7 @@ impl Cpu {
             0x28 => self.jr_z_i8(bus),
             0x29 => self.add_hl_hl(),
             0x2A => self.ldi_a_hl(bus),
-            0x2B => self.dec_hl(),
+            0x2B => self.dec_hl(bus),
             0x2C => self.inc_l(),
             0x2D => self.dec_l(),
             0x2E => self.ld_l_u8(bus),
@@ -177,7 +265,7 @@ impl Cpu {
             0x30 => self.jr_nc_i8(bus),
             0x31 => self.ld_sp_u16(bus),
             0x32 => self.ldd_hl_a(bus),
-            0x33 => self.inc_sp(),
+            0x33 => self.inc_sp(bus),
             0x34 => self.inc_hl_ptr(bus),
             0x35 => self.dec_hl_ptr(bus),
             0x36 => self.ld_hl_ptr_u8(bus),
@@ -185,7 +273,7 @@ impl Cpu {
             0x38 => self.jr_c_i8(bus),
             0x39 => self.add_hl_sp(),
             0x3A => self.ldd_a_hl(bus),
-            0x3B => self.dec_sp(),
+            0x3B => self.dec_sp(bus),
             0x3C => self.inc_a(),
             0x3D => self.dec_a(),
             0x3E => self.ld_a_u8(bus),
@@ -399,7 +487,7 @@ impl Cpu {
         }
     }
 
-    fn process_cb(&mut self, inst: u8, bus: &mut MemoryBus) {
+    fn process_cb(&mut self, inst: u8, bus: &mut impl CpuBus) {
         match inst {
             0x00 => self.rlc_b(),
             0x01 => self.rlc_c(),
@@ -675,6 +763,26 @@ impl Cpu {
         }
     }
 
+    fn illegal_opcode(&mut self) {
+        self.illegal_opcode = Some((self.program_counter, self.opcode));
+
+        match self.illegal_opcode_policy {
+            IllegalOpcodePolicy::Lock => {
+                self.locked = true;
+            }
+            IllegalOpcodePolicy::Skip => {
+                #[cfg(feature = "std")]
+                eprintln!(
+                    "opcode ilegal 0x{:02X} em pc=0x{:04X}, ignorando",
+                    self.opcode, self.program_counter
+                );
+                self.advance_program_counter(1);
+            }
+        }
+
+        self.update_cycles(4);
+    }
+
     fn update_cycles(&mut self, cycles: u8) {
         self.cycles = cycles;
     }
@@ -683,12 +791,28 @@ impl Cpu {
         self.program_counter = self.program_counter.wrapping_add(advances);
     }
 
-    fn read_u8(&mut self, addr: u16, bus: &mut MemoryBus) -> u8 {
-        bus.read(addr)
+    fn read_u8(&mut self, addr: u16, bus: &mut impl CpuBus) -> u8 {
+        let value = bus.read(addr);
+        bus.record_access(AccessKind::Read, addr, value);
+        value
     }
 
-    fn write_u8(&mut self, addr: u16, data: u8, bus: &mut MemoryBus) {
+    fn write_u8(&mut self, addr: u16, data: u8, bus: &mut impl CpuBus) {
         bus.write(addr, data);
+        bus.record_access(AccessKind::Write, addr, data);
+    }
+
+    fn read_u16(&mut self, addr: u16, bus: &mut impl CpuBus) -> u16 {
+        let value = bus.read_u16(addr);
+        bus.record_access(AccessKind::Read, addr, value as u8);
+        bus.record_access(AccessKind::Read, addr.wrapping_add(1), (value >> 8) as u8);
+        value
+    }
+
+    fn write_u16(&mut self, addr: u16, value: u16, bus: &mut impl CpuBus) {
+        bus.write_u16(addr, value);
+        bus.record_access(AccessKind::Write, addr, value as u8);
+        bus.record_access(AccessKind::Write, addr.wrapping_add(1), (value >> 8) as u8);
     }
 
     fn register_concat(&self, high: u8, low: u8) -> u16 {
@@ -839,24 +963,25 @@ impl Cpu {
         value | (1u8 << bit)
     }
 
-    fn push_u16(&mut self, value: u16, bus: &mut MemoryBus) {
-        let upper = (value >> 8) as u8;
-        let lower = value as u8;
-
-        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
-        self.write_u8(self.stack_pointer, upper, bus);
-
-        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
-        self.write_u8(self.stack_pointer, lower, bus);
+    // O OAM bug vê o SP de cada um dos dois acessos
+    pub fn push16(&mut self, value: u16, bus: &mut impl CpuBus) {
+        for _ in 0..2 {
+            bus.oam_bug(self.stack_pointer, OamBugAccess::Write);
+            self.stack_pointer = self.stack_pointer.wrapping_sub(1);
+        }
+        self.write_u16(self.stack_pointer, value, bus);
     }
 
-    fn pop_u8(&mut self, bus: &mut MemoryBus) -> u8 {
-        let value = self.read_u8(self.stack_pointer, bus);
-        self.stack_pointer = self.stack_pointer.wrapping_add(1);
+    pub fn pop16(&mut self, bus: &mut impl CpuBus) -> u16 {
+        let value = self.read_u16(self.stack_pointer, bus);
+        for _ in 0..2 {
+            bus.oam_bug(self.stack_pointer, OamBugAccess::ReadIncrement);
+            self.stack_pointer = self.stack_pointer.wrapping_add(1);
+        }
         value
     }
 
-    fn jr_cond_i8(&mut self, condition: bool, bus: &mut MemoryBus) {
+    fn jr_cond_i8(&mut self, condition: bool, bus: &mut impl CpuBus) {
         /*
             Byte lido da memória (u8):
               0xFE (254)
@@ -1007,7 +1132,7 @@ impl Cpu {
     }
 
     // d16 imediato (little-endian): low = PC+1, high = PC+2
-    fn ld_bc_u16(&mut self, bus: &mut MemoryBus) {
+    fn ld_bc_u16(&mut self, bus: &mut impl CpuBus) {
         let low = self.read_u8(self.program_counter.wrapping_add(1), bus);
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus);
 
@@ -1018,20 +1143,21 @@ impl Cpu {
         self.update_cycles(12);
     }
 
-    fn ld_bc_a(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_b, self.register_c);
+    fn ld_bc_a(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.bc();
         self.write_u8(addr, self.register_a, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn inc_bc(&mut self) {
-        let bc = self.register_concat(self.register_b, self.register_c);
+    fn inc_bc(&mut self, bus: &mut impl CpuBus) {
+        bus.oam_bug(self.bc(), OamBugAccess::Write);
+
+        let bc = self.bc();
         let bc = bc.wrapping_add(1);
 
-        self.register_b = (bc >> 8) as u8;
-        self.register_c = bc as u8;
+        self.set_bc(bc);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
@@ -1051,7 +1177,7 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_b_u8(&mut self, bus: &mut MemoryBus) {
+    fn ld_b_u8(&mut self, bus: &mut impl CpuBus) {
         self.register_b = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.advance_program_counter(2);
@@ -1074,24 +1200,20 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_u16_sp(&mut self, bus: &mut MemoryBus) {
+    fn ld_u16_sp(&mut self, bus: &mut impl CpuBus) {
         let low = self.read_u8(self.program_counter.wrapping_add(1), bus);
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus);
         let addr = (low as u16) | ((high as u16) << 8);
 
-        let sp_low = self.stack_pointer as u8;
-        let sp_high = (self.stack_pointer >> 8) as u8;
-
-        self.write_u8(addr, sp_low, bus);
-        self.write_u8(addr.wrapping_add(1), sp_high, bus);
+        self.write_u16(addr, self.stack_pointer, bus);
 
         self.advance_program_counter(3);
         self.update_cycles(20);
     }
 
     fn add_hl_bc(&mut self) {
-        let hl = self.register_concat(self.register_h, self.register_l);
-        let bc = self.register_concat(self.register_b, self.register_c);
+        let hl = self.hl();
+        let bc = self.bc();
 
         let result = hl.wrapping_add(bc);
 
@@ -1103,27 +1225,27 @@ impl Cpu {
         let carry = (hl as u32 + bc as u32) > 0xFFFF;
         self.register_f.set(FFlags::C, carry);
 
-        self.register_h = (result >> 8) as u8;
-        self.register_l = result as u8;
+        self.set_hl(result);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ld_a_bc(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_b, self.register_c);
+    fn ld_a_bc(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.bc();
         self.register_a = self.read_u8(addr, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn dec_bc(&mut self) {
-        let bc = self.register_concat(self.register_b, self.register_c);
+    fn dec_bc(&mut self, bus: &mut impl CpuBus) {
+        bus.oam_bug(self.bc(), OamBugAccess::Write);
+
+        let bc = self.bc();
         let bc = bc.wrapping_sub(1);
 
-        self.register_b = (bc >> 8) as u8;
-        self.register_c = bc as u8;
+        self.set_bc(bc);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
@@ -1143,7 +1265,7 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_c_u8(&mut self, bus: &mut MemoryBus) {
+    fn ld_c_u8(&mut self, bus: &mut impl CpuBus) {
         self.register_c = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.advance_program_counter(2);
@@ -1167,22 +1289,15 @@ impl Cpu {
     }
 
     // 0x10 ~ 0x1F
-    fn stop_inst(&mut self, bus: &mut MemoryBus) {
-        let next = self.read_u8(self.program_counter.wrapping_add(1), bus);
-        if next != 0x00 {
-            panic!(
-                "stop (0x10) inválido: esperado 0x00 após o opcode, mas veio 0x{:02x} em pc=0x{:04x}",
-                next, self.program_counter
-            );
-        }
-
-        self.stop = true;
+    // O byte depois do 0x10 é pulado seja qual for: o hardware não confere se é 0x00
+    fn stop_inst(&mut self, bus: &mut impl CpuBus) {
+        self.stop = bus.stop();
 
         self.advance_program_counter(2);
         self.update_cycles(4);
     }
 
-    fn ld_de_u16(&mut self, bus: &mut MemoryBus) {
+    fn ld_de_u16(&mut self, bus: &mut impl CpuBus) {
         let low = self.read_u8(self.program_counter.wrapping_add(1), bus);
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus);
 
@@ -1193,20 +1308,21 @@ impl Cpu {
         self.update_cycles(12);
     }
 
-    fn ld_de_a(&mut self, bus: &mut MemoryBus) {
-        let de = self.register_concat(self.register_d, self.register_e);
+    fn ld_de_a(&mut self, bus: &mut impl CpuBus) {
+        let de = self.de();
         self.write_u8(de, self.register_a, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn inc_de(&mut self) {
-        let de = self.register_concat(self.register_d, self.register_e);
+    fn inc_de(&mut self, bus: &mut impl CpuBus) {
+        bus.oam_bug(self.de(), OamBugAccess::Write);
+
+        let de = self.de();
         let de = de.wrapping_add(1);
 
-        self.register_d = (de >> 8) as u8;
-        self.register_e = de as u8;
+        self.set_de(de);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
@@ -1226,7 +1342,7 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_d_u8(&mut self, bus: &mut MemoryBus) {
+    fn ld_d_u8(&mut self, bus: &mut impl CpuBus) {
         self.register_d = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.advance_program_counter(2);
@@ -1250,7 +1366,7 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn jr_i8(&mut self, bus: &mut MemoryBus) {
+    fn jr_i8(&mut self, bus: &mut impl CpuBus) {
         let offset_u8 = self.read_u8(self.program_counter.wrapping_add(1), bus);
         let offset = offset_u8 as i8 as i16;
 
@@ -1261,8 +1377,8 @@ impl Cpu {
     }
 
     fn add_hl_de(&mut self) {
-        let hl = self.register_concat(self.register_h, self.register_l);
-        let de = self.register_concat(self.register_d, self.register_e);
+        let hl = self.hl();
+        let de = self.de();
 
         let result = hl.wrapping_add(de);
 
@@ -1274,27 +1390,27 @@ impl Cpu {
         let carry = (hl as u32 + de as u32) > 0xFFFF;
         self.register_f.set(FFlags::C, carry);
 
-        self.register_h = (result >> 8) as u8;
-        self.register_l = result as u8;
+        self.set_hl(result);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ld_a_de(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_d, self.register_e);
+    fn ld_a_de(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.de();
         self.register_a = self.read_u8(addr, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn dec_de(&mut self) {
-        let de = self.register_concat(self.register_d, self.register_e);
+    fn dec_de(&mut self, bus: &mut impl CpuBus) {
+        bus.oam_bug(self.de(), OamBugAccess::Write);
+
+        let de = self.de();
         let de = de.wrapping_sub(1);
 
-        self.register_d = (de >> 8) as u8;
-        self.register_e = de as u8;
+        self.set_de(de);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
@@ -1314,7 +1430,7 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_e_u8(&mut self, bus: &mut MemoryBus) {
+    fn ld_e_u8(&mut self, bus: &mut impl CpuBus) {
         self.register_e = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.advance_program_counter(2);
@@ -1339,13 +1455,13 @@ impl Cpu {
     }
 
     //0x20 ~ 0x2F
-    fn jr_nz_i8(&mut self, bus: &mut MemoryBus) {
-        let z_set = self.register_f.contains(FFlags::Z);
+    fn jr_nz_i8(&mut self, bus: &mut impl CpuBus) {
+        let z_set = self.flag(FFlags::Z);
 
         self.jr_cond_i8(!z_set, bus);
     }
 
-    fn ld_hl_u16(&mut self, bus: &mut MemoryBus) {
+    fn ld_hl_u16(&mut self, bus: &mut impl CpuBus) {
         let low = self.read_u8(self.program_counter.wrapping_add(1), bus);
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus);
 
@@ -1356,23 +1472,24 @@ impl Cpu {
         self.update_cycles(12);
     }
 
-    fn ldi_hl_a(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ldi_hl_a(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.write_u8(addr, self.register_a, bus);
+        bus.oam_bug(addr, OamBugAccess::Write);
 
         let addr_plus = addr.wrapping_add(1);
-        self.register_h = ((addr_plus >> 8) & 0x00FF) as u8;
-        self.register_l = (addr_plus & 0x00FF) as u8;
+        self.set_hl(addr_plus);
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn inc_hl(&mut self) {
-        let hl = self.register_concat(self.register_h, self.register_l);
+    fn inc_hl(&mut self, bus: &mut impl CpuBus) {
+        bus.oam_bug(self.hl(), OamBugAccess::Write);
+
+        let hl = self.hl();
         let hl_plus = hl.wrapping_add(1);
 
-        self.register_h = (hl_plus >> 8) as u8;
-        self.register_l = hl_plus as u8;
+        self.set_hl(hl_plus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
@@ -1392,7 +1509,7 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_h_u8(&mut self, bus: &mut MemoryBus) {
+    fn ld_h_u8(&mut self, bus: &mut impl CpuBus) {
         self.register_h = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.advance_program_counter(2);
@@ -1401,12 +1518,14 @@ impl Cpu {
 
     fn daa(&mut self) {
         let mut a = self.register_a;
-        let n = self.register_f.contains(FFlags::N);
-        let h = self.register_f.contains(FFlags::H);
-        let c = self.register_f.contains(FFlags::C);
+        let n = self.flag(FFlags::N);
+        let h = self.flag(FFlags::H);
+        let c = self.flag(FFlags::C);
 
         let mut carry_out = c;
 
+        // Depois de uma soma os dígitos acima de 9 também pedem ajuste; depois de uma
+        // subtração só H e C dizem o que corrigir, e o carry nunca muda
         if !n {
             let mut adjust: u8 = 0;
             if c || a > 0x99 {
@@ -1419,8 +1538,12 @@ impl Cpu {
             a = a.wrapping_add(adjust);
         } else {
             let mut adjust: u8 = 0;
-            if c { adjust |= 0x60; }
-            if h { adjust |= 0x06; }
+            if c {
+                adjust |= 0x60;
+            }
+            if h {
+                adjust |= 0x06;
+            }
             a = a.wrapping_sub(adjust);
         }
 
@@ -1434,14 +1557,14 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn jr_z_i8(&mut self, bus: &mut MemoryBus) {
-        let z_set = self.register_f.contains(FFlags::Z);
+    fn jr_z_i8(&mut self, bus: &mut impl CpuBus) {
+        let z_set = self.flag(FFlags::Z);
 
         self.jr_cond_i8(z_set, bus);
     }
 
     fn add_hl_hl(&mut self) {
-        let hl = self.register_concat(self.register_h, self.register_l);
+        let hl = self.hl();
 
         self.register_f.remove(FFlags::N);
 
@@ -1451,32 +1574,32 @@ impl Cpu {
         let (result, carry) = hl.overflowing_add(hl);
         self.register_f.set(FFlags::C, carry);
 
-        self.register_h = ((result >> 8) & 0x00FF) as u8;
-        self.register_l = (result & 0x00FF) as u8;
+        self.set_hl(result);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ldi_a_hl(&mut self, bus: &mut MemoryBus) {
-        let hl = self.register_concat(self.register_h, self.register_l);
+    fn ldi_a_hl(&mut self, bus: &mut impl CpuBus) {
+        let hl = self.hl();
         self.register_a = self.read_u8(hl, bus);
+        bus.oam_bug(hl, OamBugAccess::ReadIncrement);
 
         let hl_plus = hl.wrapping_add(1);
 
-        self.register_h = (hl_plus >> 8) as u8;
-        self.register_l = hl_plus as u8;
+        self.set_hl(hl_plus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn dec_hl(&mut self) {
-        let hl = self.register_concat(self.register_h, self.register_l);
+    fn dec_hl(&mut self, bus: &mut impl CpuBus) {
+        bus.oam_bug(self.hl(), OamBugAccess::Write);
+
+        let hl = self.hl();
         let hl_minus = hl.wrapping_sub(1);
 
-        self.register_h = (hl_minus >> 8) as u8;
-        self.register_l = hl_minus as u8;
+        self.set_hl(hl_minus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
@@ -1496,7 +1619,7 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_l_u8(&mut self, bus: &mut MemoryBus) {
+    fn ld_l_u8(&mut self, bus: &mut impl CpuBus) {
         self.register_l = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.advance_program_counter(2);
@@ -1514,13 +1637,13 @@ impl Cpu {
     }
 
     //0x30 ~ 0x3F
-    fn jr_nc_i8(&mut self, bus: &mut MemoryBus) {
-        let c_flag = self.register_f.contains(FFlags::C);
+    fn jr_nc_i8(&mut self, bus: &mut impl CpuBus) {
+        let c_flag = self.flag(FFlags::C);
 
         self.jr_cond_i8(!c_flag, bus);
     }
 
-    fn ld_sp_u16(&mut self, bus: &mut MemoryBus) {
+    fn ld_sp_u16(&mut self, bus: &mut impl CpuBus) {
         let low = self.read_u8(self.program_counter.wrapping_add(1), bus);
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus);
 
@@ -1530,26 +1653,28 @@ impl Cpu {
         self.update_cycles(12);
     }
 
-    fn ldd_hl_a(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ldd_hl_a(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.write_u8(addr, self.register_a, bus);
+        bus.oam_bug(addr, OamBugAccess::Write);
 
         let addr_sub = addr.wrapping_sub(1);
-        self.register_h = ((addr_sub >> 8) & 0x00FF) as u8;
-        self.register_l = (addr_sub & 0x00FF) as u8;
+        self.set_hl(addr_sub);
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn inc_sp(&mut self) {
+    fn inc_sp(&mut self, bus: &mut impl CpuBus) {
+        bus.oam_bug(self.stack_pointer, OamBugAccess::Write);
+
         self.stack_pointer = self.stack_pointer.wrapping_add(1);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn inc_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn inc_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
 
         let old_value = self.read_u8(addr, bus);
         let result = old_value.wrapping_add(1);
@@ -1564,8 +1689,8 @@ impl Cpu {
         self.update_cycles(12);
     }
 
-    fn dec_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn dec_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
 
         let old_value = self.read_u8(addr, bus);
         let result = old_value.wrapping_sub(1);
@@ -1580,9 +1705,9 @@ impl Cpu {
         self.update_cycles(12);
     }
 
-    fn ld_hl_ptr_u8(&mut self, bus: &mut MemoryBus) {
+    fn ld_hl_ptr_u8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus);
-        let addr = self.register_concat(self.register_h, self.register_l);
+        let addr = self.hl();
 
         self.write_u8(addr, value, bus);
 
@@ -1599,14 +1724,14 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn jr_c_i8(&mut self, bus: &mut MemoryBus) {
-        let c_flag = self.register_f.contains(FFlags::C);
+    fn jr_c_i8(&mut self, bus: &mut impl CpuBus) {
+        let c_flag = self.flag(FFlags::C);
 
         self.jr_cond_i8(c_flag, bus);
     }
 
     fn add_hl_sp(&mut self) {
-        let hl = self.register_concat(self.register_h, self.register_l);
+        let hl = self.hl();
         let result = self.stack_pointer.wrapping_add(hl);
 
         self.register_f.remove(FFlags::N);
@@ -1617,27 +1742,28 @@ impl Cpu {
         let carry = (hl as u32 + self.stack_pointer as u32) > 0xFFFF;
         self.register_f.set(FFlags::C, carry);
 
-        self.register_h = (result >> 8) as u8;
-        self.register_l = result as u8;
+        self.set_hl(result);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ldd_a_hl(&mut self, bus: &mut MemoryBus) {
-        let hl = self.register_concat(self.register_h, self.register_l);
+    fn ldd_a_hl(&mut self, bus: &mut impl CpuBus) {
+        let hl = self.hl();
         self.register_a = self.read_u8(hl, bus);
+        bus.oam_bug(hl, OamBugAccess::ReadIncrement);
 
         let hl_sub = hl.wrapping_sub(1);
 
-        self.register_h = (hl_sub >> 8) as u8;
-        self.register_l = hl_sub as u8;
+        self.set_hl(hl_sub);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn dec_sp(&mut self) {
+    fn dec_sp(&mut self, bus: &mut impl CpuBus) {
+        bus.oam_bug(self.stack_pointer, OamBugAccess::Write);
+
         self.stack_pointer = self.stack_pointer.wrapping_sub(1);
 
         self.advance_program_counter(1);
@@ -1658,7 +1784,7 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_a_u8(&mut self, bus: &mut MemoryBus) {
+    fn ld_a_u8(&mut self, bus: &mut impl CpuBus) {
         self.register_a = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.advance_program_counter(2);
@@ -1666,8 +1792,8 @@ impl Cpu {
     }
 
     fn ccf(&mut self) {
-        let carry = self.register_f.contains(FFlags::C);
-        self.register_f.set(FFlags::C, !carry);
+        let carry = self.flag(FFlags::C);
+        self.set_flag(FFlags::C, !carry);
         self.register_f.remove(FFlags::N);
         self.register_f.remove(FFlags::H);
 
@@ -1718,8 +1844,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_b_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_b_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.register_b = self.read_u8(addr, bus);
 
         self.advance_program_counter(1);
@@ -1775,8 +1901,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_c_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_c_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.register_c = self.read_u8(addr, bus);
 
         self.advance_program_counter(1);
@@ -1833,8 +1959,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_d_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_d_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.register_d = self.read_u8(addr, bus);
 
         self.advance_program_counter(1);
@@ -1890,8 +2016,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_e_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_e_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.register_e = self.read_u8(addr, bus);
 
         self.advance_program_counter(1);
@@ -1948,8 +2074,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_h_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_h_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.register_h = self.read_u8(addr, bus);
 
         self.advance_program_counter(1);
@@ -2005,8 +2131,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_l_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_l_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.register_l = self.read_u8(addr, bus);
 
         self.advance_program_counter(1);
@@ -2021,48 +2147,48 @@ impl Cpu {
     }
 
     //0x70 ~ 0x7F
-    fn ld_hl_ptr_b(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_hl_ptr_b(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.write_u8(addr, self.register_b, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ld_hl_ptr_c(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_hl_ptr_c(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.write_u8(addr, self.register_c, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ld_hl_ptr_d(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_hl_ptr_d(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.write_u8(addr, self.register_d, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ld_hl_ptr_e(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_hl_ptr_e(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.write_u8(addr, self.register_e, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ld_hl_ptr_h(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_hl_ptr_h(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.write_u8(addr, self.register_h, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ld_hl_ptr_l(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_hl_ptr_l(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.write_u8(addr, self.register_l, bus);
 
         self.advance_program_counter(1);
@@ -2075,8 +2201,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_hl_ptr_a(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_hl_ptr_a(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.write_u8(addr, self.register_a, bus);
 
         self.advance_program_counter(1);
@@ -2125,8 +2251,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn ld_a_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn ld_a_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         self.register_a = self.read_u8(addr, bus);
 
         self.advance_program_counter(1);
@@ -2183,8 +2309,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn add_a_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn add_a_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let data = self.read_u8(addr, bus);
 
         self.register_a = self.add(self.register_a, data);
@@ -2242,8 +2368,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn adc_a_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn adc_a_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let valor = self.read_u8(addr, bus);
 
         self.register_a = self.adc(self.register_a, valor);
@@ -2302,8 +2428,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn sub_a_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn sub_a_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let valor = self.read_u8(addr, bus);
 
         self.register_a = self.sub(self.register_a, valor);
@@ -2361,8 +2487,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn sbc_a_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn sbc_a_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let valor = self.read_u8(addr, bus);
 
         self.register_a = self.sbc(self.register_a, valor);
@@ -2421,8 +2547,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn and_a_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn and_a_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let valor = self.read_u8(addr, bus);
 
         self.register_a = self.and_(self.register_a, valor);
@@ -2480,8 +2606,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn xor_a_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn xor_a_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let valor = self.read_u8(addr, bus);
         self.register_a = self.xor(self.register_a, valor);
 
@@ -2533,8 +2659,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn or_a_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn or_a_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let valor = self.read_u8(addr, bus);
 
         self.register_a = self.or_(self.register_a, valor);
@@ -2585,8 +2711,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn cp_a_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn cp_a_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let valor = self.read_u8(addr, bus);
 
         self.cp(self.register_a, valor);
@@ -2602,32 +2728,29 @@ impl Cpu {
     }
 
     //0xC0 ~ 0xCF
-    fn ret_nz(&mut self, bus: &mut MemoryBus) {
-        let z_set = self.register_f.contains(FFlags::Z);
+    fn ret_nz(&mut self, bus: &mut impl CpuBus) {
+        let z_set = self.flag(FFlags::Z);
 
         self.advance_program_counter(1);
 
         if !z_set {
-            let low = self.pop_u8(bus) as u16;
-            let high = self.pop_u8(bus) as u16;
-
-            self.program_counter = (high << 8) | low;
+            self.program_counter = self.pop16(bus);
             self.update_cycles(20);
         } else {
             self.update_cycles(8);
         }
     }
 
-    fn pop_bc(&mut self, bus: &mut MemoryBus) {
-        self.register_c = self.pop_u8(bus);
-        self.register_b = self.pop_u8(bus);
+    fn pop_bc(&mut self, bus: &mut impl CpuBus) {
+        let value = self.pop16(bus);
+        self.set_bc(value);
 
         self.advance_program_counter(1);
         self.update_cycles(12);
     }
 
-    fn jp_nz_u16(&mut self, bus: &mut MemoryBus) {
-        let z_set = self.register_f.contains(FFlags::Z);
+    fn jp_nz_u16(&mut self, bus: &mut impl CpuBus) {
+        let z_set = self.flag(FFlags::Z);
 
         let lower = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
@@ -2641,7 +2764,7 @@ impl Cpu {
         }
     }
 
-    fn jp_u16(&mut self, bus: &mut MemoryBus) {
+    fn jp_u16(&mut self, bus: &mut impl CpuBus) {
         let lower = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
 
@@ -2650,8 +2773,8 @@ impl Cpu {
         self.update_cycles(16);
     }
 
-    fn call_nz_u16(&mut self, bus: &mut MemoryBus) {
-        let z_set = self.register_f.contains(FFlags::Z);
+    fn call_nz_u16(&mut self, bus: &mut impl CpuBus) {
+        let z_set = self.flag(FFlags::Z);
 
         let lower = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
@@ -2659,7 +2782,7 @@ impl Cpu {
 
         if !z_set {
             let ret = self.program_counter.wrapping_add(3);
-            self.push_u16(ret, bus);
+            self.push16(ret, bus);
 
             self.program_counter = target;
             self.update_cycles(24);
@@ -2669,15 +2792,15 @@ impl Cpu {
         }
     }
 
-    fn push_bc(&mut self, bus: &mut MemoryBus) {
-        let bc = ((self.register_b as u16) << 8) | (self.register_c as u16);
-        self.push_u16(bc, bus);
+    fn push_bc(&mut self, bus: &mut impl CpuBus) {
+        let bc = self.bc();
+        self.push16(bc, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(16);
     }
 
-    fn add_a_u8(&mut self, bus: &mut MemoryBus) {
+    fn add_a_u8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.register_a = self.add(self.register_a, value);
@@ -2686,21 +2809,19 @@ impl Cpu {
         self.update_cycles(8);
     }
 
-    fn rst_00(&mut self, bus: &mut MemoryBus) {
+    fn rst_00(&mut self, bus: &mut impl CpuBus) {
         let ret = self.program_counter.wrapping_add(1);
-        self.push_u16(ret, bus);
+        self.push16(ret, bus);
 
         self.program_counter = 0x0000;
         self.update_cycles(16);
     }
 
-    fn ret_z(&mut self, bus: &mut MemoryBus) {
-        let z_set = self.register_f.contains(FFlags::Z);
+    fn ret_z(&mut self, bus: &mut impl CpuBus) {
+        let z_set = self.flag(FFlags::Z);
 
         if z_set {
-            let low = self.pop_u8(bus) as u16;
-            let high = self.pop_u8(bus) as u16;
-            self.program_counter = (high << 8) | low;
+            self.program_counter = self.pop16(bus);
             self.update_cycles(20);
         } else {
             self.advance_program_counter(1);
@@ -2708,15 +2829,13 @@ impl Cpu {
         }
     }
 
-    fn ret(&mut self, bus: &mut MemoryBus) {
-        let low = self.pop_u8(bus) as u16;
-        let high = self.pop_u8(bus) as u16;
-        self.program_counter = (high << 8) | low;
+    fn ret(&mut self, bus: &mut impl CpuBus) {
+        self.program_counter = self.pop16(bus);
         self.update_cycles(16);
     }
 
-    fn jp_z_u16(&mut self, bus: &mut MemoryBus) {
-        let z_set = self.register_f.contains(FFlags::Z);
+    fn jp_z_u16(&mut self, bus: &mut impl CpuBus) {
+        let z_set = self.flag(FFlags::Z);
 
         let lower = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
@@ -2730,7 +2849,7 @@ impl Cpu {
         }
     }
 
-    fn cb_prefix(&mut self, bus: &mut MemoryBus) {
+    fn cb_prefix(&mut self, bus: &mut impl CpuBus) {
         let inst = self.read_u8(self.program_counter.wrapping_add(1), bus);
         self.advance_program_counter(2);
         self.opcode = inst;
@@ -2738,8 +2857,8 @@ impl Cpu {
         self.cycles = self.cycles.wrapping_add(4);
     }
 
-    fn call_z_u16(&mut self, bus: &mut MemoryBus) {
-        let z_set = self.register_f.contains(FFlags::Z);
+    fn call_z_u16(&mut self, bus: &mut impl CpuBus) {
+        let z_set = self.flag(FFlags::Z);
 
         let lower = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
@@ -2747,7 +2866,7 @@ impl Cpu {
 
         if z_set {
             let ret = self.program_counter.wrapping_add(3);
-            self.push_u16(ret, bus);
+            self.push16(ret, bus);
 
             self.program_counter = target;
             self.update_cycles(24);
@@ -2757,20 +2876,20 @@ impl Cpu {
         }
     }
 
-    fn call_u16(&mut self, bus: &mut MemoryBus) {
+    fn call_u16(&mut self, bus: &mut impl CpuBus) {
         let lower = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
 
         let target = (high << 8) | lower;
         let ret = self.program_counter.wrapping_add(3);
 
-        self.push_u16(ret, bus);
+        self.push16(ret, bus);
         self.program_counter = target;
 
         self.update_cycles(24);
     }
 
-    fn adc_a_u8(&mut self, bus: &mut MemoryBus) {
+    fn adc_a_u8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.register_a = self.adc(self.register_a, value);
@@ -2779,22 +2898,20 @@ impl Cpu {
         self.update_cycles(8);
     }
 
-    fn rst_08(&mut self, bus: &mut MemoryBus) {
+    fn rst_08(&mut self, bus: &mut impl CpuBus) {
         let ret = self.program_counter.wrapping_add(1);
-        self.push_u16(ret, bus);
+        self.push16(ret, bus);
 
         self.program_counter = 0x0008;
         self.update_cycles(16);
     }
 
     //0xD0 ~ 0xDF
-    fn ret_nc(&mut self, bus: &mut MemoryBus) {
-        let c_set = self.register_f.contains(FFlags::C);
+    fn ret_nc(&mut self, bus: &mut impl CpuBus) {
+        let c_set = self.flag(FFlags::C);
 
         if !c_set {
-            let low = self.pop_u8(bus) as u16;
-            let high = self.pop_u8(bus) as u16;
-            self.program_counter = (high << 8) | low;
+            self.program_counter = self.pop16(bus);
             self.update_cycles(20);
         } else {
             self.advance_program_counter(1);
@@ -2802,16 +2919,16 @@ impl Cpu {
         }
     }
 
-    fn pop_de(&mut self, bus: &mut MemoryBus) {
-        self.register_e = self.pop_u8(bus);
-        self.register_d = self.pop_u8(bus);
+    fn pop_de(&mut self, bus: &mut impl CpuBus) {
+        let value = self.pop16(bus);
+        self.set_de(value);
 
         self.advance_program_counter(1);
         self.update_cycles(12);
     }
 
-    fn jp_nc_u16(&mut self, bus: &mut MemoryBus) {
-        let c_set = self.register_f.contains(FFlags::C);
+    fn jp_nc_u16(&mut self, bus: &mut impl CpuBus) {
+        let c_set = self.flag(FFlags::C);
 
         let lower = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
@@ -2825,10 +2942,12 @@ impl Cpu {
         }
     }
 
-    fn op_d3_unused(&mut self) {}
+    fn op_d3_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn call_nc_u16(&mut self, bus: &mut MemoryBus) {
-        let c_set = self.register_f.contains(FFlags::C);
+    fn call_nc_u16(&mut self, bus: &mut impl CpuBus) {
+        let c_set = self.flag(FFlags::C);
 
         let low = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
@@ -2836,7 +2955,7 @@ impl Cpu {
 
         if !c_set {
             let ret = self.program_counter.wrapping_add(3);
-            self.push_u16(ret, bus);
+            self.push16(ret, bus);
 
             self.program_counter = target;
             self.update_cycles(24);
@@ -2846,15 +2965,15 @@ impl Cpu {
         }
     }
 
-    fn push_de(&mut self, bus: &mut MemoryBus) {
-        let de = ((self.register_d as u16) << 8) | (self.register_e as u16);
-        self.push_u16(de, bus);
+    fn push_de(&mut self, bus: &mut impl CpuBus) {
+        let de = self.de();
+        self.push16(de, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(16);
     }
 
-    fn sub_u8(&mut self, bus: &mut MemoryBus) {
+    fn sub_u8(&mut self, bus: &mut impl CpuBus) {
         let valor = self.read_u8(self.program_counter.wrapping_add(1), bus);
         self.register_a = self.sub(self.register_a, valor);
 
@@ -2862,21 +2981,19 @@ impl Cpu {
         self.update_cycles(8);
     }
 
-    fn rst_10(&mut self, bus: &mut MemoryBus) {
+    fn rst_10(&mut self, bus: &mut impl CpuBus) {
         let ret = self.program_counter.wrapping_add(1);
-        self.push_u16(ret, bus);
+        self.push16(ret, bus);
 
         self.program_counter = 0x0010;
         self.update_cycles(16);
     }
 
-    fn ret_c(&mut self, bus: &mut MemoryBus) {
-        let c_set = self.register_f.contains(FFlags::C);
+    fn ret_c(&mut self, bus: &mut impl CpuBus) {
+        let c_set = self.flag(FFlags::C);
 
         if c_set {
-            let low = self.pop_u8(bus) as u16;
-            let high = self.pop_u8(bus) as u16;
-            self.program_counter = (high << 8) | low;
+            self.program_counter = self.pop16(bus);
             self.update_cycles(20);
         } else {
             self.advance_program_counter(1);
@@ -2884,18 +3001,16 @@ impl Cpu {
         }
     }
 
-    fn reti(&mut self, bus: &mut MemoryBus) {
-        let low = self.pop_u8(bus) as u16;
-        let high = self.pop_u8(bus) as u16;
-        self.program_counter = (high << 8) | low;
+    fn reti(&mut self, bus: &mut impl CpuBus) {
+        self.program_counter = self.pop16(bus);
         self.interruption = true;
         self.ime_pending = false;
 
         self.update_cycles(16);
     }
 
-    fn jp_c_u16(&mut self, bus: &mut MemoryBus) {
-        let c_set = self.register_f.contains(FFlags::C);
+    fn jp_c_u16(&mut self, bus: &mut impl CpuBus) {
+        let c_set = self.flag(FFlags::C);
 
         let lower = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
@@ -2909,10 +3024,12 @@ impl Cpu {
         }
     }
 
-    fn op_db_unused(&mut self) {}
+    fn op_db_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn call_c_u16(&mut self, bus: &mut MemoryBus) {
-        let c_set = self.register_f.contains(FFlags::C);
+    fn call_c_u16(&mut self, bus: &mut impl CpuBus) {
+        let c_set = self.flag(FFlags::C);
 
         let low = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
@@ -2920,7 +3037,7 @@ impl Cpu {
 
         if c_set {
             let ret = self.program_counter.wrapping_add(3);
-            self.push_u16(ret, bus);
+            self.push16(ret, bus);
 
             self.program_counter = target;
             self.update_cycles(24);
@@ -2930,9 +3047,11 @@ impl Cpu {
         }
     }
 
-    fn op_dd_unused(&mut self) {}
+    fn op_dd_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn sbc_a_u8(&mut self, bus: &mut MemoryBus) {
+    fn sbc_a_u8(&mut self, bus: &mut impl CpuBus) {
         let valor = self.read_u8(self.program_counter.wrapping_add(1), bus);
         self.register_a = self.sbc(self.register_a, valor);
 
@@ -2940,16 +3059,16 @@ impl Cpu {
         self.update_cycles(8);
     }
 
-    fn rst_18(&mut self, bus: &mut MemoryBus) {
+    fn rst_18(&mut self, bus: &mut impl CpuBus) {
         let ret = self.program_counter.wrapping_add(1);
-        self.push_u16(ret, bus);
+        self.push16(ret, bus);
 
         self.program_counter = 0x0018;
         self.update_cycles(16);
     }
 
     //0xE0 ~ 0xEF
-    fn ldh_u8_a(&mut self, bus: &mut MemoryBus) {
+    fn ldh_u8_a(&mut self, bus: &mut impl CpuBus) {
         let offset = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let addr = ((0xFF << 8) as u16) | offset;
 
@@ -2959,15 +3078,15 @@ impl Cpu {
         self.update_cycles(12);
     }
 
-    fn pop_hl(&mut self, bus: &mut MemoryBus) {
-        self.register_l = self.pop_u8(bus);
-        self.register_h = self.pop_u8(bus);
+    fn pop_hl(&mut self, bus: &mut impl CpuBus) {
+        let value = self.pop16(bus);
+        self.set_hl(value);
 
         self.advance_program_counter(1);
         self.update_cycles(12);
     }
 
-    fn ldh_c_a(&mut self, bus: &mut MemoryBus) {
+    fn ldh_c_a(&mut self, bus: &mut impl CpuBus) {
         let addr = ((0xFF << 8) as u16) | (self.register_c as u16);
 
         self.write_u8(addr, self.register_a, bus);
@@ -2976,19 +3095,23 @@ impl Cpu {
         self.update_cycles(8);
     }
 
-    fn op_e3_unused(&mut self) {}
+    fn op_e3_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn op_e4_unused(&mut self) {}
+    fn op_e4_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn push_hl(&mut self, bus: &mut MemoryBus) {
-        let hl = ((self.register_h as u16) << 8) | (self.register_l as u16);
-        self.push_u16(hl, bus);
+    fn push_hl(&mut self, bus: &mut impl CpuBus) {
+        let hl = self.hl();
+        self.push16(hl, bus);
 
         self.advance_program_counter(1);
         self.update_cycles(16);
     }
 
-    fn and_u8(&mut self, bus: &mut MemoryBus) {
+    fn and_u8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus);
         self.register_a = self.and_(self.register_a, value);
 
@@ -2996,14 +3119,14 @@ impl Cpu {
         self.update_cycles(8);
     }
 
-    fn rst_20(&mut self, bus: &mut MemoryBus) {
+    fn rst_20(&mut self, bus: &mut impl CpuBus) {
         let ret = self.program_counter.wrapping_add(1);
-        self.push_u16(ret, bus);
+        self.push16(ret, bus);
         self.program_counter = 0x0020;
         self.update_cycles(16);
     }
 
-    fn add_sp_i8(&mut self, bus: &mut MemoryBus) {
+    fn add_sp_i8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus) as i8 as i16;
 
         let sp = self.stack_pointer;
@@ -3027,12 +3150,12 @@ impl Cpu {
     }
 
     fn jp_hl(&mut self) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+        let addr = self.hl();
         self.program_counter = addr;
         self.update_cycles(4);
     }
 
-    fn ld_u16_a(&mut self, bus: &mut MemoryBus) {
+    fn ld_u16_a(&mut self, bus: &mut impl CpuBus) {
         let low = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
         let addr = (high << 8) | low;
@@ -3043,13 +3166,19 @@ impl Cpu {
         self.update_cycles(16);
     }
 
-    fn op_eb_unused(&mut self) {}
+    fn op_eb_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn op_ec_unused(&mut self) {}
+    fn op_ec_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn op_ed_unused(&mut self) {}
+    fn op_ed_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn xor_u8(&mut self, bus: &mut MemoryBus) {
+    fn xor_u8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus);
         self.register_a = self.xor(self.register_a, value);
 
@@ -3057,14 +3186,14 @@ impl Cpu {
         self.update_cycles(8);
     }
 
-    fn rst_28(&mut self, bus: &mut MemoryBus) {
+    fn rst_28(&mut self, bus: &mut impl CpuBus) {
         let ret = self.program_counter.wrapping_add(1);
-        self.push_u16(ret, bus);
+        self.push16(ret, bus);
         self.program_counter = 0x0028;
         self.update_cycles(16);
     }
 
-    fn ldh_a_u8(&mut self, bus: &mut MemoryBus) {
+    fn ldh_a_u8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let addr = ((0xFF << 8) as u16) | value;
 
@@ -3074,18 +3203,15 @@ impl Cpu {
     }
 
     //0xF0 ~ 0xFF
-    fn pop_af(&mut self, bus: &mut MemoryBus) {
-        let low = self.pop_u8(bus);
-        let high = self.pop_u8(bus);
-
-        self.register_a = high;
-        self.register_f = FFlags::from_bits_truncate(low & 0xF0);
+    fn pop_af(&mut self, bus: &mut impl CpuBus) {
+        let value = self.pop16(bus);
+        self.set_af(value);
 
         self.advance_program_counter(1);
         self.update_cycles(12);
     }
 
-    fn ldh_a_c(&mut self, bus: &mut MemoryBus) {
+    fn ldh_a_c(&mut self, bus: &mut impl CpuBus) {
         let addr = self.register_concat(0xFF, self.register_c);
         self.register_a = self.read_u8(addr, bus);
 
@@ -3100,19 +3226,18 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn op_f4_unused(&mut self) {}
-
-    fn push_af(&mut self, bus: &mut MemoryBus) {
-        let f = self.register_f.bits() & 0xF0;
-        let af = ((self.register_a as u16) << 8) | (f as u16);
+    fn op_f4_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-        self.push_u16(af, bus);
+    fn push_af(&mut self, bus: &mut impl CpuBus) {
+        self.push16(self.af(), bus);
 
         self.advance_program_counter(1);
         self.update_cycles(16);
     }
 
-    fn or_u8(&mut self, bus: &mut MemoryBus) {
+    fn or_u8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus);
         self.register_a = self.or_(self.register_a, value);
 
@@ -3120,14 +3245,14 @@ impl Cpu {
         self.update_cycles(8);
     }
 
-    fn rst_30(&mut self, bus: &mut MemoryBus) {
+    fn rst_30(&mut self, bus: &mut impl CpuBus) {
         let ret = self.program_counter.wrapping_add(1);
-        self.push_u16(ret, bus);
+        self.push16(ret, bus);
         self.program_counter = 0x0030;
         self.update_cycles(16);
     }
 
-    fn ld_hl_sp_i8(&mut self, bus: &mut MemoryBus) {
+    fn ld_hl_sp_i8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus) as i8 as i16;
 
         let sp = self.stack_pointer;
@@ -3143,22 +3268,21 @@ impl Cpu {
             .set(FFlags::H, ((low_sp & 0x000F) + (low_val & 0x000F)) > 0x000F);
         self.register_f.set(FFlags::C, (low_sp + low_val) > 0x00FF);
 
-        self.register_h = (result >> 8) as u8;
-        self.register_l = result as u8;
+        self.set_hl(result);
 
         self.advance_program_counter(2);
         self.update_cycles(12);
     }
 
     fn ld_sp_hl(&mut self) {
-        let hl = self.register_concat(self.register_h, self.register_l);
+        let hl = self.hl();
         self.stack_pointer = hl;
 
         self.advance_program_counter(1);
         self.update_cycles(8);
     }
 
-    fn ld_a_u16(&mut self, bus: &mut MemoryBus) {
+    fn ld_a_u16(&mut self, bus: &mut impl CpuBus) {
         let low = self.read_u8(self.program_counter.wrapping_add(1), bus) as u16;
         let high = self.read_u8(self.program_counter.wrapping_add(2), bus) as u16;
         let addr = (high << 8) | low;
@@ -3176,11 +3300,15 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn op_fc_unused(&mut self) {}
+    fn op_fc_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn op_fd_unused(&mut self) {}
+    fn op_fd_unused(&mut self) {
+        self.illegal_opcode();
+    }
 
-    fn cp_u8(&mut self, bus: &mut MemoryBus) {
+    fn cp_u8(&mut self, bus: &mut impl CpuBus) {
         let value = self.read_u8(self.program_counter.wrapping_add(1), bus);
 
         self.cp(self.register_a, value);
@@ -3189,9 +3317,9 @@ impl Cpu {
         self.update_cycles(8);
     }
 
-    fn rst_38(&mut self, bus: &mut MemoryBus) {
+    fn rst_38(&mut self, bus: &mut impl CpuBus) {
         let ret = self.program_counter.wrapping_add(1);
-        self.push_u16(ret, bus);
+        self.push16(ret, bus);
 
         self.program_counter = 0x0038;
         self.update_cycles(16);
@@ -3228,8 +3356,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn rlc_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn rlc_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let result = self.rlc(value);
 
@@ -3272,8 +3400,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn rrc_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn rrc_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let result = self.rrc(value);
 
@@ -3323,8 +3451,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn rl_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn rl_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let result = self.rl(value);
 
@@ -3374,8 +3502,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn rr_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn rr_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let result = self.rr(value);
 
@@ -3425,8 +3553,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn sla_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn sla_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let result = self.sla(value);
 
@@ -3470,8 +3598,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn sra_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn sra_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let result = self.sra(value);
 
@@ -3514,8 +3642,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn swap_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn swap_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let result = self.swap(value);
 
@@ -3558,8 +3686,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn srl_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn srl_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let result = self.srl(value);
 
@@ -3602,8 +3730,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn bit_0_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn bit_0_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         self.bit(value, 0);
         self.update_cycles(8);
@@ -3644,8 +3772,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn bit_1_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn bit_1_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         self.bit(value, 1);
         self.update_cycles(8);
@@ -3686,8 +3814,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn bit_2_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn bit_2_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         self.bit(value, 2);
         self.update_cycles(8);
@@ -3728,8 +3856,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn bit_3_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn bit_3_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         self.bit(value, 3);
         self.update_cycles(8);
@@ -3770,8 +3898,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn bit_4_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn bit_4_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         self.bit(value, 4);
         self.update_cycles(8);
@@ -3812,8 +3940,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn bit_5_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn bit_5_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         self.bit(value, 5);
         self.update_cycles(8);
@@ -3854,8 +3982,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn bit_6_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn bit_6_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         self.bit(value, 6);
         self.update_cycles(8);
@@ -3896,8 +4024,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn bit_7_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn bit_7_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         self.bit(value, 7);
         self.update_cycles(8);
@@ -3938,8 +4066,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn res_0_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn res_0_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.res(value, 0);
         self.write_u8(addr, res_result, bus);
@@ -3981,8 +4109,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn res_1_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn res_1_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.res(value, 1);
         self.write_u8(addr, res_result, bus);
@@ -4024,8 +4152,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn res_2_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn res_2_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.res(value, 2);
         self.write_u8(addr, res_result, bus);
@@ -4067,8 +4195,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn res_3_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn res_3_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.res(value, 3);
         self.write_u8(addr, res_result, bus);
@@ -4110,8 +4238,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn res_4_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn res_4_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.res(value, 4);
         self.write_u8(addr, res_result, bus);
@@ -4153,8 +4281,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn res_5_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn res_5_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.res(value, 5);
         self.write_u8(addr, res_result, bus);
@@ -4196,8 +4324,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn res_6_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn res_6_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.res(value, 6);
         self.write_u8(addr, res_result, bus);
@@ -4239,8 +4367,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn res_7_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn res_7_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.res(value, 7);
         self.write_u8(addr, res_result, bus);
@@ -4281,8 +4409,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn set_0_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn set_0_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.set(value, 0);
         self.write_u8(addr, res_result, bus);
@@ -4324,8 +4452,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn set_1_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn set_1_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.set(value, 1);
         self.write_u8(addr, res_result, bus);
@@ -4367,8 +4495,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn set_2_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn set_2_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.set(value, 2);
         self.write_u8(addr, res_result, bus);
@@ -4410,8 +4538,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn set_3_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn set_3_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.set(value, 3);
         self.write_u8(addr, res_result, bus);
@@ -4453,8 +4581,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn set_4_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn set_4_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.set(value, 4);
         self.write_u8(addr, res_result, bus);
@@ -4496,8 +4624,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn set_5_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn set_5_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.set(value, 5);
         self.write_u8(addr, res_result, bus);
@@ -4539,8 +4667,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn set_6_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn set_6_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.set(value, 6);
         self.write_u8(addr, res_result, bus);
@@ -4582,8 +4710,8 @@ impl Cpu {
         self.update_cycles(4);
     }
 
-    fn set_7_hl_ptr(&mut self, bus: &mut MemoryBus) {
-        let addr = self.register_concat(self.register_h, self.register_l);
+    fn set_7_hl_ptr(&mut self, bus: &mut impl CpuBus) {
+        let addr = self.hl();
         let value = self.read_u8(addr, bus);
         let res_result = self.set(value, 7);
         self.write_u8(addr, res_result, bus);
@@ -4595,3 +4723,35 @@ impl Cpu {
         self.update_cycles(4);
     }
 }
+
+impl Savestate for Cpu {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u16(self.af());
+        w.write_u16(self.bc());
+        w.write_u16(self.de());
+        w.write_u16(self.hl());
+        w.write_u16(self.stack_pointer);
+        w.write_u16(self.program_counter);
+        w.write_bool(self.halt);
+        w.write_bool(self.stop);
+        w.write_bool(self.interruption);
+        w.write_bool(self.ime_pending);
+        w.write_bool(self.locked);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.set_af(r.read_u16()?);
+        self.set_bc(r.read_u16()?);
+        self.set_de(r.read_u16()?);
+        self.set_hl(r.read_u16()?);
+        self.stack_pointer = r.read_u16()?;
+        self.program_counter = r.read_u16()?;
+        self.halt = r.read_bool()?;
+        self.stop = r.read_bool()?;
+        self.interruption = r.read_bool()?;
+        self.ime_pending = r.read_bool()?;
+        self.locked = r.read_bool()?;
+        self.illegal_opcode = None;
+        Ok(())
+    }
+}
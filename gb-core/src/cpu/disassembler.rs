@@ -0,0 +1,128 @@
+use alloc::format;
+use alloc::string::String;
+
+// Operandos na ordem dos 3 bits do opcode
+const R8: [&str; 8] = ["B", "C", "D", "E", "H", "L", "(HL)", "A"];
+const R16: [&str; 4] = ["BC", "DE", "HL", "SP"];
+const R16_STACK: [&str; 4] = ["BC", "DE", "HL", "AF"];
+const R16_MEM: [&str; 4] = ["(BC)", "(DE)", "(HL+)", "(HL-)"];
+const CONDITIONS: [&str; 4] = ["NZ", "Z", "NC", "C"];
+const ALU: [&str; 8] = [
+    "ADD A, ", "ADC A, ", "SUB ", "SBC A, ", "AND ", "XOR ", "OR ", "CP ",
+];
+const ROTATIONS: [&str; 8] = ["RLC", "RRC", "RL", "RR", "SLA", "SRA", "SWAP", "SRL"];
+const ACCUMULATOR: [&str; 8] = ["RLCA", "RRCA", "RLA", "RRA", "DAA", "CPL", "SCF", "CCF"];
+
+// Decodifica a instrução em `addr` lendo os bytes por `read` (sem efeito colateral, tipo
+// o peek do bus). Devolve o texto e o tamanho em bytes; opcode ilegal vira "DB $xx"
+pub fn disassemble(addr: u16, read: impl Fn(u16) -> u8) -> (String, u16) {
+    let opcode = read(addr);
+    let d8 = read(addr.wrapping_add(1));
+    let d16 = u16::from_le_bytes([d8, read(addr.wrapping_add(2))]);
+    // Destino do JR: relativo ao fim da instrução
+    let relative = addr.wrapping_add(2).wrapping_add(d8 as i8 as u16);
+
+    let x = opcode >> 6;
+    let y = ((opcode >> 3) & 7) as usize;
+    let z = (opcode & 7) as usize;
+    let p = y >> 1;
+    let q = y & 1;
+
+    match (x, z) {
+        (0, 0) => match y {
+            0 => ("NOP".into(), 1),
+            1 => (format!("LD (${:04X}), SP", d16), 3),
+            2 => ("STOP".into(), 2),
+            3 => (format!("JR ${:04X}", relative), 2),
+            _ => (format!("JR {}, ${:04X}", CONDITIONS[y - 4], relative), 2),
+        },
+        (0, 1) if q == 0 => (format!("LD {}, ${:04X}", R16[p], d16), 3),
+        (0, 1) => (format!("ADD HL, {}", R16[p]), 1),
+        (0, 2) if q == 0 => (format!("LD {}, A", R16_MEM[p]), 1),
+        (0, 2) => (format!("LD A, {}", R16_MEM[p]), 1),
+        (0, 3) if q == 0 => (format!("INC {}", R16[p]), 1),
+        (0, 3) => (format!("DEC {}", R16[p]), 1),
+        (0, 4) => (format!("INC {}", R8[y]), 1),
+        (0, 5) => (format!("DEC {}", R8[y]), 1),
+        (0, 6) => (format!("LD {}, ${:02X}", R8[y], d8), 2),
+        (0, _) => (ACCUMULATOR[y].into(), 1),
+        (1, _) if opcode == 0x76 => ("HALT".into(), 1),
+        (1, _) => (format!("LD {}, {}", R8[y], R8[z]), 1),
+        (2, _) => (format!("{}{}", ALU[y], R8[z]), 1),
+        (_, 0) => match y {
+            0..=3 => (format!("RET {}", CONDITIONS[y]), 1),
+            4 => (format!("LDH ($FF{:02X}), A", d8), 2),
+            5 => (format!("ADD SP, {}", d8 as i8), 2),
+            6 => (format!("LDH A, ($FF{:02X})", d8), 2),
+            _ => (format!("LD HL, SP{:+}", d8 as i8), 2),
+        },
+        (_, 1) if q == 0 => (format!("POP {}", R16_STACK[p]), 1),
+        (_, 1) => (["RET", "RETI", "JP HL", "LD SP, HL"][p].into(), 1),
+        (_, 2) => match y {
+            0..=3 => (format!("JP {}, ${:04X}", CONDITIONS[y], d16), 3),
+            4 => ("LD ($FF00+C), A".into(), 1),
+            5 => (format!("LD (${:04X}), A", d16), 3),
+            6 => ("LD A, ($FF00+C)".into(), 1),
+            _ => (format!("LD A, (${:04X})", d16), 3),
+        },
+        (_, 3) => match y {
+            0 => (format!("JP ${:04X}", d16), 3),
+            1 => (prefixed(d8), 2),
+            6 => ("DI".into(), 1),
+            7 => ("EI".into(), 1),
+            _ => illegal(opcode),
+        },
+        (_, 4) if y < 4 => (format!("CALL {}, ${:04X}", CONDITIONS[y], d16), 3),
+        (_, 5) if q == 0 => (format!("PUSH {}", R16_STACK[p]), 1),
+        (_, 5) if p == 0 => (format!("CALL ${:04X}", d16), 3),
+        (_, 6) => (format!("{}${:02X}", ALU[y], d8), 2),
+        (_, 7) => (format!("RST ${:02X}", y * 8), 1),
+        _ => illegal(opcode),
+    }
+}
+
+// Segundo byte depois do 0xCB
+fn prefixed(opcode: u8) -> String {
+    let y = ((opcode >> 3) & 7) as usize;
+    let register = R8[(opcode & 7) as usize];
+    match opcode >> 6 {
+        0 => format!("{} {}", ROTATIONS[y], register),
+        1 => format!("BIT {}, {}", y, register),
+        2 => format!("RES {}, {}", y, register),
+        _ => format!("SET {}, {}", y, register),
+    }
+}
+
+fn illegal(opcode: u8) -> (String, u16) {
+    (format!("DB ${:02X}", opcode), 1)
+}
+
+// Tamanho em bytes da instrução que começa com `opcode`, sem montar o texto
+pub fn instruction_length(opcode: u8) -> u16 {
+    match opcode {
+        0x01 | 0x08 | 0x11 | 0x21 | 0x31 | 0xEA | 0xFA => 3,
+        0xC2 | 0xC3 | 0xCA | 0xD2 | 0xDA => 3,
+        0xC4 | 0xCC | 0xCD | 0xD4 | 0xDC => 3,
+        0x10 | 0x18 | 0x20 | 0x28 | 0x30 | 0x38 | 0xCB => 2,
+        0xE0 | 0xE8 | 0xF0 | 0xF8 => 2,
+        // LD r, d8 e ALU A, d8
+        _ if opcode & 0xC7 == 0x06 || opcode & 0xC7 == 0xC6 => 2,
+        _ => 1,
+    }
+}
+
+// Destino fixo de JP, JR, CALL e RST (com ou sem condição); JP HL e RET não têm
+pub fn branch_target(addr: u16, read: impl Fn(u16) -> u8) -> Option<u16> {
+    let opcode = read(addr);
+    let d8 = read(addr.wrapping_add(1));
+    let d16 = u16::from_le_bytes([d8, read(addr.wrapping_add(2))]);
+    match opcode {
+        0x18 | 0x20 | 0x28 | 0x30 | 0x38 => {
+            Some(addr.wrapping_add(2).wrapping_add(d8 as i8 as u16))
+        }
+        0xC2 | 0xC3 | 0xCA | 0xD2 | 0xDA => Some(d16),
+        0xC4 | 0xCC | 0xCD | 0xD4 | 0xDC => Some(d16),
+        _ if opcode & 0xC7 == 0xC7 => Some((opcode & 0x38) as u16),
+        _ => None,
+    }
+}
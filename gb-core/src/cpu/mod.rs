@@ -0,0 +1,9 @@
+pub mod cpu;
+pub mod disassembler;
+#[cfg(test)]
+pub mod test_cpu;
+#[cfg(test)]
+mod tests;
+
+pub use cpu::*;
+pub use disassembler::*;
@@ -0,0 +1,208 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::bus::TestBus;
+use crate::cpu::{Cpu, FFlags, disassemble, instruction_length};
+
+// Onde os programas dos testes começam, como no entry point do cartucho
+pub const PROGRAM_START: u16 = 0x0100;
+
+// Registradores pros asserts
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Reg {
+    A,
+    B,
+    C,
+    D,
+    E,
+    H,
+    L,
+    AF,
+    BC,
+    DE,
+    HL,
+    SP,
+    PC,
+}
+
+// CPU num TestBus com o programa em 0x0100: registradores zerados, SP em FFFE e IME
+// desligado
+pub struct TestCpu {
+    pub cpu: Cpu,
+    pub bus: TestBus,
+}
+
+impl TestCpu {
+    pub fn new(program: &[u8]) -> Self {
+        let mut cpu = Cpu::new();
+        cpu.program_counter = PROGRAM_START;
+        cpu.stack_pointer = 0xFFFE;
+        let mut bus = TestBus::new();
+        bus.load(PROGRAM_START, program);
+        Self { cpu, bus }
+    }
+
+    // Programa na sintaxe do disassembler (ver assemble)
+    pub fn with_source(source: &str) -> Self {
+        Self::new(&assemble(source))
+    }
+
+    // `count` passos da CPU (instruções, interrupções atendidas ou ciclos parados em HALT);
+    // devolve os ciclos somados
+    pub fn run(&mut self, count: usize) -> u32 {
+        (0..count)
+            .map(|_| self.cpu.step(&mut self.bus) as u32)
+            .sum()
+    }
+
+    pub fn reg(&self, reg: Reg) -> u16 {
+        let cpu = &self.cpu;
+        match reg {
+            Reg::A => cpu.register_a as u16,
+            Reg::B => cpu.register_b as u16,
+            Reg::C => cpu.register_c as u16,
+            Reg::D => cpu.register_d as u16,
+            Reg::E => cpu.register_e as u16,
+            Reg::H => cpu.register_h as u16,
+            Reg::L => cpu.register_l as u16,
+            Reg::AF => cpu.af(),
+            Reg::BC => cpu.bc(),
+            Reg::DE => cpu.de(),
+            Reg::HL => cpu.hl(),
+            Reg::SP => cpu.stack_pointer,
+            Reg::PC => cpu.program_counter,
+        }
+    }
+
+    // Flags escritas como "ZNHC": a letra liga, '-' desliga ("Z-H-" = Z e H)
+    pub fn set_flags(&mut self, flags: &str) {
+        self.cpu.register_f = parse_flags(flags);
+    }
+
+    #[track_caller]
+    pub fn assert_reg(&self, reg: Reg, expected: u16) {
+        let value = self.reg(reg);
+        assert_eq!(
+            value, expected,
+            "{:?}: esperado ${:X}, veio ${:X}",
+            reg, expected, value
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_flags(&self, expected: &str) {
+        let flags = format_flags(&self.cpu.register_f);
+        assert_eq!(flags, format_flags(&parse_flags(expected)), "flags");
+    }
+
+    #[track_caller]
+    pub fn assert_memory(&self, addr: u16, expected: &[u8]) {
+        let start = addr as usize;
+        assert_eq!(
+            &self.bus.memory[start..start + expected.len()],
+            expected,
+            "memória em ${:04X}",
+            addr
+        );
+    }
+}
+
+pub fn parse_flags(flags: &str) -> FFlags {
+    assert_eq!(flags.len(), 4, "flags são 4 caracteres (ZNHC): '{}'", flags);
+    let mut parsed = FFlags::empty();
+    for (c, flag) in flags
+        .chars()
+        .zip([FFlags::Z, FFlags::N, FFlags::H, FFlags::C])
+    {
+        match c {
+            '-' => {}
+            'Z' | 'N' | 'H' | 'C' => parsed |= flag,
+            other => panic!("flag inválida: '{}'", other),
+        }
+    }
+    parsed
+}
+
+pub fn format_flags(flags: &FFlags) -> String {
+    [
+        (FFlags::Z, 'Z'),
+        (FFlags::N, 'N'),
+        (FFlags::H, 'H'),
+        (FFlags::C, 'C'),
+    ]
+    .into_iter()
+    .map(|(flag, c)| if flags.contains(flag) { c } else { '-' })
+    .collect()
+}
+
+// Monta um programa a partir de 0x0100, uma instrução por linha (ou separadas por ';'),
+// com o texto exato do disassembler: "LD A, $15", "JR NZ, $0100", "LDH ($FF44), A".
+// Cada linha é comparada com o disassembly dos opcodes com o número dela como operando,
+// então o montador nunca discorda do disassembler. "DB $xx" sai como o byte cru
+pub fn assemble(source: &str) -> Vec<u8> {
+    let mut program = Vec::new();
+    let lines = source.split(['\n', ';']).map(str::trim);
+    for line in lines.filter(|line| !line.is_empty()) {
+        let pc = PROGRAM_START.wrapping_add(program.len() as u16);
+        match encode(line, pc) {
+            Some(bytes) => program.extend_from_slice(&bytes),
+            None => panic!("instrução não reconhecida: '{}'", line),
+        }
+    }
+    program
+}
+
+fn encode(line: &str, pc: u16) -> Option<Vec<u8>> {
+    let wanted = normalize(line);
+    let value = operand(line).unwrap_or(0);
+
+    for opcode in 0..=0xFF_u8 {
+        // Segundo byte (ou os dois) que o opcode levaria com esse número
+        let operands: Vec<u16> = match opcode {
+            0xCB => (0..=0xFF).collect(),
+            0x18 | 0x20 | 0x28 | 0x30 | 0x38 => {
+                vec![value.wrapping_sub(pc.wrapping_add(2)) & 0xFF]
+            }
+            _ => match instruction_length(opcode) {
+                1 => vec![0],
+                2 => vec![value & 0xFF],
+                _ => vec![value],
+            },
+        };
+        for operand in operands {
+            let [low, high] = operand.to_le_bytes();
+            let bytes = [opcode, low, high];
+            let read = |addr: u16| bytes[(addr.wrapping_sub(pc) as usize).min(2)];
+            let (text, len) = disassemble(pc, read);
+            if normalize(&text) == wanted {
+                return Some(bytes[..len as usize].to_vec());
+            }
+        }
+    }
+    None
+}
+
+// Sem espaços e em maiúsculas: "ld a,$15" = "LD A, $15"
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+// Primeiro número da linha: $hexa ou decimal com sinal (ADD SP, -2; LD HL, SP+5)
+fn operand(line: &str) -> Option<u16> {
+    if let Some((_, rest)) = line.split_once('$') {
+        let hex: String = rest.chars().take_while(char::is_ascii_hexdigit).collect();
+        return u16::from_str_radix(&hex, 16).ok();
+    }
+    let start = line.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = line[start..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    let value: i32 = digits.parse().ok()?;
+    let negative = line[..start].ends_with('-');
+    Some(if negative { -value } else { value } as u16)
+}
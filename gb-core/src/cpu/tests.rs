@@ -0,0 +1,220 @@
+use crate::bus::TestBus;
+use crate::cpu::test_cpu::{Reg, TestCpu, assemble};
+use crate::cpu::{Cpu, FFlags};
+
+#[test]
+fn add_imediato_zera_com_half_carry_e_carry() {
+    let mut t = TestCpu::with_source("LD A, $3A; ADD A, $C6");
+    t.run(2);
+    t.assert_reg(Reg::A, 0x00);
+    t.assert_flags("Z-HC");
+    t.assert_reg(Reg::PC, 0x0104);
+}
+
+#[test]
+fn push_pop_passa_pela_pilha() {
+    let mut t = TestCpu::with_source("LD BC, $1234; PUSH BC; POP DE");
+    let cycles = t.run(3);
+    t.assert_reg(Reg::DE, 0x1234);
+    t.assert_reg(Reg::SP, 0xFFFE);
+    t.assert_memory(0xFFFC, &[0x34, 0x12]);
+    assert_eq!(cycles, 12 + 16 + 12);
+}
+
+#[test]
+fn store_em_endereco_absoluto() {
+    let mut t = TestCpu::with_source("LD A, $5A; LD ($C000), A; LD HL, $C000; INC (HL)");
+    t.run(4);
+    t.assert_memory(0xC000, &[0x5B]);
+    t.assert_flags("----");
+}
+
+#[test]
+fn interrupcao_habilitada_vai_pro_vetor() {
+    let mut t = TestCpu::with_source("EI; NOP; NOP");
+    t.bus.memory[0xFFFF] = 0x04;
+    t.bus.memory[0xFF0F] = 0x04;
+    // EI só vale depois da instrução seguinte
+    t.run(2);
+    t.assert_reg(Reg::PC, 0x0102);
+    let cycles = t.run(1);
+    assert_eq!(cycles, 20);
+    t.assert_reg(Reg::PC, 0x0050);
+    t.assert_memory(0xFFFC, &[0x02, 0x01]);
+    assert_eq!(t.bus.memory[0xFF0F], 0x00);
+}
+
+#[test]
+fn halt_com_ime_desligado_acorda_sem_atender() {
+    let mut t = TestCpu::with_source("HALT; INC A");
+    t.bus.memory[0xFFFF] = 0x01;
+    t.run(3);
+    assert!(t.cpu.halt);
+    t.assert_reg(Reg::A, 0x00);
+
+    t.bus.memory[0xFF0F] = 0x01;
+    t.run(1);
+    assert!(!t.cpu.halt);
+    t.assert_reg(Reg::A, 0x01);
+    assert_eq!(t.bus.memory[0xFF0F], 0x01);
+}
+
+#[test]
+fn montador_concorda_com_o_disassembler() {
+    assert_eq!(assemble("JR $0100"), [0x18, 0xFE]);
+    assert_eq!(assemble("LDH ($FF44), A"), [0xE0, 0x44]);
+    assert_eq!(assemble("ADD SP, -2"), [0xE8, 0xFE]);
+    assert_eq!(assemble("BIT 7, H; RST $38"), [0xCB, 0x7C, 0xFF]);
+    assert_eq!(assemble("ld hl,$C000\nDB $DD"), [0x21, 0x00, 0xC0, 0xDD]);
+}
+
+// DAA de referência escrito como no SameBoy (ajuste em 16 bits, carry pelo bit 8), só
+// pra comparar com a CPU. Devolve (A, F)
+fn daa_reference(a: u8, f: u8) -> (u8, u8) {
+    let (n, h, c) = (f & 0x40 != 0, f & 0x20 != 0, f & 0x10 != 0);
+    let mut result = a as u16;
+    if n {
+        if h {
+            result = result.wrapping_sub(0x06) & 0xFF;
+        }
+        if c {
+            result = result.wrapping_sub(0x60);
+        }
+    } else {
+        if h || (result & 0x0F) > 0x09 {
+            result += 0x06;
+        }
+        if c || result > 0x9F {
+            result += 0x60;
+        }
+    }
+    let carry = c || result & 0x100 != 0;
+    let result = result as u8;
+    let mut flags = f & 0x40;
+    if result == 0 {
+        flags |= 0x80;
+    }
+    if carry {
+        flags |= 0x10;
+    }
+    (result, flags)
+}
+
+#[test]
+fn daa_bate_com_a_referencia_em_todas_as_entradas() {
+    let mut t = TestCpu::with_source("DAA");
+    for a in 0..=0xFF_u8 {
+        for flags in 0..16_u8 {
+            let f = flags << 4;
+            t.cpu.program_counter = 0x0100;
+            t.cpu.register_a = a;
+            t.cpu.register_f = FFlags::from_bits_truncate(f);
+            assert_eq!(t.run(1), 4);
+
+            let (expected_a, expected_f) = daa_reference(a, f);
+            assert_eq!(
+                (t.cpu.register_a, t.cpu.register_f.bits()),
+                (expected_a, expected_f),
+                "DAA com A=${:02X} F=${:02X}",
+                a,
+                f
+            );
+        }
+    }
+}
+
+fn bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+// O que os jogos usam de fato: soma e subtração de dois BCD válidos, com e sem carry de
+// entrada, seguidas de DAA dão o resultado decimal e o carry/borrow das centenas
+#[test]
+fn daa_depois_de_soma_e_subtracao_bcd() {
+    for (source, subtract) in [
+        ("ADD A, B; DAA", false),
+        ("ADC A, B; DAA", false),
+        ("SUB B; DAA", true),
+        ("SBC A, B; DAA", true),
+    ] {
+        let mut t = TestCpu::with_source(source);
+        for x in 0..100_u8 {
+            for y in 0..100_u8 {
+                for carry_in in [false, true] {
+                    t.cpu.program_counter = 0x0100;
+                    t.cpu.register_a = bcd(x);
+                    t.cpu.register_b = bcd(y);
+                    t.set_flags(if carry_in { "---C" } else { "----" });
+                    t.run(2);
+
+                    let carry = (source.contains("ADC") || source.contains("SBC")) && carry_in;
+                    let (value, overflow) = if subtract {
+                        let value = x as i16 - y as i16 - carry as i16;
+                        (value.rem_euclid(100) as u8, value < 0)
+                    } else {
+                        let value = x as u16 + y as u16 + carry as u16;
+                        ((value % 100) as u8, value >= 100)
+                    };
+                    assert_eq!(
+                        (t.cpu.register_a, t.cpu.flag(FFlags::C)),
+                        (bcd(value), overflow),
+                        "{} com {} e {} (carry {})",
+                        source,
+                        x,
+                        y,
+                        carry_in
+                    );
+                    assert_eq!(t.cpu.flag(FFlags::Z), value == 0);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn pares_de_registradores_vao_e_voltam() {
+    let mut cpu = Cpu::new();
+    cpu.set_bc(0x1234);
+    cpu.set_de(0x5678);
+    cpu.set_hl(0x9ABC);
+    assert_eq!((cpu.bc(), cpu.de(), cpu.hl()), (0x1234, 0x5678, 0x9ABC));
+    assert_eq!((cpu.register_b, cpu.register_c), (0x12, 0x34));
+    assert_eq!((cpu.register_d, cpu.register_e), (0x56, 0x78));
+    assert_eq!((cpu.register_h, cpu.register_l), (0x9A, 0xBC));
+
+    cpu.set_af(0x12F0);
+    assert_eq!(cpu.af(), 0x12F0);
+    assert_eq!(cpu.register_a, 0x12);
+}
+
+#[test]
+fn set_af_descarta_o_nibble_baixo_de_f() {
+    let mut cpu = Cpu::new();
+    cpu.set_af(0xABFF);
+    assert_eq!(cpu.af(), 0xABF0);
+    assert!(cpu.flag(FFlags::Z) && cpu.flag(FFlags::N));
+    assert!(cpu.flag(FFlags::H) && cpu.flag(FFlags::C));
+
+    cpu.set_flag(FFlags::N, false);
+    cpu.set_flag(FFlags::C, false);
+    assert_eq!(cpu.af(), 0xABA0);
+    assert!(!cpu.flag(FFlags::C));
+}
+
+#[test]
+fn push16_e_pop16_andam_o_sp_em_little_endian() {
+    let mut cpu = Cpu::new();
+    let mut bus = TestBus::new();
+    cpu.stack_pointer = 0xFFFE;
+
+    cpu.push16(0x1234, &mut bus);
+    assert_eq!(cpu.stack_pointer, 0xFFFC);
+    // Byte baixo no endereço menor, como o PUSH
+    assert_eq!(bus.memory[0xFFFC..0xFFFE], [0x34, 0x12]);
+
+    cpu.push16(0xBEEF, &mut bus);
+    assert_eq!(cpu.stack_pointer, 0xFFFA);
+    assert_eq!(cpu.pop16(&mut bus), 0xBEEF);
+    assert_eq!(cpu.pop16(&mut bus), 0x1234);
+    assert_eq!(cpu.stack_pointer, 0xFFFE);
+}
@@ -0,0 +1,92 @@
+use alloc::vec::Vec;
+
+use crate::debug::Location;
+
+// Recursão sem fim não cresce a pilha sombra pra sempre: os quadros mais antigos saem
+const MAX_DEPTH: usize = 1024;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FrameKind {
+    Call,
+    Rst,
+    Interrupt,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct CallFrame {
+    pub kind: FrameKind,
+    // Destino: começo da função ou vetor da interrupção
+    pub entry: Location,
+    // Para onde o RET volta (a instrução seguinte ao CALL, ou a interrompida)
+    pub return_to: Location,
+    // SP com o endereço de retorno já empilhado
+    pub stack_pointer: u16,
+}
+
+// Pilha de chamadas paralela à da CPU. Um quadro sai quando o SP sobe acima do endereço de
+// retorno dele, seja por RET/RETI ou por truques que descartam o retorno (POP, ADD SP,
+// LD SP, JP no lugar do RET)
+#[derive(Clone, Default)]
+pub struct CallStack {
+    frames: Vec<CallFrame>,
+}
+
+impl CallStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Do mais antigo pro mais recente
+    pub fn frames(&self) -> &[CallFrame] {
+        &self.frames
+    }
+
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    // Depois de um step que mexeu no SP. `at` é a instrução executada (opcode `opcode`), ou
+    // o PC interrompido quando `interrupt`; `next` e `stack_pointer` são os de depois
+    pub fn record(
+        &mut self,
+        at: Location,
+        opcode: u8,
+        interrupt: bool,
+        next: Location,
+        stack_pointer: u16,
+    ) {
+        while self
+            .frames
+            .last()
+            .is_some_and(|frame| stack_pointer > frame.stack_pointer)
+        {
+            self.frames.pop();
+        }
+
+        let (kind, length) = match opcode {
+            _ if interrupt => (FrameKind::Interrupt, 0),
+            0xCD | 0xC4 | 0xCC | 0xD4 | 0xDC => (FrameKind::Call, 3),
+            0xC7 | 0xCF | 0xD7 | 0xDF | 0xE7 | 0xEF | 0xF7 | 0xFF => (FrameKind::Rst, 1),
+            _ => return,
+        };
+        let return_to = (at.0, at.1.wrapping_add(length));
+        // CALL cc não tomado não empilha nada
+        if !interrupt && next.1 == return_to.1 {
+            return;
+        }
+
+        if self.frames.len() == MAX_DEPTH {
+            self.frames.remove(0);
+        }
+        self.frames.push(CallFrame {
+            kind,
+            entry: next,
+            return_to,
+            stack_pointer,
+        });
+    }
+}
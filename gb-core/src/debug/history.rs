@@ -0,0 +1,128 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::bus::MemoryBus;
+use crate::cpu::Cpu;
+use crate::debug::{Location, Symbols, location};
+
+// Uma instrução como estava antes de executar: 20 bytes, copiados sem formatar nada
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct HistoryEntry {
+    pub pc: u16,
+    // Banco da ROM onde o PC está e o mapeado em 4000-7FFF (destino de JP/CALL)
+    pub bank: u16,
+    pub rom_bank: u16,
+    // Opcode e os dois bytes seguintes: o disassembly não depende da memória de depois
+    pub bytes: [u8; 3],
+    pub af: u16,
+    pub bc: u16,
+    pub de: u16,
+    pub hl: u16,
+    pub sp: u16,
+}
+
+impl HistoryEntry {
+    pub fn capture(cpu: &Cpu, bus: &MemoryBus) -> Self {
+        let pc = cpu.program_counter;
+        let (bank, _) = location(bus, pc);
+        Self {
+            pc,
+            bank: bank as u16,
+            rom_bank: location(bus, 0x4000).0 as u16,
+            bytes: [
+                bus.peek(pc),
+                bus.peek(pc.wrapping_add(1)),
+                bus.peek(pc.wrapping_add(2)),
+            ],
+            af: cpu.af(),
+            bc: cpu.bc(),
+            de: cpu.de(),
+            hl: cpu.hl(),
+            sp: cpu.stack_pointer,
+        }
+    }
+
+    pub fn location(&self) -> Location {
+        (self.bank as usize, self.pc)
+    }
+
+    // A instrução como estava na memória, com o nome do destino de JP/CALL
+    pub fn disassemble(&self, symbols: &Symbols) -> String {
+        let read = |addr: u16| {
+            let index = addr.wrapping_sub(self.pc) as usize;
+            self.bytes.get(index).copied().unwrap_or(0xFF)
+        };
+        symbols.disassemble(self.pc, self.rom_bank as usize, read).0
+    }
+
+    // Uma linha como no log de execução: banco:PC, label, registradores e o disassembly
+    pub fn format(&self, symbols: &Symbols) -> String {
+        let text = self.disassemble(symbols);
+        let label = symbols
+            .describe(self.bank as usize, self.pc)
+            .unwrap_or_default();
+        format!(
+            "{:02X}:{:04X} {:<24} AF={:04X} BC={:04X} DE={:04X} HL={:04X} SP={:04X}  {}",
+            self.bank, self.pc, label, self.af, self.bc, self.de, self.hl, self.sp, text
+        )
+    }
+}
+
+// Buffer circular das últimas instruções executadas, sempre ligado: gravar é só copiar
+// uma HistoryEntry por cima da mais antiga
+#[derive(Clone)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    capacity: usize,
+    // Próxima posição a sobrescrever quando o buffer está cheio
+    next: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            capacity: capacity.max(1),
+            next: 0,
+        }
+    }
+
+    pub fn record(&mut self, entry: HistoryEntry) {
+        if self.entries.len() < self.capacity {
+            self.entries.push(entry);
+        } else {
+            self.entries[self.next] = entry;
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Da mais antiga pra mais recente. Antes de encher, `next` é o fim do Vec e a primeira
+    // metade fica vazia
+    pub fn iter(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries[self.next..]
+            .iter()
+            .chain(&self.entries[..self.next])
+    }
+
+    // As `count` mais recentes, da mais antiga pra mais recente
+    pub fn last(&self, count: usize) -> Vec<HistoryEntry> {
+        self.iter()
+            .skip(self.len().saturating_sub(count))
+            .copied()
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.next = 0;
+    }
+}
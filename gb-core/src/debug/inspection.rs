@@ -0,0 +1,80 @@
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::bus::MemoryBus;
+use crate::cpu::Cpu;
+use crate::debug::{History, HistoryEntry, IoRegisters, Symbols, Watch, WatchValue};
+
+// Bytes lidos a partir do PC: dá pra umas 16 instruções
+const CODE_BYTES: usize = 48;
+pub const MEMORY_BYTES: usize = 256;
+// Instruções já executadas mostradas antes do PC
+const RECENT: usize = 8;
+
+// Retrato do estado da CPU e de um pedaço da memória, pros debuggers fora da thread de
+// emulação (TUI, painéis de debug). Tudo lido com peek, sem efeito colateral
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inspection {
+    // PC, bancos e registradores antes da próxima instrução
+    pub cpu: HistoryEntry,
+    pub ime: bool,
+    pub halt: bool,
+    pub locked: bool,
+    // Memória a partir do PC, pro disassembly das próximas instruções
+    pub code: Vec<u8>,
+    pub memory_start: u16,
+    pub memory: Vec<u8>,
+    // Últimas instruções executadas, da mais antiga pra mais recente
+    pub recent: Vec<HistoryEntry>,
+    pub breakpoints: Vec<u16>,
+    pub io: IoRegisters,
+    pub watches: Vec<WatchValue>,
+}
+
+impl Inspection {
+    pub fn capture(
+        cpu: &Cpu,
+        bus: &MemoryBus,
+        history: &History,
+        breakpoints: &BTreeSet<u16>,
+        watches: &[Watch],
+        memory_start: u16,
+    ) -> Self {
+        Self {
+            cpu: HistoryEntry::capture(cpu, bus),
+            ime: cpu.interruption,
+            halt: cpu.halt,
+            locked: cpu.locked,
+            code: bus.peek_range(cpu.program_counter, CODE_BYTES),
+            memory_start,
+            memory: bus.peek_range(memory_start, MEMORY_BYTES),
+            recent: history.last(RECENT),
+            breakpoints: breakpoints.iter().copied().collect(),
+            io: IoRegisters::capture(cpu, bus),
+            watches: watches.iter().map(|watch| watch.eval(cpu, bus)).collect(),
+        }
+    }
+
+    // Byte em `addr` se estiver no pedaço lido a partir do PC
+    pub fn code_at(&self, addr: u16) -> Option<u8> {
+        let offset = addr.wrapping_sub(self.cpu.pc) as usize;
+        self.code.get(offset).copied()
+    }
+
+    // Instruções a partir do PC (endereço e texto) até acabar o pedaço lido
+    pub fn upcoming(&self, symbols: &Symbols) -> Vec<(u16, String)> {
+        let read = |addr: u16| self.code_at(addr).unwrap_or(0xFF);
+        let mut lines = Vec::new();
+        let mut addr = self.cpu.pc;
+        loop {
+            let (text, length) = symbols.disassemble(addr, self.cpu.rom_bank as usize, read);
+            // Instrução cortada no fim do pedaço
+            if self.code_at(addr.wrapping_add(length - 1)).is_none() {
+                return lines;
+            }
+            lines.push((addr, text));
+            addr = addr.wrapping_add(length);
+        }
+    }
+}
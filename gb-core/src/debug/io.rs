@@ -0,0 +1,132 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::bus::MemoryBus;
+use crate::cpu::Cpu;
+
+// Bits 0-4 do IE/IF, nessa ordem de prioridade
+const INTERRUPTS: [&str; 5] = ["VBlank", "STAT", "Timer", "Serial", "Joypad"];
+const MODES: [&str; 4] = ["HBlank", "VBlank", "OAM scan", "desenho"];
+// Fontes do STAT nos bits 3-6
+const STAT_SOURCES: [&str; 4] = ["HBlank", "VBlank", "OAM", "LYC"];
+// Frequência do TIMA pelos bits 0-1 do TAC
+const TIMER_HZ: [u32; 4] = [4096, 262144, 65536, 16384];
+
+// Registradores de interrupção, LCD, timer e DMA, pro painel que explica por que uma
+// interrupção (não) chega
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct IoRegisters {
+    pub interrupt_enable: u8,
+    pub interrupt_flag: u8,
+    pub ime: bool,
+    pub lcdc: u8,
+    pub stat: u8,
+    pub ly: u8,
+    pub lyc: u8,
+    pub div: u8,
+    pub tima: u8,
+    pub tma: u8,
+    pub tac: u8,
+    pub dma_source: u8,
+    pub dma_active: bool,
+}
+
+impl IoRegisters {
+    pub fn capture(cpu: &Cpu, bus: &MemoryBus) -> Self {
+        Self {
+            interrupt_enable: bus.peek(0xFFFF),
+            interrupt_flag: bus.peek(0xFF0F),
+            ime: cpu.interruption,
+            lcdc: bus.peek(0xFF40),
+            stat: bus.peek(0xFF41),
+            ly: bus.peek(0xFF44),
+            lyc: bus.peek(0xFF45),
+            div: bus.peek(0xFF04),
+            tima: bus.peek(0xFF05),
+            tma: bus.peek(0xFF06),
+            tac: bus.peek(0xFF07),
+            dma_source: bus.dma.source(),
+            dma_active: bus.dma.active(),
+        }
+    }
+
+    // Um campo por linha, em texto
+    pub fn describe(&self) -> Vec<String> {
+        let lcdc = |bit: u8| self.lcdc & (1 << bit) != 0;
+        let on = |set: bool| if set { "on" } else { "off" };
+        let map = |bit: u8| if lcdc(bit) { 0x9C00 } else { 0x9800 };
+        let mut lines = Vec::new();
+
+        lines.push(format!(
+            "IME {}  IE=${:02X}  IF=${:02X}",
+            on(self.ime),
+            self.interrupt_enable,
+            self.interrupt_flag
+        ));
+        for (bit, name) in INTERRUPTS.iter().enumerate() {
+            let enabled = self.interrupt_enable & (1 << bit) != 0;
+            let requested = self.interrupt_flag & (1 << bit) != 0;
+            let state = match (enabled, requested) {
+                (true, true) if self.ime => "vai ser atendida",
+                (true, true) => "pendente, IME off",
+                (false, true) => "pedida, IE desligado",
+                (true, false) => "habilitada",
+                (false, false) => "-",
+            };
+            lines.push(format!("  {:<7} {}", name, state));
+        }
+
+        let sprite_size = if lcdc(2) { "8x16" } else { "8x8" };
+        let tiles = if lcdc(4) { 0x8000 } else { 0x8800 };
+        lines.push(format!(
+            "LCDC=${:02X}  LCD {}  BG {}  OBJ {} {}",
+            self.lcdc,
+            on(lcdc(7)),
+            on(lcdc(0)),
+            on(lcdc(1)),
+            sprite_size
+        ));
+        lines.push(format!("  tiles ${:04X}  mapa BG ${:04X}", tiles, map(3)));
+        lines.push(format!("  janela {}  mapa ${:04X}", on(lcdc(5)), map(6)));
+
+        let coincidence = if self.stat & (1 << 2) != 0 {
+            " (igual)"
+        } else {
+            ""
+        };
+        let sources: Vec<&str> = STAT_SOURCES
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| self.stat & (1 << (bit + 3)) != 0)
+            .map(|(_, name)| *name)
+            .collect();
+        lines.push(format!(
+            "STAT=${:02X}  modo {}  LY={} LYC={}{}",
+            self.stat,
+            MODES[(self.stat & 0b11) as usize],
+            self.ly,
+            self.lyc,
+            coincidence
+        ));
+        if sources.is_empty() {
+            lines.push(String::from("  fontes: nenhuma"));
+        } else {
+            lines.push(format!("  fontes: {}", sources.join(" ")));
+        }
+
+        lines.push(format!(
+            "DIV=${:02X} TIMA=${:02X} TMA=${:02X} TAC=${:02X}",
+            self.div, self.tima, self.tma, self.tac
+        ));
+        lines.push(format!(
+            "  timer {} a {} Hz",
+            on(self.tac & (1 << 2) != 0),
+            TIMER_HZ[(self.tac & 0b11) as usize]
+        ));
+
+        let dma = if self.dma_active { "rodando" } else { "parado" };
+        lines.push(format!("DMA {} (origem ${:02X}00)", dma, self.dma_source));
+        lines
+    }
+}
@@ -0,0 +1,20 @@
+pub mod call_stack;
+pub mod history;
+pub mod inspection;
+pub mod io;
+pub mod symbols;
+pub mod triggers;
+pub mod video;
+pub mod watch;
+
+pub use call_stack::*;
+pub use history::*;
+pub use inspection::*;
+pub use io::*;
+pub use symbols::*;
+pub use triggers::*;
+pub use video::*;
+pub use watch::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,155 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use crate::bus::MemoryBus;
+use crate::cpu::{branch_target, disassemble};
+
+// Endereço executado: banco da ROM (0 fora de 0x0000-0x7FFF) e PC
+pub type Location = (usize, u16);
+
+pub fn location(bus: &MemoryBus, pc: u16) -> Location {
+    let bank = if pc < 0x8000 {
+        bus.cartridge.rom_bank(pc)
+    } else {
+        0
+    };
+    (bank, pc)
+}
+
+// Símbolos de um .sym do RGBDS: "banco:endereço nome" por linha, comentários com ;. Os
+// endereços são Locations, então o banco só vale em 4000-7FFF e fora dali fica 0 (labels
+// de WRAMX/SRAM em bancos diferentes no mesmo endereço: vale a primeira)
+#[derive(Clone, Default)]
+pub struct Symbols {
+    names: BTreeMap<Location, String>,
+    addresses: BTreeMap<String, Location>,
+}
+
+impl Symbols {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|erro| format!("erro ao ler '{}': {}", path.display(), erro))?;
+        Self::parse(&text).map_err(|erro| format!("{}: {}", path.display(), erro))
+    }
+
+    // O rgblink gera o <rom>.sym do lado da ROM; sem ele, nenhum símbolo
+    #[cfg(feature = "std")]
+    pub fn for_rom(rom_path: &Path) -> Result<Self, String> {
+        let path = rom_path.with_extension("sym");
+        if path.exists() {
+            Self::load(&path)
+        } else {
+            Ok(Self::new())
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut symbols = Self::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = || format!("linha {}: esperado 'banco:endereço nome'", number + 1);
+            let (location, name) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let (bank, addr) = location.split_once(':').ok_or_else(invalid)?;
+            let bank = usize::from_str_radix(bank, 16).map_err(|_| invalid())?;
+            let addr = u16::from_str_radix(addr, 16).map_err(|_| invalid())?;
+
+            let location = (banked(addr, bank), addr);
+            let name = name.trim().to_string();
+            symbols
+                .names
+                .entry(location)
+                .or_insert_with(|| name.clone());
+            symbols.addresses.insert(name, location);
+        }
+
+        Ok(symbols)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    // (banco, endereço) de um label; o nome diferencia maiúsculas como no RGBDS
+    pub fn resolve(&self, name: &str) -> Option<Location> {
+        self.addresses.get(name).copied()
+    }
+
+    // Label exatamente neste endereço
+    pub fn name(&self, bank: usize, addr: u16) -> Option<&str> {
+        self.names
+            .get(&(banked(addr, bank), addr))
+            .map(String::as_str)
+    }
+
+    // Label mais próximo abaixo, na mesma área: "Main" ou "Main+$1C"
+    pub fn describe(&self, bank: usize, addr: u16) -> Option<String> {
+        let location = (banked(addr, bank), addr);
+        let (&(label_bank, label_addr), name) = self.names.range(..=location).next_back()?;
+        if label_bank != location.0 || area(label_addr) != area(addr) {
+            return None;
+        }
+        Some(match addr - label_addr {
+            0 => name.clone(),
+            offset => format!("{}+${:X}", name, offset),
+        })
+    }
+
+    // banco:endereço como no RGBDS, com o label quando tiver
+    pub fn locate(&self, (bank, addr): Location) -> String {
+        match self.describe(bank, addr) {
+            Some(label) => format!("{:02X}:{:04X} {}", bank, addr, label),
+            None => format!("{:02X}:{:04X}", bank, addr),
+        }
+    }
+
+    // Como o cpu::disassemble, com o nome do destino de JP/JR/CALL/RST num comentário.
+    // `bank` é o banco mapeado em 4000-7FFF
+    pub fn disassemble(&self, addr: u16, bank: usize, read: impl Fn(u16) -> u8) -> (String, u16) {
+        let (text, length) = disassemble(addr, &read);
+        let name = branch_target(addr, &read).and_then(|target| self.describe(bank, target));
+        match name {
+            Some(name) => (format!("{:<16} ; {}", text, name), length),
+            None => (text, length),
+        }
+    }
+}
+
+fn banked(addr: u16, bank: usize) -> usize {
+    if (0x4000..0x8000).contains(&addr) {
+        bank
+    } else {
+        0
+    }
+}
+
+// Região do mapa de memória: ROM0, ROMX, VRAM, SRAM, WRAM e o resto. Um label não cobre
+// endereços de outra região
+pub fn area(addr: u16) -> u8 {
+    match addr {
+        0x0000..=0x3FFF => 0,
+        0x4000..=0x7FFF => 1,
+        0x8000..=0x9FFF => 2,
+        0xA000..=0xBFFF => 3,
+        0xC000..=0xFDFF => 4,
+        _ => 5,
+    }
+}
@@ -0,0 +1,58 @@
+use alloc::vec;
+
+use crate::bus::MemoryBus;
+use crate::cartridge::Cartridge;
+use crate::cpu::Cpu;
+use crate::debug::{Symbols, Watch};
+
+fn machine() -> (Cpu, MemoryBus) {
+    let mut cpu = Cpu::new();
+    cpu.register_a = 0x3C;
+    cpu.set_hl(0xC100);
+    let mut bus = MemoryBus::new(Cartridge::load(vec![0; 0x8000]).unwrap());
+    bus.write(0xC100, 0x34);
+    bus.write(0xC101, 0x12);
+    bus.write(0xC102, 0x07);
+    (cpu, bus)
+}
+
+fn eval(text: &str) -> Option<u16> {
+    let symbols = Symbols::parse("00:C100 wPlayerHP\n00:C102 wLives").unwrap();
+    let (cpu, bus) = machine();
+    Watch::parse(text, &symbols).unwrap().eval(&cpu, &bus).value
+}
+
+#[test]
+fn watch_avalia_registradores_memoria_e_labels() {
+    assert_eq!(eval("a"), Some(0x3C));
+    assert_eq!(eval("HL"), Some(0xC100));
+    assert_eq!(eval("[hl]"), Some(0x34));
+    assert_eq!(eval("w[wPlayerHP]"), Some(0x1234));
+    assert_eq!(eval("[wLives] * 10 + 1"), Some(71));
+    assert_eq!(eval("(a & $0F) << 4 | 0x1"), Some(0xC1));
+    assert_eq!(eval("[hl + 1] - 0x13"), Some(0xFFFF));
+    assert_eq!(eval("a / ([hl] - $34)"), None);
+}
+
+#[test]
+fn watch_mostra_byte_ou_word() {
+    let symbols = Symbols::new();
+    let (cpu, bus) = machine();
+    let describe = |text| {
+        Watch::parse(text, &symbols)
+            .unwrap()
+            .eval(&cpu, &bus)
+            .describe()
+    };
+    assert_eq!(describe("[$C102]"), "[$C102] = $07 (7)");
+    assert_eq!(describe("hl"), "hl = $C100 (49408)");
+}
+
+#[test]
+fn watch_invalido() {
+    let symbols = Symbols::new();
+    assert!(Watch::parse("wNaoExiste", &symbols).is_err());
+    assert!(Watch::parse("[hl", &symbols).is_err());
+    assert!(Watch::parse("a +", &symbols).is_err());
+    assert!(Watch::parse("a b", &symbols).is_err());
+}
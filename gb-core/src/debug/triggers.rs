@@ -0,0 +1,48 @@
+use crate::debug::Location;
+
+// Nomes das interrupções na ordem dos bits do IF/IE
+pub const INTERRUPT_NAMES: [&str; 5] = ["vblank", "stat", "timer", "serial", "joypad"];
+
+// Condições além do PC que param a emulação como um breakpoint
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Trigger {
+    // Qualquer interrupção atendida, ou só a do bit dado
+    Interrupt(Option<u8>),
+    // Banco trocado no MBC (ROM em 4000-7FFF, RAM em A000-BFFF)
+    RomBank,
+    RamBank,
+}
+
+impl Trigger {
+    // "interrupt", o nome de uma interrupção, "rom-bank" ou "ram-bank"
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.to_lowercase().as_str() {
+            "interrupt" => Some(Trigger::Interrupt(None)),
+            "rom-bank" => Some(Trigger::RomBank),
+            "ram-bank" => Some(Trigger::RamBank),
+            name => INTERRUPT_NAMES
+                .iter()
+                .position(|&interrupt| interrupt == name)
+                .map(|bit| Trigger::Interrupt(Some(bit as u8))),
+        }
+    }
+}
+
+// O que disparou e onde. `pc` é a instrução que trocou o banco, ou a interrompida
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TriggerHit {
+    Interrupt {
+        bit: u8,
+        pc: Location,
+    },
+    RomBank {
+        old: usize,
+        new: usize,
+        pc: Location,
+    },
+    RamBank {
+        old: usize,
+        new: usize,
+        pc: Location,
+    },
+}
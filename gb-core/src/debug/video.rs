@@ -0,0 +1,204 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::bus::MemoryBus;
+use crate::ppu::LineRegisters;
+use crate::ppu::framebuffer::{DMG_SHADES, GB_H, GB_W};
+use crate::ppu::sprite::{ATTR_FLIP_X, ATTR_FLIP_Y, ATTR_PALETTE, Sprite};
+
+// Os 384 tiles da VRAM em 16 colunas
+pub const TILE_SHEET_W: usize = 128;
+pub const TILE_SHEET_H: usize = 192;
+// Os 40 objetos da OAM em 8 colunas de células 8x16
+pub const OAM_SHEET_W: usize = 64;
+pub const OAM_SHEET_H: usize = 80;
+pub const OAM_COLUMNS: usize = 8;
+// O plano inteiro do BG: 32x32 tiles
+pub const CANVAS_SIZE: usize = 256;
+
+// Contornos no canvas: área visível, janela e objetos
+const VIEWPORT_COLOR: [u8; 3] = [230, 41, 55];
+const WINDOW_COLOR: [u8; 3] = [0, 121, 241];
+const SPRITE_COLOR: [u8; 3] = [0, 228, 48];
+
+const LCDC_TALL_SPRITES: u8 = 1 << 2;
+const LCDC_BG_MAP: u8 = 1 << 3;
+const LCDC_TILE_DATA: u8 = 1 << 4;
+
+// Cor na tela de cada índice 0-3 com um BGP/OBP
+pub fn palette_rgb(palette: u8) -> [[u8; 3]; 4] {
+    core::array::from_fn(|color| DMG_SHADES[((palette >> (color * 2)) & 0b11) as usize])
+}
+
+// VRAM, OAM e os registradores que decidem como elas aparecem, pros visualizadores de
+// tiles e objetos dos frontends
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VideoInspection {
+    pub vram: Vec<u8>,
+    pub oam: Vec<u8>,
+    pub lcdc: u8,
+    pub bgp: u8,
+    pub obp0: u8,
+    pub obp1: u8,
+    // SCX/SCY/WX/WY de cada linha da tela no último frame
+    pub lines: Vec<LineRegisters>,
+}
+
+impl VideoInspection {
+    pub fn capture(bus: &MemoryBus) -> Self {
+        Self {
+            vram: bus.peek_range(0x8000, 0x2000),
+            oam: bus.peek_range(0xFE00, 0xA0),
+            lcdc: bus.peek(0xFF40),
+            bgp: bus.peek(0xFF47),
+            obp0: bus.peek(0xFF48),
+            obp1: bus.peek(0xFF49),
+            lines: bus.ppu.line_registers().to_vec(),
+        }
+    }
+
+    // Índice de cor (0-3) do pixel de um tile, contando do 0 em 8000
+    pub fn tile_pixel(&self, tile: usize, x: usize, y: usize) -> u8 {
+        let row = tile * 16 + y * 2;
+        let bit = 7 - x;
+        let low = (self.vram[row] >> bit) & 1;
+        let high = (self.vram[row + 1] >> bit) & 1;
+        (high << 1) | low
+    }
+
+    // Tiles com o índice de cor cru (sem BGP), que é o que está na VRAM
+    pub fn tile_sheet_rgba(&self) -> Vec<u8> {
+        let mut rgba = vec![0; TILE_SHEET_W * TILE_SHEET_H * 4];
+        for (index, pixel) in rgba.chunks_exact_mut(4).enumerate() {
+            let (x, y) = (index % TILE_SHEET_W, index / TILE_SHEET_W);
+            let tile = (y / 8) * (TILE_SHEET_W / 8) + x / 8;
+            let [r, g, b] = DMG_SHADES[self.tile_pixel(tile, x % 8, y % 8) as usize];
+            pixel.copy_from_slice(&[r, g, b, 255]);
+        }
+        rgba
+    }
+
+    // Plano de 256x256 do mapa do BG com o BGP, e por cima a área que cada linha da tela
+    // mostrou (SCX/SCY da linha), onde a janela cobriu e as caixas dos objetos
+    pub fn canvas_rgba(&self) -> Vec<u8> {
+        let mut rgba = vec![0; CANVAS_SIZE * CANVAS_SIZE * 4];
+        let colors = palette_rgb(self.bgp);
+        let map = if self.lcdc & LCDC_BG_MAP != 0 {
+            0x1C00
+        } else {
+            0x1800
+        };
+        for (index, pixel) in rgba.chunks_exact_mut(4).enumerate() {
+            let (x, y) = (index % CANVAS_SIZE, index / CANVAS_SIZE);
+            let tile = self.vram[map + (y / 8) * 32 + x / 8];
+            // Com o bit 4 do LCDC desligado os índices são com sinal a partir de 9000
+            let tile = if self.lcdc & LCDC_TILE_DATA != 0 {
+                tile as usize
+            } else {
+                (256 + tile as i8 as isize) as usize
+            };
+            let [r, g, b] = colors[self.tile_pixel(tile, x % 8, y % 8) as usize];
+            pixel.copy_from_slice(&[r, g, b, 255]);
+        }
+
+        // Pixel da tela na linha `ly` -> posição no plano, com a rolagem daquela linha
+        let mut mark = |ly: usize, sx: i32, color: [u8; 3]| {
+            let Some(line) = self.lines.get(ly) else {
+                return;
+            };
+            let x = (line.scx as i32 + sx).rem_euclid(CANVAS_SIZE as i32) as usize;
+            let y = (line.scy as usize + ly) % CANVAS_SIZE;
+            let offset = (y * CANVAS_SIZE + x) * 4;
+            rgba[offset..offset + 4].copy_from_slice(&[color[0], color[1], color[2], 255]);
+        };
+
+        for ly in 0..GB_H.min(self.lines.len()) {
+            let edges: Vec<i32> = if ly == 0 || ly == GB_H - 1 {
+                (0..GB_W as i32).collect()
+            } else {
+                vec![0, GB_W as i32 - 1]
+            };
+            for sx in edges {
+                mark(ly, sx, VIEWPORT_COLOR);
+            }
+            if self.lines[ly].window {
+                mark(ly, self.lines[ly].wx as i32 - 7, WINDOW_COLOR);
+            }
+        }
+
+        let height = if self.tall_sprites() { 16 } else { 8 };
+        for sprite in self.sprites() {
+            let (left, top) = (sprite.x as i32 - 8, sprite.y as i32 - 16);
+            for row in 0..height {
+                let ly = top + row;
+                if !(0..GB_H as i32).contains(&ly) {
+                    continue;
+                }
+                let columns: Vec<i32> = if row == 0 || row == height - 1 {
+                    (left..left + 8).collect()
+                } else {
+                    vec![left, left + 7]
+                };
+                for sx in columns {
+                    mark(ly as usize, sx, SPRITE_COLOR);
+                }
+            }
+        }
+        rgba
+    }
+
+    pub fn tall_sprites(&self) -> bool {
+        self.lcdc & LCDC_TALL_SPRITES != 0
+    }
+
+    pub fn sprites(&self) -> Vec<Sprite> {
+        self.oam
+            .chunks_exact(4)
+            .map(|bytes| Sprite::from_oam([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect()
+    }
+
+    // Cada objeto na sua célula, com o OBP dele e espelhamento; a cor 0 fica transparente
+    pub fn oam_sheet_rgba(&self) -> Vec<u8> {
+        let mut rgba = vec![0; OAM_SHEET_W * OAM_SHEET_H * 4];
+        let height = if self.tall_sprites() { 16 } else { 8 };
+
+        for (index, sprite) in self.sprites().iter().enumerate() {
+            let (left, top) = ((index % OAM_COLUMNS) * 8, (index / OAM_COLUMNS) * 16);
+            let colors = palette_rgb(if sprite.attributes & ATTR_PALETTE != 0 {
+                self.obp1
+            } else {
+                self.obp0
+            });
+            // No modo 8x16 o bit 0 do tile é ignorado
+            let first = if height == 16 {
+                sprite.tile & 0xFE
+            } else {
+                sprite.tile
+            } as usize;
+
+            for y in 0..height {
+                let row = if sprite.attributes & ATTR_FLIP_Y != 0 {
+                    height - 1 - y
+                } else {
+                    y
+                };
+                for x in 0..8 {
+                    let column = if sprite.attributes & ATTR_FLIP_X != 0 {
+                        7 - x
+                    } else {
+                        x
+                    };
+                    let color = self.tile_pixel(first + row / 8, column, row % 8);
+                    if color == 0 {
+                        continue;
+                    }
+                    let [r, g, b] = colors[color as usize];
+                    let offset = ((top + y) * OAM_SHEET_W + left + x) * 4;
+                    rgba[offset..offset + 4].copy_from_slice(&[r, g, b, 255]);
+                }
+            }
+        }
+        rgba
+    }
+}
@@ -0,0 +1,333 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::bus::MemoryBus;
+use crate::cpu::Cpu;
+use crate::debug::Symbols;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Register {
+    A,
+    F,
+    B,
+    C,
+    D,
+    E,
+    H,
+    L,
+    Af,
+    Bc,
+    De,
+    Hl,
+    Sp,
+    Pc,
+}
+
+impl Register {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "a" => Register::A,
+            "f" => Register::F,
+            "b" => Register::B,
+            "c" => Register::C,
+            "d" => Register::D,
+            "e" => Register::E,
+            "h" => Register::H,
+            "l" => Register::L,
+            "af" => Register::Af,
+            "bc" => Register::Bc,
+            "de" => Register::De,
+            "hl" => Register::Hl,
+            "sp" => Register::Sp,
+            "pc" => Register::Pc,
+            _ => return None,
+        })
+    }
+
+    fn read(self, cpu: &Cpu) -> u16 {
+        match self {
+            Register::A => cpu.register_a as u16,
+            Register::F => cpu.af() & 0xFF,
+            Register::B => cpu.register_b as u16,
+            Register::C => cpu.register_c as u16,
+            Register::D => cpu.register_d as u16,
+            Register::E => cpu.register_e as u16,
+            Register::H => cpu.register_h as u16,
+            Register::L => cpu.register_l as u16,
+            Register::Af => cpu.af(),
+            Register::Bc => cpu.bc(),
+            Register::De => cpu.de(),
+            Register::Hl => cpu.hl(),
+            Register::Sp => cpu.stack_pointer,
+            Register::Pc => cpu.program_counter,
+        }
+    }
+
+    fn wide(self) -> bool {
+        matches!(
+            self,
+            Register::Af | Register::Bc | Register::De | Register::Hl | Register::Sp | Register::Pc
+        )
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Expr {
+    Number(u16),
+    Register(Register),
+    // [expr]: um byte da memória; w[expr]: dois, little-endian
+    Byte(Box<Expr>),
+    Word(Box<Expr>),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    // Conta em 16 bits com overflow; None só na divisão por zero
+    fn eval(&self, cpu: &Cpu, bus: &MemoryBus) -> Option<u16> {
+        Some(match self {
+            Expr::Number(value) => *value,
+            Expr::Register(register) => register.read(cpu),
+            Expr::Byte(addr) => bus.peek(addr.eval(cpu, bus)?) as u16,
+            Expr::Word(addr) => {
+                let addr = addr.eval(cpu, bus)?;
+                u16::from_le_bytes([bus.peek(addr), bus.peek(addr.wrapping_add(1))])
+            }
+            Expr::Negate(value) => value.eval(cpu, bus)?.wrapping_neg(),
+            Expr::Not(value) => !value.eval(cpu, bus)?,
+            Expr::Binary(op, left, right) => {
+                let (left, right) = (left.eval(cpu, bus)?, right.eval(cpu, bus)?);
+                match op {
+                    '+' => left.wrapping_add(right),
+                    '-' => left.wrapping_sub(right),
+                    '*' => left.wrapping_mul(right),
+                    '/' => left.checked_div(right)?,
+                    '&' => left & right,
+                    '|' => left | right,
+                    '^' => left ^ right,
+                    '<' => left.checked_shl(right as u32).unwrap_or(0),
+                    _ => left.checked_shr(right as u32).unwrap_or(0),
+                }
+            }
+        })
+    }
+
+    // Resultado de 16 bits (mostrado com 4 dígitos) ou de um byte
+    fn wide(&self) -> bool {
+        match self {
+            Expr::Number(value) => *value > 0xFF,
+            Expr::Register(register) => register.wide(),
+            Expr::Byte(_) => false,
+            Expr::Word(_) => true,
+            Expr::Negate(_) | Expr::Not(_) => true,
+            Expr::Binary(_, left, right) => left.wide() || right.wide(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Token {
+    Number(u16),
+    Name(String),
+    // Operadores e parênteses; << e >> viram '<' e '>'
+    Symbol(char),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        i += 1;
+        match c {
+            _ if c.is_whitespace() => {}
+            '<' | '>' => {
+                if chars.get(i) != Some(&c) {
+                    return Err(format!("esperado '{}{}'", c, c));
+                }
+                i += 1;
+                tokens.push(Token::Symbol(c));
+            }
+            '+' | '-' | '*' | '/' | '&' | '|' | '^' | '~' | '(' | ')' | '[' | ']' => {
+                tokens.push(Token::Symbol(c))
+            }
+            '$' | '0'..='9' => {
+                while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(parse_number(&text)?));
+            }
+            _ if c.is_alphabetic() || c == '_' || c == '.' => {
+                // Labels do RGBDS: Main, Main.loop, wPlayerHP, .local
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.' | '@' | '#'))
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Name(chars[start..i].iter().collect()));
+            }
+            _ => return Err(format!("caractere inesperado: '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+// Decimal, 0x.. ou $..
+fn parse_number(text: &str) -> Result<u16, String> {
+    let parsed = if let Some(hex) = text.strip_prefix("0x").or(text.strip_prefix('$')) {
+        u16::from_str_radix(hex, 16)
+    } else {
+        text.parse()
+    };
+    parsed.map_err(|_| format!("número inválido: '{}'", text))
+}
+
+// Descida recursiva, uma função por nível de precedência (como em C): | ^ & << >> + - * /
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    symbols: &'a Symbols,
+}
+
+const LEVELS: [&[char]; 6] = [
+    &['|'],
+    &['^'],
+    &['&'],
+    &['<', '>'],
+    &['+', '-'],
+    &['*', '/'],
+];
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(found)) if found == c => Ok(()),
+            _ => Err(format!("esperado '{}'", c)),
+        }
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expr, String> {
+        let Some(ops) = LEVELS.get(level) else {
+            return self.unary();
+        };
+        let mut left = self.binary(level + 1)?;
+        while let Some(&Token::Symbol(op)) = self.peek() {
+            if !ops.contains(&op) {
+                break;
+            }
+            self.position += 1;
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Symbol('-')) => Ok(Expr::Negate(Box::new(self.unary()?))),
+            Some(Token::Symbol('~')) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Symbol('(')) => {
+                let expr = self.binary(0)?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(Token::Symbol('[')) => Ok(Expr::Byte(Box::new(self.memory()?))),
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::Name(name)) => {
+                if name == "w" && self.peek() == Some(&Token::Symbol('[')) {
+                    self.position += 1;
+                    return Ok(Expr::Word(Box::new(self.memory()?)));
+                }
+                // Label vale o endereço, como no RGBDS; o conteúdo é [label]
+                match (Register::parse(&name), self.symbols.resolve(&name)) {
+                    (Some(register), _) => Ok(Expr::Register(register)),
+                    (None, Some((_, addr))) => Ok(Expr::Number(addr)),
+                    (None, None) => Err(format!("símbolo desconhecido: '{}'", name)),
+                }
+            }
+            Some(Token::Symbol(c)) => Err(format!("'{}' inesperado", c)),
+            None => Err("expressão incompleta".to_string()),
+        }
+    }
+
+    // Endereço depois do '[' até o ']'
+    fn memory(&mut self) -> Result<Expr, String> {
+        let addr = self.binary(0)?;
+        self.expect(']')?;
+        Ok(addr)
+    }
+}
+
+// Expressão acompanhada pelo debugger: registradores (a, hl, sp...), números, labels do
+// .sym, memória ([endereço] e w[endereço]) e + - * / & | ^ ~ << >> com parênteses
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Watch {
+    pub text: String,
+    expr: Expr,
+}
+
+impl Watch {
+    pub fn parse(text: &str, symbols: &Symbols) -> Result<Self, String> {
+        let text = text.trim();
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            position: 0,
+            symbols,
+        };
+        let expr = parser
+            .binary(0)
+            .and_then(|expr| match parser.peek() {
+                None => Ok(expr),
+                Some(_) => Err("sobrou texto depois da expressão".to_string()),
+            })
+            .map_err(|erro| format!("watch '{}': {}", text, erro))?;
+        Ok(Self {
+            text: text.to_string(),
+            expr,
+        })
+    }
+
+    pub fn eval(&self, cpu: &Cpu, bus: &MemoryBus) -> WatchValue {
+        WatchValue {
+            text: self.text.clone(),
+            value: self.expr.eval(cpu, bus),
+            wide: self.expr.wide(),
+        }
+    }
+}
+
+// Valor de um watch num instante, pro debugger fora da thread de emulação
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WatchValue {
+    pub text: String,
+    // None = divisão por zero
+    pub value: Option<u16>,
+    pub wide: bool,
+}
+
+impl WatchValue {
+    // "[wHP] = $2A (42)"
+    pub fn describe(&self) -> String {
+        match (self.value, self.wide) {
+            (Some(value), true) => format!("{} = ${:04X} ({})", self.text, value, value),
+            (Some(value), false) => format!("{} = ${:02X} ({})", self.text, value, value),
+            (None, _) => format!("{} = ?", self.text),
+        }
+    }
+}
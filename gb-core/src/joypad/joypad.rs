@@ -0,0 +1,204 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::BitOr;
+
+use bitflags::bitflags;
+
+use crate::joypad::InputMacro;
+use crate::state::{Savestate, StateReader, StateWriter};
+
+pub const P1: u16 = 0xFF00;
+
+bitflags! {
+    // Botões apertados (1 = apertado), na ordem das linhas do P1
+    #[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+    pub struct Buttons: u8 {
+        const RIGHT  = 1 << 0;
+        const LEFT   = 1 << 1;
+        const UP     = 1 << 2;
+        const DOWN   = 1 << 3;
+        const A      = 1 << 4;
+        const B      = 1 << 5;
+        const SELECT = 1 << 6;
+        const START  = 1 << 7;
+    }
+}
+
+// Nomes dos botões nas tabelas de input do Lua (emu.get_input / emu.set_input) e nos
+// bindings Python
+pub const BUTTON_NAMES: [(&str, Buttons); 8] = [
+    ("right", Buttons::RIGHT),
+    ("left", Buttons::LEFT),
+    ("up", Buttons::UP),
+    ("down", Buttons::DOWN),
+    ("a", Buttons::A),
+    ("b", Buttons::B),
+    ("select", Buttons::SELECT),
+    ("start", Buttons::START),
+];
+
+// Input de um frame: `buttons` seguros e `turbo` em auto-fire (alternam apertado/solto)
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct JoypadInput {
+    pub buttons: Buttons,
+    pub turbo: Buttons,
+}
+
+impl JoypadInput {
+    pub const fn held(buttons: Buttons) -> Self {
+        Self {
+            buttons,
+            turbo: Buttons::empty(),
+        }
+    }
+
+    pub const fn turbo(buttons: Buttons) -> Self {
+        Self {
+            buttons: Buttons::empty(),
+            turbo: buttons,
+        }
+    }
+}
+
+impl BitOr for JoypadInput {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self {
+            buttons: self.buttons | other.buttons,
+            turbo: self.turbo | other.turbo,
+        }
+    }
+}
+
+// Frames em cada fase do turbo (1 = aperta num frame, solta no outro)
+const TURBO_FRAMES: u8 = 1;
+
+const SELECT_DPAD: u8 = 1 << 4;
+const SELECT_BUTTONS: u8 = 1 << 5;
+
+pub struct Joypad {
+    // Bits 4-5 do P1 (0 = grupo selecionado)
+    select: u8,
+    pressed: Buttons,
+    // Conta os frames pro turbo; faz parte do state pra movies reproduzirem igual
+    turbo_frame: u8,
+    // Macro tocando e o próximo frame dela
+    playback: Option<(InputMacro, usize)>,
+    // Input ao vivo de cada frame desde o início da gravação
+    recording: Option<Vec<Buttons>>,
+}
+
+impl Joypad {
+    pub fn new() -> Self {
+        Self {
+            select: 0x30,
+            pressed: Buttons::empty(),
+            turbo_frame: 0,
+            playback: None,
+            recording: None,
+        }
+    }
+
+    // Linhas P10-P13 em nível baixo (1 = linha puxada pra 0)
+    fn low_lines(&self) -> u8 {
+        let mut lines = 0;
+        if self.select & SELECT_DPAD == 0 {
+            lines |= self.pressed.bits() & 0x0F;
+        }
+        if self.select & SELECT_BUTTONS == 0 {
+            lines |= self.pressed.bits() >> 4;
+        }
+        lines
+    }
+
+    pub fn read(&self) -> u8 {
+        0xC0 | self.select | (!self.low_lines() & 0x0F)
+    }
+
+    // Retorna true se alguma linha caiu pra 0 (interrupção de joypad)
+    pub fn write(&mut self, data: u8) -> bool {
+        let before = self.low_lines();
+        self.select = data & 0x30;
+        self.low_lines() & !before != 0
+    }
+
+    // Alguma linha selecionada em 0 (acorda a CPU do STOP)
+    pub fn any_low(&self) -> bool {
+        self.low_lines() != 0
+    }
+
+    pub fn buttons(&self) -> Buttons {
+        self.pressed
+    }
+
+    // Chamado uma vez por frame, antes de rodar o frame
+    pub fn set_input(&mut self, input: JoypadInput) -> bool {
+        self.turbo_frame = self.turbo_frame.wrapping_add(1);
+
+        let mut buttons = input.buttons;
+        if (self.turbo_frame / TURBO_FRAMES).is_multiple_of(2) {
+            buttons |= input.turbo;
+        }
+        self.set_buttons(buttons)
+    }
+
+    // Começa a macro no próximo frame; tocar outra por cima troca
+    pub fn play_macro(&mut self, input_macro: InputMacro) {
+        self.playback = (!input_macro.is_empty()).then_some((input_macro, 0));
+    }
+
+    pub fn playing_macro(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    pub fn start_macro_recording(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    // None se não estava gravando
+    pub fn stop_macro_recording(&mut self) -> Option<InputMacro> {
+        self.recording.take().map(InputMacro::new)
+    }
+
+    // Chamado uma vez por frame com o input ao vivo, antes do netplay e do movie: a macro
+    // entra somada aos botões como se fosse do jogador, então movies e o outro lado do
+    // netplay veem o mesmo input. A gravação guarda só o input ao vivo
+    pub fn blend_macro(&mut self, input: JoypadInput) -> JoypadInput {
+        if let Some(recording) = &mut self.recording {
+            recording.push(input.buttons | input.turbo);
+        }
+        let Some((input_macro, frame)) = &mut self.playback else {
+            return input;
+        };
+        let buttons = input_macro.frames()[*frame];
+        *frame += 1;
+        if *frame == input_macro.frames().len() {
+            self.playback = None;
+        }
+        JoypadInput {
+            buttons: input.buttons | buttons,
+            turbo: input.turbo,
+        }
+    }
+
+    fn set_buttons(&mut self, buttons: Buttons) -> bool {
+        let before = self.low_lines();
+        self.pressed = buttons;
+        self.low_lines() & !before != 0
+    }
+}
+
+// Os botões apertados vêm do frontend; só o select do P1 e a fase do turbo fazem parte do estado
+impl Savestate for Joypad {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.select);
+        w.write_u8(self.turbo_frame);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.select = r.read_u8()? & 0x30;
+        self.turbo_frame = r.read_u8()?;
+        Ok(())
+    }
+}
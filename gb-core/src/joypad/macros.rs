@@ -0,0 +1,102 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::joypad::{BUTTON_NAMES, Buttons};
+
+// Sequência curta de input (um combo, um caminho de menu), um estado dos botões por frame.
+// No config: "down*2 down+right*2 right+a*3 -*4", com '-' pra nenhum botão e *n pra repetir
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InputMacro {
+    frames: Vec<Buttons>,
+}
+
+impl InputMacro {
+    // Frames vazios no começo e no fim (antes de apertar, depois de soltar) saem
+    pub fn new(frames: Vec<Buttons>) -> Self {
+        let start = frames.iter().position(|buttons| !buttons.is_empty());
+        let end = frames.iter().rposition(|buttons| !buttons.is_empty());
+        let frames = match (start, end) {
+            (Some(start), Some(end)) => frames[start..=end].to_vec(),
+            _ => Vec::new(),
+        };
+        Self { frames }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut frames = Vec::new();
+        for step in text.split([' ', ',']).filter(|step| !step.is_empty()) {
+            let (names, count) = match step.split_once('*') {
+                Some((names, count)) => {
+                    let count = count
+                        .parse()
+                        .ok()
+                        .filter(|&count: &usize| count > 0)
+                        .ok_or_else(|| format!("repetição inválida: '{}'", step))?;
+                    (names, count)
+                }
+                None => (step, 1),
+            };
+            let buttons = parse_buttons(names)?;
+            frames.extend(core::iter::repeat_n(buttons, count));
+        }
+        if frames.is_empty() {
+            return Err("macro vazia".into());
+        }
+        Ok(Self { frames })
+    }
+
+    pub fn frames(&self) -> &[Buttons] {
+        &self.frames
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // O texto que o parse lê, juntando frames iguais seguidos
+    pub fn format(&self) -> String {
+        let mut steps: Vec<String> = Vec::new();
+        let mut frames = self.frames.iter().peekable();
+        while let Some(&buttons) = frames.next() {
+            let mut count = 1;
+            while frames.next_if(|&&next| next == buttons).is_some() {
+                count += 1;
+            }
+            let names = format_buttons(buttons);
+            steps.push(match count {
+                1 => names,
+                _ => format!("{}*{}", names, count),
+            });
+        }
+        steps.join(" ")
+    }
+}
+
+// "a+b", "down+right" ou "-"
+fn parse_buttons(names: &str) -> Result<Buttons, String> {
+    if names == "-" {
+        return Ok(Buttons::empty());
+    }
+    names
+        .split('+')
+        .try_fold(Buttons::empty(), |buttons, name| {
+            BUTTON_NAMES
+                .iter()
+                .find(|(button, _)| button.eq_ignore_ascii_case(name))
+                .map(|&(_, button)| buttons | button)
+                .ok_or_else(|| format!("botão desconhecido: '{}'", name))
+        })
+}
+
+fn format_buttons(buttons: Buttons) -> String {
+    if buttons.is_empty() {
+        return "-".into();
+    }
+    let names: Vec<&str> = BUTTON_NAMES
+        .iter()
+        .filter(|&&(_, button)| buttons.contains(button))
+        .map(|&(name, _)| name)
+        .collect();
+    names.join("+")
+}
@@ -0,0 +1,10 @@
+pub mod joypad;
+pub mod macros;
+pub mod sensor;
+
+pub use joypad::*;
+pub use macros::*;
+pub use sensor::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,16 @@
+// Inclinação do cartucho (acelerômetro do MBC7), de -1.0 a 1.0 em cada eixo:
+// x positivo = direita, y positivo = baixo
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct Tilt {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Tilt {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            x: x.clamp(-1.0, 1.0),
+            y: y.clamp(-1.0, 1.0),
+        }
+    }
+}
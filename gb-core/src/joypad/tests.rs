@@ -0,0 +1,55 @@
+use alloc::vec::Vec;
+
+use crate::joypad::{Buttons, InputMacro, Joypad, JoypadInput};
+
+#[test]
+fn macro_do_config_ida_e_volta() {
+    let input_macro = InputMacro::parse("down*2 down+right, right+A*3 -*2 start").unwrap();
+    assert_eq!(input_macro.frames().len(), 9);
+    assert_eq!(input_macro.frames()[2], Buttons::DOWN | Buttons::RIGHT);
+    assert_eq!(
+        input_macro.format(),
+        "down*2 right+down right+a*3 -*2 start"
+    );
+    assert_eq!(
+        InputMacro::parse(&input_macro.format()).unwrap(),
+        input_macro
+    );
+
+    assert!(InputMacro::parse("").is_err());
+    assert!(InputMacro::parse("a*0").is_err());
+    assert!(InputMacro::parse("turbo").is_err());
+}
+
+#[test]
+fn macro_soma_com_o_input_ao_vivo_e_grava_sem_as_pontas_vazias() {
+    let mut joypad = Joypad::new();
+    joypad.start_macro_recording();
+    joypad.play_macro(InputMacro::parse("a -").unwrap());
+
+    let live = [
+        Buttons::empty(),
+        Buttons::LEFT,
+        Buttons::empty(),
+        Buttons::empty(),
+    ];
+    let blended: Vec<Buttons> = live
+        .iter()
+        .map(|&buttons| joypad.blend_macro(JoypadInput::held(buttons)).buttons)
+        .collect();
+    assert_eq!(
+        blended,
+        [
+            Buttons::A,
+            Buttons::LEFT,
+            Buttons::empty(),
+            Buttons::empty()
+        ]
+    );
+    assert!(!joypad.playing_macro());
+
+    // Só o input ao vivo, sem o que a macro apertou
+    let recorded = joypad.stop_macro_recording().unwrap();
+    assert_eq!(recorded.frames(), [Buttons::LEFT]);
+    assert!(joypad.stop_macro_recording().is_none());
+}
@@ -0,0 +1,30 @@
+// Core do emulador, sem janela: usado pelos frontends (gb-frontends), pelas ferramentas
+// de linha de comando (gb-tools) e pelas bindings em C e Python (gb-ffi). Sem a feature
+// std só o core compila, com no_std + alloc
+#![cfg_attr(not(feature = "std"), no_std)]
+// Os componentes do core são criados com new(), sem Default
+#![allow(clippy::new_without_default)]
+
+extern crate alloc;
+
+pub mod achievements;
+pub mod apu;
+pub mod archive;
+pub mod bus;
+pub mod cartridge;
+pub mod cheats;
+pub mod config;
+pub mod cpu;
+pub mod debug;
+pub mod joypad;
+pub mod machine;
+#[cfg(feature = "std")]
+pub mod netplay;
+pub mod patch;
+pub mod png;
+pub mod ppu;
+#[cfg(feature = "std")]
+pub mod script;
+pub mod sgb;
+pub mod state;
+pub mod timer;
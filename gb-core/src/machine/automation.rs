@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use crate::machine::EmulatorEvent;
+
+// Códigos de saída do processo quando uma condição de parada encerra a execução
+pub const EXIT_OK: i32 = 0;
+// Argumentos ou ROM inválidos: nem chegou a rodar
+pub const EXIT_ERROR: i32 = 1;
+// --frames acabou antes do --until-pc/--until-serial
+pub const EXIT_TIMEOUT: i32 = 2;
+// CPU travou num opcode ilegal: a condição nunca seria atingida
+pub const EXIT_LOCKED: i32 = 3;
+
+// Condições de parada pra testes automatizados (--frames, --until-pc, --until-serial)
+#[derive(Clone, Default, Debug)]
+pub struct ExitConditions {
+    pub frames: Option<u32>,
+    pub until_pc: Option<u16>,
+    pub until_serial: Option<String>,
+}
+
+impl ExitConditions {
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_none() && !self.has_target()
+    }
+
+    // Com um --until-*, o --frames vira limite de tempo
+    fn has_target(&self) -> bool {
+        self.until_pc.is_some() || self.until_serial.is_some()
+    }
+}
+
+// --screenshot-at <frame>:<arquivo.png>
+#[derive(Clone, Debug)]
+pub struct ScheduledScreenshot {
+    pub frame: u32,
+    pub path: PathBuf,
+}
+
+impl ScheduledScreenshot {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (frame, path) = value.split_once(':')?;
+        let frame = frame.parse().ok().filter(|&frame| frame > 0)?;
+        if path.is_empty() {
+            return None;
+        }
+        Some(Self {
+            frame,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+// Acompanha os eventos do core frame a frame até alguma condição bater
+pub struct ExitWatch {
+    conditions: ExitConditions,
+    // Final da saída serial, do tamanho do texto procurado
+    serial: Vec<u8>,
+}
+
+impl ExitWatch {
+    pub fn new(conditions: ExitConditions) -> Self {
+        Self {
+            conditions,
+            serial: Vec::new(),
+        }
+    }
+
+    pub fn until_pc(&self) -> Option<u16> {
+        self.conditions.until_pc
+    }
+
+    // `frame` conta a partir de 1; `events` são os do frame que acabou de rodar.
+    // Some(código) encerra
+    pub fn end_frame(&mut self, frame: u32, events: &[EmulatorEvent]) -> Option<i32> {
+        for event in events {
+            match event {
+                EmulatorEvent::Breakpoint { pc, .. } if Some(*pc) == self.conditions.until_pc => {
+                    return Some(EXIT_OK);
+                }
+                EmulatorEvent::IllegalOpcode { locked: true, .. } => return Some(EXIT_LOCKED),
+                EmulatorEvent::SerialByte(byte) => self.serial.push(*byte),
+                _ => {}
+            }
+        }
+
+        if let Some(text) = &self.conditions.until_serial {
+            let text = text.as_bytes();
+            if self.serial.windows(text.len()).any(|window| window == text) {
+                return Some(EXIT_OK);
+            }
+            // O texto pode estar dividido entre dois frames
+            let keep = text.len().saturating_sub(1);
+            if self.serial.len() > keep {
+                self.serial.drain(..self.serial.len() - keep);
+            }
+        }
+
+        match self.conditions.frames {
+            Some(frames) if frame >= frames => Some(if self.conditions.has_target() {
+                EXIT_TIMEOUT
+            } else {
+                EXIT_OK
+            }),
+            _ => None,
+        }
+    }
+}
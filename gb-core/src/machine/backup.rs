@@ -0,0 +1,39 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Cópias antigas mantidas por padrão de cada slot de state e do .sav
+pub const DEFAULT_BACKUPS: usize = 3;
+
+// "jogo.sav" -> "jogo.sav.1" (a mais nova) até "jogo.sav.N"
+pub fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+// Grava num temporário e troca pelo arquivo com rename, que é atômico: um crash no meio
+// deixa o arquivo anterior inteiro. Antes da troca o anterior vira a cópia .1 e as
+// outras andam uma posição (a .N some)
+pub fn write_rotated(path: &Path, data: &[u8], backups: usize) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+
+    let mut file = File::create(&temp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    drop(file);
+
+    if backups > 0 && path.exists() {
+        for index in (1..backups).rev() {
+            let from = backup_path(path, index);
+            if from.exists() {
+                fs::rename(&from, backup_path(path, index + 1))?;
+            }
+        }
+        // Cópia, não rename: o arquivo original continua lá até o temporário o substituir
+        fs::copy(path, backup_path(path, 1))?;
+    }
+    fs::rename(&temp, path)
+}
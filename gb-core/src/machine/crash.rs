@@ -0,0 +1,99 @@
+use std::fmt;
+
+use crate::bus::MemoryBus;
+use crate::cpu::Cpu;
+use crate::debug::{CallStack, History, Symbols, location};
+use crate::machine::Model;
+
+// Instruções do histórico que entram no relatório
+const CRASH_HISTORY: usize = 64;
+
+// Relatório de crash: motivo, registradores, bancos, pilha de chamadas, últimas instruções
+// (com os registradores de antes de cada uma) e os registradores de IO
+pub struct CrashReport<'a> {
+    pub reason: &'a str,
+    pub cpu: &'a Cpu,
+    pub bus: &'a MemoryBus,
+    pub history: &'a History,
+    pub call_stack: &'a CallStack,
+    pub symbols: &'a Symbols,
+}
+
+impl fmt::Display for CrashReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (cpu, bus) = (self.cpu, self.bus);
+        let cartridge = &bus.cartridge;
+
+        writeln!(f, "Relatório de crash do gb-emu-rust")?;
+        writeln!(f, "Motivo: {}", self.reason)?;
+        writeln!(
+            f,
+            "ROM: {} (CRC {:08X}), modelo {:?}",
+            cartridge.title(),
+            cartridge.rom_crc,
+            bus.model
+        )?;
+
+        writeln!(f, "\nRegistradores:")?;
+        writeln!(
+            f,
+            "  AF={:04X} BC={:04X} DE={:04X} HL={:04X} SP={:04X} PC={:04X}",
+            cpu.af(),
+            cpu.bc(),
+            cpu.de(),
+            cpu.hl(),
+            cpu.stack_pointer,
+            cpu.program_counter
+        )?;
+        writeln!(
+            f,
+            "  IME={} HALT={} STOP={} travada={}",
+            cpu.interruption as u8, cpu.halt as u8, cpu.stop as u8, cpu.locked as u8
+        )?;
+
+        writeln!(f, "\nBancos:")?;
+        writeln!(
+            f,
+            "  ROM 0000-3FFF: {:02X}  ROM 4000-7FFF: {:02X}  RAM: {:02X}",
+            cartridge.rom_bank(0x0000),
+            cartridge.rom_bank(0x4000),
+            cartridge.ram_bank()
+        )?;
+        if bus.model == Model::Cgb {
+            writeln!(
+                f,
+                "  WRAM: {}  VRAM: {}",
+                (bus.peek(0xFF70) & 0x07).max(1),
+                bus.peek(0xFF4F) & 0x01
+            )?;
+        }
+
+        writeln!(f, "\nPilha de chamadas:")?;
+        let pc = location(bus, cpu.program_counter);
+        writeln!(f, "  #0  {}", self.symbols.locate(pc))?;
+        for (depth, frame) in self.call_stack.frames().iter().rev().enumerate() {
+            writeln!(
+                f,
+                "  #{:<2} {}  ({:?} {})",
+                depth + 1,
+                self.symbols.locate(frame.return_to),
+                frame.kind,
+                self.symbols.locate(frame.entry)
+            )?;
+        }
+
+        writeln!(f, "\nÚltimas instruções:")?;
+        for entry in self.history.last(CRASH_HISTORY) {
+            writeln!(f, "  {}", entry.format(self.symbols))?;
+        }
+
+        writeln!(f, "\nIO:")?;
+        for row in (0xFF00..0xFF80).step_by(16) {
+            let bytes: Vec<String> = (row..row + 16)
+                .map(|addr| format!("{:02X}", bus.peek(addr)))
+                .collect();
+            writeln!(f, "  {:04X}: {}", row, bytes.join(" "))?;
+        }
+        writeln!(f, "  FFFF: {:02X}", bus.peek(0xFFFF))
+    }
+}
@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use crate::cheats::SearchResult;
+use crate::debug::{
+    CallFrame, HistoryEntry, Inspection, Location, TriggerHit, VideoInspection, WatchValue,
+};
+use crate::joypad::{Buttons, InputMacro};
+use crate::machine::EmulationStats;
+use crate::ppu::PpuEvent;
+use crate::script::OverlayItem;
+
+// Eventos que o core publica pros frontends durante run_frame
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EmulatorEvent {
+    // PPU entrou em vblank: o frame da tela está completo
+    FrameReady,
+    // Byte que o jogo mandou pelo serial (com ou sem cabo)
+    SerialByte(u8),
+    // Aviso do dispositivo na porta serial (ligado, impressão salva, conexão perdida)
+    SerialMessage(String),
+    // Amostras estéreo intercaladas geradas no frame
+    AudioSamples(Vec<i16>),
+    // CPU parou antes de executar o endereço; a emulação fica pausada. `bank` é o banco da
+    // ROM onde o PC está (0 fora de 4000-7FFF), pros labels do .sym
+    Breakpoint { pc: u16, bank: usize },
+    // Trigger do debugger (interrupção, troca de banco); pausa como o breakpoint
+    Triggered(TriggerHit),
+    // Fim de um EmulatorCommand::Step; a emulação fica pausada como no breakpoint
+    Stepped { pc: u16, bank: usize },
+    // Pilha de chamadas pedida pelo frontend: PC atual e quadros do mais antigo pro atual
+    Backtrace { pc: Location, frames: Vec<CallFrame> },
+    // Últimas instruções executadas, da mais antiga pra mais recente
+    History(Vec<HistoryEntry>),
+    Inspection(Box<Inspection>),
+    VideoInspection(Box<VideoInspection>),
+    Watches(Vec<WatchValue>),
+    // Fim de um EmulatorCommand::RecordMacro, já sem os frames vazios das pontas
+    MacroRecorded(InputMacro),
+    // Conquista do RetroAchievements desbloqueada neste frame
+    AchievementUnlocked {
+        title: String,
+        description: String,
+        points: u32,
+    },
+    // Código de cada cheat e se está ligado
+    CheatList(Vec<(String, bool)>),
+    // Eventos da PPU no último frame, em ordem
+    PpuLog(Vec<PpuEvent>),
+    // `locked` indica se a CPU travou (policy Lock) ou só pulou o opcode
+    IllegalOpcode { pc: u16, opcode: u8, locked: bool },
+    StateSaved(PathBuf),
+    StateLoaded(PathBuf),
+    // Candidatos da busca de cheats (só os primeiros `results`)
+    SearchResults { total: usize, results: Vec<SearchResult> },
+    CheatAdded(String),
+    MovieRecording(PathBuf),
+    MoviePlaying(PathBuf),
+    // Gravação salva ou reprodução encerrada, com quantos frames passaram
+    MovieStopped { frames: usize },
+    // State da própria gravação carregado: o movie voltou pro `frame` e segue gravando
+    MovieRerecorded { frame: usize, rerecords: u32 },
+    // Intensidade do motor de vibração do cartucho, em % (0 = parado)
+    Rumble(u8),
+    // Melodia tocada pelo piezo do cartucho (HuC3), pelo número
+    Melody(u8),
+    // Estado igualado com o peer do netplay (início da sessão ou depois de um desync)
+    NetplaySynced { frame: u32 },
+    // State do auto-resume carregado na abertura da ROM
+    Resumed,
+    // RAM com bateria gravada no .sav
+    BatterySaved,
+    // Cartucho trocado com o jogo já rodando; `crc` é o CRC32 da ROM, pros perfis por jogo
+    RomLoaded {
+        path: PathBuf,
+        title: String,
+        crc: u32,
+    },
+    // Saída digital (0-15) de cada canal do APU por amostra, com o scope ligado
+    Scope([Vec<u8>; 4]),
+    // Médias do último segundo, com o overlay de estatísticas ligado
+    Stats(EmulationStats),
+    // Botões que o jogo viu no frame (turbo já aplicado) e o frame do movie, se tiver
+    InputDisplay {
+        buttons: Buttons,
+        movie_frame: Option<usize>,
+    },
+    // Desenhos do script no último frame; substituem os anteriores
+    Overlay(Vec<OverlayItem>),
+    // Relatório do profiler gravado
+    ProfileSaved(PathBuf),
+    // Mapa de cobertura da ROM gravado
+    CoverageSaved(PathBuf),
+    // Log de execução fechado
+    TraceSaved(PathBuf),
+    // Relatório de crash gravado (CPU travada num opcode ilegal)
+    CrashReport(PathBuf),
+    // PNG do --screenshot-at gravado
+    ScreenshotSaved(PathBuf),
+    // Condição de parada do --frames/--until-* atingida, com o código de saída
+    Exit(i32),
+    // Falha em um comando do frontend (arquivo de state, etc.)
+    Error(String),
+}
@@ -0,0 +1,2072 @@
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryRecvError, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::achievements::Achievements;
+use crate::apu::Apu;
+use crate::bus::{
+    Coverage, DeviceTimes, Disconnected, LinkPort, MemoryBus, SerialPort, Watchpoints,
+};
+use crate::cartridge::{Cartridge, IrDevice, NoIr};
+use crate::cheats::{CheatSearch, Cheats, Freeze, SearchFilter};
+use crate::cpu::Cpu;
+use crate::debug::{
+    CallStack, History, HistoryEntry, Inspection, Location, Symbols, Trigger, TriggerHit,
+    VideoInspection, Watch, location,
+};
+use crate::joypad::{InputMacro, JoypadInput, Tilt};
+use crate::machine::{
+    CrashReport, DEFAULT_BACKUPS, EmulatorEvent, ExitConditions, ExitWatch, Model, Movie,
+    MovieAnchor, MovieSession, Pacer, Profiler,
+    RewindBuffer, Rng, ScheduledScreenshot, StatsCollector, Trace, clock_nanos, write_rotated,
+};
+use crate::netplay::Netplay;
+use crate::png;
+use crate::ppu::{ColorTransform, ColorizationMode, GB_H, GB_W, LcdGhosting};
+use crate::script::Script;
+use crate::sgb::{SGB_H, SGB_W, Sgb};
+use crate::state::{
+    BLOCK_STATE_VERSION, BlockId, MIN_STATE_VERSION, STATE_MAGIC, STATE_VERSION, Savestate,
+    StateBlocks, StateReader, StateWriter,
+};
+
+pub struct Emulator {
+    pub cpu: Cpu,
+    pub bus: MemoryBus,
+    events: Vec<EmulatorEvent>,
+    search: CheatSearch,
+    // Script Lua carregado dentro da thread de emulação (o estado do Lua não é Send)
+    pub script_path: Option<PathBuf>,
+    // Dispositivo da porta serial pelo nome no registro ("printer", "tcp:host:porta"),
+    // ligado depois do script, que pode registrar o "lua"
+    pub serial_device: Option<String>,
+    movie: Option<MovieSession>,
+    // Frames especulativos por frame real (0 = desligado)
+    pub run_ahead: u8,
+    // Conteúdo da RAM no power-on; None = sorteado pelo relógio
+    pub seed: Option<u64>,
+    // Hora (UNIX) que faz o papel do relógio de parede pro RTC: ao carregar o .sav o
+    // relógio anda até ela, e é ela que vai no rodapé. None = hora de verdade
+    pub rtc_epoch: Option<u64>,
+    pub netplay: Option<Netplay>,
+    // RAM com bateria (e RTC) do cartucho; None = não persiste
+    pub sav_path: Option<PathBuf>,
+    // Pasta dos states de auto-resume (um por CRC da ROM); None = desligado
+    pub resume_dir: Option<PathBuf>,
+    // Cópias antigas de cada slot de state e do .sav (0 = só a gravação atômica)
+    pub backups: usize,
+    // Intensidade do motor no último frame, em RUMBLE_LEVELS passos
+    rumble: u8,
+    // RAM como está no .sav, pra só regravar o que mudou de fato
+    saved_ram: Vec<u8>,
+    // Imagem do host pra Pocket Camera (cinza 128x112); vale também pros cartuchos
+    // carregados depois
+    camera_image: Option<Vec<u8>>,
+    // Endereços onde a CPU para antes de executar
+    pub breakpoints: BTreeSet<u16>,
+    // Continuando de um breakpoint: a primeira instrução não para de novo
+    skip_breakpoint: bool,
+    // Pilha sombra de CALL/RST/interrupções, pro backtrace e o step over/out
+    pub call_stack: CallStack,
+    stepping: Option<Stepping>,
+    // Interrupções e trocas de banco que pausam como um breakpoint
+    pub triggers: BTreeSet<Trigger>,
+    // Expressões avaliadas a cada Inspect, pros painéis do debugger
+    pub watches: Vec<Watch>,
+    // Disparado na última instrução; para antes da próxima
+    trigger_hit: Option<TriggerHit>,
+    // Condições de parada da automação; None = roda até fechar
+    exit_watch: Option<ExitWatch>,
+    // PNGs do --screenshot-at ainda por tirar
+    pub screenshots: Vec<ScheduledScreenshot>,
+    // Frames rodados desde o início da thread (ou do run_headless), contando do 1
+    frame_count: u32,
+    // Contadores do overlay de estatísticas; None = desligado, sem custo no laço
+    stats: Option<StatsCollector>,
+    input_display: bool,
+    profiler: Option<Profiler>,
+    // Relatório do profiler do run_headless, que amostra a execução inteira
+    pub profile_path: Option<PathBuf>,
+    // Mapa de cobertura do run_headless (.json ou binário)
+    pub coverage_path: Option<PathBuf>,
+    // Labels do .sym da ROM, pros relatórios e o log de execução
+    pub symbols: Symbols,
+    trace: Option<Trace>,
+    // Log de execução do run_headless
+    pub trace_path: Option<PathBuf>,
+    // Últimas instruções executadas, pro comando history e o relatório de crash
+    history: History,
+    // Relatório gravado em panic ou CPU travada; None = não grava
+    pub crash_path: Option<PathBuf>,
+    // Paletas de cor pra jogos de DMG, aplicadas a cada hard reset
+    pub colorization: ColorizationMode,
+    // Correção de cor e gamma da saída ([video] color_correction e gamma)
+    pub color_transform: Option<ColorTransform>,
+    // Rastro do LCD do DMG ([video] ghosting); None = desligado
+    pub ghosting: Option<LcdGhosting>,
+    // Conquistas do RetroAchievements do jogo; somem com a troca de cartucho
+    pub achievements: Option<Achievements>,
+    // Callbacks de frame e de scanline de quem embute o core; vazio = nada no laço
+    callbacks: Vec<PpuHook>,
+    next_callback: u32,
+}
+
+pub const CYCLES_PER_FRAME: u64 = 70_224;
+pub const CYCLES_PER_LINE: u64 = 456;
+
+// Linha atual da PPU, pro Steps por scanline
+const LY: u16 = 0xFF44;
+
+// Instruções guardadas no histórico (20 bytes cada)
+const HISTORY_SIZE: usize = 1024;
+
+// Quantos frames prontos podem ficar esperando o frontend antes de começarmos a descartar
+const FRAME_QUEUE: usize = 2;
+
+// Quantos candidatos da busca de cheats vão pro frontend
+const SEARCH_RESULTS: usize = 20;
+
+// Passos de intensidade do rumble (evita um evento por frame com o PWM oscilando)
+const RUMBLE_LEVELS: u64 = 4;
+
+// De quanto em quanto tempo a RAM com bateria suja vai pro disco
+const BATTERY_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+// Bloco da CPU no save state; os do bus ficam no MemoryBus
+const CPU_BLOCK: BlockId = (b"CPU ", 7);
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ResetKind {
+    Soft,
+    Hard,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StepKind {
+    // Uma instrução (ou o atendimento de uma interrupção)
+    Into,
+    // Uma instrução; CALL, RST e interrupções rodam até voltar pra profundidade atual
+    Over,
+    // Até a função atual retornar
+    Out,
+}
+
+// Passo em andamento: para quando a profundidade da pilha sombra chegar no alvo
+struct Stepping {
+    kind: StepKind,
+    depth: usize,
+    // Já rodou a primeira instrução
+    started: bool,
+}
+
+// Contadores de um frame: o overlay de estatísticas (com `timed`) e o rumble
+struct FrameWork {
+    timed: bool,
+    instructions: u64,
+    cpu_cycles: u64,
+    cpu_time: Duration,
+    rumble_cycles: u64,
+}
+
+impl FrameWork {
+    fn new(timed: bool) -> Self {
+        Self {
+            timed,
+            instructions: 0,
+            cpu_cycles: 0,
+            cpu_time: Duration::ZERO,
+            rumble_cycles: 0,
+        }
+    }
+}
+
+// Uma instrução executada: ciclos no clock normal, se a PPU entrou em vblank nela e a
+// linha que começou, se alguma
+struct Step {
+    cycles: u64,
+    vblank: bool,
+    line: Option<u8>,
+}
+
+// Código rodando num ponto exato da PPU, dentro do laço de emulação
+pub type PpuCallback = Box<dyn FnMut(&mut Emulator) + Send>;
+
+// Devolvido pelo register_*_callback, pro unregister_callback
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct CallbackId(u32);
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum CallbackPoint {
+    Frame,
+    Scanline(u8),
+}
+
+struct PpuHook {
+    id: CallbackId,
+    point: CallbackPoint,
+    // None enquanto está rodando: o callback recebe o Emulator inteiro
+    callback: Option<PpuCallback>,
+}
+
+// Onde o Steps devolve o controle
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Boundary {
+    Frame,
+    Scanline,
+}
+
+// O que fez o Steps devolver o controle
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Yielded {
+    // Começo do vblank, ou um frame inteiro de ciclos com o LCD desligado
+    Frame,
+    // LY mudou pra esta linha (ou passou uma linha de ciclos com o LCD desligado)
+    Scanline(u8),
+    // Breakpoint, trigger ou fim de step: o evento diz qual; o próximo next continua
+    Stopped,
+}
+
+// Emulação em pedaços, um frame ou uma linha por next; nunca termina
+pub struct Steps<'a> {
+    emulator: &'a mut Emulator,
+    boundary: Boundary,
+}
+
+impl Iterator for Steps<'_> {
+    type Item = Yielded;
+
+    fn next(&mut self) -> Option<Yielded> {
+        Some(self.emulator.run_until(self.boundary))
+    }
+}
+
+// Comandos do frontend pra thread de emulação
+pub enum EmulatorCommand {
+    Quit,
+    SetInput(JoypadInput),
+    SetTilt(Tilt),
+    // Macro somada ao input a partir do próximo frame
+    PlayMacro(InputMacro),
+    // Começa a gravar o input numa macro, ou termina (EmulatorEvent::MacroRecorded)
+    RecordMacro(bool),
+    SaveState(PathBuf),
+    LoadState(PathBuf),
+    Reset(ResetKind),
+    SetPaused(bool),
+    SetFastForward(bool),
+    SetRewind(bool),
+    SetCheatsEnabled(bool),
+    AddCheat(String),
+    RemoveCheat(String),
+    SetCheatEnabled(String, bool),
+    // Prende um endereço da RAM num valor (ou solta), fora da lista de códigos
+    Freeze(Freeze),
+    Unfreeze(u16),
+    // Canais do APU fora da mixagem (1-4 → índices 0-3)
+    SetMutedChannels([bool; 4]),
+    // Liga o envio das formas de onda de cada canal (EmulatorEvent::Scope)
+    SetScope(bool),
+    // Liga os contadores de desempenho (EmulatorEvent::Stats a cada segundo)
+    SetStats(bool),
+    // Botões vistos pelo jogo a cada frame (EmulatorEvent::InputDisplay)
+    SetInputDisplay(bool),
+    SearchStart,
+    SearchFilter(SearchFilter),
+    // Congela o candidato `index` com `value` (ou o valor atual)
+    SearchFreeze { index: usize, value: Option<u8> },
+    // Grava a partir do power-on (reset antes) ou do estado atual
+    RecordMovie(PathBuf, MovieAnchor),
+    PlayMovie(PathBuf),
+    StopMovie,
+    AddBreakpoint(u16),
+    RemoveBreakpoint(u16),
+    // Roda e pausa de novo (EmulatorEvent::Stepped), mesmo partindo da pausa
+    Step(StepKind),
+    // Pilha de chamadas atual (EmulatorEvent::Backtrace)
+    Backtrace,
+    // Últimas `n` instruções executadas (EmulatorEvent::History)
+    History(usize),
+    // Registradores, código no PC e MEMORY_BYTES a partir do endereço
+    // (EmulatorEvent::Inspection)
+    Inspect(u16),
+    // VRAM, OAM e registradores do LCD (EmulatorEvent::VideoInspection)
+    InspectVideo,
+    AddWatch(Watch),
+    // Pelo índice na lista
+    RemoveWatch(usize),
+    // Valor atual de cada watch (EmulatorEvent::Watches)
+    ListWatches,
+    // Cheats carregados e se estão ligados (EmulatorEvent::CheatList)
+    ListCheats,
+    // Liga/desliga o log de eventos da PPU
+    SetPpuLog(bool),
+    // Eventos do último frame completo (EmulatorEvent::PpuLog)
+    PpuLog,
+    // Troca as cores na hora, sem esperar o próximo hard reset (paleta .pal do jogo)
+    SetColorization(ColorizationMode),
+    // Velocidade do perfil do jogo, trocada junto com a ROM
+    SetOverclock(u8),
+    SetRunAhead(u8),
+    AddTrigger(Trigger),
+    RemoveTrigger(Trigger),
+    // Começa (ou recomeça do zero) a amostragem do profiler
+    StartProfile,
+    // Para e grava o relatório
+    StopProfile(PathBuf),
+    // Começa do zero o mapa de cobertura da ROM
+    StartCoverage,
+    // Para e grava: JSON se o arquivo terminar em .json, senão o mapa binário
+    StopCoverage(PathBuf),
+    // Abre o log de execução (uma linha por instrução) e fecha
+    StartTrace(PathBuf),
+    StopTrace,
+    // Liga outro dispositivo na porta serial, pelo nome no registro
+    AttachSerial(String),
+    // Troca o cartucho e liga de novo, sem reiniciar a thread
+    LoadRom {
+        cartridge: Box<Cartridge>,
+        path: PathBuf,
+    },
+}
+
+// Estado da thread de emulação controlado pelos comandos
+struct RunState {
+    paused: bool,
+    fast_forward: bool,
+    rewinding: bool,
+    // Último input do frontend (o script pode sobrescrever)
+    input: JoypadInput,
+    tilt: Tilt,
+}
+
+// Lado do frontend: frames em RGBA, eventos do core (áudio incluso) e canal de comandos
+pub struct EmulatorHandle {
+    pub frames: Receiver<Vec<u8>>,
+    pub events: Receiver<EmulatorEvent>,
+    commands: Sender<EmulatorCommand>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EmulatorHandle {
+    pub fn send(&self, command: EmulatorCommand) {
+        let _ = self.commands.send(command);
+    }
+
+    pub fn stop(&mut self) {
+        self.send(EmulatorCommand::Quit);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for EmulatorHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl Emulator {
+    pub fn new(cartridge: Cartridge) -> Self {
+        let bus = MemoryBus::new(cartridge);
+
+        Self {
+            cpu: Cpu::new(),
+            bus,
+            events: Vec::new(),
+            search: CheatSearch::new(),
+            script_path: None,
+            serial_device: None,
+            movie: None,
+            run_ahead: 0,
+            seed: None,
+            rtc_epoch: None,
+            netplay: None,
+            sav_path: None,
+            resume_dir: None,
+            backups: DEFAULT_BACKUPS,
+            rumble: 0,
+            saved_ram: Vec::new(),
+            camera_image: None,
+            breakpoints: BTreeSet::new(),
+            skip_breakpoint: false,
+            call_stack: CallStack::new(),
+            stepping: None,
+            triggers: BTreeSet::new(),
+            watches: Vec::new(),
+            trigger_hit: None,
+            exit_watch: None,
+            screenshots: Vec::new(),
+            frame_count: 0,
+            stats: None,
+            input_display: false,
+            profiler: None,
+            profile_path: None,
+            coverage_path: None,
+            symbols: Symbols::new(),
+            trace: None,
+            trace_path: None,
+            history: History::new(HISTORY_SIZE),
+            crash_path: None,
+            colorization: ColorizationMode::Auto,
+            color_transform: None,
+            ghosting: None,
+            achievements: None,
+            callbacks: Vec::new(),
+            next_callback: 0,
+        }
+    }
+
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, EmulatorEvent> {
+        self.events.drain(..)
+    }
+
+    // Soft: como o botão de reset (a RAM fica). Hard: desliga e liga, com memória e
+    // registros do mapper zerados e WRAM/HRAM sorteadas de novo
+    pub fn reset(&mut self, kind: ResetKind) {
+        if kind == ResetKind::Hard {
+            self.bus.power_cycle();
+            let mut rng = match self.seed {
+                Some(seed) => Rng::new(seed),
+                None => Rng::from_time(),
+            };
+            self.bus.randomize_ram(&mut rng);
+            self.bus.sgb = (self.bus.model == Model::Sgb && self.bus.cartridge.supports_sgb())
+                .then(Sgb::new);
+            self.bus.ppu.set_color_transform(self.color_transform.clone());
+            if let Some(ghosting) = &mut self.ghosting {
+                ghosting.clear();
+            }
+        }
+        self.cpu.reset();
+        self.bus.reset();
+        // A paleta do modo compatível depende do KEY0 que o reset acabou de escrever
+        self.set_colorization(self.colorization);
+        self.call_stack.clear();
+        self.stepping = None;
+        self.trigger_hit = None;
+        if let Some(achievements) = &mut self.achievements {
+            achievements.reset();
+        }
+    }
+
+    // Troca o cartucho sem recriar a thread nem a janela; cheats, watchpoints e modelo
+    // continuam
+    pub fn load_cartridge(&mut self, mut cartridge: Cartridge) {
+        // A porta IR continua ligada no cartucho novo
+        if let Some(device) = self.bus.cartridge.take_ir_device() {
+            cartridge.set_ir_device(device);
+        }
+        if let Some(image) = &self.camera_image {
+            cartridge.set_camera_image(image);
+        }
+        let mut bus = MemoryBus::new(cartridge);
+        bus.model = self.bus.model;
+        bus.cheats = std::mem::replace(&mut self.bus.cheats, Cheats::new());
+        bus.watch = std::mem::replace(&mut self.bus.watch, Watchpoints::new());
+        bus.infrared = std::mem::replace(&mut self.bus.infrared, Box::new(NoIr));
+        bus.ir_light = self.bus.ir_light;
+        // O dispositivo no cabo e o registro continuam; o power-on zera SB e SC
+        bus.serial = std::mem::replace(&mut self.bus.serial, SerialPort::new());
+        // Mute e scope do áudio são do frontend, não do jogo; as estatísticas também
+        bus.apu = std::mem::replace(&mut self.bus.apu, Apu::new());
+        bus.device_times = self.bus.device_times.take();
+        bus.overclock = self.bus.overclock;
+        self.bus = bus;
+        self.search = CheatSearch::new();
+        self.achievements = None;
+        self.reset(ResetKind::Hard);
+    }
+
+    fn set_colorization(&mut self, mode: ColorizationMode) {
+        self.colorization = mode;
+        let colors = mode.resolve(self.bus.dmg_compat(), self.bus.cartridge.rom());
+        self.bus.ppu.set_colorization(colors);
+    }
+
+    // Liga a porta infravermelha do cartucho (HuC1) em outra instância ou dispositivo
+    pub fn set_ir_device(&mut self, device: Box<dyn IrDevice>) {
+        self.bus.cartridge.set_ir_device(device);
+    }
+
+    // O --until-pc usa um breakpoint: a CPU para exatamente antes do endereço
+    pub fn set_exit_conditions(&mut self, conditions: ExitConditions) {
+        let watch = ExitWatch::new(conditions);
+        if let Some(addr) = watch.until_pc() {
+            self.breakpoints.insert(addr);
+        }
+        self.exit_watch = Some(watch);
+    }
+
+    pub fn set_camera_image(&mut self, image: Vec<u8>) {
+        self.bus.cartridge.set_camera_image(&image);
+        self.camera_image = Some(image);
+    }
+
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut w = StateWriter::new();
+        w.write_bytes(STATE_MAGIC);
+        w.write_u16(STATE_VERSION);
+        w.write_block(CPU_BLOCK, |w| self.cpu.save_state(w));
+        self.bus.save_blocks(&mut w);
+        w.into_bytes()
+    }
+
+    // CRC32 do save state sem o cabeçalho: registradores da CPU, memórias e estado dos
+    // dispositivos. O formato é fixo (little-endian), então o mesmo estado dá o mesmo hash
+    // em qualquer máquina; só muda junto com STATE_VERSION
+    pub fn state_hash(&self) -> u32 {
+        let mut w = StateWriter::new();
+        self.cpu.save_state(&mut w);
+        self.bus.save_state(&mut w);
+        png::crc32(&w.into_bytes())
+    }
+
+    // Em caso de erro o estado atual pode ter sido parcialmente sobrescrito. States de
+    // versões mais novas também carregam se os blocos que esta conhece não mudaram
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), String> {
+        let mut r = StateReader::new(data);
+
+        let mut magic = [0; 4];
+        r.read_bytes(&mut magic)?;
+        if &magic != STATE_MAGIC {
+            return Err("arquivo não é um save state".to_string());
+        }
+        let version = r.read_u16()?;
+        if version < MIN_STATE_VERSION {
+            return Err(format!(
+                "save state na versão {}; esta versão do emulador lê a partir da {}",
+                version, MIN_STATE_VERSION
+            ));
+        }
+
+        if version < BLOCK_STATE_VERSION {
+            // Sem blocos: CPU e bus em sequência
+            let mut r = StateReader::with_version(&data[6..], version);
+            self.cpu.load_state(&mut r)?;
+            self.bus.load_state(&mut r)?;
+            if !r.is_empty() {
+                return Err("save state com dados sobrando".to_string());
+            }
+        } else {
+            let mut blocks = StateBlocks::parse(&mut r)?;
+            blocks.require(CPU_BLOCK, |r| self.cpu.load_state(r))?;
+            self.bus.load_blocks(&mut blocks)?;
+            for warning in blocks.finish()? {
+                self.events.push(EmulatorEvent::Error(warning));
+            }
+        }
+        // A pilha sombra não vai no state: recomeça vazia a partir daqui
+        self.call_stack.clear();
+        self.stepping = None;
+        Ok(())
+    }
+
+    fn save_state_file(&mut self, path: &Path) {
+        match write_rotated(path, &self.save_state(), self.backups) {
+            Ok(()) => {
+                // Gravando um movie: o state marca o frame pra onde um re-record volta
+                if matches!(self.movie, Some(MovieSession::Recording { .. })) {
+                    let hash = self.state_hash();
+                    if let Some(MovieSession::Recording { movie, marks, .. }) = &mut self.movie {
+                        marks.push((hash, movie.frames.len()));
+                    }
+                }
+                self.events.push(EmulatorEvent::StateSaved(path.to_path_buf()));
+            }
+            Err(erro) => self.events.push(EmulatorEvent::Error(format!(
+                "erro ao salvar '{}': {}",
+                path.display(),
+                erro
+            ))),
+        }
+    }
+
+    // false se o state não carregou (e o estado de antes foi restaurado)
+    fn load_state_file(&mut self, path: &Path) -> bool {
+        // Se o state for inválido, volta pro estado de antes
+        let backup = self.save_state();
+        let result = fs::read(path)
+            .map_err(|erro| erro.to_string())
+            .and_then(|data| self.load_state(&data));
+
+        match result {
+            Ok(()) => {
+                self.events.push(EmulatorEvent::StateLoaded(path.to_path_buf()));
+                true
+            }
+            Err(erro) => {
+                self.load_state(&backup).unwrap();
+                self.events.push(EmulatorEvent::Error(format!(
+                    "erro ao carregar '{}': {}",
+                    path.display(),
+                    erro
+                )));
+                false
+            }
+        }
+    }
+
+    fn push_search_results(&mut self) {
+        self.events.push(EmulatorEvent::SearchResults {
+            total: self.search.len(),
+            results: self.search.results(SEARCH_RESULTS),
+        });
+    }
+
+    fn freeze(&mut self, index: usize, value: Option<u8>) {
+        let Some(result) = self.search.result(index) else {
+            self.events.push(EmulatorEvent::Error(format!(
+                "candidato {} não existe",
+                index
+            )));
+            return;
+        };
+
+        let code = result.address.freeze_code(value.unwrap_or(result.value));
+        match self.bus.cheats.add(&code) {
+            Ok(()) => self.events.push(EmulatorEvent::CheatAdded(code)),
+            Err(erro) => self.events.push(EmulatorEvent::Error(erro)),
+        }
+    }
+
+    fn record_movie(&mut self, path: PathBuf, anchor: MovieAnchor) {
+        self.stop_movie();
+        if anchor == MovieAnchor::PowerOn {
+            self.reset(ResetKind::Hard);
+        }
+
+        let movie = Movie::new(
+            anchor,
+            self.bus.cartridge.rom_crc,
+            self.title(),
+            self.save_state(),
+        );
+        self.movie = Some(MovieSession::Recording {
+            movie,
+            path: path.clone(),
+            marks: Vec::new(),
+        });
+        self.events.push(EmulatorEvent::MovieRecording(path));
+    }
+
+    fn play_movie(&mut self, path: PathBuf) {
+        self.stop_movie();
+
+        let result = Movie::load(&path).and_then(|movie| {
+            let warning = movie.check(self.bus.cartridge.rom_crc)?;
+            let backup = self.save_state();
+            if let Err(erro) = self.load_state(&movie.start_state) {
+                self.load_state(&backup).unwrap();
+                return Err(erro);
+            }
+            Ok((movie, warning))
+        });
+
+        match result {
+            Ok((movie, warning)) => {
+                if let Some(warning) = warning {
+                    self.events.push(EmulatorEvent::Error(warning));
+                }
+                self.movie = Some(MovieSession::Playing { movie, frame: 0 });
+                self.events.push(EmulatorEvent::MoviePlaying(path));
+            }
+            Err(erro) => self.events.push(EmulatorEvent::Error(format!(
+                "erro ao carregar '{}': {}",
+                path.display(),
+                erro
+            ))),
+        }
+    }
+
+    fn stop_movie(&mut self) {
+        match self.movie.take() {
+            Some(MovieSession::Recording { movie, path, .. }) => match movie.save(&path) {
+                Ok(()) => self.events.push(EmulatorEvent::MovieStopped {
+                    frames: movie.frames.len(),
+                }),
+                Err(erro) => self.events.push(EmulatorEvent::Error(format!(
+                    "erro ao salvar '{}': {}",
+                    path.display(),
+                    erro
+                ))),
+            },
+            Some(MovieSession::Playing { frame, .. }) => {
+                self.events.push(EmulatorEvent::MovieStopped { frames: frame });
+            }
+            None => {}
+        }
+    }
+
+    // State carregado com um movie em andamento. Na gravação, um state salvo durante ela
+    // mesma volta o movie pro frame dele e a gravação segue de lá (re-record); qualquer
+    // outro quebra a sequência de input
+    fn rerecord(&mut self) {
+        if matches!(self.movie, Some(MovieSession::Recording { .. })) {
+            let hash = self.state_hash();
+            if let Some(MovieSession::Recording { movie, marks, .. }) = &mut self.movie
+                && let Some(&(_, frame)) = marks.iter().rev().find(|(mark, _)| *mark == hash)
+            {
+                // Os states de depois desse frame eram de um input que foi descartado
+                marks.retain(|&(_, mark)| mark <= frame);
+                movie.frames.truncate(frame);
+                movie.rerecords += 1;
+                self.events.push(EmulatorEvent::MovieRerecorded {
+                    frame,
+                    rerecords: movie.rerecords,
+                });
+                return;
+            }
+        }
+        self.stop_movie();
+    }
+
+    // Frame do movie que acabou de rodar, contando do 1
+    fn movie_frame(&self) -> Option<usize> {
+        match &self.movie {
+            Some(MovieSession::Recording { movie, .. }) => Some(movie.frames.len()),
+            Some(MovieSession::Playing { frame, .. }) => Some(*frame),
+            None => None,
+        }
+    }
+
+    // Input do próximo frame: na reprodução vem do movie, na gravação é registrado
+    fn movie_input(&mut self, input: JoypadInput) -> JoypadInput {
+        match &mut self.movie {
+            Some(MovieSession::Recording { movie, .. }) => {
+                movie.frames.push(input);
+                input
+            }
+            Some(MovieSession::Playing { movie, frame }) => {
+                if let Some(&recorded) = movie.frames.get(*frame) {
+                    *frame += 1;
+                    return recorded;
+                }
+                self.stop_movie();
+                input
+            }
+            None => input,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        self.bus.cartridge.title()
+    }
+
+    fn load_battery(&mut self) {
+        self.saved_ram = self.bus.cartridge.ram().to_vec();
+        self.bus.cartridge.ram_dirty = false;
+
+        let Some(path) = &self.sav_path else {
+            return;
+        };
+        if !self.bus.cartridge.has_battery() || !path.exists() {
+            return;
+        }
+
+        // Com seed fixa o RTC não pode depender de quanto tempo passou de verdade: sem
+        // epoch o relógio fica como estava no arquivo
+        let now = match (self.rtc_epoch, self.seed) {
+            (Some(epoch), _) => Some(epoch),
+            (None, None) => Some(unix_time()),
+            (None, Some(_)) => None,
+        };
+        let result = fs::read(path)
+            .map_err(|erro| erro.to_string())
+            .and_then(|data| self.bus.cartridge.load_battery_data(&data, now));
+        match result {
+            Ok(()) => self.saved_ram = self.bus.cartridge.ram().to_vec(),
+            Err(erro) => self.events.push(EmulatorEvent::Error(format!(
+                "erro ao carregar '{}': {}",
+                path.display(),
+                erro
+            ))),
+        }
+    }
+
+    // Só grava se houve escrita na RAM e o conteúdo mudou (run-ahead e rewind escrevem
+    // e voltam atrás)
+    fn flush_battery(&mut self) {
+        if !self.bus.cartridge.ram_dirty {
+            return;
+        }
+        self.bus.cartridge.ram_dirty = false;
+        if self.bus.cartridge.ram() != self.saved_ram.as_slice() {
+            self.save_battery();
+        }
+    }
+
+    // Fechando o cartucho: com RTC o rodapé é sempre atualizado (o timestamp conta)
+    fn close_battery(&mut self) {
+        if self.bus.cartridge.has_rtc() {
+            self.save_battery();
+        } else {
+            self.flush_battery();
+        }
+    }
+
+    fn save_battery(&mut self) {
+        let Some(path) = &self.sav_path else {
+            return;
+        };
+        if !self.bus.cartridge.has_battery() {
+            return;
+        }
+
+        let data = self
+            .bus
+            .cartridge
+            .battery_data(self.rtc_epoch.unwrap_or_else(unix_time));
+        match write_rotated(path, &data, self.backups) {
+            Ok(()) => {
+                self.saved_ram = self.bus.cartridge.ram().to_vec();
+                self.events.push(EmulatorEvent::BatterySaved);
+            }
+            Err(erro) => self.events.push(EmulatorEvent::Error(format!(
+                "erro ao salvar '{}': {}",
+                path.display(),
+                erro
+            ))),
+        }
+    }
+
+    fn resume_path(&self) -> Option<PathBuf> {
+        let dir = self.resume_dir.as_ref()?;
+        Some(dir.join(format!("{:08X}.state", self.bus.cartridge.rom_crc)))
+    }
+
+    // Volta pro ponto em que a ROM foi fechada da última vez
+    fn resume(&mut self) {
+        let Some(path) = self.resume_path() else {
+            return;
+        };
+        if !path.exists() {
+            return;
+        }
+
+        let backup = self.save_state();
+        let result = fs::read(&path)
+            .map_err(|erro| erro.to_string())
+            .and_then(|data| self.load_state(&data));
+        match result {
+            Ok(()) => self.events.push(EmulatorEvent::Resumed),
+            Err(erro) => {
+                self.load_state(&backup).unwrap();
+                self.events.push(EmulatorEvent::Error(format!(
+                    "erro ao carregar '{}': {}",
+                    path.display(),
+                    erro
+                )));
+            }
+        }
+    }
+
+    fn suspend(&mut self) {
+        let Some(path) = self.resume_path() else {
+            return;
+        };
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| write_rotated(&path, &self.save_state(), 0));
+        if let Err(erro) = result {
+            self.events.push(EmulatorEvent::Error(format!(
+                "erro ao salvar '{}': {}",
+                path.display(),
+                erro
+            )));
+        }
+    }
+
+    // Cartucho vindo do frontend: o .sav e o resume do anterior são gravados antes
+    fn load_rom(&mut self, cartridge: Cartridge, path: PathBuf) {
+        self.stop_movie();
+        self.close_battery();
+        self.suspend();
+
+        self.load_cartridge(cartridge);
+        self.sav_path = Some(path.with_extension("sav"));
+        if self.crash_path.is_some() {
+            self.crash_path = Some(path.with_extension("crash.txt"));
+        }
+        self.symbols = Symbols::for_rom(&path).unwrap_or_else(|erro| {
+            self.events.push(EmulatorEvent::Error(erro));
+            Symbols::new()
+        });
+        self.load_battery();
+        self.resume();
+
+        let title = self.title();
+        let crc = self.bus.cartridge.rom_crc;
+        self.events.push(EmulatorEvent::RomLoaded { path, title, crc });
+    }
+
+    // Power-on e move o core pra uma thread própria; o frontend fica só com o handle
+    pub fn start(mut self) -> EmulatorHandle {
+        self.reset(ResetKind::Hard);
+        self.load_battery();
+        self.resume();
+
+        let (frame_tx, frames) = mpsc::sync_channel(FRAME_QUEUE);
+        let (event_tx, events) = mpsc::channel();
+        let (commands, command_rx) = mpsc::channel();
+
+        let thread = thread::spawn(move || {
+            let result =
+                panic::catch_unwind(AssertUnwindSafe(|| self.run(frame_tx, event_tx, command_rx)));
+            if let Err(payload) = result {
+                self.report_panic(payload.as_ref());
+                panic::resume_unwind(payload);
+            }
+            // Saindo no meio de uma gravação: o arquivo ainda é salvo
+            self.stop_movie();
+            self.close_battery();
+            self.suspend();
+        });
+
+        EmulatorHandle {
+            frames,
+            events,
+            commands,
+            thread: Some(thread),
+        }
+    }
+
+    // Duas instâncias no mesmo processo com os seriais ligados direto, cada uma na sua
+    // thread. Run-ahead fica desligado: os frames especulativos mandariam bytes pelo cabo
+    pub fn start_linked(mut self, mut peer: Emulator) -> (EmulatorHandle, EmulatorHandle) {
+        let (port, peer_port) = LinkPort::pair();
+        self.bus.serial.attach(Box::new(port));
+        peer.bus.serial.attach(Box::new(peer_port));
+        self.run_ahead = 0;
+        peer.run_ahead = 0;
+        (self.start(), peer.start())
+    }
+
+    // Sem janela nem pacer: roda o mais rápido possível até uma condição de parada e
+    // devolve o código de saída do processo
+    pub fn run_headless(mut self) -> i32 {
+        self.reset(ResetKind::Hard);
+        self.load_battery();
+
+        if self.profile_path.is_some() {
+            self.profiler = Some(Profiler::new());
+        }
+        if self.coverage_path.is_some() {
+            self.start_coverage();
+        }
+        if let Some(path) = self.trace_path.take() {
+            self.start_trace(&path);
+        }
+
+        let mut script = self.load_script();
+        if let Some(spec) = self.serial_device.take() {
+            self.attach_serial(&spec);
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            loop {
+                self.run_frame(&mut script);
+                self.check_achievements();
+                let code = self.end_automation_frame();
+
+                for event in self.drain_events() {
+                    match event {
+                        EmulatorEvent::Error(erro) => eprintln!("{}", erro),
+                        EmulatorEvent::CrashReport(path) => {
+                            eprintln!("relatório de crash: {}", path.display());
+                        }
+                        _ => {}
+                    }
+                }
+                if let Some(code) = code {
+                    break code;
+                }
+            }
+        }));
+        let code = match result {
+            Ok(code) => code,
+            Err(payload) => {
+                self.report_panic(payload.as_ref());
+                panic::resume_unwind(payload);
+            }
+        };
+
+        if let Some(path) = self.profile_path.take() {
+            self.save_profile(&path);
+        }
+        if let Some(path) = self.coverage_path.take() {
+            self.save_coverage(&path);
+        }
+        self.stop_trace();
+        for event in self.drain_events() {
+            if let EmulatorEvent::Error(erro) = event {
+                eprintln!("{}", erro);
+            }
+        }
+
+        self.close_battery();
+        code
+    }
+
+    fn start_coverage(&mut self) {
+        self.bus.coverage = Some(Coverage::new(self.bus.cartridge.rom().len()));
+    }
+
+    fn save_coverage(&mut self, path: &Path) {
+        let Some(coverage) = self.bus.coverage.take() else {
+            self.events.push(EmulatorEvent::Error(
+                "o mapa de cobertura não está ligado".to_string(),
+            ));
+            return;
+        };
+        let json = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let result = if json {
+            fs::write(path, coverage.to_json())
+        } else {
+            fs::write(path, coverage.map())
+        };
+        match result {
+            Ok(()) => self
+                .events
+                .push(EmulatorEvent::CoverageSaved(path.to_path_buf())),
+            Err(erro) => self.events.push(EmulatorEvent::Error(format!(
+                "erro ao salvar '{}': {}",
+                path.display(),
+                erro
+            ))),
+        }
+    }
+
+    fn save_profile(&mut self, path: &Path) {
+        let Some(profiler) = self.profiler.take() else {
+            self.events
+                .push(EmulatorEvent::Error("o profiler não está rodando".to_string()));
+            return;
+        };
+        match fs::write(path, profiler.report(&self.symbols).to_string()) {
+            Ok(()) => self
+                .events
+                .push(EmulatorEvent::ProfileSaved(path.to_path_buf())),
+            Err(erro) => self.events.push(EmulatorEvent::Error(format!(
+                "erro ao salvar '{}': {}",
+                path.display(),
+                erro
+            ))),
+        }
+    }
+
+    fn save_crash_report(&mut self, reason: &str) {
+        let Some(path) = self.crash_path.clone() else {
+            return;
+        };
+        let report = CrashReport {
+            reason,
+            cpu: &self.cpu,
+            bus: &self.bus,
+            history: &self.history,
+            call_stack: &self.call_stack,
+            symbols: &self.symbols,
+        };
+        match fs::write(&path, report.to_string()) {
+            Ok(()) => self.events.push(EmulatorEvent::CrashReport(path)),
+            Err(erro) => self.events.push(EmulatorEvent::Error(format!(
+                "erro ao salvar '{}': {}",
+                path.display(),
+                erro
+            ))),
+        }
+    }
+
+    // A thread vai morrer: os eventos não chegam mais no frontend, saem direto no stderr
+    fn report_panic(&mut self, payload: &(dyn Any + Send)) {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        self.save_crash_report(&format!("panic: {}", message));
+        for event in self.drain_events() {
+            match event {
+                EmulatorEvent::Error(erro) => eprintln!("{}", erro),
+                EmulatorEvent::CrashReport(path) => {
+                    eprintln!("relatório de crash: {}", path.display());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn start_trace(&mut self, path: &Path) {
+        match Trace::create(path) {
+            Ok(trace) => self.trace = Some(trace),
+            Err(erro) => self.events.push(EmulatorEvent::Error(erro)),
+        }
+    }
+
+    fn stop_trace(&mut self) {
+        match self.trace.take().map(Trace::finish) {
+            Some(Ok(path)) => self.events.push(EmulatorEvent::TraceSaved(path)),
+            Some(Err(erro)) => self.events.push(EmulatorEvent::Error(erro)),
+            None => {}
+        }
+    }
+
+    // Um frame na thread de quem chama, sem script (API em C); os eventos ficam pro
+    // drain_events
+    pub fn step_frame(&mut self) {
+        self.run_frame(&mut None);
+    }
+
+    // Pra quem embute o core e tem o próprio laço (GUI, runtime async, event loop do
+    // WASM): roda pelo menos `cycles` ciclos do clock normal e devolve quantos rodou, menos
+    // se parar num breakpoint. Sem script, como o step_frame
+    pub fn step_cycles(&mut self, cycles: u64) -> u64 {
+        let mut work = FrameWork::new(false);
+        let mut done = 0;
+        while done < cycles {
+            match self.step_instruction(&mut None, &mut work) {
+                Some(step) => done += step.cycles,
+                None => break,
+            }
+        }
+        self.flush_outputs();
+        done
+    }
+
+    // O mesmo em pedaços: cada next roda até o próximo frame ou linha
+    // (for _ in emulator.steps(Boundary::Scanline).take(154))
+    pub fn steps(&mut self, boundary: Boundary) -> Steps<'_> {
+        Steps {
+            emulator: self,
+            boundary,
+        }
+    }
+
+    // Roda no começo do vblank de cada frame (com o frame pronto pro take_frame)
+    pub fn register_frame_callback(
+        &mut self,
+        callback: impl FnMut(&mut Emulator) + Send + 'static,
+    ) -> CallbackId {
+        self.add_callback(CallbackPoint::Frame, Box::new(callback))
+    }
+
+    // Roda quando LY passa a valer `line` (0-153), antes da linha ser desenhada; com o LCD
+    // desligado não roda
+    pub fn register_scanline_callback(
+        &mut self,
+        line: u8,
+        callback: impl FnMut(&mut Emulator) + Send + 'static,
+    ) -> CallbackId {
+        self.add_callback(CallbackPoint::Scanline(line), Box::new(callback))
+    }
+
+    // false se o id não estava registrado
+    pub fn unregister_callback(&mut self, id: CallbackId) -> bool {
+        let registered = self.callbacks.len();
+        self.callbacks.retain(|hook| hook.id != id);
+        self.callbacks.len() != registered
+    }
+
+    fn add_callback(&mut self, point: CallbackPoint, callback: PpuCallback) -> CallbackId {
+        let id = CallbackId(self.next_callback);
+        self.next_callback += 1;
+        self.callbacks.push(PpuHook {
+            id,
+            point,
+            callback: Some(callback),
+        });
+        id
+    }
+
+    // Um callback pode registrar ou tirar outros (e a si mesmo) enquanto roda
+    fn run_callbacks(&mut self, point: CallbackPoint) {
+        if !self.callbacks.iter().any(|hook| hook.point == point) {
+            return;
+        }
+        let ids: Vec<CallbackId> = self
+            .callbacks
+            .iter()
+            .filter(|hook| hook.point == point)
+            .map(|hook| hook.id)
+            .collect();
+        for id in ids {
+            let Some(mut callback) = self
+                .callbacks
+                .iter_mut()
+                .find(|hook| hook.id == id)
+                .and_then(|hook| hook.callback.take())
+            else {
+                continue;
+            };
+            callback(self);
+            if let Some(hook) = self.callbacks.iter_mut().find(|hook| hook.id == id) {
+                hook.callback = Some(callback);
+            }
+        }
+    }
+
+    fn run_until(&mut self, boundary: Boundary) -> Yielded {
+        let mut work = FrameWork::new(false);
+        let mut done = 0;
+        let yielded = loop {
+            let Some(step) = self.step_instruction(&mut None, &mut work) else {
+                break Yielded::Stopped;
+            };
+            done += step.cycles;
+            match (boundary, step.line) {
+                (Boundary::Frame, _) if step.vblank || done >= CYCLES_PER_FRAME => {
+                    break Yielded::Frame;
+                }
+                (Boundary::Scanline, Some(line)) => break Yielded::Scanline(line),
+                (Boundary::Scanline, None) if done >= CYCLES_PER_LINE => {
+                    break Yielded::Scanline(self.bus.peek(LY));
+                }
+                _ => {}
+            }
+        };
+        self.flush_outputs();
+        yielded
+    }
+
+    fn attach_serial(&mut self, spec: &str) {
+        match self.bus.serial.attach_named(spec) {
+            Ok(()) => self.events.push(EmulatorEvent::SerialMessage(format!(
+                "serial: {}",
+                spec
+            ))),
+            Err(erro) => self.events.push(EmulatorEvent::Error(erro)),
+        }
+    }
+
+    fn load_script(&mut self) -> Option<Script> {
+        let path = self.script_path.clone()?;
+        match Script::load(&path, self) {
+            Ok(script) => Some(script),
+            Err(erro) => {
+                self.events.push(EmulatorEvent::Error(erro));
+                None
+            }
+        }
+    }
+
+    // Conta o frame, tira os screenshots agendados pra ele e checa as condições de parada
+    fn end_automation_frame(&mut self) -> Option<i32> {
+        self.frame_count += 1;
+        if self.screenshots.iter().any(|shot| shot.frame == self.frame_count) {
+            self.take_screenshots();
+        }
+        let frame = self.frame_count;
+        self.exit_watch.as_mut()?.end_frame(frame, &self.events)
+    }
+
+    fn take_screenshots(&mut self) {
+        let mut rgba = Vec::new();
+        let (width, height) = self.render_frame(&mut rgba);
+
+        let frame = self.frame_count;
+        let (due, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.screenshots)
+            .into_iter()
+            .partition(|shot| shot.frame == frame);
+        self.screenshots = pending;
+
+        for shot in due {
+            match png::write_png(&shot.path, width, height, &rgba) {
+                Ok(()) => self.events.push(EmulatorEvent::ScreenshotSaved(shot.path)),
+                Err(erro) => self.events.push(EmulatorEvent::Error(format!(
+                    "erro ao salvar '{}': {}",
+                    shot.path.display(),
+                    erro
+                ))),
+            }
+        }
+    }
+
+    fn run(
+        &mut self,
+        frames: SyncSender<Vec<u8>>,
+        events: Sender<EmulatorEvent>,
+        commands: Receiver<EmulatorCommand>,
+    ) {
+        let mut pacer = Pacer::new();
+        let mut rewind = RewindBuffer::new();
+        let mut last_flush = Instant::now();
+        let mut rgba: Vec<u8> = vec![0; GB_W * GB_H * 4];
+        let mut state = RunState {
+            paused: false,
+            fast_forward: false,
+            rewinding: false,
+            input: JoypadInput::default(),
+            tilt: Tilt::default(),
+        };
+
+        let mut netplay = self.netplay.take();
+        let mut script = self.load_script();
+        if let Some(spec) = self.serial_device.take() {
+            self.attach_serial(&spec);
+        }
+
+        loop {
+            loop {
+                let command = match commands.try_recv() {
+                    Ok(command) => command,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => return,
+                };
+
+                if netplay.is_some() && breaks_lockstep(&command) {
+                    self.events.push(EmulatorEvent::Error(
+                        "indisponível durante o netplay".to_string(),
+                    ));
+                    continue;
+                }
+
+                match command {
+                    EmulatorCommand::Quit => return,
+                    EmulatorCommand::SetInput(input) => state.input = input,
+                    EmulatorCommand::PlayMacro(input_macro) => {
+                        self.bus.joypad.play_macro(input_macro);
+                    }
+                    EmulatorCommand::RecordMacro(true) => self.bus.joypad.start_macro_recording(),
+                    EmulatorCommand::RecordMacro(false) => {
+                        match self.bus.joypad.stop_macro_recording() {
+                            Some(input_macro) if !input_macro.is_empty() => self
+                                .events
+                                .push(EmulatorEvent::MacroRecorded(input_macro)),
+                            Some(_) => self.events.push(EmulatorEvent::Error(
+                                "aviso: macro vazia, nenhum botão apertado".to_string(),
+                            )),
+                            None => {}
+                        }
+                    }
+                    EmulatorCommand::SetTilt(tilt) => state.tilt = tilt,
+                    EmulatorCommand::SaveState(path) => self.save_state_file(&path),
+                    EmulatorCommand::LoadState(path) => {
+                        if self.load_state_file(&path) {
+                            self.rerecord();
+                            rewind.clear();
+                        }
+                    }
+                    EmulatorCommand::Reset(kind) => {
+                        self.stop_movie();
+                        self.reset(kind);
+                        rewind.clear();
+                    }
+                    EmulatorCommand::SetPaused(paused) => state.paused = paused,
+                    EmulatorCommand::SetFastForward(on) => state.fast_forward = on,
+                    EmulatorCommand::SetRewind(on) => {
+                        if on {
+                            self.stop_movie();
+                        }
+                        state.rewinding = on;
+                    }
+                    EmulatorCommand::SetCheatsEnabled(on) => self.bus.cheats.enabled = on,
+                    EmulatorCommand::SetMutedChannels(muted) => self.bus.apu.muted = muted,
+                    EmulatorCommand::SetScope(enabled) => self.bus.apu.set_scope(enabled),
+                    EmulatorCommand::SetStats(enabled) => {
+                        self.stats = enabled.then(StatsCollector::new);
+                        self.bus.device_times = enabled.then(|| DeviceTimes::new(clock_nanos));
+                    }
+                    EmulatorCommand::SetInputDisplay(on) => self.input_display = on,
+                    EmulatorCommand::AddCheat(code) => {
+                        if let Err(erro) = self.bus.cheats.add(&code) {
+                            self.events.push(EmulatorEvent::Error(erro));
+                        }
+                    }
+                    EmulatorCommand::RemoveCheat(code) => self.bus.cheats.remove(&code),
+                    EmulatorCommand::SetCheatEnabled(code, on) => {
+                        self.bus.cheats.set_enabled(&code, on);
+                    }
+                    EmulatorCommand::Freeze(freeze) => match self.bus.cheats.freeze(freeze) {
+                        Ok(()) => self.bus.write(freeze.address, freeze.value),
+                        Err(erro) => self.events.push(EmulatorEvent::Error(erro)),
+                    },
+                    EmulatorCommand::Unfreeze(address) => {
+                        if !self.bus.cheats.unfreeze(address) {
+                            self.events.push(EmulatorEvent::Error(format!(
+                                "${:04X} não está congelado",
+                                address
+                            )));
+                        }
+                    }
+                    EmulatorCommand::SearchStart => {
+                        self.search.start(self.bus.search_ram());
+                        self.push_search_results();
+                    }
+                    EmulatorCommand::SearchFilter(filter) => {
+                        self.search.filter(self.bus.search_ram(), filter);
+                        self.push_search_results();
+                    }
+                    EmulatorCommand::SearchFreeze { index, value } => self.freeze(index, value),
+                    EmulatorCommand::RecordMovie(path, anchor) => {
+                        self.record_movie(path, anchor);
+                        rewind.clear();
+                    }
+                    EmulatorCommand::PlayMovie(path) => {
+                        self.play_movie(path);
+                        rewind.clear();
+                    }
+                    EmulatorCommand::StopMovie => self.stop_movie(),
+                    EmulatorCommand::AddBreakpoint(addr) => {
+                        self.breakpoints.insert(addr);
+                    }
+                    EmulatorCommand::RemoveBreakpoint(addr) => {
+                        self.breakpoints.remove(&addr);
+                    }
+                    EmulatorCommand::Step(kind) => {
+                        if kind == StepKind::Out && self.call_stack.depth() == 0 {
+                            self.events.push(EmulatorEvent::Error(
+                                "pilha de chamadas vazia: não tem função pra sair".to_string(),
+                            ));
+                        } else {
+                            self.stepping = Some(Stepping {
+                                kind,
+                                depth: self.call_stack.depth(),
+                                started: false,
+                            });
+                            state.paused = false;
+                        }
+                    }
+                    EmulatorCommand::AddTrigger(trigger) => {
+                        self.triggers.insert(trigger);
+                    }
+                    EmulatorCommand::RemoveTrigger(trigger) => {
+                        self.triggers.remove(&trigger);
+                    }
+                    EmulatorCommand::Backtrace => {
+                        self.events.push(EmulatorEvent::Backtrace {
+                            pc: location(&self.bus, self.cpu.program_counter),
+                            frames: self.call_stack.frames().to_vec(),
+                        });
+                    }
+                    EmulatorCommand::History(count) => {
+                        self.events
+                            .push(EmulatorEvent::History(self.history.last(count)));
+                    }
+                    EmulatorCommand::Inspect(memory_start) => {
+                        let inspection = Inspection::capture(
+                            &self.cpu,
+                            &self.bus,
+                            &self.history,
+                            &self.breakpoints,
+                            &self.watches,
+                            memory_start,
+                        );
+                        self.events
+                            .push(EmulatorEvent::Inspection(Box::new(inspection)));
+                    }
+                    EmulatorCommand::InspectVideo => {
+                        let video = VideoInspection::capture(&self.bus);
+                        self.events
+                            .push(EmulatorEvent::VideoInspection(Box::new(video)));
+                    }
+                    EmulatorCommand::AddWatch(watch) => self.watches.push(watch),
+                    EmulatorCommand::RemoveWatch(index) => {
+                        if index < self.watches.len() {
+                            self.watches.remove(index);
+                        }
+                    }
+                    EmulatorCommand::ListWatches => {
+                        let watches = self.watches.iter();
+                        let values = watches.map(|watch| watch.eval(&self.cpu, &self.bus));
+                        self.events.push(EmulatorEvent::Watches(values.collect()));
+                    }
+                    EmulatorCommand::ListCheats => {
+                        let cheats = self.bus.cheats.iter();
+                        let list = cheats.map(|cheat| (cheat.code.clone(), cheat.enabled));
+                        self.events.push(EmulatorEvent::CheatList(list.collect()));
+                    }
+                    EmulatorCommand::SetPpuLog(on) => self.bus.ppu.set_event_log(on),
+                    EmulatorCommand::PpuLog => match self.bus.ppu.event_log() {
+                        Some(events) => self.events.push(EmulatorEvent::PpuLog(events.to_vec())),
+                        None => self.events.push(EmulatorEvent::Error(String::from(
+                            "log de eventos da PPU desligado",
+                        ))),
+                    },
+                    EmulatorCommand::SetColorization(mode) => self.set_colorization(mode),
+                    EmulatorCommand::SetOverclock(factor) => {
+                        let factor = factor.max(1);
+                        if factor > 1 && factor != self.bus.overclock {
+                            self.events.push(EmulatorEvent::Error(overclock_warning(factor)));
+                        }
+                        self.bus.overclock = factor;
+                    }
+                    EmulatorCommand::SetRunAhead(frames) => self.run_ahead = frames,
+                    EmulatorCommand::StartProfile => self.profiler = Some(Profiler::new()),
+                    EmulatorCommand::StopProfile(path) => self.save_profile(&path),
+                    EmulatorCommand::StartCoverage => self.start_coverage(),
+                    EmulatorCommand::StopCoverage(path) => self.save_coverage(&path),
+                    EmulatorCommand::StartTrace(path) => {
+                        self.stop_trace();
+                        self.start_trace(&path);
+                    }
+                    EmulatorCommand::StopTrace => self.stop_trace(),
+                    EmulatorCommand::AttachSerial(spec) => self.attach_serial(&spec),
+                    EmulatorCommand::LoadRom { cartridge, path } => {
+                        self.load_rom(*cartridge, path);
+                        rewind.clear();
+                    }
+                }
+            }
+
+            if state.paused {
+                // Respostas dos comandos (backtrace, states) não esperam despausar
+                if !self.send_events(&events) {
+                    return;
+                }
+                pacer.wait();
+                continue;
+            }
+
+            let input = script
+                .as_ref()
+                .and_then(Script::input)
+                .map(JoypadInput::held)
+                .unwrap_or(state.input);
+            let input = self.bus.joypad.blend_macro(input);
+            let input = match netplay.as_mut().map(|net| self.netplay_input(net, input)) {
+                None => Some(input),
+                Some(Ok(combined)) => combined,
+                Some(Err(erro)) => {
+                    netplay = None;
+                    self.events.push(EmulatorEvent::Error(erro));
+                    Some(input)
+                }
+            };
+            let Some(input) = input else {
+                // Peer atrasado: o frame espera, os eventos não
+                if !self.send_events(&events) {
+                    return;
+                }
+                continue;
+            };
+            let input = self.movie_input(input);
+            self.bus.set_input(input);
+            self.bus.cartridge.set_tilt(state.tilt);
+
+            if state.rewinding {
+                // Sem histórico o jogo fica parado no frame mais antigo
+                if let Some(snapshot) = rewind.pop() {
+                    self.load_state(&snapshot).unwrap();
+                    state.paused = !self.run_frame(&mut script);
+                }
+            } else {
+                // O frontend fica sabendo pelo evento Breakpoint e despausa quando quiser
+                state.paused = !self.run_frame(&mut script);
+                self.check_achievements();
+                rewind.record(|| self.save_state());
+
+                if let Some(net) = &mut netplay
+                    && let Err(erro) = net.end_frame(|| self.state_hash())
+                {
+                    netplay = None;
+                    self.events.push(EmulatorEvent::Error(erro));
+                }
+            }
+
+            if let Some(code) = self.end_automation_frame() {
+                self.events.push(EmulatorEvent::Exit(code));
+            }
+
+            if let Some(stats) = &mut self.stats
+                && let Some(stats) = stats.end_frame(self.bus.device_times.as_mut())
+            {
+                self.events.push(EmulatorEvent::Stats(stats));
+            }
+
+            // Com run-ahead a imagem mostrada vem dos frames especulativos
+            let frame_ready = if self.run_ahead > 0 && !state.rewinding {
+                self.run_ahead_frames(&mut rgba)
+            } else {
+                self.take_frame(&mut rgba)
+            };
+
+            if frame_ready {
+                // Frontend atrasado: descarta o frame em vez de segurar a emulação
+                if let Err(TrySendError::Disconnected(_)) = frames.try_send(rgba.clone()) {
+                    return;
+                }
+            }
+
+            if last_flush.elapsed() >= BATTERY_FLUSH_INTERVAL {
+                last_flush = Instant::now();
+                self.flush_battery();
+            }
+
+            if !self.send_events(&events) {
+                return;
+            }
+
+            // Ritmo fixo do lado da emulação, independente do refresh do monitor
+            if !state.fast_forward {
+                pacer.wait();
+            }
+        }
+    }
+
+    // false = parou num breakpoint no meio do frame
+    fn run_frame(&mut self, script: &mut Option<Script>) -> bool {
+        let mut cycles_this_frame: u64 = 0;
+        let mut completed = true;
+        // Só medidos com o overlay de estatísticas aberto
+        let frame_start = self.stats.is_some().then(Instant::now);
+        let mut work = FrameWork::new(frame_start.is_some());
+
+        while cycles_this_frame < CYCLES_PER_FRAME {
+            match self.step_instruction(script, &mut work) {
+                Some(step) => cycles_this_frame += step.cycles,
+                None => {
+                    completed = false;
+                    break;
+                }
+            }
+        }
+
+        self.flush_outputs();
+
+        // Os jogos fazem PWM no motor: a intensidade é a fração do frame com ele ligado
+        let rumble = (work.rumble_cycles * RUMBLE_LEVELS / cycles_this_frame.max(1)) as u8;
+        if rumble != self.rumble {
+            self.rumble = rumble;
+            self.events
+                .push(EmulatorEvent::Rumble(rumble * 100 / RUMBLE_LEVELS as u8));
+        }
+
+        if let Some(running) = script {
+            match running.end_frame(self) {
+                Ok(overlay) => self.events.push(EmulatorEvent::Overlay(overlay)),
+                Err(erro) => self.script_failed(script, erro),
+            }
+        }
+
+        if let (Some(stats), Some(start)) = (&mut self.stats, frame_start) {
+            stats.add_work(work.instructions, work.cpu_cycles, start.elapsed(), work.cpu_time);
+        }
+        if self.input_display {
+            self.events.push(EmulatorEvent::InputDisplay {
+                buttons: self.bus.joypad.buttons(),
+                movie_frame: self.movie_frame(),
+            });
+        }
+        completed
+    }
+
+    // Uma instrução (ou um passo em HALT/STOP) com tudo que o debugger acompanha; None
+    // quando parou antes dela num breakpoint, trigger ou fim de step, com o evento já
+    // publicado
+    fn step_instruction(
+        &mut self,
+        script: &mut Option<Script>,
+        work: &mut FrameWork,
+    ) -> Option<Step> {
+        if let Some(hit) = self.trigger_hit.take() {
+            self.skip_breakpoint = self.breakpoints.contains(&self.cpu.program_counter);
+            self.events.push(EmulatorEvent::Triggered(hit));
+            return None;
+        }
+        if self.step_done() {
+            let (bank, pc) = location(&self.bus, self.cpu.program_counter);
+            self.events.push(EmulatorEvent::Stepped { pc, bank });
+            return None;
+        }
+        if self.at_breakpoint() {
+            let (bank, pc) = location(&self.bus, self.cpu.program_counter);
+            self.events.push(EmulatorEvent::Breakpoint { pc, bank });
+            return None;
+        }
+
+        self.bus.cover_instruction(self.cpu.program_counter);
+        // Em HALT/STOP (ou com a CPU travada) não tem instrução executando
+        if !self.cpu.halt && !self.cpu.stop && !self.cpu.locked {
+            let entry = HistoryEntry::capture(&self.cpu, &self.bus);
+            self.history.record(entry);
+            if let Some(trace) = &mut self.trace
+                && let Err(erro) = trace.record(&entry, &self.symbols)
+            {
+                self.trace = None;
+                self.events.push(EmulatorEvent::Error(erro));
+            }
+        }
+        let profiled = self.profiler.is_some().then(|| {
+            let pc = self.cpu.program_counter;
+            (location(&self.bus, pc), self.bus.peek(pc))
+        });
+        let pc = self.cpu.program_counter;
+        let stack_pointer = self.cpu.stack_pointer;
+        // Com triggers ligados: onde estava e os bancos de antes da instrução
+        let watched = (!self.triggers.is_empty()).then(|| {
+            let cartridge = &self.bus.cartridge;
+            let banks = (cartridge.rom_bank(0x4000), cartridge.ram_bank());
+            (location(&self.bus, pc), banks)
+        });
+
+        let cycles = if work.timed {
+            let start = Instant::now();
+            if !self.cpu.halt && !self.cpu.stop {
+                work.instructions += 1;
+            }
+            let cycles = self.cpu.step(&mut self.bus) as u64;
+            work.cpu_time += start.elapsed();
+            work.cpu_cycles += cycles;
+            cycles
+        } else {
+            self.cpu.step(&mut self.bus) as u64
+        };
+
+        if let (Some(profiler), Some((at, opcode))) = (&mut self.profiler, profiled) {
+            let next = location(&self.bus, self.cpu.program_counter);
+            profiler.record(at, opcode, cycles, next);
+        }
+
+        // Só CALL, RST, RET, PUSH/POP e afins mexem no SP: o resto não custa nada
+        let interrupt = self.cpu.interrupt_dispatched.take();
+        if self.cpu.stack_pointer != stack_pointer || interrupt.is_some() {
+            let at = location(&self.bus, pc);
+            let next = location(&self.bus, self.cpu.program_counter);
+            let opcode = self.bus.peek(pc);
+            let stack_pointer = self.cpu.stack_pointer;
+            self.call_stack
+                .record(at, opcode, interrupt.is_some(), next, stack_pointer);
+        }
+        if let Some(stepping) = &mut self.stepping {
+            stepping.started = true;
+        }
+        if let Some((at, banks)) = watched {
+            self.trigger_hit = self.check_triggers(at, banks, interrupt);
+        }
+
+        if self.bus.watch.has_hits()
+            && let Some(running) = script
+            && let Err(erro) = running.memory_hooks(self)
+        {
+            self.script_failed(script, erro);
+        }
+
+        // Em double speed o bus devolve metade dos ciclos: o frame segue o clock normal
+        let clock = self.bus.tick(cycles, self.cpu.stop);
+
+        let vblank = self.bus.ppu.take_vblank();
+        if vblank {
+            self.bus.apply_ram_cheats();
+            self.events.push(EmulatorEvent::FrameReady);
+        }
+
+        let line = self.bus.ppu.take_line_start();
+        if let Some(line) = line
+            && let Some(running) = script
+            && running.watches_line(line)
+            && let Err(erro) = running.scanline_hooks(self, line)
+        {
+            self.script_failed(script, erro);
+        }
+        if !self.callbacks.is_empty() {
+            if let Some(line) = line {
+                self.run_callbacks(CallbackPoint::Scanline(line));
+            }
+            if vblank {
+                self.run_callbacks(CallbackPoint::Frame);
+            }
+        }
+
+        if let Some((pc, opcode)) = self.cpu.illegal_opcode.take() {
+            self.events.push(EmulatorEvent::IllegalOpcode {
+                pc,
+                opcode,
+                locked: self.cpu.locked,
+            });
+            if self.cpu.locked {
+                self.save_crash_report(&format!(
+                    "CPU travada no opcode ilegal ${:02X} em ${:04X}",
+                    opcode, pc
+                ));
+            }
+        }
+
+        if self.bus.cartridge.rumble() {
+            work.rumble_cycles += clock;
+        }
+        Some(Step {
+            cycles: clock,
+            vblank,
+            line,
+        })
+    }
+
+    // Serial, áudio e afins que se acumularam desde a última vez viram eventos
+    fn flush_outputs(&mut self) {
+        for byte in self.bus.serial.take_sent() {
+            self.events.push(EmulatorEvent::SerialByte(byte));
+        }
+        while let Some(message) = self.bus.serial.take_message() {
+            self.events.push(EmulatorEvent::SerialMessage(message));
+        }
+
+        let samples = self.bus.apu.take_samples();
+        if !samples.is_empty() {
+            self.events.push(EmulatorEvent::AudioSamples(samples));
+        }
+
+        if let Some(tone) = self.bus.cartridge.take_tone() {
+            self.events.push(EmulatorEvent::Melody(tone));
+        }
+
+        if let Some(scope) = self.bus.apu.take_scope() {
+            self.events.push(EmulatorEvent::Scope(scope));
+        }
+    }
+
+    fn check_triggers(
+        &self,
+        at: Location,
+        (rom_bank, ram_bank): (usize, usize),
+        interrupt: Option<u8>,
+    ) -> Option<TriggerHit> {
+        if let Some(bit) = interrupt
+            && (self.triggers.contains(&Trigger::Interrupt(None))
+                || self.triggers.contains(&Trigger::Interrupt(Some(bit))))
+        {
+            return Some(TriggerHit::Interrupt { bit, pc: at });
+        }
+
+        let cartridge = &self.bus.cartridge;
+        let (new_rom, new_ram) = (cartridge.rom_bank(0x4000), cartridge.ram_bank());
+        if new_rom != rom_bank && self.triggers.contains(&Trigger::RomBank) {
+            return Some(TriggerHit::RomBank {
+                old: rom_bank,
+                new: new_rom,
+                pc: at,
+            });
+        }
+        if new_ram != ram_bank && self.triggers.contains(&Trigger::RamBank) {
+            return Some(TriggerHit::RamBank {
+                old: ram_bank,
+                new: new_ram,
+                pc: at,
+            });
+        }
+        None
+    }
+
+    // Como o breakpoint, o passo só termina com uma instrução pra executar
+    fn step_done(&mut self) -> bool {
+        let Some(stepping) = &self.stepping else {
+            return false;
+        };
+        if !stepping.started || self.cpu.halt || self.cpu.stop {
+            return false;
+        }
+        let depth = self.call_stack.depth();
+        let done = match stepping.kind {
+            StepKind::Into => true,
+            StepKind::Over => depth <= stepping.depth,
+            StepKind::Out => depth < stepping.depth,
+        };
+        if done {
+            self.stepping = None;
+            // Parado em cima de um breakpoint: continuar não para nele de novo
+            self.skip_breakpoint = self.breakpoints.contains(&self.cpu.program_counter);
+        }
+        done
+    }
+
+    // Com a CPU em HALT o PC não anda: o breakpoint só vale na hora de executar
+    fn at_breakpoint(&mut self) -> bool {
+        if self.breakpoints.is_empty() || self.cpu.halt || self.cpu.stop {
+            return false;
+        }
+        if std::mem::take(&mut self.skip_breakpoint) {
+            return false;
+        }
+        let hit = self.breakpoints.contains(&self.cpu.program_counter);
+        self.skip_breakpoint = hit;
+        hit
+    }
+
+    // false = frontend foi embora
+    fn send_events(&mut self, events: &Sender<EmulatorEvent>) -> bool {
+        self.drain_events().all(|event| events.send(event).is_ok())
+    }
+
+    // Input combinado do frame no netplay; None = ainda esperando o peer
+    fn netplay_input(
+        &mut self,
+        net: &mut Netplay,
+        local: JoypadInput,
+    ) -> Result<Option<JoypadInput>, String> {
+        if let Some(data) = net.take_state() {
+            self.load_state(&data)?;
+            self.events
+                .push(EmulatorEvent::NetplaySynced { frame: net.frame() });
+        }
+        if net.needs_state() {
+            net.send_state(self.save_state())?;
+            self.events
+                .push(EmulatorEvent::NetplaySynced { frame: net.frame() });
+        }
+        net.input(local)
+    }
+
+    // Frame completo no formato do frontend: 160x144, ou o quadro de 256x224 com a
+    // moldura no SGB
+    fn take_frame(&mut self, rgba: &mut Vec<u8>) -> bool {
+        let ready = match &mut self.bus.sgb {
+            None => {
+                rgba.resize(GB_W * GB_H * 4, 0);
+                self.bus.ppu.take_frame_rgba(rgba)
+            }
+            Some(sgb) => {
+                let Some(shades) = self.bus.ppu.take_frame() else {
+                    return false;
+                };
+                rgba.resize(SGB_W * SGB_H * 4, 0);
+                sgb.render(shades, rgba);
+                if let Some(transform) = &self.color_transform {
+                    transform.apply_rgba(rgba);
+                }
+                true
+            }
+        };
+        if ready && let Some(ghosting) = &mut self.ghosting {
+            ghosting.apply(rgba, self.bus.ppu.frame_number());
+        }
+        ready
+    }
+
+    // Como take_frame, mas sem consumir o frame que ainda vai pro frontend; devolve o tamanho
+    pub fn render_frame(&mut self, rgba: &mut Vec<u8>) -> (usize, usize) {
+        let size = match &mut self.bus.sgb {
+            None => {
+                rgba.resize(GB_W * GB_H * 4, 0);
+                self.bus.ppu.frame_rgba(rgba);
+                (GB_W, GB_H)
+            }
+            Some(sgb) => {
+                rgba.resize(SGB_W * SGB_H * 4, 0);
+                sgb.render(self.bus.ppu.frame(), rgba);
+                if let Some(transform) = &self.color_transform {
+                    transform.apply_rgba(rgba);
+                }
+                (SGB_W, SGB_H)
+            }
+        };
+        if let Some(ghosting) = &mut self.ghosting {
+            ghosting.apply(rgba, self.bus.ppu.frame_number());
+        }
+        size
+    }
+
+    // Roda frames com o mesmo input e volta pro snapshot: a imagem mostrada já reflete o
+    // input atual, que o jogo normalmente só desenharia `run_ahead` frames depois
+    fn run_ahead_frames(&mut self, rgba: &mut Vec<u8>) -> bool {
+        let snapshot = self.save_state();
+        let events = self.events.len();
+        let rumble = self.rumble;
+        let skip_breakpoint = self.skip_breakpoint;
+        // O profiler, o log de execução e os callbacks só veem os frames de verdade
+        let profiler = self.profiler.take();
+        let trace = self.trace.take();
+        let callbacks = std::mem::take(&mut self.callbacks);
+        let call_stack = self.call_stack.clone();
+        let stepping = self.stepping.take();
+        let trigger_hit = self.trigger_hit.take();
+        let history = self.history.clone();
+        let crash_path = self.crash_path.take();
+        // Impressora, cabo e afins não têm como desfazer o que receberam
+        let device = self.bus.serial.swap_device(Box::new(Disconnected));
+
+        // Sem script: os hooks só devem ver os frames de verdade
+        for _ in 0..self.run_ahead {
+            self.run_frame(&mut None);
+        }
+        let ready = self.take_frame(rgba);
+
+        // Tudo que os frames especulativos produziram se repete nos frames reais
+        self.events.truncate(events);
+        self.rumble = rumble;
+        self.skip_breakpoint = skip_breakpoint;
+        self.profiler = profiler;
+        self.trace = trace;
+        self.callbacks = callbacks;
+        self.bus.watch.take_hits();
+        self.load_state(&snapshot).unwrap();
+        self.bus.serial.swap_device(device);
+        self.call_stack = call_stack;
+        self.stepping = stepping;
+        self.trigger_hit = trigger_hit;
+        self.history = history;
+        self.crash_path = crash_path;
+        ready
+    }
+
+    // Só depois dos frames de verdade: nem o run-ahead nem o rewind desbloqueiam nada
+    fn check_achievements(&mut self) {
+        let Some(achievements) = &mut self.achievements else {
+            return;
+        };
+        let bus = &self.bus;
+        for achievement in achievements.update(&|addr| bus.peek(addr)) {
+            self.events.push(EmulatorEvent::AchievementUnlocked {
+                title: achievement.title.clone(),
+                description: achievement.description.clone(),
+                points: achievement.points,
+            });
+        }
+    }
+
+    // Erro num callback desliga o script em vez de repetir a mesma mensagem todo frame
+    fn script_failed(&mut self, script: &mut Option<Script>, erro: String) {
+        *script = None;
+        self.bus.watch.clear();
+        self.events.push(EmulatorEvent::Overlay(Vec::new()));
+        self.events.push(EmulatorEvent::Error(format!("script desativado: {}", erro)));
+    }
+}
+
+// Segundos desde 1970, pro timestamp do RTC no .sav
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+// O timer, a PPU e o áudio seguem no ritmo normal; só a CPU acelera
+pub fn overclock_warning(factor: u8) -> String {
+    format!(
+        "aviso: overclock de {}x; jogos que dependem do timing exato podem quebrar",
+        factor
+    )
+}
+
+// Comandos que mudariam o estado só de um lado do netplay
+fn breaks_lockstep(command: &EmulatorCommand) -> bool {
+    matches!(
+        command,
+        EmulatorCommand::LoadState(_)
+            | EmulatorCommand::Reset(_)
+            | EmulatorCommand::SetPaused(true)
+            | EmulatorCommand::SetRewind(true)
+            | EmulatorCommand::SetCheatsEnabled(_)
+            | EmulatorCommand::AddCheat(_)
+            | EmulatorCommand::RemoveCheat(_)
+            | EmulatorCommand::SetCheatEnabled(..)
+            | EmulatorCommand::SetOverclock(_)
+            | EmulatorCommand::SetRunAhead(_)
+            | EmulatorCommand::Freeze(_)
+            | EmulatorCommand::Unfreeze(_)
+            | EmulatorCommand::SearchFreeze { .. }
+            | EmulatorCommand::RecordMovie(..)
+            | EmulatorCommand::PlayMovie(_)
+            | EmulatorCommand::LoadRom { .. }
+            | EmulatorCommand::AddBreakpoint(_)
+            | EmulatorCommand::Step(_)
+            | EmulatorCommand::AddTrigger(_)
+    )
+}
@@ -0,0 +1,54 @@
+#[cfg(feature = "std")]
+pub mod automation;
+#[cfg(feature = "std")]
+pub mod backup;
+#[cfg(feature = "std")]
+pub mod crash;
+#[cfg(feature = "std")]
+pub mod event;
+#[cfg(feature = "std")]
+pub mod machine;
+pub mod model;
+#[cfg(feature = "std")]
+pub mod movie;
+#[cfg(feature = "std")]
+pub mod pacing;
+#[cfg(feature = "std")]
+pub mod profiler;
+#[cfg(feature = "std")]
+pub mod rewind;
+pub mod rng;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod trace;
+
+#[cfg(feature = "std")]
+pub use automation::*;
+#[cfg(feature = "std")]
+pub use backup::*;
+#[cfg(feature = "std")]
+pub use crash::*;
+#[cfg(feature = "std")]
+pub use event::*;
+#[cfg(feature = "std")]
+pub use machine::*;
+pub use model::*;
+#[cfg(feature = "std")]
+pub use crate::ppu::{GB_H, GB_W};
+#[cfg(feature = "std")]
+pub use movie::*;
+#[cfg(feature = "std")]
+pub use pacing::*;
+#[cfg(feature = "std")]
+pub use profiler::*;
+#[cfg(feature = "std")]
+pub use rewind::*;
+pub use rng::*;
+#[cfg(feature = "std")]
+pub use stats::*;
+#[cfg(feature = "std")]
+pub use trace::*;
+
+#[cfg(test)]
+mod tests;
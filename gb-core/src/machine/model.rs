@@ -0,0 +1,20 @@
+// Modelo de hardware emulado; decide quais quirks valem
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Model {
+    Dmg,
+    Cgb,
+    // DMG dentro do Super Game Boy: paletas e moldura nos jogos com suporte
+    Sgb,
+}
+
+impl Model {
+    // Nomes do --model e dos bindings Python
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "dmg" => Some(Model::Dmg),
+            "cgb" => Some(Model::Cgb),
+            "sgb" => Some(Model::Sgb),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::joypad::{Buttons, JoypadInput};
+use crate::state::{MIN_STATE_VERSION, STATE_MAGIC, StateReader, StateWriter};
+
+// Formato do movie: cabeçalho, save state de partida e dois bytes por frame (botões, turbo)
+pub const MOVIE_MAGIC: &[u8; 4] = b"GBMV";
+pub const MOVIE_VERSION: u16 = 3;
+// Versão 2: sem âncora, título, versão do emulador nem contagem de re-records
+const MOVIE_VERSION_V2: u16 = 2;
+
+// Gravada no movie pra avisar quando a reprodução é feita por outra versão
+pub const EMULATOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// De onde a gravação partiu. O save state de partida vai no movie nos dois casos; a
+// âncora só diz se ele é o power-on ou um ponto qualquer do jogo
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MovieAnchor {
+    PowerOn,
+    SaveState,
+}
+
+// Input gravado frame a frame. Sempre começa de um save state (mesmo os gravados a
+// partir do power-on), então o replay não depende do lixo que estava na RAM.
+pub struct Movie {
+    pub anchor: MovieAnchor,
+    pub rom_crc: u32,
+    // Título do cabeçalho da ROM, só pras mensagens de erro
+    pub rom_title: String,
+    pub emulator_version: String,
+    // Quantas vezes a gravação voltou pra um save state e continuou de lá
+    pub rerecords: u32,
+    pub start_state: Vec<u8>,
+    pub frames: Vec<JoypadInput>,
+}
+
+impl Movie {
+    pub fn new(anchor: MovieAnchor, rom_crc: u32, rom_title: String, start_state: Vec<u8>) -> Self {
+        Self {
+            anchor,
+            rom_crc,
+            rom_title,
+            emulator_version: EMULATOR_VERSION.to_string(),
+            rerecords: 0,
+            start_state,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = StateWriter::new();
+        w.write_bytes(MOVIE_MAGIC);
+        w.write_u16(MOVIE_VERSION);
+        w.write_bool(self.anchor == MovieAnchor::SaveState);
+        w.write_u32(self.rom_crc);
+        write_block(&mut w, self.rom_title.as_bytes());
+        write_block(&mut w, self.emulator_version.as_bytes());
+        w.write_u32(self.rerecords);
+        write_block(&mut w, &self.start_state);
+        w.write_u32(self.frames.len() as u32 * 2);
+        for input in &self.frames {
+            w.write_u8(input.buttons.bits());
+            w.write_u8(input.turbo.bits());
+        }
+        w.into_bytes()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let mut r = StateReader::new(data);
+
+        let mut magic = [0; 4];
+        r.read_bytes(&mut magic)?;
+        if &magic != MOVIE_MAGIC {
+            return Err("arquivo não é um movie".to_string());
+        }
+        let version = r.read_u16()?;
+        if version != MOVIE_VERSION && version != MOVIE_VERSION_V2 {
+            return Err(format!(
+                "versão de movie não suportada: {} (este emulador lê a {} e a {})",
+                version, MOVIE_VERSION_V2, MOVIE_VERSION
+            ));
+        }
+
+        // Os da versão 2 sempre partiam do power-on
+        let mut movie = Self::new(MovieAnchor::PowerOn, 0, String::new(), Vec::new());
+        movie.emulator_version = String::new();
+        if version == MOVIE_VERSION {
+            if r.read_bool()? {
+                movie.anchor = MovieAnchor::SaveState;
+            }
+            movie.rom_crc = r.read_u32()?;
+            movie.rom_title = read_text(&mut r, data.len())?;
+            movie.emulator_version = read_text(&mut r, data.len())?;
+            movie.rerecords = r.read_u32()?;
+        } else {
+            movie.rom_crc = r.read_u32()?;
+        }
+        movie.start_state = read_block(&mut r, data.len())?;
+        let frames = read_block(&mut r, data.len())?;
+        if !r.is_empty() || frames.len() % 2 != 0 {
+            return Err("movie com dados sobrando".to_string());
+        }
+
+        movie.frames = frames
+            .chunks_exact(2)
+            .map(|pair| JoypadInput {
+                buttons: Buttons::from_bits_retain(pair[0]),
+                turbo: Buttons::from_bits_retain(pair[1]),
+            })
+            .collect();
+        Ok(movie)
+    }
+
+    // Confere se o movie reproduz com a ROM carregada e o formato de save state desta
+    // versão. Versão diferente do emulador não impede a reprodução: volta como aviso,
+    // porque uma mudança de timing no meio pode dessincronizar o input
+    pub fn check(&self, rom_crc: u32) -> Result<Option<String>, String> {
+        if self.rom_crc != rom_crc {
+            let title = if self.rom_title.is_empty() {
+                String::new()
+            } else {
+                format!("'{}', ", self.rom_title)
+            };
+            return Err(format!(
+                "movie gravado com outra ROM ({}CRC32 {:08X}); a carregada tem CRC32 {:08X}",
+                title, self.rom_crc, rom_crc
+            ));
+        }
+
+        let recorded_by = if self.emulator_version.is_empty() {
+            "por uma versão antiga".to_string()
+        } else {
+            format!("pela versão {}", self.emulator_version)
+        };
+        let state = &self.start_state;
+        if state.len() < 6 || &state[..4] != STATE_MAGIC {
+            return Err("save state de partida do movie inválido".to_string());
+        }
+        let state_version = u16::from_le_bytes([state[4], state[5]]);
+        if state_version < MIN_STATE_VERSION {
+            return Err(format!(
+                "movie gravado {} do emulador, com save state versão {}; a {} só lê a partir \
+                 da {}",
+                recorded_by, state_version, EMULATOR_VERSION, MIN_STATE_VERSION
+            ));
+        }
+
+        if self.emulator_version == EMULATOR_VERSION {
+            return Ok(None);
+        }
+        Ok(Some(format!(
+            "aviso: movie gravado {} do emulador (esta é a {}); a reprodução pode \
+             dessincronizar",
+            recorded_by, EMULATOR_VERSION
+        )))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|erro| erro.to_string())?;
+        Self::from_bytes(&data)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_bytes()).map_err(|erro| erro.to_string())
+    }
+}
+
+fn write_block(w: &mut StateWriter, bytes: &[u8]) {
+    w.write_u32(bytes.len() as u32);
+    w.write_bytes(bytes);
+}
+
+// Tamanho u32 + bytes; o limite evita alocar gigabytes por causa de um arquivo corrompido
+fn read_block(r: &mut StateReader, limit: usize) -> Result<Vec<u8>, String> {
+    let len = r.read_u32()? as usize;
+    if len > limit {
+        return Err("movie truncado".to_string());
+    }
+    let mut block = vec![0; len];
+    r.read_bytes(&mut block)?;
+    Ok(block)
+}
+
+fn read_text(r: &mut StateReader, limit: usize) -> Result<String, String> {
+    String::from_utf8(read_block(r, limit)?).map_err(|_| "texto inválido no movie".to_string())
+}
+
+// Movie em andamento na thread de emulação
+pub enum MovieSession {
+    // `marks`: hash e frame de cada save state gravado durante a gravação, pro re-record
+    Recording {
+        movie: Movie,
+        path: PathBuf,
+        marks: Vec<(u32, usize)>,
+    },
+    // `frame` = próximo frame a ser reproduzido
+    Playing {
+        movie: Movie,
+        frame: usize,
+    },
+}
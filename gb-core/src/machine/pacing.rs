@@ -0,0 +1,48 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 4194304 Hz / 70224 ciclos por frame ≈ 59.7275 Hz
+pub const FRAME_RATE: f64 = 4_194_304.0 / 70_224.0;
+
+// O sleep do SO erra por ~1 ms; o resto é feito em espera ativa
+const SPIN_MARGIN: Duration = Duration::from_millis(1);
+
+// Atraso máximo (em frames) antes de desistir de recuperar o tempo perdido
+const MAX_LAG_FRAMES: u32 = 4;
+
+// Ritmo da thread de emulação por timer de alta resolução
+pub struct Pacer {
+    frame_time: Duration,
+    deadline: Instant,
+}
+
+impl Pacer {
+    pub fn new() -> Self {
+        Self {
+            frame_time: Duration::from_secs_f64(1.0 / FRAME_RATE),
+            deadline: Instant::now(),
+        }
+    }
+
+    // Espera até o horário do próximo frame
+    pub fn wait(&mut self) {
+        self.deadline += self.frame_time;
+
+        let now = Instant::now();
+        if now > self.deadline {
+            // Muito atrasado (debugger, máquina lenta): não acelera pra compensar
+            if now - self.deadline > self.frame_time * MAX_LAG_FRAMES {
+                self.deadline = now;
+            }
+            return;
+        }
+
+        let remaining = self.deadline - now;
+        if remaining > SPIN_MARGIN {
+            thread::sleep(remaining - SPIN_MARGIN);
+        }
+        while Instant::now() < self.deadline {
+            std::hint::spin_loop();
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::debug::{Location, Symbols, area};
+use crate::machine::CYCLES_PER_FRAME;
+
+// Uma amostra a cada 64 ciclos da CPU (~15 µs de Game Boy)
+const SAMPLE_CYCLES: u64 = 64;
+
+// Linhas de cada tabela do relatório
+const REPORT_FUNCTIONS: usize = 40;
+const REPORT_ADDRESSES: usize = 40;
+
+// Entradas conhecidas desde o início: reset e vetores de interrupção (banco 0)
+const VECTORS: [u16; 6] = [0x0100, 0x0040, 0x0048, 0x0050, 0x0058, 0x0060];
+
+// Profiler por amostragem: a cada SAMPLE_CYCLES ciclos o PC da instrução em execução
+// ganha uma amostra. As "funções" são os destinos de CALL/RST vistos durante a sessão,
+// mais os vetores; cada endereço pertence à entrada mais próxima abaixo dele
+pub struct Profiler {
+    samples: HashMap<Location, u64>,
+    entries: BTreeSet<Location>,
+    // Ciclos desde a última amostra
+    pending: u64,
+    total_cycles: u64,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            samples: HashMap::new(),
+            entries: VECTORS.iter().map(|&addr| (0, addr)).collect(),
+            pending: 0,
+            total_cycles: 0,
+        }
+    }
+
+    // Instrução em `at` (opcode `opcode`) gastou `cycles` e deixou o PC em `next`
+    pub fn record(&mut self, at: Location, opcode: u8, cycles: u64, next: Location) {
+        self.total_cycles += cycles;
+        self.pending += cycles;
+        while self.pending >= SAMPLE_CYCLES {
+            self.pending -= SAMPLE_CYCLES;
+            *self.samples.entry(at).or_default() += 1;
+        }
+
+        // CALL, CALL cc e RST tomados: o PC não caiu na instrução seguinte
+        let call_length = match opcode {
+            0xCD | 0xC4 | 0xCC | 0xD4 | 0xDC => 3,
+            0xC7 | 0xCF | 0xD7 | 0xDF | 0xE7 | 0xEF | 0xF7 | 0xFF => 1,
+            _ => return,
+        };
+        if next.1 != at.1.wrapping_add(call_length) {
+            self.entries.insert(next);
+        }
+    }
+
+    // Entrada conhecida mais próxima abaixo, na mesma área; sem nenhuma, o próprio endereço
+    fn function_of(&self, location: Location) -> Location {
+        self.entries
+            .range(..=location)
+            .next_back()
+            .copied()
+            .filter(|&entry| entry.0 == location.0 && area(entry.1) == area(location.1))
+            .unwrap_or(location)
+    }
+
+    // Com os labels do .sym ao lado dos endereços
+    pub fn report<'a>(&'a self, symbols: &'a Symbols) -> ProfileReport<'a> {
+        ProfileReport {
+            profiler: self,
+            symbols,
+        }
+    }
+}
+
+pub struct ProfileReport<'a> {
+    profiler: &'a Profiler,
+    symbols: &'a Symbols,
+}
+
+// Relatório em texto: por área/banco, por função e os endereços mais quentes
+impl fmt::Display for ProfileReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let profiler = self.profiler;
+        let total: u64 = profiler.samples.values().sum();
+        let row = |f: &mut fmt::Formatter<'_>, samples: u64, label: String| {
+            let percent = samples as f64 * 100.0 / total.max(1) as f64;
+            writeln!(f, "  {:6.2}%  {:>10}  {}", percent, samples, label)
+        };
+
+        writeln!(
+            f,
+            "{} amostras de {} ciclos ({} ciclos executados, {:.1} frames)",
+            total,
+            SAMPLE_CYCLES,
+            profiler.total_cycles,
+            profiler.total_cycles as f64 / CYCLES_PER_FRAME as f64
+        )?;
+
+        let mut areas: HashMap<String, u64> = HashMap::new();
+        let mut functions: HashMap<Location, u64> = HashMap::new();
+        for (&location, &samples) in &profiler.samples {
+            *areas.entry(area_name(location)).or_default() += samples;
+            *functions.entry(profiler.function_of(location)).or_default() += samples;
+        }
+
+        writeln!(f, "\nPor área:")?;
+        for (name, samples) in sorted(areas) {
+            row(f, samples, name)?;
+        }
+
+        writeln!(f, "\nPor função (destino de CALL/RST ou vetor):")?;
+        for (location, samples) in sorted(functions).into_iter().take(REPORT_FUNCTIONS) {
+            row(f, samples, self.format_location(location))?;
+        }
+
+        writeln!(f, "\nEndereços:")?;
+        for (location, samples) in sorted(profiler.samples.clone())
+            .into_iter()
+            .take(REPORT_ADDRESSES)
+        {
+            row(f, samples, self.format_location(location))?;
+        }
+        Ok(())
+    }
+}
+
+impl ProfileReport<'_> {
+    // banco:endereço como no RGBDS (fora da ROM só o endereço), mais o label se houver
+    fn format_location(&self, (bank, addr): Location) -> String {
+        let location = if addr < 0x8000 {
+            format!("{:02X}:{:04X}", bank, addr)
+        } else {
+            format!("{:04X}", addr)
+        };
+        match self.symbols.describe(bank, addr) {
+            Some(name) => format!("{}  {}", location, name),
+            None => location,
+        }
+    }
+}
+
+// Mais amostras primeiro; empates pela chave, pro relatório sair sempre igual
+fn sorted<K: Ord>(counts: HashMap<K, u64>) -> Vec<(K, u64)> {
+    let mut counts: Vec<(K, u64)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+fn area_name((bank, addr): Location) -> String {
+    match area(addr) {
+        0 | 1 => format!("ROM {:02X}", bank),
+        2 => "VRAM".to_string(),
+        3 => "RAM do cartucho".to_string(),
+        4 => "WRAM".to_string(),
+        _ => "HRAM/IO".to_string(),
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::VecDeque;
+
+// Um snapshot a cada INTERVAL frames; voltar um snapshot = INTERVAL frames pra trás
+const INTERVAL: u32 = 2;
+// ~10 segundos de histórico
+const CAPACITY: usize = 300;
+
+pub struct RewindBuffer {
+    states: VecDeque<Vec<u8>>,
+    frames: u32,
+}
+
+impl RewindBuffer {
+    pub fn new() -> Self {
+        Self {
+            states: VecDeque::with_capacity(CAPACITY),
+            frames: 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.states.clear();
+        self.frames = 0;
+    }
+
+    // Chamado a cada frame emulado; `save` só roda quando é hora de guardar
+    pub fn record(&mut self, save: impl FnOnce() -> Vec<u8>) {
+        self.frames += 1;
+        if self.frames < INTERVAL {
+            return;
+        }
+        self.frames = 0;
+
+        if self.states.len() == CAPACITY {
+            self.states.pop_front();
+        }
+        self.states.push_back(save());
+    }
+
+    pub fn pop(&mut self) -> Option<Vec<u8>> {
+        self.states.pop_back()
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::bus::DeviceTimes;
+use crate::machine::FRAME_RATE;
+
+// Janela das médias do overlay
+const STATS_INTERVAL: Duration = Duration::from_secs(1);
+
+// Médias do último segundo de emulação (EmulatorEvent::Stats)
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct EmulationStats {
+    // Frames emulados em relação ao Game Boy real (~59,73 por segundo), em %
+    pub speed: u32,
+    // Contando os frames especulativos do run-ahead
+    pub instructions_per_second: u64,
+    pub cycles_per_second: u64,
+    // Tempo de host por frame, em microssegundos. O que sobra do frame além de CPU, PPU e
+    // APU é timer, serial, DMA, cartucho e o próprio laço
+    pub frame_us: u64,
+    pub cpu_us: u64,
+    pub ppu_us: u64,
+    pub apu_us: u64,
+}
+
+// Relógio dos DeviceTimes: nanossegundos desde a primeira chamada
+pub fn clock_nanos() -> u64 {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
+// Contadores somados a cada frame até fechar a janela
+pub struct StatsCollector {
+    started: Instant,
+    frames: u32,
+    instructions: u64,
+    cycles: u64,
+    emulation: Duration,
+    cpu: Duration,
+}
+
+impl StatsCollector {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            frames: 0,
+            instructions: 0,
+            cycles: 0,
+            emulation: Duration::ZERO,
+            cpu: Duration::ZERO,
+        }
+    }
+
+    // Trabalho de um run_frame (real ou especulativo)
+    pub fn add_work(&mut self, instructions: u64, cycles: u64, total: Duration, cpu: Duration) {
+        self.instructions += instructions;
+        self.cycles += cycles;
+        self.emulation += total;
+        self.cpu += cpu;
+    }
+
+    // Fecha um frame mostrado; a cada STATS_INTERVAL devolve as médias e recomeça
+    pub fn end_frame(&mut self, devices: Option<&mut DeviceTimes>) -> Option<EmulationStats> {
+        self.frames += 1;
+        let elapsed = self.started.elapsed();
+        if elapsed < STATS_INTERVAL {
+            return None;
+        }
+
+        let seconds = elapsed.as_secs_f64();
+        let frames = self.frames as u64;
+        let (ppu, apu) = devices.map_or((0, 0), |devices| {
+            let spent = (devices.ppu(), devices.apu());
+            devices.clear();
+            spent
+        });
+        let stats = EmulationStats {
+            speed: (self.frames as f64 / seconds / FRAME_RATE * 100.0).round() as u32,
+            instructions_per_second: (self.instructions as f64 / seconds) as u64,
+            cycles_per_second: (self.cycles as f64 / seconds) as u64,
+            frame_us: self.emulation.as_micros() as u64 / frames,
+            cpu_us: self.cpu.as_micros() as u64 / frames,
+            ppu_us: ppu / 1000 / frames,
+            apu_us: apu / 1000 / frames,
+        };
+        *self = Self::new();
+        Some(stats)
+    }
+}
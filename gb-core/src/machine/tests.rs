@@ -0,0 +1,143 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+use crate::cartridge::Cartridge;
+use crate::machine::{
+    Boundary, Emulator, EmulatorEvent, ExitConditions, ResetKind, Yielded, backup_path,
+    write_rotated,
+};
+
+#[test]
+fn gravacao_roda_as_copias_e_descarta_a_mais_velha() {
+    let dir = std::env::temp_dir().join(format!("gb-backup-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("jogo.sav");
+
+    for data in [b"a", b"b", b"c", b"d"] {
+        write_rotated(&path, data, 2).unwrap();
+    }
+    assert_eq!(fs::read(&path).unwrap(), b"d");
+    assert_eq!(fs::read(backup_path(&path, 1)).unwrap(), b"c");
+    assert_eq!(fs::read(backup_path(&path, 2)).unwrap(), b"b");
+    assert!(!backup_path(&path, 3).exists());
+    assert!(!dir.join("jogo.sav.tmp").exists());
+
+    // Sem cópias: só troca o arquivo
+    write_rotated(&path, b"e", 0).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"e");
+    assert_eq!(fs::read(backup_path(&path, 1)).unwrap(), b"c");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+fn emulator() -> Emulator {
+    let mut emulator = Emulator::new(Cartridge::load(vec![0; 0x8000]).unwrap());
+    emulator.seed = Some(1);
+    emulator.reset(ResetKind::Hard);
+    emulator
+}
+
+#[test]
+fn step_cycles_roda_pelo_menos_o_pedido() {
+    let mut emulator = emulator();
+    let done = emulator.step_cycles(1000);
+    assert!((1000..1024).contains(&done));
+    assert_eq!(emulator.step_cycles(0), 0);
+}
+
+#[test]
+fn steps_devolve_o_controle_em_cada_linha_e_frame() {
+    let mut emulator = emulator();
+    // LCD ligado, senão o LY fica parado no 0
+    emulator.bus.write(0xFF40, 0x91);
+    let lines: BTreeSet<u8> = emulator
+        .steps(Boundary::Scanline)
+        .take(154)
+        .map(|yielded| match yielded {
+            Yielded::Scanline(line) => line,
+            other => panic!("esperado Scanline, veio {:?}", other),
+        })
+        .collect();
+    assert_eq!(lines, (0..154).collect());
+
+    emulator.drain_events();
+    assert_eq!(emulator.steps(Boundary::Frame).next(), Some(Yielded::Frame));
+    assert!(
+        emulator
+            .drain_events()
+            .any(|event| matches!(event, EmulatorEvent::FrameReady))
+    );
+
+    // Só NOPs: o PC anda um byte por instrução
+    emulator
+        .breakpoints
+        .insert(emulator.cpu.program_counter + 4);
+    assert_eq!(
+        emulator.steps(Boundary::Frame).next(),
+        Some(Yielded::Stopped)
+    );
+}
+
+#[test]
+fn callbacks_rodam_na_linha_e_no_frame_ate_sair() {
+    let mut emulator = emulator();
+    emulator.bus.write(0xFF40, 0x91);
+    let calls = Arc::new(Mutex::new(Vec::new()));
+
+    let log = calls.clone();
+    let line = emulator.register_scanline_callback(10, move |emulator| {
+        log.lock()
+            .unwrap()
+            .push(("linha", emulator.bus.peek(0xFF44)));
+    });
+    let log = calls.clone();
+    let frame = emulator.register_frame_callback(move |emulator| {
+        log.lock()
+            .unwrap()
+            .push(("frame", emulator.bus.peek(0xFF44)));
+    });
+
+    for _ in emulator.steps(Boundary::Frame).take(2) {}
+    assert_eq!(
+        *calls.lock().unwrap(),
+        [("linha", 10), ("frame", 144), ("linha", 10), ("frame", 144)]
+    );
+
+    assert!(emulator.unregister_callback(line));
+    assert!(emulator.unregister_callback(frame));
+    assert!(!emulator.unregister_callback(frame));
+    emulator.step_frame();
+    assert_eq!(calls.lock().unwrap().len(), 4);
+}
+
+// MBC3 com RTC e bateria: sem --rtc-epoch o rodapé do .sav levaria a hora real
+fn battery_run(dir: &std::path::Path) -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+    rom[0x147] = 0x10;
+    rom[0x149] = 0x02;
+    let mut emulator = Emulator::new(Cartridge::load(rom).unwrap());
+    emulator.seed = Some(1);
+    emulator.rtc_epoch = Some(0);
+    emulator.sav_path = Some(dir.join("jogo.sav"));
+    emulator.set_exit_conditions(ExitConditions {
+        frames: Some(30),
+        ..Default::default()
+    });
+    emulator.run_headless();
+    fs::read(dir.join("jogo.sav")).unwrap()
+}
+
+#[test]
+fn bateria_com_rtc_igual_em_duas_execucoes_deterministicas() {
+    let dir = std::env::temp_dir().join(format!("gb-rtc-epoch-{}", std::process::id()));
+    let (first, second) = (dir.join("a"), dir.join("b"));
+    fs::create_dir_all(&first).unwrap();
+    fs::create_dir_all(&second).unwrap();
+
+    let data = battery_run(&first);
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    assert_eq!(data, battery_run(&second));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,36 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::debug::{HistoryEntry, Symbols};
+
+// Log de execução: uma linha por instrução, antes de executar, com banco:PC (e o label
+// do .sym quando houver), registradores e o disassembly
+pub struct Trace {
+    path: PathBuf,
+    out: BufWriter<File>,
+}
+
+impl Trace {
+    pub fn create(path: &Path) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|erro| format!("erro ao criar '{}': {}", path.display(), erro))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            out: BufWriter::new(file),
+        })
+    }
+
+    pub fn record(&mut self, entry: &HistoryEntry, symbols: &Symbols) -> Result<(), String> {
+        writeln!(self.out, "{}", entry.format(symbols)).map_err(|erro| self.write_error(erro))
+    }
+
+    pub fn finish(mut self) -> Result<PathBuf, String> {
+        self.out.flush().map_err(|erro| self.write_error(erro))?;
+        Ok(self.path)
+    }
+
+    fn write_error(&self, erro: std::io::Error) -> String {
+        format!("erro ao gravar '{}': {}", self.path.display(), erro)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod netplay;
+pub mod protocol;
+
+pub use netplay::*;
+pub use protocol::*;
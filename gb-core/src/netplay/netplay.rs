@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::joypad::{Buttons, JoypadInput};
+use crate::netplay::Message;
+
+// A cada quantos frames os peers comparam o hash do estado
+const HASH_INTERVAL: u32 = 60;
+// Quanto esperar pelo input do peer antes de devolver o controle pro loop de emulação
+const INPUT_WAIT: Duration = Duration::from_millis(100);
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Role {
+    Host,
+    Client,
+}
+
+// Lockstep: os dois lados rodam a mesma máquina e só avançam o frame F quando têm o input
+// dos dois pra F (os botões são combinados). O input local vale `delay` frames depois de
+// lido, o que esconde a latência da rede. O host é a referência: num desync ele manda o
+// save state dele e o client carrega.
+pub struct Netplay {
+    role: Role,
+    stream: TcpStream,
+    incoming: Receiver<Result<Message, String>>,
+    delay: u32,
+    // Próximo frame a rodar
+    frame: u32,
+    // Próximo frame cujo input local ainda não foi enviado
+    next_send: u32,
+    local: HashMap<u32, JoypadInput>,
+    remote: HashMap<u32, JoypadInput>,
+    generation: u8,
+    hashes: HashMap<u32, u32>,
+    remote_hashes: HashMap<u32, u32>,
+    // Host: precisa mandar o state; client: esperando o state do host
+    desynced: bool,
+    pending_state: Option<(u8, u32, Vec<u8>)>,
+}
+
+impl Netplay {
+    // Bloqueia até um peer conectar
+    pub fn host(port: u16, delay: u8, rom_crc: u32) -> Result<Self, String> {
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .map_err(|erro| format!("netplay: erro ao abrir a porta {}: {}", port, erro))?;
+        let (mut stream, _) = listener
+            .accept()
+            .map_err(|erro| format!("netplay: {}", erro))?;
+
+        Message::Hello { rom_crc, delay }
+            .write_to(&mut stream)
+            .map_err(|erro| format!("netplay: {}", erro))?;
+        Self::new(Role::Host, stream, delay)
+    }
+
+    pub fn connect(addr: &str, rom_crc: u32) -> Result<Self, String> {
+        let mut stream = TcpStream::connect(addr)
+            .map_err(|erro| format!("netplay: erro ao conectar em '{}': {}", addr, erro))?;
+
+        match Message::read_from(&mut stream) {
+            Ok(Message::Hello {
+                rom_crc: host_crc,
+                delay,
+            }) => {
+                if host_crc != rom_crc {
+                    return Err("netplay: o host está com outra ROM".to_string());
+                }
+                Self::new(Role::Client, stream, delay)
+            }
+            Ok(_) => Err("netplay: handshake inválido".to_string()),
+            Err(erro) => Err(format!("netplay: {}", erro)),
+        }
+    }
+
+    fn new(role: Role, stream: TcpStream, delay: u8) -> Result<Self, String> {
+        stream
+            .set_nodelay(true)
+            .map_err(|erro| format!("netplay: {}", erro))?;
+        let reader = stream
+            .try_clone()
+            .map_err(|erro| format!("netplay: {}", erro))?;
+
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            loop {
+                let message = Message::read_from(&mut reader)
+                    .map_err(|erro| format!("netplay: conexão perdida: {}", erro));
+                let failed = message.is_err();
+                if sender.send(message).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        // Ninguém tem input pros primeiros `delay` frames
+        let delay = delay as u32;
+        let empty: HashMap<u32, JoypadInput> = (0..delay)
+            .map(|frame| (frame, JoypadInput::default()))
+            .collect();
+
+        Ok(Self {
+            role,
+            stream,
+            incoming,
+            delay,
+            frame: 0,
+            next_send: delay,
+            local: empty.clone(),
+            remote: empty,
+            generation: 0,
+            hashes: HashMap::new(),
+            remote_hashes: HashMap::new(),
+            // Os dois começam pela transferência do state do host
+            desynced: true,
+            pending_state: None,
+        })
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    // Host com desync (ou no começo): o emulador deve chamar send_state
+    pub fn needs_state(&self) -> bool {
+        self.role == Role::Host && self.desynced
+    }
+
+    pub fn send_state(&mut self, data: Vec<u8>) -> Result<(), String> {
+        self.generation = self.generation.wrapping_add(1);
+        self.hashes.clear();
+        self.remote_hashes.clear();
+        self.desynced = false;
+
+        self.send(Message::State {
+            generation: self.generation,
+            frame: self.frame,
+            data,
+        })
+    }
+
+    // Client: state do host a carregar; depois de carregado o frame atual passa a ser o dele
+    pub fn take_state(&mut self) -> Option<Vec<u8>> {
+        let (generation, frame, data) = self.pending_state.take()?;
+
+        self.generation = generation;
+        self.frame = frame;
+        self.next_send = self.next_send.max(frame);
+        self.local.retain(|&f, _| f >= frame);
+        self.remote.retain(|&f, _| f >= frame);
+        self.hashes.clear();
+        self.remote_hashes.clear();
+        self.desynced = false;
+        Some(data)
+    }
+
+    // Input combinado do frame atual, ou None se o do peer ainda não chegou
+    pub fn input(&mut self, local: JoypadInput) -> Result<Option<JoypadInput>, String> {
+        while self.next_send <= self.frame + self.delay {
+            let frame = self.next_send;
+            self.local.insert(frame, local);
+            self.send(Message::Input {
+                frame,
+                buttons: local.buttons.bits(),
+                turbo: local.turbo.bits(),
+            })?;
+            self.next_send += 1;
+        }
+
+        self.receive(false)?;
+        if self.role == Role::Client && self.desynced {
+            self.receive(true)?;
+            return Ok(None);
+        }
+        if !self.remote.contains_key(&self.frame) {
+            self.receive(true)?;
+        }
+
+        match (self.local.get(&self.frame), self.remote.get(&self.frame)) {
+            (Some(&local), Some(&remote)) => Ok(Some(local | remote)),
+            _ => Ok(None),
+        }
+    }
+
+    // Depois de rodar o frame; `state_hash` só é chamado nos frames de checagem
+    pub fn end_frame(&mut self, state_hash: impl FnOnce() -> u32) -> Result<(), String> {
+        self.local.remove(&self.frame);
+        self.remote.remove(&self.frame);
+        self.frame += 1;
+
+        if self.frame.is_multiple_of(HASH_INTERVAL) {
+            let hash = state_hash();
+            self.hashes.insert(self.frame, hash);
+            self.send(Message::Hash {
+                generation: self.generation,
+                frame: self.frame,
+                hash,
+            })?;
+            self.compare_hashes();
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, message: Message) -> Result<(), String> {
+        message
+            .write_to(&mut self.stream)
+            .map_err(|erro| format!("netplay: conexão perdida: {}", erro))
+    }
+
+    // Processa o que chegou; com `wait`, espera pela primeira mensagem (até INPUT_WAIT)
+    fn receive(&mut self, wait: bool) -> Result<(), String> {
+        if wait {
+            match self.incoming.recv_timeout(INPUT_WAIT) {
+                Ok(message) => self.handle(message?),
+                Err(RecvTimeoutError::Timeout) => return Ok(()),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err("netplay: conexão perdida".to_string());
+                }
+            }
+        }
+
+        while let Ok(message) = self.incoming.try_recv() {
+            self.handle(message?);
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, message: Message) {
+        match message {
+            Message::Hello { .. } => {}
+            Message::Input {
+                frame,
+                buttons,
+                turbo,
+            } => {
+                let input = JoypadInput {
+                    buttons: Buttons::from_bits_retain(buttons),
+                    turbo: Buttons::from_bits_retain(turbo),
+                };
+                self.remote.insert(frame, input);
+            }
+            Message::Hash {
+                generation,
+                frame,
+                hash,
+            } => {
+                // Hash de antes da última ressincronização não vale mais
+                if generation == self.generation {
+                    self.remote_hashes.insert(frame, hash);
+                    self.compare_hashes();
+                }
+            }
+            Message::State {
+                generation,
+                frame,
+                data,
+            } => self.pending_state = Some((generation, frame, data)),
+        }
+    }
+
+    // Os dois lados detectam o mesmo desync: o host manda o state, o client para e espera
+    fn compare_hashes(&mut self) {
+        let frames: Vec<u32> = self
+            .remote_hashes
+            .keys()
+            .filter(|frame| self.hashes.contains_key(frame))
+            .copied()
+            .collect();
+
+        for frame in frames {
+            if self.hashes.remove(&frame) != self.remote_hashes.remove(&frame) {
+                self.desynced = true;
+            }
+        }
+    }
+}
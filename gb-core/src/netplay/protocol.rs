@@ -0,0 +1,131 @@
+use std::io::{self, Read, Write};
+
+// Maior save state aceito do peer (os nossos têm poucas dezenas de KB)
+const MAX_STATE_SIZE: usize = 4 * 1024 * 1024;
+
+// Mensagens do netplay. `generation` conta as ressincronizações: hashes calculados antes
+// do último state recebido são ignorados.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Message {
+    // Primeira mensagem do host
+    Hello {
+        rom_crc: u32,
+        delay: u8,
+    },
+    Input {
+        frame: u32,
+        buttons: u8,
+        turbo: u8,
+    },
+    Hash {
+        generation: u8,
+        frame: u32,
+        hash: u32,
+    },
+    State {
+        generation: u8,
+        frame: u32,
+        data: Vec<u8>,
+    },
+}
+
+const TAG_HELLO: u8 = 0;
+const TAG_INPUT: u8 = 1;
+const TAG_HASH: u8 = 2;
+const TAG_STATE: u8 = 3;
+
+impl Message {
+    pub fn write_to(&self, w: &mut impl Write) -> io::Result<()> {
+        let mut buf = Vec::new();
+        match self {
+            Message::Hello { rom_crc, delay } => {
+                buf.push(TAG_HELLO);
+                buf.extend_from_slice(&rom_crc.to_le_bytes());
+                buf.push(*delay);
+            }
+            Message::Input {
+                frame,
+                buttons,
+                turbo,
+            } => {
+                buf.push(TAG_INPUT);
+                buf.extend_from_slice(&frame.to_le_bytes());
+                buf.push(*buttons);
+                buf.push(*turbo);
+            }
+            Message::Hash {
+                generation,
+                frame,
+                hash,
+            } => {
+                buf.push(TAG_HASH);
+                buf.push(*generation);
+                buf.extend_from_slice(&frame.to_le_bytes());
+                buf.extend_from_slice(&hash.to_le_bytes());
+            }
+            Message::State {
+                generation,
+                frame,
+                data,
+            } => {
+                buf.push(TAG_STATE);
+                buf.push(*generation);
+                buf.extend_from_slice(&frame.to_le_bytes());
+                buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                buf.extend_from_slice(data);
+            }
+        }
+        w.write_all(&buf)
+    }
+
+    pub fn read_from(r: &mut impl Read) -> io::Result<Self> {
+        match read_u8(r)? {
+            TAG_HELLO => Ok(Message::Hello {
+                rom_crc: read_u32(r)?,
+                delay: read_u8(r)?,
+            }),
+            TAG_INPUT => Ok(Message::Input {
+                frame: read_u32(r)?,
+                buttons: read_u8(r)?,
+                turbo: read_u8(r)?,
+            }),
+            TAG_HASH => Ok(Message::Hash {
+                generation: read_u8(r)?,
+                frame: read_u32(r)?,
+                hash: read_u32(r)?,
+            }),
+            TAG_STATE => {
+                let generation = read_u8(r)?;
+                let frame = read_u32(r)?;
+                let len = read_u32(r)? as usize;
+                if len > MAX_STATE_SIZE {
+                    return Err(invalid(format!("state grande demais: {} bytes", len)));
+                }
+                let mut data = vec![0; len];
+                r.read_exact(&mut data)?;
+                Ok(Message::State {
+                    generation,
+                    frame,
+                    data,
+                })
+            }
+            tag => Err(invalid(format!("mensagem desconhecida: {}", tag))),
+        }
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_u8(r: &mut impl Read) -> io::Result<u8> {
+    let mut bytes = [0; 1];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+fn read_u32(r: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    r.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
@@ -0,0 +1,156 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::png;
+
+// BPS: ações sobre a ROM original com CRC32 da origem, do resultado e do próprio patch
+const HEADER: &[u8] = b"BPS1";
+const FOOTER_SIZE: usize = 12;
+
+const SOURCE_READ: u64 = 0;
+const TARGET_READ: u64 = 1;
+const SOURCE_COPY: u64 = 2;
+const TARGET_COPY: u64 = 3;
+
+struct PatchReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PatchReader<'a> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self.data.get(self.pos).ok_or("patch BPS truncado")?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or("patch BPS truncado")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    // Número de tamanho variável: 7 bits por byte, bit 7 marca o último
+    fn number(&mut self) -> Result<u64, String> {
+        let mut value: u64 = 0;
+        let mut shift: u64 = 1;
+        loop {
+            let byte = self.byte()?;
+            value = value
+                .checked_add((byte & 0x7F) as u64 * shift)
+                .ok_or("número inválido no patch BPS")?;
+            if byte & 0x80 != 0 {
+                return Ok(value);
+            }
+            shift = shift.checked_shl(7).ok_or("número inválido no patch BPS")?;
+            value += shift;
+        }
+    }
+
+    // Deslocamento relativo: bit 0 é o sinal
+    fn offset(&mut self) -> Result<i64, String> {
+        let value = self.number()?;
+        let magnitude = (value >> 1) as i64;
+        Ok(if value & 1 != 0 {
+            -magnitude
+        } else {
+            magnitude
+        })
+    }
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+fn moved(position: usize, delta: i64, limit: usize) -> Result<usize, String> {
+    let position = position as i64 + delta;
+    if position < 0 || position as usize > limit {
+        return Err("cópia fora dos limites no patch BPS".to_string());
+    }
+    Ok(position as usize)
+}
+
+pub fn apply_bps(source: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    if patch.len() < HEADER.len() + FOOTER_SIZE {
+        return Err("patch BPS truncado".to_string());
+    }
+    let footer = patch.len() - FOOTER_SIZE;
+    if png::crc32(&patch[..patch.len() - 4]) != u32_at(patch, footer + 8) {
+        return Err("CRC do patch BPS não confere".to_string());
+    }
+    if png::crc32(source) != u32_at(patch, footer) {
+        return Err("patch BPS feito pra outra ROM".to_string());
+    }
+
+    let mut reader = PatchReader {
+        data: &patch[..footer],
+        pos: HEADER.len(),
+    };
+    let source_size = reader.number()? as usize;
+    let target_size = reader.number()? as usize;
+    let metadata_size = reader.number()? as usize;
+    reader.bytes(metadata_size)?;
+    if source_size != source.len() {
+        return Err("patch BPS feito pra outra ROM".to_string());
+    }
+
+    let mut target: Vec<u8> = Vec::with_capacity(target_size);
+    let mut source_pos = 0;
+    let mut target_pos = 0;
+
+    while reader.pos < reader.data.len() {
+        let action = reader.number()?;
+        let len = (action >> 2) as usize + 1;
+        if target.len() + len > target_size {
+            return Err("patch BPS escreve além do tamanho final".to_string());
+        }
+
+        match action & 3 {
+            SOURCE_READ => {
+                let start = target.len();
+                let bytes = source
+                    .get(start..start + len)
+                    .ok_or("leitura fora da ROM no patch BPS")?;
+                target.extend_from_slice(bytes);
+            }
+            TARGET_READ => target.extend_from_slice(reader.bytes(len)?),
+            SOURCE_COPY => {
+                source_pos = moved(source_pos, reader.offset()?, source.len())?;
+                let bytes = source
+                    .get(source_pos..source_pos + len)
+                    .ok_or("cópia fora da ROM no patch BPS")?;
+                target.extend_from_slice(bytes);
+                source_pos += len;
+            }
+            TARGET_COPY => {
+                target_pos = moved(target_pos, reader.offset()?, target.len())?;
+                if target_pos >= target.len() {
+                    return Err("cópia fora dos limites no patch BPS".to_string());
+                }
+                // Pode sobrepor o que está sendo escrito (funciona como RLE)
+                for _ in 0..len {
+                    target.push(target[target_pos]);
+                    target_pos += 1;
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    if target.len() != target_size {
+        return Err("patch BPS não gerou o tamanho esperado".to_string());
+    }
+    if png::crc32(&target) != u32_at(patch, footer + 4) {
+        return Err("CRC da ROM com patch não confere".to_string());
+    }
+    Ok(target)
+}
@@ -0,0 +1,55 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+// IPS: registros (offset de 24 bits, tamanho de 16) até "EOF"; tamanho 0 é um RLE
+const HEADER: &[u8] = b"PATCH";
+const FOOTER: &[u8] = b"EOF";
+
+fn take<'a>(patch: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    let bytes = patch
+        .get(*pos..*pos + len)
+        .ok_or_else(|| "patch IPS truncado".to_string())?;
+    *pos += len;
+    Ok(bytes)
+}
+
+fn be(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .fold(0, |value, &byte| (value << 8) | byte as usize)
+}
+
+pub fn apply_ips(mut rom: Vec<u8>, patch: &[u8]) -> Result<Vec<u8>, String> {
+    let mut pos = HEADER.len();
+
+    loop {
+        let offset = take(patch, &mut pos, 3)?;
+        if offset == FOOTER {
+            break;
+        }
+        let offset = be(offset);
+        let size = be(take(patch, &mut pos, 2)?);
+
+        let data = if size == 0 {
+            let count = be(take(patch, &mut pos, 2)?);
+            let value = take(patch, &mut pos, 1)?[0];
+            vec![value; count]
+        } else {
+            take(patch, &mut pos, size)?.to_vec()
+        };
+
+        // Registros além do fim aumentam a ROM
+        let end = offset + data.len();
+        if end > rom.len() {
+            rom.resize(end, 0);
+        }
+        rom[offset..end].copy_from_slice(&data);
+    }
+
+    // Extensão comum: 3 bytes depois do "EOF" com o tamanho final da ROM
+    if let Ok(size) = take(patch, &mut pos, 3) {
+        rom.truncate(be(size));
+    }
+    Ok(rom)
+}
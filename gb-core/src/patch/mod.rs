@@ -0,0 +1,7 @@
+pub mod bps;
+pub mod ips;
+#[cfg(feature = "std")]
+pub mod patch;
+
+#[cfg(feature = "std")]
+pub use patch::*;
@@ -0,0 +1,20 @@
+use std::fs;
+use std::path::Path;
+
+use super::bps::apply_bps;
+use super::ips::apply_ips;
+
+// Formato reconhecido pelo magic, não pela extensão
+pub fn apply_patch(rom: Vec<u8>, path: &Path) -> Result<Vec<u8>, String> {
+    let patch = fs::read(path)
+        .map_err(|erro| format!("Error ao ler o arquivo '{}': {}", path.display(), erro))?;
+
+    let result = if patch.starts_with(b"PATCH") {
+        apply_ips(rom, &patch)
+    } else if patch.starts_with(b"BPS1") {
+        apply_bps(&rom, &patch)
+    } else {
+        Err("formato de patch desconhecido (esperado IPS ou BPS)".to_string())
+    };
+    result.map_err(|erro| format!("'{}': {}", path.display(), erro))
+}
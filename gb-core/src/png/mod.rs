@@ -0,0 +1,3 @@
+pub mod png;
+
+pub use png::*;
@@ -0,0 +1,205 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+// Encoder PNG mínimo (RGBA 8 bits, deflate sem compressão) pra screenshots
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const MAX_STORED_BLOCK: usize = 0xFFFF;
+
+// Também usado como hash da ROM (movies)
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+pub fn encode_png(width: usize, height: usize, rgba: &[u8]) -> Vec<u8> {
+    // Cada linha começa com o byte de filtro (0 = nenhum)
+    let mut raw = Vec::with_capacity(height * (width * 4 + 1));
+    for row in rgba.chunks_exact(width * 4).take(height) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    // zlib: cabeçalho + blocos "stored" + adler32
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(MAX_STORED_BLOCK).peekable();
+    while let Some(block) = blocks.next() {
+        zlib.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // 8 bits por canal, RGBA, deflate, filtro padrão, sem interlace
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut out = SIGNATURE.to_vec();
+    chunk(&mut out, b"IHDR", &header);
+    chunk(&mut out, b"IDAT", &zlib);
+    chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+#[cfg(feature = "std")]
+pub fn write_png(path: &Path, width: usize, height: usize, rgba: &[u8]) -> io::Result<()> {
+    fs::write(path, encode_png(width, height, rgba))
+}
+
+// Decoder PNG mínimo (8 bits por canal, sem interlace) pra imagens de entrada; devolve
+// largura, altura e pixels RGBA
+pub fn decode_png(data: &[u8]) -> Result<(usize, usize, Vec<u8>), String> {
+    if !data.starts_with(&SIGNATURE) {
+        return Err("não é um PNG".to_string());
+    }
+
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut zlib = Vec::new();
+    let mut pos = SIGNATURE.len();
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        let kind = &data[pos + 4..pos + 8];
+        let body = data.get(pos + 8..pos + 8 + len).ok_or("PNG truncado")?;
+        match kind {
+            b"IHDR" if len >= 13 => header = Some(body),
+            b"PLTE" => palette = body,
+            b"IDAT" => zlib.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+
+    let header = header.ok_or("PNG sem IHDR")?;
+    let width = u32::from_be_bytes(header[0..4].try_into().unwrap()) as usize;
+    let height = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+    let (depth, color_type, interlace) = (header[8], header[9], header[12]);
+    let channels = match color_type {
+        0 | 3 => 1,
+        4 => 2,
+        2 => 3,
+        6 => 4,
+        _ => return Err(format!("tipo de cor PNG inválido: {}", color_type)),
+    };
+    if depth != 8 || interlace != 0 {
+        return Err("só PNG de 8 bits por canal sem interlace".to_string());
+    }
+
+    // zlib: pula o cabeçalho de 2 bytes; o adler32 no fim fica sem checar
+    let raw = crate::archive::inflate::inflate(zlib.get(2..).ok_or("PNG sem dados")?)?;
+    let stride = width * channels;
+    if raw.len() < height * (stride + 1) {
+        return Err("PNG com dados a menos".to_string());
+    }
+
+    let mut pixels = vec![0u8; height * stride];
+    for y in 0..height {
+        let filter = raw[y * (stride + 1)];
+        let line = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        for x in 0..stride {
+            let a = if x >= channels {
+                pixels[y * stride + x - channels]
+            } else {
+                0
+            };
+            let b = if y > 0 {
+                pixels[(y - 1) * stride + x]
+            } else {
+                0
+            };
+            let c = if x >= channels && y > 0 {
+                pixels[(y - 1) * stride + x - channels]
+            } else {
+                0
+            };
+            let predictor = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return Err(format!("filtro PNG inválido: {}", filter)),
+            };
+            pixels[y * stride + x] = line[x].wrapping_add(predictor);
+        }
+    }
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for pixel in pixels.chunks_exact(channels) {
+        match color_type {
+            0 => rgba.extend_from_slice(&[pixel[0], pixel[0], pixel[0], 0xFF]),
+            4 => rgba.extend_from_slice(&[pixel[0], pixel[0], pixel[0], pixel[1]]),
+            2 => rgba.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 0xFF]),
+            6 => rgba.extend_from_slice(pixel),
+            _ => {
+                let entry = palette
+                    .get(pixel[0] as usize * 3..pixel[0] as usize * 3 + 3)
+                    .ok_or("índice fora da paleta do PNG")?;
+                rgba.extend_from_slice(&[entry[0], entry[1], entry[2], 0xFF]);
+            }
+        }
+    }
+    Ok((width, height, rgba))
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn read_png(path: &Path) -> Result<(usize, usize, Vec<u8>), String> {
+    let data = fs::read(path).map_err(|e| format!("erro ao ler '{}': {}", path.display(), e))?;
+    decode_png(&data).map_err(|e| format!("'{}': {}", path.display(), e))
+}
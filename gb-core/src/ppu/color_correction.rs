@@ -0,0 +1,128 @@
+use alloc::format;
+use alloc::string::String;
+
+// Gamma aceita no [video] gamma
+pub const MIN_GAMMA: f32 = 0.5;
+pub const MAX_GAMMA: f32 = 3.0;
+
+// Como as cores RGB888 da saída chegam na tela
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ColorCorrection {
+    // Como saem da paleta
+    Off,
+    // Cortadas pros 5 bits por canal do CGB, sem correção
+    Rgb555,
+    // RGB555 passando pela matriz do LCD do CGB, que mistura os canais e tira a saturação
+    // que as cores cruas têm num monitor atual
+    CgbLcd,
+}
+
+impl ColorCorrection {
+    // Valor do [video] color_correction
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "off" => Ok(Self::Off),
+            "rgb555" => Ok(Self::Rgb555),
+            "cgb" => Ok(Self::CgbLcd),
+            other => Err(format!(
+                "correção de cor desconhecida: '{}' (off, rgb555, cgb)",
+                other
+            )),
+        }
+    }
+}
+
+// Correção de cor e gamma aplicadas na conversão do framebuffer pro frontend
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ColorTransform {
+    correction: ColorCorrection,
+    // Canal (0-255) depois do gamma
+    gamma: [u8; 256],
+}
+
+impl ColorTransform {
+    // gamma 1.0 deixa os tons como estão; acima disso os meios-tons clareiam
+    pub fn new(correction: ColorCorrection, gamma: f32) -> Self {
+        let exponent = 1.0 / gamma.clamp(MIN_GAMMA, MAX_GAMMA) as f64;
+        let mut table = [0; 256];
+        for (value, out) in table.iter_mut().enumerate() {
+            let level = powf(value as f64 / 255.0, exponent);
+            *out = (level * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+        }
+        Self {
+            correction,
+            gamma: table,
+        }
+    }
+
+    pub fn apply(&self, [r, g, b]: [u8; 3]) -> [u8; 3] {
+        let (r5, g5, b5) = (r as u32 >> 3, g as u32 >> 3, b as u32 >> 3);
+        let expand = |c: u32| ((c << 3) | (c >> 2)) as u8;
+        let rgb = match self.correction {
+            ColorCorrection::Off => [r, g, b],
+            ColorCorrection::Rgb555 => [expand(r5), expand(g5), expand(b5)],
+            // Matriz do Gambatte, com entrada de 5 bits e saída de 8 (31, 31, 31 -> 248)
+            ColorCorrection::CgbLcd => [
+                ((r5 * 13 + g5 * 2 + b5) >> 1) as u8,
+                ((g5 * 3 + b5) << 1) as u8,
+                ((r5 * 3 + g5 * 2 + b5 * 11) >> 1) as u8,
+            ],
+        };
+        rgb.map(|channel| self.gamma[channel as usize])
+    }
+
+    // RGBA já renderizado (o quadro do SGB), no lugar
+    pub fn apply_rgba(&self, rgba: &mut [u8]) {
+        for pixel in rgba.chunks_exact_mut(4) {
+            let [r, g, b] = self.apply([pixel[0], pixel[1], pixel[2]]);
+            pixel[..3].copy_from_slice(&[r, g, b]);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn powf(base: f64, exponent: f64) -> f64 {
+    base.powf(exponent)
+}
+
+// Sem std não há libm: base em 0-1 reduzida pra [0.5, 1) pro ln por atanh e exp por
+// Taylor depois de tirar as potências de 2. Só monta a tabela do gamma, uma vez
+#[cfg(not(feature = "std"))]
+fn powf(base: f64, exponent: f64) -> f64 {
+    const LN_2: f64 = core::f64::consts::LN_2;
+    if base <= 0.0 {
+        return 0.0;
+    }
+
+    let (mut mantissa, mut halvings) = (base, 0);
+    while mantissa < 0.5 {
+        mantissa *= 2.0;
+        halvings += 1;
+    }
+    let z = (mantissa - 1.0) / (mantissa + 1.0);
+    let mut ln = 0.0;
+    let mut term = z;
+    for n in 0..12 {
+        ln += term / (2 * n + 1) as f64;
+        term *= z * z;
+    }
+    let y = exponent * (2.0 * ln - halvings as f64 * LN_2);
+
+    // y <= 0: exp(y) = exp(r) / 2^k com r em [0, ln 2)
+    let mut k = 0;
+    let mut r = y;
+    while r < 0.0 {
+        r += LN_2;
+        k += 1;
+    }
+    let mut exp = 1.0;
+    let mut term = 1.0;
+    for n in 1..12 {
+        term *= r / n as f64;
+        exp += term;
+    }
+    for _ in 0..k {
+        exp /= 2.0;
+    }
+    exp
+}
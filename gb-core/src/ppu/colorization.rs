@@ -0,0 +1,245 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// Camadas de cada pixel no framebuffer, pra escolher a paleta de cor
+pub const LAYER_BG: u8 = 0;
+pub const LAYER_OBJ0: u8 = 1;
+pub const LAYER_OBJ1: u8 = 2;
+
+// Quatro cores em 0xRRGGBB, do tom 0 ao 3
+type Palette = [u32; 4];
+
+// Combinações do menu do boot ROM do CGB (direcional + A/B na logo)
+const WHITE_BROWN: Palette = [0xFFFFFF, 0xFFAD63, 0x843100, 0x000000];
+const WHITE_RED: Palette = [0xFFFFFF, 0xFF8484, 0x943A3A, 0x000000];
+const WHITE_GREEN: Palette = [0xFFFFFF, 0x7BFF31, 0x008400, 0x000000];
+const WHITE_BLUE: Palette = [0xFFFFFF, 0x63A5FF, 0x0000FF, 0x000000];
+const DARK_BROWN: Palette = [0xFFE6C5, 0xCE9C84, 0x846B29, 0x5A3108];
+const DARK_BLUE: Palette = [0xFFFFFF, 0x8C8CDE, 0x52528C, 0x000000];
+const GRAY: Palette = [0xFFFFFF, 0xA5A5A5, 0x525252, 0x000000];
+const PASTEL: Palette = [0xFFFFA5, 0xFF9494, 0x9494FF, 0x000000];
+const ORANGE: Palette = [0xFFFFFF, 0xFFFF00, 0xFF0000, 0x000000];
+const YELLOW: Palette = [0xFFFFFF, 0xFFFF00, 0x7B4A00, 0x000000];
+const GREEN: Palette = [0xFFFFFF, 0x52FF00, 0xFF4200, 0x000000];
+const DARK_GREEN: Palette = [0xFFFFFF, 0x7BFF31, 0x0063C5, 0x000000];
+const INVERTED: Palette = [0x000000, 0x008484, 0xFFDE00, 0xFFFFFF];
+
+// Nome, BG, OBJ0 e OBJ1
+const PRESETS: [(&str, Palette, Palette, Palette); 12] = [
+    ("brown", WHITE_BROWN, WHITE_BROWN, WHITE_BROWN),
+    ("red", WHITE_RED, WHITE_GREEN, WHITE_BLUE),
+    ("dark-brown", DARK_BROWN, DARK_BROWN, DARK_BROWN),
+    ("blue", WHITE_BLUE, WHITE_RED, WHITE_GREEN),
+    ("dark-blue", DARK_BLUE, WHITE_RED, WHITE_BROWN),
+    ("gray", GRAY, GRAY, GRAY),
+    ("pastel", PASTEL, PASTEL, PASTEL),
+    ("orange", ORANGE, ORANGE, ORANGE),
+    ("yellow", YELLOW, WHITE_BLUE, WHITE_GREEN),
+    ("green", GREEN, GREEN, GREEN),
+    ("dark-green", DARK_GREEN, WHITE_RED, WHITE_RED),
+    ("inverted", INVERTED, INVERTED, INVERTED),
+];
+
+// O que o boot ROM usa quando o título não está na tabela (ou o jogo não é da Nintendo)
+const DEFAULT_PRESET: &str = "dark-green";
+
+// Títulos com combinação própria. O boot ROM compara só a soma dos bytes do título, então
+// a tabela guarda o título e compara a soma do mesmo jeito. Não é a tabela inteira do CGB,
+// só os títulos conferidos
+const TITLES: [(&[u8], &str); 3] = [
+    (b"POKEMON RED", "red"),
+    (b"POKEMON BLUE", "blue"),
+    (b"POKEMON GREEN", "green"),
+];
+
+// Cabeçalho dos .pal em texto (Paint Shop Pro), o formato que os editores de paleta gravam
+const JASC_HEADER: &str = "JASC-PAL";
+
+const TITLE_START: usize = 0x134;
+const TITLE_END: usize = 0x144;
+const OLD_LICENSEE: usize = 0x14B;
+const NEW_LICENSEE: usize = 0x144;
+
+fn rgb(hex: u32) -> [u8; 3] {
+    [(hex >> 16) as u8, (hex >> 8) as u8, hex as u8]
+}
+
+fn palette(colors: Palette) -> [[u8; 3]; 4] {
+    colors.map(rgb)
+}
+
+fn title_checksum(title: &[u8]) -> u8 {
+    title.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+}
+
+// Jogo de DMG mostrado em cores, como o CGB faz: uma paleta RGB pro BG e uma pra cada
+// paleta de sprites
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Colorization {
+    pub bg: [[u8; 3]; 4],
+    pub obj0: [[u8; 3]; 4],
+    pub obj1: [[u8; 3]; 4],
+}
+
+impl Colorization {
+    pub fn preset(name: &str) -> Option<Self> {
+        PRESETS
+            .iter()
+            .find(|(preset, ..)| *preset == name)
+            .map(|&(_, bg, obj0, obj1)| Self {
+                bg: palette(bg),
+                obj0: palette(obj0),
+                obj1: palette(obj1),
+            })
+    }
+
+    pub fn preset_names() -> impl Iterator<Item = &'static str> {
+        PRESETS.iter().map(|(name, ..)| *name)
+    }
+
+    // Escolha do boot ROM do CGB pelo header: só jogos da Nintendo têm combinação pelo
+    // título, o resto fica no padrão
+    pub fn for_rom(rom: &[u8]) -> Self {
+        let name = match rom.get(TITLE_START..TITLE_END) {
+            Some(title) if Self::nintendo(rom) => {
+                let checksum = title_checksum(title);
+                TITLES
+                    .iter()
+                    .find(|(known, _)| title_checksum(known) == checksum)
+                    .map_or(DEFAULT_PRESET, |(_, name)| *name)
+            }
+            _ => DEFAULT_PRESET,
+        };
+        Self::preset(name).unwrap()
+    }
+
+    fn nintendo(rom: &[u8]) -> bool {
+        match rom.get(OLD_LICENSEE) {
+            Some(0x01) => true,
+            Some(0x33) => rom.get(NEW_LICENSEE..NEW_LICENSEE + 2) == Some(b"01"),
+            _ => false,
+        }
+    }
+
+    // Arquivo .pal com 4 cores (as três camadas iguais) ou 12 (BG, OBJ0 e OBJ1, nessa
+    // ordem): JASC-PAL em texto ou RGB cru, 3 bytes por cor
+    pub fn parse_pal(data: &[u8]) -> Result<Self, String> {
+        let colors = if data.starts_with(JASC_HEADER.as_bytes()) {
+            let text = core::str::from_utf8(data)
+                .map_err(|_| String::from("paleta JASC-PAL com texto inválido"))?;
+            parse_jasc(text)?
+        } else if data.len().is_multiple_of(3) {
+            data.chunks_exact(3)
+                .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+                .collect()
+        } else {
+            return Err(format!(
+                "paleta com {} bytes: esperado 3 por cor",
+                data.len()
+            ));
+        };
+
+        let layer = |n: usize| -> [[u8; 3]; 4] { colors[n * 4..n * 4 + 4].try_into().unwrap() };
+        match colors.len() {
+            4 => Ok(Self {
+                bg: layer(0),
+                obj0: layer(0),
+                obj1: layer(0),
+            }),
+            12 => Ok(Self {
+                bg: layer(0),
+                obj0: layer(1),
+                obj1: layer(2),
+            }),
+            _ => Err(format!(
+                "paleta com {} cores: esperado 4 ou 12",
+                colors.len()
+            )),
+        }
+    }
+
+    pub fn layer(&self, layer: u8) -> &[[u8; 3]; 4] {
+        match layer {
+            LAYER_OBJ0 => &self.obj0,
+            LAYER_OBJ1 => &self.obj1,
+            _ => &self.bg,
+        }
+    }
+}
+
+// JASC-PAL: cabeçalho, versão, quantidade de cores e uma cor "R G B" por linha
+fn parse_jasc(text: &str) -> Result<Vec<[u8; 3]>, String> {
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .skip(2);
+    let count = lines
+        .next()
+        .and_then(|line| line.parse::<usize>().ok())
+        .ok_or_else(|| String::from("paleta JASC-PAL sem a quantidade de cores"))?;
+
+    let mut colors = Vec::new();
+    for line in lines.take(count) {
+        let channels: Vec<u8> = line
+            .split_whitespace()
+            .map(|channel| channel.parse::<u8>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("cor inválida na paleta: '{}'", line))?;
+        let [r, g, b] = channels[..] else {
+            return Err(format!("cor inválida na paleta: '{}'", line));
+        };
+        colors.push([r, g, b]);
+    }
+    if colors.len() != count {
+        return Err(format!(
+            "paleta JASC-PAL com {} de {} cores",
+            colors.len(),
+            count
+        ));
+    }
+    Ok(colors)
+}
+
+// Quando colorir: `auto` só no modo compatível do CGB (jogo de DMG no modelo CGB, como o
+// boot ROM faz), `title` pela tabela do boot ROM em qualquer modelo
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ColorizationMode {
+    Off,
+    Auto,
+    Title,
+    Preset(Colorization),
+}
+
+impl ColorizationMode {
+    // Valor do [video] colorization
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "off" => Ok(Self::Off),
+            "auto" => Ok(Self::Auto),
+            "title" => Ok(Self::Title),
+            name => Colorization::preset(name).map(Self::Preset).ok_or_else(|| {
+                let names: Vec<&str> = Colorization::preset_names().collect();
+                format!(
+                    "colorização desconhecida: '{}' (off, auto, title, {})",
+                    name,
+                    names.join(", ")
+                )
+            }),
+        }
+    }
+
+    // Paleta pro jogo; None = tons de cinza do DMG. `dmg_compat` é o KEY0 do CGB depois do
+    // boot. Jogos com suporte a CGB nunca são colorizados
+    pub fn resolve(&self, dmg_compat: bool, rom: &[u8]) -> Option<Colorization> {
+        let dmg_only = rom.get(0x143).is_none_or(|flag| flag & 0x80 == 0);
+        match *self {
+            _ if !dmg_only => None,
+            Self::Off => None,
+            Self::Auto if !dmg_compat => None,
+            Self::Auto | Self::Title => Some(Colorization::for_rom(rom)),
+            Self::Preset(colors) => Some(colors),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// Um frame normal tem umas 500 trocas de modo; acima disso algo está muito errado e o log
+// só guarda o começo
+const MAX_EVENTS: usize = 4096;
+
+const MODE_NAMES: [&str; 4] = ["HBlank", "VBlank", "OAM scan", "desenho"];
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PpuEventKind {
+    // Novo modo no STAT (0-3)
+    Mode(u8),
+    // LY passou a bater com o LYC
+    LycMatch,
+    // Borda de subida da linha de interrupção do STAT
+    StatInterrupt,
+    // A janela começou na linha, a partir do pixel `x`
+    WindowStart { x: u8 },
+    // OAM DMA disparado pelo FF46, com a página de origem
+    Dma(u8),
+    LcdOn,
+    LcdOff,
+}
+
+// Evento com a linha interna e o dot (0-455) em que aconteceu
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PpuEvent {
+    pub line: u8,
+    pub dot: u16,
+    pub kind: PpuEventKind,
+}
+
+impl PpuEvent {
+    pub fn describe(&self) -> String {
+        let what = match self.kind {
+            PpuEventKind::Mode(mode) => {
+                format!("modo {} ({})", mode, MODE_NAMES[(mode & 0b11) as usize])
+            }
+            PpuEventKind::LycMatch => String::from("LY = LYC"),
+            PpuEventKind::StatInterrupt => String::from("interrupção do STAT"),
+            PpuEventKind::WindowStart { x } => format!("janela a partir do pixel {}", x),
+            PpuEventKind::Dma(source) => format!("OAM DMA de ${:02X}00", source),
+            PpuEventKind::LcdOn => String::from("LCD ligado"),
+            PpuEventKind::LcdOff => String::from("LCD desligado"),
+        };
+        format!("LY {:3} dot {:3}  {}", self.line, self.dot, what)
+    }
+}
+
+// Eventos do frame em andamento e do último completo, pra diagnosticar efeitos de raster
+// que dependem da duração do modo 3
+pub struct PpuEventLog {
+    current: Vec<PpuEvent>,
+    last: Vec<PpuEvent>,
+}
+
+impl PpuEventLog {
+    pub fn new() -> Self {
+        Self {
+            current: Vec::new(),
+            last: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, event: PpuEvent) {
+        if self.current.len() < MAX_EVENTS {
+            self.current.push(event);
+        }
+    }
+
+    // Linha 0 de novo: o frame em andamento vira o último
+    pub fn end_frame(&mut self) {
+        self.last = core::mem::take(&mut self.current);
+    }
+
+    // Último frame completo; antes do primeiro, o que já entrou no atual
+    pub fn frame(&self) -> &[PpuEvent] {
+        if self.last.is_empty() {
+            &self.current
+        } else {
+            &self.last
+        }
+    }
+}
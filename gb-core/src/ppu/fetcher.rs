@@ -0,0 +1,193 @@
+use alloc::string::{String, ToString};
+
+use crate::ppu::fifo::{Pixel, PixelFifo};
+use crate::ppu::memory::{LCDC, SCX, SCY, VideoMemory};
+use crate::state::{Savestate, StateReader, StateWriter};
+
+const LCDC_WINDOW_MAP: u8 = 1 << 6;
+const LCDC_TILE_DATA: u8 = 1 << 4;
+const LCDC_BG_MAP: u8 = 1 << 3;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum FetcherStep {
+    Tile,
+    DataLow,
+    DataHigh,
+    Push,
+}
+
+// Fetcher de BG/janela: cada passo leva 2 dots, o push espera a FIFO esvaziar
+pub struct Fetcher {
+    step: FetcherStep,
+    dots: u8,
+    tile_x: u8,
+    tile_row: u8,
+    tile_index: u8,
+    data_low: u8,
+    data_high: u8,
+    window: bool,
+}
+
+impl Fetcher {
+    pub fn new() -> Self {
+        Self {
+            step: FetcherStep::Tile,
+            dots: 0,
+            tile_x: 0,
+            tile_row: 0,
+            tile_index: 0,
+            data_low: 0,
+            data_high: 0,
+            window: false,
+        }
+    }
+
+    pub fn start_line(&mut self) {
+        self.step = FetcherStep::Tile;
+        self.dots = 0;
+        self.tile_x = 0;
+        self.window = false;
+    }
+
+    // Ao entrar na janela o fetcher recomeça do tile 0 do mapa da janela
+    pub fn start_window(&mut self) {
+        self.step = FetcherStep::Tile;
+        self.dots = 0;
+        self.tile_x = 0;
+        self.window = true;
+    }
+
+    pub fn in_window(&self) -> bool {
+        self.window
+    }
+
+    // Dados do tile prontos, só esperando espaço na FIFO
+    pub fn ready_to_push(&self) -> bool {
+        self.step == FetcherStep::Push
+    }
+
+    pub fn tick(&mut self, mem: &VideoMemory, ly: u8, window_line: u8, fifo: &mut PixelFifo) {
+        match self.step {
+            FetcherStep::Tile => {
+                if !self.wait() {
+                    return;
+                }
+
+                let lcdc = mem.reg(LCDC);
+
+                let (map_base, column, row) = if self.window {
+                    let map_base = if (lcdc & LCDC_WINDOW_MAP) != 0 {
+                        0x9C00
+                    } else {
+                        0x9800
+                    };
+                    (map_base, self.tile_x, window_line)
+                } else {
+                    let map_base = if (lcdc & LCDC_BG_MAP) != 0 {
+                        0x9C00
+                    } else {
+                        0x9800
+                    };
+                    let scx = mem.reg(SCX);
+                    let scy = mem.reg(SCY);
+                    (
+                        map_base,
+                        (scx / 8).wrapping_add(self.tile_x),
+                        ly.wrapping_add(scy),
+                    )
+                };
+
+                self.tile_row = row % 8;
+                let addr = map_base + ((row / 8) as u16 & 31) * 32 + (column as u16 & 31);
+                self.tile_index = mem.vram(addr);
+                self.step = FetcherStep::DataLow;
+            }
+            FetcherStep::DataLow => {
+                if !self.wait() {
+                    return;
+                }
+                let addr = self.tile_data_addr(mem);
+                self.data_low = mem.vram(addr);
+                self.step = FetcherStep::DataHigh;
+            }
+            FetcherStep::DataHigh => {
+                if !self.wait() {
+                    return;
+                }
+                let addr = self.tile_data_addr(mem);
+                self.data_high = mem.vram(addr + 1);
+                self.step = FetcherStep::Push;
+            }
+            FetcherStep::Push => {
+                // A FIFO de BG só aceita um tile novo quando está vazia
+                if !fifo.is_empty() {
+                    return;
+                }
+
+                for bit in (0..8).rev() {
+                    let b0 = (self.data_low >> bit) & 1;
+                    let b1 = (self.data_high >> bit) & 1;
+                    fifo.push(Pixel {
+                        color: (b1 << 1) | b0,
+                        ..Pixel::default()
+                    });
+                }
+
+                self.tile_x = self.tile_x.wrapping_add(1);
+                self.step = FetcherStep::Tile;
+            }
+        }
+    }
+
+    fn wait(&mut self) -> bool {
+        self.dots += 1;
+        if self.dots < 2 {
+            return false;
+        }
+        self.dots = 0;
+        true
+    }
+
+    // bit4=1 => 0x8000 unsigned index, bit4=0 => 0x8800 signed index
+    fn tile_data_addr(&self, mem: &VideoMemory) -> u16 {
+        let lcdc = mem.reg(LCDC);
+        let base: u16 = if (lcdc & LCDC_TILE_DATA) != 0 {
+            0x8000 + (self.tile_index as u16) * 16
+        } else {
+            let signed = self.tile_index as i8 as i32;
+            (0x9000i32 + signed * 16) as u16
+        };
+        base + (self.tile_row as u16) * 2
+    }
+}
+
+impl Savestate for Fetcher {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.step as u8);
+        w.write_u8(self.dots);
+        w.write_u8(self.tile_x);
+        w.write_u8(self.tile_row);
+        w.write_u8(self.tile_index);
+        w.write_u8(self.data_low);
+        w.write_u8(self.data_high);
+        w.write_bool(self.window);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.step = match r.read_u8()? {
+            0 => FetcherStep::Tile,
+            1 => FetcherStep::DataLow,
+            2 => FetcherStep::DataHigh,
+            3 => FetcherStep::Push,
+            _ => return Err("passo do fetcher inválido no save state".to_string()),
+        };
+        self.dots = r.read_u8()?;
+        self.tile_x = r.read_u8()?;
+        self.tile_row = r.read_u8()?;
+        self.tile_index = r.read_u8()?;
+        self.data_low = r.read_u8()?;
+        self.data_high = r.read_u8()?;
+        self.window = r.read_bool()?;
+        Ok(())
+    }
+}
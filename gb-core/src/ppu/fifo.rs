@@ -0,0 +1,103 @@
+use alloc::string::{String, ToString};
+
+use crate::state::{Savestate, StateReader, StateWriter};
+
+#[derive(Copy, Clone, Default)]
+pub struct Pixel {
+    // Índice 0..3 antes da paleta
+    pub color: u8,
+    // Só pra OBJ: 0 = OBP0, 1 = OBP1
+    pub palette: u8,
+    // Só pra OBJ: bit 7 dos atributos (BG cores 1-3 por cima do objeto)
+    pub bg_priority: bool,
+    // Só pra OBJ: entre dois objetos opacos vence o menor (x no modo DMG, índice na OAM
+    // no modo CGB)
+    pub priority: u8,
+}
+
+const CAPACITY: usize = 16;
+
+// Fila circular de pixels (BG ou OBJ); nunca passa de 16 no hardware
+pub struct PixelFifo {
+    pixels: [Pixel; CAPACITY],
+    head: usize,
+    len: usize,
+}
+
+impl PixelFifo {
+    pub fn new() -> Self {
+        Self {
+            pixels: [Pixel::default(); CAPACITY],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, pixel: Pixel) {
+        if self.len == CAPACITY {
+            return;
+        }
+        self.pixels[(self.head + self.len) % CAPACITY] = pixel;
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<Pixel> {
+        if self.len == 0 {
+            return None;
+        }
+        let pixel = self.pixels[self.head];
+        self.head = (self.head + 1) % CAPACITY;
+        self.len -= 1;
+        Some(pixel)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Pixel> {
+        if index >= self.len {
+            return None;
+        }
+        Some(&mut self.pixels[(self.head + index) % CAPACITY])
+    }
+}
+
+impl Savestate for PixelFifo {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.len as u8);
+        for index in 0..self.len {
+            let pixel = self.pixels[(self.head + index) % CAPACITY];
+            w.write_u8(pixel.color);
+            w.write_u8(pixel.palette);
+            w.write_bool(pixel.bg_priority);
+            w.write_u8(pixel.priority);
+        }
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.clear();
+        let len = r.read_u8()? as usize;
+        if len > CAPACITY {
+            return Err("FIFO de pixels inválida no save state".to_string());
+        }
+        for _ in 0..len {
+            self.push(Pixel {
+                color: r.read_u8()?,
+                palette: r.read_u8()?,
+                bg_priority: r.read_bool()?,
+                priority: r.read_u8()?,
+            });
+        }
+        Ok(())
+    }
+}
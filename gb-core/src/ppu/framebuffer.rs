@@ -0,0 +1,144 @@
+use alloc::string::String;
+
+use crate::png;
+use crate::ppu::color_correction::ColorTransform;
+use crate::ppu::colorization::{Colorization, LAYER_BG};
+use crate::state::{Savestate, StateReader, StateWriter};
+
+// Tela do Game Boy
+pub const GB_W: usize = 160;
+pub const GB_H: usize = 144;
+
+// Tons do DMG (0 = mais claro) em RGB
+pub const DMG_SHADES: [[u8; 3]; 4] = [[255, 255, 255], [170, 170, 170], [85, 85, 85], [0, 0, 0]];
+
+// RGB888 -> RGB565
+fn rgb565(rgb: [u8; 3]) -> u16 {
+    ((rgb[0] as u16 >> 3) << 11) | ((rgb[1] as u16 >> 2) << 5) | (rgb[2] as u16 >> 3)
+}
+
+// Como os tons viram cor na saída; sem nada, os tons de cinza do DMG
+pub struct FrameOutput<'a> {
+    pub colors: Option<&'a Colorization>,
+    pub transform: Option<&'a ColorTransform>,
+}
+
+// Dois buffers: a PPU desenha no back enquanto o frontend lê o front (último frame completo)
+pub struct FrameBuffer {
+    buffers: [[u8; GB_W * GB_H]; 2],
+    // Camada (BG, OBJ0, OBJ1) de cada pixel, só pra colorização; os tons ficam separados
+    // pro hash e o SGB não mudarem
+    layers: [[u8; GB_W * GB_H]; 2],
+    back: usize,
+    frame_complete: bool,
+    // Frames completos até aqui (dá a volta), pra saber se o front mudou
+    frames: u32,
+}
+
+impl FrameBuffer {
+    pub fn new() -> Self {
+        Self {
+            buffers: [[0; GB_W * GB_H]; 2],
+            layers: [[LAYER_BG; GB_W * GB_H]; 2],
+            back: 0,
+            frame_complete: false,
+            frames: 0,
+        }
+    }
+
+    // Tom nos bits 0-1, camada nos bits 2-3
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        self.buffers[self.back][y * GB_W + x] = value & 0b11;
+        self.layers[self.back][y * GB_W + x] = value >> 2;
+    }
+
+    pub fn get(&mut self, x: usize, y: usize) -> u8 {
+        self.buffers[self.back][y * GB_W + x] & 0b11
+    }
+
+    pub fn clear(&mut self, value: u8) {
+        let c = value & 0b11;
+        self.buffers[self.back].fill(c);
+        self.layers[self.back].fill(LAYER_BG);
+    }
+
+    // Fim do frame: o back vira front e a PPU continua no outro buffer
+    pub fn swap(&mut self) {
+        self.back ^= 1;
+        self.frame_complete = true;
+        self.frames = self.frames.wrapping_add(1);
+    }
+
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    pub fn front(&self) -> &[u8] {
+        &self.buffers[self.back ^ 1]
+    }
+
+    // CRC32 dos tons (0-3) do último frame completo, independente da paleta do frontend
+    pub fn hash(&self) -> u32 {
+        png::crc32(self.front())
+    }
+
+    // Entrega o frame completo uma única vez
+    pub fn take_complete(&mut self) -> Option<&[u8]> {
+        if self.frame_complete {
+            self.frame_complete = false;
+            Some(self.front())
+        } else {
+            None
+        }
+    }
+
+    // Cor de cada pixel do front: tons do DMG, ou a paleta da camada com colorização, e
+    // depois a correção de cor
+    fn front_colors<'a>(&'a self, output: &'a FrameOutput) -> impl Iterator<Item = [u8; 3]> + 'a {
+        let layers = &self.layers[self.back ^ 1];
+        self.front().iter().zip(layers).map(move |(&shade, &layer)| {
+            let palette = output.colors.map_or(&DMG_SHADES, |colors| colors.layer(layer));
+            let rgb = palette[(shade & 0b11) as usize];
+            output.transform.map_or(rgb, |transform| transform.apply(rgb))
+        })
+    }
+
+    // out precisa ter 160 * 144 * 4 bytes
+    pub fn take_complete_rgba(&mut self, out: &mut [u8], output: &FrameOutput) -> bool {
+        if self.take_complete().is_none() {
+            return false;
+        }
+        self.front_rgba(out, output);
+        true
+    }
+
+    // Último frame completo em RGBA, sem consumir
+    pub fn front_rgba(&self, out: &mut [u8], output: &FrameOutput) {
+        for (pixel, [r, g, b]) in out.chunks_exact_mut(4).zip(self.front_colors(output)) {
+            pixel.copy_from_slice(&[r, g, b, 255]);
+        }
+    }
+
+    // out precisa ter 160 * 144 entradas
+    pub fn take_complete_rgb565(&mut self, out: &mut [u16], output: &FrameOutput) -> bool {
+        if self.take_complete().is_none() {
+            return false;
+        }
+
+        for (pixel, color) in out.iter_mut().zip(self.front_colors(output)) {
+            *pixel = rgb565(color);
+        }
+        true
+    }
+}
+
+// Só o back buffer (frame em andamento); o front já foi entregue ao frontend
+impl Savestate for FrameBuffer {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bytes(&self.buffers[self.back]);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        r.read_bytes(&mut self.buffers[self.back])
+    }
+}
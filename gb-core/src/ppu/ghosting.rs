@@ -0,0 +1,60 @@
+use alloc::vec::Vec;
+
+// Acima disso a imagem praticamente não sai do lugar
+pub const MAX_PERSISTENCE: u8 = 90;
+// [video] filter = ghosting sem um [video] ghosting explícito
+pub const DEFAULT_PERSISTENCE: u8 = 40;
+
+// Resposta lenta do LCD do DMG: cada frame sai misturado com o anterior já misturado, então
+// os frames velhos vão sumindo aos poucos (passa-baixa por pixel). Sprites piscando em
+// frames alternados viram transparência, como no aparelho
+pub struct LcdGhosting {
+    // Quanto do frame anterior fica, em %
+    persistence: u8,
+    // Último RGBA misturado e o frame da PPU de onde ele veio
+    previous: Vec<u8>,
+    frame: Option<u32>,
+}
+
+impl LcdGhosting {
+    pub fn new(persistence: u8) -> Self {
+        Self {
+            persistence: persistence.min(MAX_PERSISTENCE),
+            previous: Vec::new(),
+            frame: None,
+        }
+    }
+
+    // Mistura no lugar e guarda o resultado pro próximo frame. O mesmo frame pedido de
+    // novo (screenshot do que já foi entregue) sai igual, sem decair outra vez
+    pub fn apply(&mut self, rgba: &mut [u8], frame: u32) {
+        // Tamanho novo (moldura do SGB ligando): não tem o que misturar
+        if self.previous.len() == rgba.len() {
+            if self.frame == Some(frame) {
+                rgba.copy_from_slice(&self.previous);
+                return;
+            }
+            self.blend(rgba);
+        }
+        self.previous.clear();
+        self.previous.extend_from_slice(rgba);
+        self.frame = Some(frame);
+    }
+
+    fn blend(&self, rgba: &mut [u8]) {
+        let old = self.persistence as u32;
+        let new = 100 - old;
+        for (pixel, previous) in rgba.chunks_exact_mut(4).zip(self.previous.chunks_exact(4)) {
+            for channel in 0..3 {
+                let mixed = pixel[channel] as u32 * new + previous[channel] as u32 * old;
+                pixel[channel] = ((mixed + 50) / 100) as u8;
+            }
+        }
+    }
+
+    // Esquece os frames anteriores (ROM nova)
+    pub fn clear(&mut self) {
+        self.previous.clear();
+        self.frame = None;
+    }
+}
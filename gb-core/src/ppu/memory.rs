@@ -0,0 +1,71 @@
+use alloc::string::String;
+
+use crate::state::{Savestate, StateReader, StateWriter};
+
+// Registros do LCD (FF40-FF4B); o FF46 no meio é do DMA e fica com o bus
+pub const LCDC: u16 = 0xFF40;
+pub const STAT: u16 = 0xFF41;
+pub const SCY: u16 = 0xFF42;
+pub const SCX: u16 = 0xFF43;
+pub const LY: u16 = 0xFF44;
+pub const LYC: u16 = 0xFF45;
+pub const BGP: u16 = 0xFF47;
+pub const OBP0: u16 = 0xFF48;
+pub const OBP1: u16 = 0xFF49;
+pub const WY: u16 = 0xFF4A;
+pub const WX: u16 = 0xFF4B;
+pub const LCD_END: u16 = WX;
+// Prioridade entre objetos do CGB: bit 0 = 0 pelo índice na OAM, 1 pelo x (como no DMG)
+pub const OPRI: u16 = 0xFF6C;
+
+pub const VRAM_BASE: u16 = 0x8000;
+pub const VRAM_END: u16 = 0x9FFF;
+pub const OAM_BASE: u16 = 0xFE00;
+pub const OAM_END: u16 = 0xFE9F;
+
+// Tudo que a PPU lê pra desenhar: VRAM, OAM e os registros do LCD
+pub struct VideoMemory {
+    pub vram: [u8; 0x2000],
+    pub oam: [u8; 0xA0],
+    regs: [u8; 0x0C],
+}
+
+impl VideoMemory {
+    pub fn new() -> Self {
+        Self {
+            vram: [0; 0x2000],
+            oam: [0; 0xA0],
+            regs: [0; 0x0C],
+        }
+    }
+
+    pub fn reg(&self, addr: u16) -> u8 {
+        self.regs[(addr - LCDC) as usize]
+    }
+
+    pub fn set_reg(&mut self, addr: u16, value: u8) {
+        self.regs[(addr - LCDC) as usize] = value;
+    }
+
+    pub fn vram(&self, addr: u16) -> u8 {
+        self.vram[(addr - VRAM_BASE) as usize]
+    }
+
+    pub fn oam(&self, addr: u16) -> u8 {
+        self.oam[(addr - OAM_BASE) as usize]
+    }
+}
+
+impl Savestate for VideoMemory {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bytes(&self.vram);
+        w.write_bytes(&self.oam);
+        w.write_bytes(&self.regs);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        r.read_bytes(&mut self.vram)?;
+        r.read_bytes(&mut self.oam)?;
+        r.read_bytes(&mut self.regs)
+    }
+}
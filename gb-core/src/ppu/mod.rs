@@ -0,0 +1,20 @@
+pub mod color_correction;
+pub mod colorization;
+pub mod event_log;
+pub mod fetcher;
+pub mod fifo;
+pub mod framebuffer;
+pub mod ghosting;
+pub mod memory;
+pub mod ppu;
+pub mod sprite;
+#[cfg(test)]
+mod tests;
+
+pub use color_correction::*;
+pub use colorization::*;
+pub use event_log::*;
+pub use framebuffer::{GB_H, GB_W};
+pub use ghosting::*;
+pub use memory::*;
+pub use ppu::*;
@@ -0,0 +1,828 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::{
+    bus::{ClockDomain, Clocked, InterruptFlags},
+    ppu::{
+        color_correction::ColorTransform,
+        colorization::{Colorization, LAYER_BG, LAYER_OBJ0, LAYER_OBJ1},
+        event_log::{PpuEvent, PpuEventKind, PpuEventLog},
+        fetcher::Fetcher,
+        fifo::{Pixel, PixelFifo},
+        framebuffer::{FrameBuffer, FrameOutput, GB_H},
+        memory::{
+            BGP, LCDC, LY, LYC, OAM_BASE, OAM_END, OBP0, OBP1, SCX, SCY, STAT, VRAM_BASE, VRAM_END,
+            VideoMemory, WX, WY,
+        },
+        sprite::{ATTR_BG_PRIORITY, ATTR_FLIP_X, ATTR_FLIP_Y, ATTR_PALETTE, Sprite},
+    },
+    state::{Savestate, StateReader, StateWriter},
+};
+
+const OAM_ENTRIES: u16 = 40;
+const MAX_SPRITES_PER_LINE: usize = 10;
+
+// Bits do STAT (0-2 são só leitura)
+const STAT_READ_ONLY: u8 = 0x07;
+const STAT_LYC_FLAG: u8 = 1 << 2;
+const STAT_HBLANK_INT: u8 = 1 << 3;
+const STAT_VBLANK_INT: u8 = 1 << 4;
+const STAT_OAM_INT: u8 = 1 << 5;
+const STAT_LYC_INT: u8 = 1 << 6;
+
+// Bits do LCDC
+const LCDC_ENABLE: u8 = 1 << 7;
+const LCDC_WINDOW_ENABLE: u8 = 1 << 5;
+const LCDC_OBJ_SIZE: u8 = 1 << 2;
+const LCDC_OBJ_ENABLE: u8 = 1 << 1;
+const LCDC_BG_ENABLE: u8 = 1 << 0;
+
+// Modos da PPU (STAT bits 0-1)
+const MODE_HBLANK: u8 = 0;
+const MODE_VBLANK: u8 = 1;
+const MODE_OAM: u8 = 2;
+const MODE_XFER: u8 = 3;
+
+// Timings por linha (em "dots"/t-cycles da PPU; no GB 1 M-cycle CPU = 4 dots)
+// O modo 3 não tem duração fixa: termina quando a FIFO entrega o pixel 160
+// (mínimo de 172 dots; SCX, objetos e janela esticam o modo 3 e encurtam o HBLANK)
+const DOTS_PER_LINE: u16 = 456;
+const OAM_DOTS: u16 = 80;
+// Fetch descartado no início de toda linha
+const XFER_STARTUP_DOTS: u8 = 6;
+const SPRITE_FETCH_DOTS: u8 = 6;
+const SCREEN_WIDTH: u8 = 160;
+const LAST_LINE: u8 = 153;
+// Na linha 153 o LY volta a ler 0 depois de poucos dots
+const LINE_153_LY_RESET_DOT: u16 = 4;
+
+// Registradores de uma linha da tela como estavam no começo do modo 3, pro canvas de debug
+// mostrar efeitos de raster
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct LineRegisters {
+    pub lcdc: u8,
+    pub scx: u8,
+    pub scy: u8,
+    pub wx: u8,
+    pub wy: u8,
+    // A janela foi desenhada nessa linha
+    pub window: bool,
+}
+
+pub struct Ppu {
+    // A PPU é dona da memória de vídeo; o bus só encaminha os acessos da CPU
+    mem: VideoMemory,
+    framebuffer: Box<FrameBuffer>,
+    mode: u8,
+    dot: u16,
+    // Linha interna (0..153); o registro LY nem sempre bate com ela (linha 153)
+    line: u8,
+    line_sprites: Vec<Sprite>,
+
+    // Estado do LCDC bit 7 no último tick
+    lcd_on: bool,
+    // Primeira linha depois de ligar o LCD: sem OAM scan, começa em modo 0
+    enable_line: bool,
+    // O primeiro frame depois de ligar o LCD não é mostrado
+    skip_frame: bool,
+    // OR das fontes de interrupção do STAT; a interrupção sai só na borda de subida
+    stat_line: bool,
+
+    // Pipeline do modo 3
+    fetcher: Fetcher,
+    bg_fifo: PixelFifo,
+    obj_fifo: PixelFifo,
+    // Próximo x de saída na linha (0..160)
+    lx: u8,
+    // Pixels do primeiro tile descartados por SCX % 8
+    discard: u8,
+    startup_dots: u8,
+    // Bitmask de line_sprites já buscados nesta linha
+    fetched_sprites: u16,
+    fetching_sprite: Option<usize>,
+    sprite_dots: u8,
+
+    // Janela: WY bateu com LY em algum momento do frame + contador interno de linhas
+    window_triggered: bool,
+    window_line: u8,
+    window_drawn_this_line: bool,
+
+    // Entrou no VBlank desde a última consulta (consumido pelo Emulator)
+    vblank_entered: bool,
+    // Linha (LY) que começou desde a última consulta, pros callbacks de scanline
+    line_started: Option<u8>,
+    // Pedidas durante o tick, entregues ao bus no fim
+    interrupts: InterruptFlags,
+    // Linha da OAM sendo lida (só no modo 2), pro OAM bug do DMG
+    oam_scan_row: Option<u8>,
+    // Log de eventos do frame, ligado pelo debugger
+    event_log: Option<PpuEventLog>,
+    lines: [LineRegisters; GB_H],
+    // OPRI bit 0: objetos se sobrepõem pelo x (1, DMG e CGB em modo compatível) ou pelo
+    // índice na OAM (0, jogos de CGB)
+    opri: u8,
+    // Paletas RGB do jogo de DMG colorizado; None = tons de cinza
+    colorization: Option<Colorization>,
+    // Correção de cor e gamma da saída; None = cores como saem da paleta
+    color_transform: Option<ColorTransform>,
+}
+
+impl Ppu {
+    pub fn new() -> Self {
+        Self {
+            mem: VideoMemory::new(),
+            framebuffer: Box::new(FrameBuffer::new()),
+            mode: MODE_OAM,
+            dot: 0,
+            line: 0,
+            line_sprites: Vec::with_capacity(MAX_SPRITES_PER_LINE),
+
+            lcd_on: false,
+            enable_line: false,
+            skip_frame: false,
+            stat_line: false,
+
+            fetcher: Fetcher::new(),
+            bg_fifo: PixelFifo::new(),
+            obj_fifo: PixelFifo::new(),
+            lx: 0,
+            discard: 0,
+            startup_dots: 0,
+            fetched_sprites: 0,
+            fetching_sprite: None,
+            sprite_dots: 0,
+
+            window_triggered: false,
+            window_line: 0,
+            window_drawn_this_line: false,
+
+            vblank_entered: false,
+            line_started: None,
+            interrupts: InterruptFlags::empty(),
+            oam_scan_row: None,
+            event_log: None,
+            lines: [LineRegisters::default(); GB_H],
+            opri: 1,
+            colorization: None,
+            color_transform: None,
+        }
+    }
+
+    // Reset do console: a PPU recomeça, mas VRAM, OAM e registros ficam
+    pub fn reset(&mut self) {
+        let mem = core::mem::replace(&mut self.mem, VideoMemory::new());
+        let opri = self.opri;
+        let event_log = self.event_log.take();
+        let colorization = self.colorization;
+        let color_transform = self.color_transform.take();
+        *self = Self::new();
+        self.mem = mem;
+        self.opri = opri;
+        self.event_log = event_log;
+        self.colorization = colorization;
+        self.color_transform = color_transform;
+    }
+
+    // Acessos da CPU a VRAM, OAM e registros do LCD
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            VRAM_BASE..=VRAM_END => self.mem.vram(addr),
+            OAM_BASE..=OAM_END => self.mem.oam(addr),
+            STAT => self.mem.reg(STAT) | 0x80,
+            _ => self.mem.reg(addr),
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, data: u8) {
+        match addr {
+            VRAM_BASE..=VRAM_END => self.mem.vram[(addr - VRAM_BASE) as usize] = data,
+            OAM_BASE..=OAM_END => self.mem.oam[(addr - OAM_BASE) as usize] = data,
+            STAT => {
+                let stat = self.mem.reg(STAT);
+                self.mem
+                    .set_reg(STAT, (data & !STAT_READ_ONLY) | (stat & STAT_READ_ONLY));
+            }
+            // LY é só leitura
+            LY => {}
+            _ => self.mem.set_reg(addr, data),
+        }
+    }
+
+    // O bus só repassa o OPRI no CGB; no DMG a prioridade é sempre pelo x
+    pub fn opri(&self) -> u8 {
+        self.opri
+    }
+
+    pub fn set_opri(&mut self, value: u8) {
+        self.opri = value & 1;
+    }
+
+    pub fn vram(&self) -> &[u8] {
+        &self.mem.vram
+    }
+
+    pub fn vram_mut(&mut self) -> &mut [u8] {
+        &mut self.mem.vram
+    }
+
+    pub fn oam_mut(&mut self) -> &mut [u8] {
+        &mut self.mem.oam
+    }
+
+    pub fn oam_scan_row(&self) -> Option<u8> {
+        self.oam_scan_row
+    }
+
+    // Uma entrada por linha visível, do último frame desenhado até a linha atual
+    pub fn line_registers(&self) -> &[LineRegisters] {
+        &self.lines
+    }
+
+    pub fn set_colorization(&mut self, colorization: Option<Colorization>) {
+        self.colorization = colorization;
+    }
+
+    pub fn colorization(&self) -> Option<&Colorization> {
+        self.colorization.as_ref()
+    }
+
+    pub fn set_color_transform(&mut self, transform: Option<ColorTransform>) {
+        self.color_transform = transform;
+    }
+
+    pub fn color_transform(&self) -> Option<&ColorTransform> {
+        self.color_transform.as_ref()
+    }
+
+    pub fn set_event_log(&mut self, enabled: bool) {
+        if enabled != self.event_log.is_some() {
+            self.event_log = enabled.then(PpuEventLog::new);
+        }
+    }
+
+    // Eventos do último frame completo, com o log ligado
+    pub fn event_log(&self) -> Option<&[PpuEvent]> {
+        self.event_log.as_ref().map(PpuEventLog::frame)
+    }
+
+    // O DMA mora no bus, mas entra no log junto com o resto
+    pub fn log_dma(&mut self, source: u8) {
+        self.log(PpuEventKind::Dma(source));
+    }
+
+    fn log(&mut self, kind: PpuEventKind) {
+        if let Some(log) = &mut self.event_log {
+            log.record(PpuEvent {
+                line: self.line,
+                dot: self.dot,
+                kind,
+            });
+        }
+    }
+
+    fn end_line(&mut self) {
+        if self.window_drawn_this_line {
+            self.window_line = self.window_line.wrapping_add(1);
+            self.window_drawn_this_line = false;
+        }
+
+        self.line = if self.line >= LAST_LINE {
+            0
+        } else {
+            self.line + 1
+        };
+
+        if self.line == 0
+            && let Some(log) = &mut self.event_log
+        {
+            log.end_frame();
+        }
+
+        self.mem.set_reg(LY, self.line);
+        self.update_lyc(self.line);
+        self.line_started = Some(self.line);
+
+        if self.line == 144 {
+            self.set_mode(MODE_VBLANK);
+            self.interrupts |= InterruptFlags::VBLANK;
+            self.vblank_entered = true;
+
+            if self.skip_frame {
+                self.skip_frame = false;
+            } else {
+                self.framebuffer.swap(); // 1x por frame
+            }
+        } else if self.line < 144 {
+            if self.line == 0 {
+                self.window_triggered = false;
+                self.window_line = 0;
+            }
+            self.set_mode(MODE_OAM);
+        }
+    }
+
+    // Desligar o LCD no meio do frame: tela em branco e PPU parada em LY=0, modo 0
+    fn disable_lcd(&mut self) {
+        self.log(PpuEventKind::LcdOff);
+        self.lcd_on = false;
+        self.oam_scan_row = None;
+        self.enable_line = false;
+        self.dot = 0;
+        self.line = 0;
+        self.window_triggered = false;
+        self.window_line = 0;
+        self.window_drawn_this_line = false;
+
+        self.mem.set_reg(LY, 0);
+        self.set_mode(MODE_HBLANK);
+
+        self.framebuffer.clear(0);
+        self.framebuffer.swap();
+    }
+
+    // Ao ligar, a linha 0 começa direto no modo 0 (sem OAM scan)
+    fn enable_lcd(&mut self) {
+        self.lcd_on = true;
+        self.enable_line = true;
+        self.skip_frame = true;
+        self.dot = 0;
+        self.line = 0;
+        self.log(PpuEventKind::LcdOn);
+
+        self.mem.set_reg(LY, 0);
+        self.set_mode(MODE_HBLANK);
+        self.update_lyc(0);
+    }
+
+    // Seleciona até 10 objetos que cobrem a linha, na ordem da OAM
+    fn oam_scan(&mut self, ly: u8) {
+        self.line_sprites.clear();
+
+        let lcdc = self.mem.reg(LCDC);
+        let height: i16 = if (lcdc & LCDC_OBJ_SIZE) != 0 { 16 } else { 8 };
+
+        for index in 0..OAM_ENTRIES {
+            if self.line_sprites.len() >= MAX_SPRITES_PER_LINE {
+                break;
+            }
+
+            let addr = OAM_BASE + index * 4;
+            let bytes = [
+                self.mem.oam(addr),
+                self.mem.oam(addr + 1),
+                self.mem.oam(addr + 2),
+                self.mem.oam(addr + 3),
+            ];
+            let sprite = Sprite::from_oam(bytes);
+
+            let top = sprite.y as i16 - 16;
+            let line = ly as i16;
+            if line >= top && line < top + height {
+                self.line_sprites.push(sprite);
+            }
+        }
+
+        // WY é comparado com LY no começo de cada linha
+        if self.mem.reg(WY) == ly {
+            self.window_triggered = true;
+        }
+    }
+
+    fn start_xfer(&mut self) {
+        self.lines[self.line as usize] = LineRegisters {
+            lcdc: self.mem.reg(LCDC),
+            scx: self.mem.reg(SCX),
+            scy: self.mem.reg(SCY),
+            wx: self.mem.reg(WX),
+            wy: self.mem.reg(WY),
+            window: false,
+        };
+        self.fetcher.start_line();
+        self.bg_fifo.clear();
+        self.obj_fifo.clear();
+        self.lx = 0;
+        self.discard = self.mem.reg(SCX) % 8;
+        self.startup_dots = XFER_STARTUP_DOTS;
+        self.fetched_sprites = 0;
+        self.fetching_sprite = None;
+        self.sprite_dots = 0;
+    }
+
+    // Um dot do modo 3: fetch de objeto (pausa o BG) ou fetch de BG + saída de 1 pixel
+    fn xfer_dot(&mut self, ly: u8) {
+        if self.startup_dots > 0 {
+            self.startup_dots -= 1;
+            return;
+        }
+
+        let lcdc = self.mem.reg(LCDC);
+
+        if self.fetching_sprite.is_none() && (lcdc & LCDC_OBJ_ENABLE) != 0 {
+            self.fetching_sprite = self.next_sprite();
+        }
+
+        if let Some(index) = self.fetching_sprite {
+            // O objeto espera a FIFO de BG ter pixels e o fetcher terminar o tile atual
+            if self.bg_fifo.is_empty() || !self.fetcher.ready_to_push() {
+                self.fetcher
+                    .tick(&self.mem, ly, self.window_line, &mut self.bg_fifo);
+                return;
+            }
+
+            self.sprite_dots += 1;
+            if self.sprite_dots < SPRITE_FETCH_DOTS {
+                return;
+            }
+
+            self.sprite_dots = 0;
+            self.fetching_sprite = None;
+            self.fetched_sprites |= 1 << index;
+            self.merge_sprite(index, ly);
+            return;
+        }
+
+        if !self.fetcher.in_window() && self.window_starts_here(lcdc) {
+            self.fetcher.start_window();
+            self.bg_fifo.clear();
+            self.discard = 0;
+            self.window_drawn_this_line = true;
+            self.lines[ly as usize].window = true;
+            self.log(PpuEventKind::WindowStart { x: self.lx });
+        }
+
+        self.fetcher
+            .tick(&self.mem, ly, self.window_line, &mut self.bg_fifo);
+
+        let Some(bg) = self.bg_fifo.pop() else {
+            return;
+        };
+
+        if self.discard > 0 {
+            self.discard -= 1;
+            return;
+        }
+
+        let obj = self.obj_fifo.pop();
+        let pixel = self.mix_pixel(lcdc, bg, obj);
+        self.framebuffer.set(self.lx as usize, ly as usize, pixel);
+        self.lx += 1;
+    }
+
+    fn window_starts_here(&self, lcdc: u8) -> bool {
+        // No DMG o bit 0 do LCDC desliga BG e janela juntos
+        if (lcdc & LCDC_WINDOW_ENABLE) == 0 || (lcdc & LCDC_BG_ENABLE) == 0 {
+            return false;
+        }
+        if !self.window_triggered {
+            return false;
+        }
+
+        let wx = self.mem.reg(WX);
+        wx <= 166 && self.lx as u16 + 7 >= wx as u16
+    }
+
+    // Primeiro objeto (ordem da OAM) cujo x já foi alcançado e ainda não foi buscado
+    fn next_sprite(&self) -> Option<usize> {
+        self.line_sprites
+            .iter()
+            .enumerate()
+            .position(|(index, sprite)| {
+                (self.fetched_sprites & (1 << index)) == 0
+                    && (sprite.x as u16) <= self.lx as u16 + 8
+            })
+    }
+
+    // Mistura a linha do objeto na FIFO de OBJ. Um pixel opaco só substitui outro opaco se
+    // tiver prioridade maior: x menor no modo DMG (empate fica com o primeiro buscado, que
+    // tem o menor índice) ou índice menor na OAM no modo CGB
+    fn merge_sprite(&mut self, index: usize, ly: u8) {
+        let sprite = self.line_sprites[index];
+        // line_sprites está na ordem da OAM, então a posição ali serve de índice
+        let priority = if self.opri & 1 != 0 {
+            sprite.x
+        } else {
+            index as u8
+        };
+        let lcdc = self.mem.reg(LCDC);
+        let height: i16 = if (lcdc & LCDC_OBJ_SIZE) != 0 { 16 } else { 8 };
+
+        let mut row = ly as i16 - (sprite.y as i16 - 16);
+        if (sprite.attributes & ATTR_FLIP_Y) != 0 {
+            row = height - 1 - row;
+        }
+
+        // Em 8x16 o bit 0 do índice é ignorado
+        let tile = if height == 16 {
+            sprite.tile & 0xFE
+        } else {
+            sprite.tile
+        };
+        let addr = 0x8000 + (tile as u16) * 16 + (row as u16) * 2;
+        let lo = self.mem.vram(addr);
+        let hi = self.mem.vram(addr + 1);
+
+        let flip_x = (sprite.attributes & ATTR_FLIP_X) != 0;
+
+        for i in 0..8i16 {
+            let screen_x = sprite.x as i16 - 8 + i;
+            if screen_x < self.lx as i16 {
+                continue;
+            }
+
+            let bit = if flip_x { i } else { 7 - i } as u8;
+            let b0 = (lo >> bit) & 1;
+            let b1 = (hi >> bit) & 1;
+
+            let slot = (screen_x - self.lx as i16) as usize;
+            while self.obj_fifo.len() <= slot {
+                self.obj_fifo.push(Pixel::default());
+            }
+
+            let color = (b1 << 1) | b0;
+            if let Some(existing) = self.obj_fifo.get_mut(slot)
+                && (existing.color == 0 || (color != 0 && priority < existing.priority))
+            {
+                *existing = Pixel {
+                    color,
+                    palette: ((sprite.attributes & ATTR_PALETTE) != 0) as u8,
+                    bg_priority: (sprite.attributes & ATTR_BG_PRIORITY) != 0,
+                    priority,
+                };
+            }
+        }
+    }
+
+    // Paletas são lidas na hora da saída, então trocas no meio da linha aparecem. Devolve o
+    // tom nos bits 0-1 e a camada (pra colorização) nos bits 2-3
+    fn mix_pixel(&self, lcdc: u8, bg: Pixel, obj: Option<Pixel>) -> u8 {
+        let bg_color = if (lcdc & LCDC_BG_ENABLE) != 0 {
+            bg.color
+        } else {
+            0
+        };
+
+        if let Some(obj) = obj {
+            let visible = obj.color != 0 && (lcdc & LCDC_OBJ_ENABLE) != 0;
+            if visible && !(obj.bg_priority && bg_color != 0) {
+                let (palette, layer) = if obj.palette == 0 {
+                    (self.mem.reg(OBP0), LAYER_OBJ0)
+                } else {
+                    (self.mem.reg(OBP1), LAYER_OBJ1)
+                };
+                return ((palette >> (obj.color * 2)) & 0b11) | (layer << 2);
+            }
+        }
+
+        let bgp = self.mem.reg(BGP);
+        ((bgp >> (bg_color * 2)) & 0b11) | (LAYER_BG << 2)
+    }
+
+    fn update_lyc(&mut self, ly: u8) {
+        let lyc = self.mem.reg(LYC);
+        let mut stat = self.mem.reg(STAT);
+
+        if ly == lyc {
+            if stat & STAT_LYC_FLAG == 0 {
+                self.log(PpuEventKind::LycMatch);
+            }
+            stat |= STAT_LYC_FLAG; // coincidence flag
+        } else {
+            stat &= !STAT_LYC_FLAG;
+        }
+        self.mem.set_reg(STAT, stat);
+        self.update_stat_interrupt();
+    }
+
+    fn update_stat_interrupt(&mut self) {
+        let stat = self.mem.reg(STAT);
+
+        let line = ((stat & STAT_LYC_INT) != 0 && (stat & STAT_LYC_FLAG) != 0)
+            || ((stat & STAT_HBLANK_INT) != 0 && self.mode == MODE_HBLANK)
+            || ((stat & STAT_VBLANK_INT) != 0 && self.mode == MODE_VBLANK)
+            || ((stat & STAT_OAM_INT) != 0 && self.mode == MODE_OAM);
+
+        if line && !self.stat_line {
+            self.interrupts |= InterruptFlags::LCDSTAT;
+            self.log(PpuEventKind::StatInterrupt);
+        }
+        self.stat_line = line;
+    }
+
+    // Tons do DMG (0-3) do frame completo, uma vez só
+    pub fn take_frame(&mut self) -> Option<&[u8]> {
+        self.framebuffer.take_complete()
+    }
+
+    // Último frame completo, sem consumir (screenshots da automação)
+    pub fn frame(&self) -> &[u8] {
+        self.framebuffer.front()
+    }
+
+    pub fn frame_rgba(&self, out: &mut [u8]) {
+        let output = FrameOutput {
+            colors: self.colorization.as_ref(),
+            transform: self.color_transform.as_ref(),
+        };
+        self.framebuffer.front_rgba(out, &output);
+    }
+
+    // Número do último frame completo (dá a volta), pra identificar o front
+    pub fn frame_number(&self) -> u32 {
+        self.framebuffer.frames()
+    }
+
+    // Hash do último frame completo (FrameBuffer::hash), sem consumir o frame
+    pub fn frame_hash(&self) -> u32 {
+        self.framebuffer.hash()
+    }
+
+    // Entrou no VBlank desde a última consulta (limpa a flag)
+    pub fn take_vblank(&mut self) -> bool {
+        core::mem::take(&mut self.vblank_entered)
+    }
+
+    pub fn take_line_start(&mut self) -> Option<u8> {
+        self.line_started.take()
+    }
+
+    // Converte o frame completo pro formato do frontend; false se não há frame novo
+    pub fn take_frame_rgba(&mut self, out: &mut [u8]) -> bool {
+        let output = FrameOutput {
+            colors: self.colorization.as_ref(),
+            transform: self.color_transform.as_ref(),
+        };
+        self.framebuffer.take_complete_rgba(out, &output)
+    }
+
+    pub fn take_frame_rgb565(&mut self, out: &mut [u16]) -> bool {
+        let output = FrameOutput {
+            colors: self.colorization.as_ref(),
+            transform: self.color_transform.as_ref(),
+        };
+        self.framebuffer.take_complete_rgb565(out, &output)
+    }
+
+    fn set_mode(&mut self, mode: u8) {
+        self.mode = mode;
+        self.log(PpuEventKind::Mode(mode));
+
+        let mut stat = self.mem.reg(STAT);
+        stat = (stat & !0b11) | (mode & 0b11);
+        self.mem.set_reg(STAT, stat);
+        self.update_stat_interrupt();
+    }
+}
+
+impl Clocked for Ppu {
+    fn domain(&self) -> ClockDomain {
+        ClockDomain::System
+    }
+
+    fn tick(&mut self, t_cycles: u64) -> InterruptFlags {
+        let lcdc = self.mem.reg(LCDC);
+        if (lcdc & LCDC_ENABLE) == 0 {
+            if self.lcd_on {
+                self.disable_lcd();
+            }
+            return core::mem::take(&mut self.interrupts);
+        }
+
+        if !self.lcd_on {
+            self.enable_lcd();
+        }
+
+        let mut dots_to_advance = t_cycles as u16;
+
+        while dots_to_advance > 0 {
+            dots_to_advance -= 1;
+            self.dot += 1;
+
+            let ly = self.line;
+
+            match self.mode {
+                MODE_OAM if self.dot == OAM_DOTS => {
+                    self.oam_scan(ly);
+                    self.start_xfer();
+                    self.set_mode(MODE_XFER);
+                }
+                MODE_HBLANK if self.enable_line && self.dot == OAM_DOTS => {
+                    self.enable_line = false;
+                    self.line_sprites.clear();
+                    self.start_xfer();
+                    self.set_mode(MODE_XFER);
+                }
+                MODE_XFER => {
+                    self.xfer_dot(ly);
+
+                    if self.lx == SCREEN_WIDTH {
+                        self.set_mode(MODE_HBLANK);
+                    }
+                }
+                MODE_VBLANK if ly == LAST_LINE && self.dot == LINE_153_LY_RESET_DOT => {
+                    self.mem.set_reg(LY, 0);
+                    self.update_lyc(0);
+                }
+                _ => {}
+            }
+
+            // End of line
+            if self.dot >= DOTS_PER_LINE {
+                self.dot = 0;
+                self.end_line();
+            }
+        }
+
+        // No modo 2 a PPU lê uma linha de 8 bytes da OAM por M-cycle
+        let oam_row = if self.mode == MODE_OAM {
+            Some((self.dot / 4) as u8)
+        } else {
+            None
+        };
+        self.oam_scan_row = oam_row;
+        core::mem::take(&mut self.interrupts)
+    }
+}
+
+impl Savestate for Ppu {
+    fn save_state(&self, w: &mut StateWriter) {
+        self.mem.save_state(w);
+        self.framebuffer.save_state(w);
+        w.write_u8(self.mode);
+        w.write_u16(self.dot);
+        w.write_u8(self.line);
+
+        w.write_u8(self.line_sprites.len() as u8);
+        for sprite in &self.line_sprites {
+            w.write_bytes(&[sprite.y, sprite.x, sprite.tile, sprite.attributes]);
+        }
+
+        w.write_bool(self.lcd_on);
+        w.write_bool(self.enable_line);
+        w.write_bool(self.skip_frame);
+        w.write_bool(self.stat_line);
+
+        self.fetcher.save_state(w);
+        self.bg_fifo.save_state(w);
+        self.obj_fifo.save_state(w);
+        w.write_u8(self.lx);
+        w.write_u8(self.discard);
+        w.write_u8(self.startup_dots);
+        w.write_u16(self.fetched_sprites);
+        w.write_u8(self.fetching_sprite.map_or(0xFF, |index| index as u8));
+        w.write_u8(self.sprite_dots);
+
+        w.write_bool(self.window_triggered);
+        w.write_u8(self.window_line);
+        w.write_bool(self.window_drawn_this_line);
+        w.write_u8(self.oam_scan_row.unwrap_or(0xFF));
+        w.write_u8(self.opri);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.mem.load_state(r)?;
+        self.framebuffer.load_state(r)?;
+        self.mode = r.read_u8()?;
+        self.dot = r.read_u16()?;
+        self.line = r.read_u8()?;
+
+        let count = r.read_u8()? as usize;
+        if count > MAX_SPRITES_PER_LINE {
+            return Err("sprites por linha inválido no save state".to_string());
+        }
+        self.line_sprites.clear();
+        for _ in 0..count {
+            let mut bytes = [0; 4];
+            r.read_bytes(&mut bytes)?;
+            self.line_sprites.push(Sprite::from_oam(bytes));
+        }
+
+        self.lcd_on = r.read_bool()?;
+        self.enable_line = r.read_bool()?;
+        self.skip_frame = r.read_bool()?;
+        self.stat_line = r.read_bool()?;
+
+        self.fetcher.load_state(r)?;
+        self.bg_fifo.load_state(r)?;
+        self.obj_fifo.load_state(r)?;
+        self.lx = r.read_u8()?;
+        self.discard = r.read_u8()?;
+        self.startup_dots = r.read_u8()?;
+        self.fetched_sprites = r.read_u16()?;
+        self.fetching_sprite = match r.read_u8()? {
+            0xFF => None,
+            index => Some(index as usize),
+        };
+        self.sprite_dots = r.read_u8()?;
+
+        self.window_triggered = r.read_bool()?;
+        self.window_line = r.read_u8()?;
+        self.window_drawn_this_line = r.read_bool()?;
+        self.oam_scan_row = match r.read_u8()? {
+            0xFF => None,
+            row => Some(row),
+        };
+        self.opri = r.read_u8()? & 1;
+        Ok(())
+    }
+}
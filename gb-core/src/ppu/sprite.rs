@@ -0,0 +1,25 @@
+// Atributos (byte 3 da OAM)
+pub const ATTR_BG_PRIORITY: u8 = 1 << 7;
+pub const ATTR_FLIP_Y: u8 = 1 << 6;
+pub const ATTR_FLIP_X: u8 = 1 << 5;
+pub const ATTR_PALETTE: u8 = 1 << 4;
+
+// Entrada da OAM (4 bytes por objeto)
+#[derive(Copy, Clone)]
+pub struct Sprite {
+    pub y: u8,
+    pub x: u8,
+    pub tile: u8,
+    pub attributes: u8,
+}
+
+impl Sprite {
+    pub fn from_oam(bytes: [u8; 4]) -> Self {
+        Self {
+            y: bytes[0],
+            x: bytes[1],
+            tile: bytes[2],
+            attributes: bytes[3],
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use alloc::vec;
+
+use crate::bus::{Clocked, InterruptFlags, MemoryBus};
+use crate::cartridge::Cartridge;
+use crate::machine::{CYCLES_PER_FRAME, Model};
+use crate::ppu::{BGP, LCDC, LY, LYC, OAM_BASE, OBP0, OPRI, Ppu, STAT, VRAM_BASE};
+
+// Dois objetos na linha 0 que se cruzam nos x 0..3 da tela: o objeto 0 (cor 1) começa em
+// 0 e o objeto 1 (cor 3) sai pela esquerda em -4. Os dois são buscados no x 0, o 0
+// primeiro. Devolve o tom de cada x da sobreposição
+fn overlap(opri: u8) -> [u8; 4] {
+    let mut ppu = Ppu::new();
+    ppu.set_opri(opri);
+    for row in 0..8 {
+        ppu.write(VRAM_BASE + 16 + row * 2, 0xFF);
+        ppu.write(VRAM_BASE + 32 + row * 2, 0xFF);
+        ppu.write(VRAM_BASE + 32 + row * 2 + 1, 0xFF);
+    }
+    for (index, x, tile) in [(0, 8, 1), (1, 4, 2)] {
+        let entry = OAM_BASE + index * 4;
+        ppu.write(entry, 16);
+        ppu.write(entry + 1, x);
+        ppu.write(entry + 2, tile);
+        ppu.write(entry + 3, 0);
+    }
+    ppu.write(BGP, 0xE4);
+    ppu.write(OBP0, 0xE4);
+    ppu.write(LCDC, 0x83);
+
+    // O primeiro frame depois de ligar o LCD não é mostrado
+    for _ in 0..2 * CYCLES_PER_FRAME / 456 {
+        ppu.tick(456);
+    }
+    let frame = ppu.frame();
+    [0, 1, 2, 3].map(|x| frame[x] & 0b11)
+}
+
+#[test]
+fn objetos_sobrepostos_pelo_x_no_modo_dmg() {
+    // x menor vence mesmo com índice maior na OAM e buscado depois
+    assert_eq!(overlap(1), [3; 4]);
+}
+
+#[test]
+fn objetos_sobrepostos_pelo_indice_no_modo_cgb() {
+    assert_eq!(overlap(0), [1; 4]);
+}
+
+fn bus(model: Model, cgb_flag: u8) -> MemoryBus {
+    let mut rom = vec![0; 0x8000];
+    rom[0x143] = cgb_flag;
+    let mut bus = MemoryBus::new(Cartridge::load(rom).unwrap());
+    bus.model = model;
+    bus.reset();
+    bus
+}
+
+#[test]
+fn opri_depois_do_boot() {
+    assert_eq!(bus(Model::Cgb, 0x80).read(OPRI), 0xFE);
+    assert_eq!(bus(Model::Cgb, 0xC0).read(OPRI), 0xFE);
+    // Jogo de DMG no CGB: prioridade pelo x
+    assert_eq!(bus(Model::Cgb, 0x00).read(OPRI), 0xFF);
+    assert_eq!(bus(Model::Dmg, 0x80).read(OPRI), 0xFF);
+}
+
+#[test]
+fn opri_so_escreve_no_cgb() {
+    let mut cgb = bus(Model::Cgb, 0x80);
+    cgb.write(OPRI, 0x01);
+    assert_eq!(cgb.read(OPRI), 0xFF);
+    assert_eq!(cgb.ppu.opri(), 1);
+
+    let mut dmg = bus(Model::Dmg, 0x00);
+    dmg.write(OPRI, 0x00);
+    assert_eq!(dmg.read(OPRI), 0xFF);
+    assert_eq!(dmg.ppu.opri(), 1);
+}
+
+const LINE: u64 = 456;
+
+// LCD ligando agora com o fundo todo no tom 3 (tile 0 zerado, BGP = FF)
+fn lcd_on() -> Ppu {
+    let mut ppu = Ppu::new();
+    ppu.write(BGP, 0xFF);
+    ppu.write(LCDC, 0x91);
+    ppu
+}
+
+// Roda linhas inteiras e junta as interrupções pedidas
+fn run_lines(ppu: &mut Ppu, lines: u64) -> InterruptFlags {
+    (0..lines).fold(InterruptFlags::empty(), |flags, _| flags | ppu.tick(LINE))
+}
+
+fn mode(ppu: &Ppu) -> u8 {
+    ppu.read(STAT) & 0b11
+}
+
+#[test]
+fn desligar_o_lcd_no_meio_do_frame_apaga_a_tela() {
+    let mut ppu = lcd_on();
+    run_lines(&mut ppu, 2 * 154);
+    assert!(ppu.frame().iter().all(|&tone| tone == 3));
+
+    run_lines(&mut ppu, 50);
+    ppu.tick(100);
+    assert_eq!(ppu.read(LY), 50);
+    ppu.write(LCDC, 0x11);
+    ppu.tick(4);
+    assert_eq!(ppu.read(LY), 0);
+    assert_eq!(mode(&ppu), 0);
+    assert!(ppu.frame().iter().all(|&tone| tone == 0));
+
+    // Desligado, nada anda
+    run_lines(&mut ppu, 10);
+    assert_eq!(ppu.read(LY), 0);
+    assert_eq!(mode(&ppu), 0);
+}
+
+#[test]
+fn religar_o_lcd_comeca_na_linha_0_sem_oam_scan() {
+    let mut ppu = lcd_on();
+    run_lines(&mut ppu, 20);
+    ppu.write(LCDC, 0x11);
+    ppu.tick(4);
+
+    ppu.write(LCDC, 0x91);
+    // A primeira linha fica em modo 0 no lugar do modo 2 e só então desenha
+    ppu.tick(79);
+    assert_eq!(ppu.read(LY), 0);
+    assert_eq!(mode(&ppu), 0);
+    ppu.tick(1);
+    assert_eq!(mode(&ppu), 3);
+
+    // Da linha 1 em diante o tempo é o normal
+    ppu.tick(LINE - 80);
+    assert_eq!(ppu.read(LY), 1);
+    assert_eq!(mode(&ppu), 2);
+
+    // O primeiro frame depois de ligar não é mostrado
+    run_lines(&mut ppu, 153);
+    assert!(ppu.frame().iter().all(|&tone| tone == 0));
+    run_lines(&mut ppu, 154);
+    assert!(ppu.frame().iter().all(|&tone| tone == 3));
+}
+
+#[test]
+fn linha_153_le_ly_0_depois_de_4_dots() {
+    let mut ppu = Ppu::new();
+    ppu.write(LYC, 0);
+    // Interrupção do STAT só pela coincidência com o LYC
+    ppu.write(STAT, 0x40);
+    ppu.write(LCDC, 0x91);
+    assert!(ppu.tick(1).contains(InterruptFlags::LCDSTAT));
+    ppu.tick(LINE - 1);
+
+    run_lines(&mut ppu, 152);
+    assert_eq!(ppu.read(LY), 153);
+    assert_eq!(ppu.read(STAT) & 0x04, 0);
+
+    assert!(!ppu.tick(3).contains(InterruptFlags::LCDSTAT));
+    assert_eq!(ppu.read(LY), 153);
+    assert!(ppu.tick(1).contains(InterruptFlags::LCDSTAT));
+    assert_eq!(ppu.read(LY), 0);
+    assert_eq!(ppu.read(STAT) & 0x04, 0x04);
+
+    // A linha 0 de verdade continua a coincidência, sem outra interrupção
+    assert!(!ppu.tick(LINE - 4).contains(InterruptFlags::LCDSTAT));
+    assert_eq!(ppu.read(LY), 0);
+    assert_eq!(mode(&ppu), 2);
+}
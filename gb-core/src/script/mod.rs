@@ -0,0 +1,7 @@
+pub mod overlay;
+pub mod script;
+pub mod serial;
+
+pub use overlay::*;
+pub use script::*;
+pub use serial::*;
@@ -0,0 +1,17 @@
+// Desenhos do script por cima do jogo, em pixels do Game Boy (160x144); cor em 0xRRGGBBAA
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum OverlayItem {
+    Text {
+        x: i32,
+        y: i32,
+        text: String,
+        color: u32,
+    },
+    Rect {
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+        color: u32,
+    },
+}
@@ -0,0 +1,349 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use mlua::{Function, Lua, Table};
+
+use crate::bus::{AccessKind, MemoryAccess};
+use crate::joypad::{BUTTON_NAMES, Buttons};
+use crate::machine::Emulator;
+use crate::script::{LuaSerial, LuaSerialQueues, OverlayItem};
+
+const DEFAULT_COLOR: u32 = 0xFFFFFFFF;
+
+// Estado compartilhado entre as funções registradas no Lua e o emulador
+#[derive(Default)]
+struct Hooks {
+    frame: Vec<Function>,
+    serial: Vec<Function>,
+    // Por LY
+    scanline: HashMap<u8, Vec<Function>>,
+    memory: HashMap<(AccessKind, u16), Vec<Function>>,
+    // on_read/on_write mudaram e o barramento ainda não sabe
+    watches_changed: bool,
+    // Botões forçados pelo script; None = input do frontend
+    input: Option<Buttons>,
+    overlay: Vec<OverlayItem>,
+}
+
+// Script Lua com API `emu` (memória, input, callbacks) e `gui` (overlay).
+// Roda na thread de emulação: o estado do Lua não é Send.
+pub struct Script {
+    lua: Lua,
+    hooks: Rc<RefCell<Hooks>>,
+    // Filas do dispositivo serial "lua", que fica registrado na porta enquanto o script vive
+    serial: Arc<Mutex<LuaSerialQueues>>,
+    frame: u64,
+}
+
+impl Script {
+    // O corpo do script roda aqui; é nele que os callbacks são registrados
+    pub fn load(path: &Path, emulator: &mut Emulator) -> Result<Self, String> {
+        let source = fs::read_to_string(path)
+            .map_err(|erro| format!("erro ao ler '{}': {}", path.display(), erro))?;
+
+        let script = Self {
+            lua: Lua::new(),
+            hooks: Rc::new(RefCell::new(Hooks::default())),
+            serial: Arc::new(Mutex::new(LuaSerialQueues::default())),
+            frame: 0,
+        };
+
+        let queues = script.serial.clone();
+        emulator.bus.serial.devices.register("lua", move |_| {
+            Ok(Box::new(LuaSerial::new(queues.clone())))
+        });
+
+        script
+            .register_api()
+            .and_then(|()| {
+                script.with_emulator(emulator, || {
+                    script
+                        .lua
+                        .load(&source)
+                        .set_name(path.display().to_string())
+                        .exec()
+                })
+            })
+            .map_err(|erro| format!("erro no script '{}': {}", path.display(), erro))?;
+
+        Ok(script)
+    }
+
+    pub fn input(&self) -> Option<Buttons> {
+        self.hooks.borrow().input
+    }
+
+    // Chamado a cada instrução que tocou um endereço observado
+    pub fn memory_hooks(&mut self, emulator: &mut Emulator) -> Result<(), String> {
+        let accesses = emulator.bus.watch.take_hits();
+
+        // Clona os callbacks: um callback pode registrar outros enquanto roda
+        let calls: Vec<(Function, MemoryAccess)> = {
+            let hooks = self.hooks.borrow();
+            accesses
+                .iter()
+                .flat_map(|access| {
+                    hooks
+                        .memory
+                        .get(&(access.kind, access.addr))
+                        .into_iter()
+                        .flatten()
+                        .map(move |callback| (callback.clone(), *access))
+                })
+                .collect()
+        };
+
+        self.with_emulator(emulator, || {
+            for (callback, access) in &calls {
+                callback.call::<()>((access.addr, access.value))?;
+            }
+            Ok(())
+        })
+        .map_err(|erro| erro.to_string())
+    }
+
+    // Sem callback na linha, o laço não paga nada além da consulta
+    pub fn watches_line(&self, line: u8) -> bool {
+        self.hooks.borrow().scanline.contains_key(&line)
+    }
+
+    // Começo da linha `line`, com LY já valendo ela
+    pub fn scanline_hooks(&mut self, emulator: &mut Emulator, line: u8) -> Result<(), String> {
+        let callbacks = self.hooks.borrow().scanline.get(&line).cloned();
+        self.with_emulator(emulator, || {
+            for callback in callbacks.iter().flatten() {
+                callback.call::<()>(line)?;
+            }
+            Ok(())
+        })
+        .map_err(|erro| erro.to_string())
+    }
+
+    // Fim de frame: entrega os bytes do serial pros emu.on_serial, roda os emu.on_frame e
+    // devolve o que foi desenhado desde o último frame
+    pub fn end_frame(&mut self, emulator: &mut Emulator) -> Result<Vec<OverlayItem>, String> {
+        self.frame += 1;
+        let frame = self.frame;
+        let callbacks = self.hooks.borrow().frame.clone();
+        let serial_callbacks = self.hooks.borrow().serial.clone();
+        let sent = std::mem::take(&mut self.serial.lock().unwrap().sent);
+
+        self.with_emulator(emulator, || {
+            // Um número devolvido vira a resposta de uma próxima transferência
+            for byte in sent {
+                for callback in &serial_callbacks {
+                    if let Some(reply) = callback.call::<Option<u8>>(byte)? {
+                        self.serial.lock().unwrap().replies.push_back(reply);
+                    }
+                }
+            }
+            for callback in &callbacks {
+                callback.call::<()>(frame)?;
+            }
+            Ok(())
+        })
+        .map_err(|erro| erro.to_string())?;
+
+        Ok(std::mem::take(&mut self.hooks.borrow_mut().overlay))
+    }
+
+    // Funções que não mexem no emulador: ficam registradas pra sempre
+    fn register_api(&self) -> mlua::Result<()> {
+        let lua = &self.lua;
+        let emu = lua.create_table()?;
+        let gui = lua.create_table()?;
+
+        let hooks = self.hooks.clone();
+        emu.set(
+            "on_frame",
+            lua.create_function(move |_, callback: Function| {
+                hooks.borrow_mut().frame.push(callback);
+                Ok(())
+            })?,
+        )?;
+
+        let hooks = self.hooks.clone();
+        emu.set(
+            "on_serial",
+            lua.create_function(move |_, callback: Function| {
+                hooks.borrow_mut().serial.push(callback);
+                Ok(())
+            })?,
+        )?;
+
+        let hooks = self.hooks.clone();
+        emu.set(
+            "on_scanline",
+            lua.create_function(move |_, (line, callback): (u8, Function)| {
+                if line > 153 {
+                    return Err(mlua::Error::runtime(format!("linha inválida: {}", line)));
+                }
+                hooks.borrow_mut().scanline.entry(line).or_default().push(callback);
+                Ok(())
+            })?,
+        )?;
+
+        // Byte mandado pelo script dando o clock, com o dispositivo "lua" na porta
+        let queues = self.serial.clone();
+        emu.set(
+            "serial_send",
+            lua.create_function(move |_, value: u8| {
+                queues.lock().unwrap().clocked.push_back(value);
+                Ok(())
+            })?,
+        )?;
+
+        for (name, kind) in [
+            ("on_read", AccessKind::Read),
+            ("on_write", AccessKind::Write),
+        ] {
+            let hooks = self.hooks.clone();
+            emu.set(
+                name,
+                lua.create_function(move |_, (addr, callback): (u16, Function)| {
+                    let mut hooks = hooks.borrow_mut();
+                    hooks.memory.entry((kind, addr)).or_default().push(callback);
+                    hooks.watches_changed = true;
+                    Ok(())
+                })?,
+            )?;
+        }
+
+        let hooks = self.hooks.clone();
+        emu.set(
+            "set_input",
+            lua.create_function(move |_, input: Option<Table>| {
+                hooks.borrow_mut().input =
+                    input.map(|table| buttons_from_table(&table)).transpose()?;
+                Ok(())
+            })?,
+        )?;
+
+        let hooks = self.hooks.clone();
+        gui.set(
+            "text",
+            lua.create_function(
+                move |_, (x, y, text, color): (i32, i32, String, Option<u32>)| {
+                    hooks.borrow_mut().overlay.push(OverlayItem::Text {
+                        x,
+                        y,
+                        text,
+                        color: color.unwrap_or(DEFAULT_COLOR),
+                    });
+                    Ok(())
+                },
+            )?,
+        )?;
+
+        let hooks = self.hooks.clone();
+        gui.set(
+            "rect",
+            lua.create_function(
+                move |_, (x, y, w, h, color): (i32, i32, i32, i32, Option<u32>)| {
+                    hooks.borrow_mut().overlay.push(OverlayItem::Rect {
+                        x,
+                        y,
+                        w,
+                        h,
+                        color: color.unwrap_or(DEFAULT_COLOR),
+                    });
+                    Ok(())
+                },
+            )?,
+        )?;
+
+        lua.globals().set("emu", emu)?;
+        lua.globals().set("gui", gui)?;
+        Ok(())
+    }
+
+    // emu.read/emu.write/emu.get_input só existem enquanto o emulador está emprestado pro
+    // script; guardar essas funções e chamar depois dá erro no Lua
+    fn with_emulator<R>(
+        &self,
+        emulator: &mut Emulator,
+        body: impl FnOnce() -> mlua::Result<R>,
+    ) -> mlua::Result<R> {
+        let cell = RefCell::new(emulator);
+
+        let result = self.lua.scope(|scope| {
+            let emu: Table = self.lua.globals().get("emu")?;
+            emu.set(
+                "read",
+                scope.create_function(|_, addr: u16| Ok(cell.borrow().bus.peek(addr)))?,
+            )?;
+            emu.set(
+                "read_range",
+                scope.create_function(|_, (addr, len): (u16, usize)| {
+                    Ok(cell.borrow().bus.peek_range(addr, len))
+                })?,
+            )?;
+            emu.set(
+                "write",
+                scope.create_function(|_, (addr, value): (u16, u8)| {
+                    cell.borrow_mut().bus.write(addr, value);
+                    Ok(())
+                })?,
+            )?;
+            // Hashes pra scripts de regressão compararem com valores de referência
+            emu.set(
+                "frame_hash",
+                scope.create_function(|_, ()| Ok(cell.borrow().bus.ppu.frame_hash()))?,
+            )?;
+            emu.set(
+                "state_hash",
+                scope.create_function(|_, ()| Ok(cell.borrow().state_hash()))?,
+            )?;
+            // Porta IR do CGB: o script pode fazer o papel do outro lado
+            emu.set(
+                "ir_led",
+                scope.create_function(|_, ()| Ok(cell.borrow().bus.ir_led()))?,
+            )?;
+            emu.set(
+                "set_ir_light",
+                scope.create_function(|_, light: Option<bool>| {
+                    cell.borrow_mut().bus.ir_light = light;
+                    Ok(())
+                })?,
+            )?;
+            emu.set(
+                "get_input",
+                scope.create_function(|lua, ()| {
+                    buttons_to_table(lua, cell.borrow().bus.joypad.buttons())
+                })?,
+            )?;
+            body()
+        });
+
+        let emulator = cell.into_inner();
+        let mut hooks = self.hooks.borrow_mut();
+        if hooks.watches_changed {
+            hooks.watches_changed = false;
+            emulator.bus.watch.set(hooks.memory.keys().copied());
+        }
+
+        result
+    }
+}
+
+fn buttons_from_table(table: &Table) -> mlua::Result<Buttons> {
+    let mut buttons = Buttons::empty();
+    for (name, button) in BUTTON_NAMES {
+        if table.get::<bool>(name)? {
+            buttons |= button;
+        }
+    }
+    Ok(buttons)
+}
+
+fn buttons_to_table(lua: &Lua, buttons: Buttons) -> mlua::Result<Table> {
+    let table = lua.create_table()?;
+    for (name, button) in BUTTON_NAMES {
+        table.set(name, buttons.contains(button))?;
+    }
+    Ok(table)
+}
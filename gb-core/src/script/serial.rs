@@ -0,0 +1,55 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::bus::SerialDevice;
+
+// Filas entre o dispositivo (na porta serial, a cada transferência) e o script (no fim do
+// frame)
+#[derive(Default)]
+pub struct LuaSerialQueues {
+    // Bytes que o jogo mandou, pros emu.on_serial
+    pub sent: Vec<u8>,
+    // Respostas dos callbacks, uma por transferência seguinte
+    pub replies: VecDeque<u8>,
+    // emu.serial_send: bytes que o script manda dando o clock
+    pub clocked: VecDeque<u8>,
+}
+
+// Dispositivo "lua": o script faz o papel do outro lado. Os callbacks rodam no fim do
+// frame, então cada resposta vale pra uma transferência depois; sem resposta na fila a
+// linha fica em 1 (0xFF)
+pub struct LuaSerial {
+    queues: Arc<Mutex<LuaSerialQueues>>,
+    // SB do jogo, que sai quando o script dá o clock
+    sb: u8,
+}
+
+impl LuaSerial {
+    pub fn new(queues: Arc<Mutex<LuaSerialQueues>>) -> Self {
+        Self { queues, sb: 0xFF }
+    }
+}
+
+impl SerialDevice for LuaSerial {
+    fn transfer(&mut self, sent: u8) -> u8 {
+        let mut queues = self.queues.lock().unwrap();
+        queues.sent.push(sent);
+        queues.replies.pop_front().unwrap_or(0xFF)
+    }
+
+    fn poll(&mut self) -> Option<u8> {
+        let mut queues = self.queues.lock().unwrap();
+        let value = queues.clocked.pop_front()?;
+        queues.sent.push(self.sb);
+        self.sb = value;
+        Some(value)
+    }
+
+    fn set_sb(&mut self, value: u8) {
+        self.sb = value;
+    }
+
+    fn drives_clock(&self) -> bool {
+        true
+    }
+}
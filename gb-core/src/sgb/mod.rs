@@ -0,0 +1,3 @@
+pub mod sgb;
+
+pub use sgb::*;
@@ -0,0 +1,536 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::ppu::{GB_H, GB_W};
+use crate::state::{StateReader, StateWriter};
+
+// Quadro do SGB: a tela do Game Boy no meio da moldura
+pub const SGB_W: usize = 256;
+pub const SGB_H: usize = 224;
+const SCREEN_X: usize = (SGB_W - GB_W) / 2;
+const SCREEN_Y: usize = (SGB_H - GB_H) / 2;
+
+const PACKET_SIZE: usize = 16;
+const PACKET_BITS: usize = PACKET_SIZE * 8;
+// Comandos com vários pacotes têm no máximo 7
+const MAX_PACKETS: usize = 7;
+
+// Atributos: um palette (0-3) por bloco de 8x8 da tela
+const ATTR_W: usize = GB_W / 8;
+const ATTR_H: usize = GB_H / 8;
+const ATTR_FILES: usize = 45;
+const ATTR_FILE_SIZE: usize = ATTR_W * ATTR_H / 4;
+
+// Transferências por VRAM: 4 KB que o jogo desenha na tela
+pub const TRANSFER_SIZE: usize = 0x1000;
+const SYSTEM_PALETTES: usize = 512;
+// Moldura: 256 tiles 4bpp, mapa 32x32 (só 28 linhas aparecem) e paletas 4-7
+const BORDER_TILES: usize = 256;
+const BORDER_TILE_SIZE: usize = 32;
+const BORDER_MAP_W: usize = 32;
+const BORDER_PALETTES: usize = 4;
+
+const CMD_PAL01: u8 = 0x00;
+const CMD_PAL23: u8 = 0x01;
+const CMD_PAL03: u8 = 0x02;
+const CMD_PAL12: u8 = 0x03;
+const CMD_ATTR_BLK: u8 = 0x04;
+const CMD_ATTR_LIN: u8 = 0x05;
+const CMD_ATTR_DIV: u8 = 0x06;
+const CMD_ATTR_CHR: u8 = 0x07;
+const CMD_PAL_SET: u8 = 0x0A;
+const CMD_PAL_TRN: u8 = 0x0B;
+const CMD_MLT_REQ: u8 = 0x11;
+const CMD_CHR_TRN: u8 = 0x13;
+const CMD_PCT_TRN: u8 = 0x14;
+const CMD_ATTR_TRN: u8 = 0x15;
+const CMD_ATTR_SET: u8 = 0x16;
+const CMD_MASK_EN: u8 = 0x17;
+
+// Cores BGR555 do SNES
+fn rgb(color: u16) -> [u8; 3] {
+    let channel = |shift: u16| {
+        let value = ((color >> shift) & 0x1F) as u8;
+        (value << 3) | (value >> 2)
+    };
+    [channel(0), channel(5), channel(10)]
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mask {
+    None,
+    // Tela congelada no último frame
+    Freeze,
+    Black,
+    // Cor 0 da paleta 0
+    Color0,
+}
+
+impl Mask {
+    fn from(value: u8) -> Self {
+        match value & 0x03 {
+            1 => Mask::Freeze,
+            2 => Mask::Black,
+            3 => Mask::Color0,
+            _ => Mask::None,
+        }
+    }
+
+    fn bits(self) -> u8 {
+        match self {
+            Mask::None => 0,
+            Mask::Freeze => 1,
+            Mask::Black => 2,
+            Mask::Color0 => 3,
+        }
+    }
+}
+
+// Super Game Boy: pacotes de comando chegam pelos bits P14/P15 do P1. Cuida de paletas,
+// atributos, moldura, máscara da tela e do multiplayer
+pub struct Sgb {
+    // Recepção do pacote atual (só depois de um pulso de reset, P14 e P15 em 0)
+    receiving: bool,
+    bits: usize,
+    packet: [u8; PACKET_SIZE],
+    packets: Vec<[u8; PACKET_SIZE]>,
+    previous_p1: u8,
+    // Multiplayer (MLT_REQ): jogadores 1, 2 ou 4 e qual está sendo lido
+    players: u8,
+    player: u8,
+    palettes: [[u16; 4]; 4],
+    system_palettes: Vec<[u16; 4]>,
+    attributes: Vec<u8>,
+    attribute_files: Vec<u8>,
+    border_tiles: Vec<u8>,
+    border_map: Vec<u16>,
+    border_palettes: [[u16; 16]; BORDER_PALETTES],
+    mask: Mask,
+    // Tela mostrada (tons do DMG), mantida enquanto a máscara congela
+    screen: Vec<u8>,
+}
+
+impl Sgb {
+    pub fn new() -> Self {
+        Self {
+            receiving: false,
+            bits: 0,
+            packet: [0; PACKET_SIZE],
+            packets: Vec::new(),
+            previous_p1: 0x30,
+            players: 1,
+            player: 0,
+            palettes: [[0x7FFF, 0x56B5, 0x294A, 0x0000]; 4],
+            system_palettes: vec![[0; 4]; SYSTEM_PALETTES],
+            attributes: vec![0; ATTR_W * ATTR_H],
+            attribute_files: vec![0; ATTR_FILES * ATTR_FILE_SIZE],
+            border_tiles: vec![0; BORDER_TILES * BORDER_TILE_SIZE],
+            border_map: vec![0; BORDER_MAP_W * BORDER_MAP_W],
+            border_palettes: [[0; 16]; BORDER_PALETTES],
+            mask: Mask::None,
+            screen: vec![0; GB_W * GB_H],
+        }
+    }
+
+    // Escrita no P1. `transfer` lê os 4 KB da tela, usado pelos comandos *_TRN
+    pub fn write_joypad(&mut self, data: u8, transfer: impl FnOnce() -> Vec<u8>) {
+        let lines = data & 0x30;
+        let previous = core::mem::replace(&mut self.previous_p1, lines);
+
+        match lines {
+            0x00 => {
+                self.receiving = true;
+                self.bits = 0;
+                self.packet = [0; PACKET_SIZE];
+            }
+            // Cada bit é um pulso em P14 (0) ou P15 (1) saindo de 0x30
+            0x10 | 0x20 if self.receiving && previous == 0x30 => {
+                let bit = lines == 0x10;
+                if self.bits == PACKET_BITS {
+                    // Bit de parada (sempre 0): pacote completo
+                    self.receiving = false;
+                    if !bit {
+                        self.packet_done(transfer);
+                    }
+                } else {
+                    if bit {
+                        self.packet[self.bits / 8] |= 1 << (self.bits % 8);
+                    }
+                    self.bits += 1;
+                }
+            }
+            // Subida do P15 fora de um pacote passa pro próximo controle
+            0x30 if !self.receiving && previous & 0x20 == 0 && self.players > 1 => {
+                self.player = (self.player + 1) % self.players;
+            }
+            _ => {}
+        }
+    }
+
+    // Com multiplayer ligado e nenhuma linha selecionada, o P1 devolve qual controle está
+    // sendo lido; os controles 2-4 não têm nada apertado
+    pub fn read_joypad(&self, value: u8) -> u8 {
+        if self.players == 1 {
+            return value;
+        }
+        if value & 0x30 == 0x30 {
+            (value & 0xF0) | (0x0F - self.player)
+        } else if self.player != 0 {
+            value | 0x0F
+        } else {
+            value
+        }
+    }
+
+    fn packet_done(&mut self, transfer: impl FnOnce() -> Vec<u8>) {
+        if self.packets.is_empty() && self.packet[0] & 0x07 == 0 {
+            // Pacote com tamanho 0 não é comando
+            return;
+        }
+        self.packets.push(self.packet);
+        let length = (self.packets[0][0] & 0x07) as usize;
+        if self.packets.len() < length.min(MAX_PACKETS) {
+            return;
+        }
+
+        let data: Vec<u8> = core::mem::take(&mut self.packets).concat();
+        self.command(&data, transfer);
+    }
+
+    fn command(&mut self, data: &[u8], transfer: impl FnOnce() -> Vec<u8>) {
+        match data[0] >> 3 {
+            CMD_PAL01 => self.set_palettes(0, 1, data),
+            CMD_PAL23 => self.set_palettes(2, 3, data),
+            CMD_PAL03 => self.set_palettes(0, 3, data),
+            CMD_PAL12 => self.set_palettes(1, 2, data),
+            CMD_ATTR_BLK => self.attr_blk(data),
+            CMD_ATTR_LIN => self.attr_lin(data),
+            CMD_ATTR_DIV => self.attr_div(data),
+            CMD_ATTR_CHR => self.attr_chr(data),
+            CMD_PAL_SET => self.pal_set(data),
+            CMD_PAL_TRN => {
+                let vram = transfer();
+                for (palette, colors) in self.system_palettes.iter_mut().zip(vram.chunks(8)) {
+                    for (color, bytes) in palette.iter_mut().zip(colors.chunks(2)) {
+                        *color = u16::from_le_bytes([bytes[0], bytes[1]]);
+                    }
+                }
+            }
+            CMD_MLT_REQ => {
+                self.players = match data[1] & 0x03 {
+                    1 => 2,
+                    3 => 4,
+                    _ => 1,
+                };
+                self.player = 0;
+            }
+            CMD_CHR_TRN => {
+                let half = (data[1] & 0x01) as usize * TRANSFER_SIZE;
+                self.border_tiles[half..half + TRANSFER_SIZE].copy_from_slice(&transfer());
+            }
+            CMD_PCT_TRN => {
+                let vram = transfer();
+                for (entry, bytes) in self.border_map.iter_mut().zip(vram.chunks(2)) {
+                    *entry = u16::from_le_bytes([bytes[0], bytes[1]]);
+                }
+                let colors = &vram[0x800..];
+                for (palette, colors) in self.border_palettes.iter_mut().zip(colors.chunks(32)) {
+                    for (color, bytes) in palette.iter_mut().zip(colors.chunks(2)) {
+                        *color = u16::from_le_bytes([bytes[0], bytes[1]]);
+                    }
+                }
+            }
+            CMD_ATTR_TRN => {
+                let vram = transfer();
+                let size = self.attribute_files.len();
+                self.attribute_files.copy_from_slice(&vram[..size]);
+            }
+            CMD_ATTR_SET => {
+                self.apply_attribute_file(data[1] & 0x3F);
+                if data[1] & 0x40 != 0 {
+                    self.mask = Mask::None;
+                }
+            }
+            CMD_MASK_EN => self.mask = Mask::from(data[1]),
+            // Som, ícone, JUMP e afins não têm efeito aqui
+            _ => {}
+        }
+    }
+
+    // Cor 0 é compartilhada pelas quatro paletas
+    fn set_palettes(&mut self, first: usize, second: usize, data: &[u8]) {
+        let color = |index: usize| u16::from_le_bytes([data[1 + index * 2], data[2 + index * 2]]);
+        for palette in self.palettes.iter_mut() {
+            palette[0] = color(0);
+        }
+        for i in 1..4 {
+            self.palettes[first][i] = color(i);
+            self.palettes[second][i] = color(i + 3);
+        }
+    }
+
+    fn set_attribute(&mut self, x: usize, y: usize, palette: u8) {
+        if x < ATTR_W && y < ATTR_H {
+            self.attributes[y * ATTR_W + x] = palette & 0x03;
+        }
+    }
+
+    // Retângulos com paleta pra dentro, pra borda e pra fora
+    fn attr_blk(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+        for set in data[2..].chunks_exact(6).take(count) {
+            let control = set[0] & 0x07;
+            let inside = set[1] & 0x03;
+            let mut border = (set[1] >> 2) & 0x03;
+            let outside = (set[1] >> 4) & 0x03;
+            let (x1, y1, x2, y2) = (
+                set[2] as usize & 0x1F,
+                set[3] as usize & 0x1F,
+                set[4] as usize & 0x1F,
+                set[5] as usize & 0x1F,
+            );
+            // Só dentro ou só fora: a borda vai junto
+            let mut border_on = control & 0x02 != 0;
+            if control == 0x01 {
+                border = inside;
+                border_on = true;
+            } else if control == 0x04 {
+                border = outside;
+                border_on = true;
+            }
+
+            for y in 0..ATTR_H {
+                for x in 0..ATTR_W {
+                    let within = (x1..=x2).contains(&x) && (y1..=y2).contains(&y);
+                    let edge = within && (x == x1 || x == x2 || y == y1 || y == y2);
+                    if edge {
+                        if border_on {
+                            self.set_attribute(x, y, border);
+                        }
+                    } else if within {
+                        if control & 0x01 != 0 {
+                            self.set_attribute(x, y, inside);
+                        }
+                    } else if control & 0x04 != 0 {
+                        self.set_attribute(x, y, outside);
+                    }
+                }
+            }
+        }
+    }
+
+    // Linhas ou colunas inteiras
+    fn attr_lin(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+        for &line in data[2..].iter().take(count) {
+            let index = (line & 0x1F) as usize;
+            let palette = (line >> 5) & 0x03;
+            if line & 0x80 != 0 {
+                for x in 0..ATTR_W {
+                    self.set_attribute(x, index, palette);
+                }
+            } else {
+                for y in 0..ATTR_H {
+                    self.set_attribute(index, y, palette);
+                }
+            }
+        }
+    }
+
+    // Tela dividida numa linha ou coluna: antes, na divisão e depois
+    fn attr_div(&mut self, data: &[u8]) {
+        let after = data[1] & 0x03;
+        let before = (data[1] >> 2) & 0x03;
+        let line = (data[1] >> 4) & 0x03;
+        let horizontal = data[1] & 0x40 != 0;
+        let split = data[2] as usize & 0x1F;
+        for y in 0..ATTR_H {
+            for x in 0..ATTR_W {
+                let position = if horizontal { y } else { x };
+                let palette = match position.cmp(&split) {
+                    core::cmp::Ordering::Less => before,
+                    core::cmp::Ordering::Equal => line,
+                    core::cmp::Ordering::Greater => after,
+                };
+                self.set_attribute(x, y, palette);
+            }
+        }
+    }
+
+    // Bloco a bloco a partir de (x, y), 2 bits por bloco
+    fn attr_chr(&mut self, data: &[u8]) {
+        let (mut x, mut y) = (data[1] as usize, data[2] as usize);
+        let count = u16::from_le_bytes([data[3], data[4]]) as usize;
+        let vertical = data[5] & 0x01 != 0;
+        for i in 0..count.min(ATTR_W * ATTR_H) {
+            let Some(&byte) = data.get(6 + i / 4) else {
+                break;
+            };
+            self.set_attribute(x, y, byte >> (6 - (i % 4) * 2));
+            if vertical {
+                y += 1;
+                if y == ATTR_H {
+                    y = 0;
+                    x = (x + 1) % ATTR_W;
+                }
+            } else {
+                x += 1;
+                if x == ATTR_W {
+                    x = 0;
+                    y = (y + 1) % ATTR_H;
+                }
+            }
+        }
+    }
+
+    // Quatro paletas do sistema (PAL_TRN) e, opcionalmente, um arquivo de atributos
+    fn pal_set(&mut self, data: &[u8]) {
+        for i in 0..4 {
+            let index = u16::from_le_bytes([data[1 + i * 2], data[2 + i * 2]]) as usize & 0x1FF;
+            self.palettes[i] = self.system_palettes[index];
+        }
+        // A cor 0 da paleta 0 vale pra todas
+        let shared = self.palettes[0][0];
+        for palette in self.palettes.iter_mut() {
+            palette[0] = shared;
+        }
+        let flags = data[9];
+        if flags & 0x80 != 0 {
+            self.apply_attribute_file(flags & 0x3F);
+        }
+        if flags & 0x40 != 0 {
+            self.mask = Mask::None;
+        }
+    }
+
+    fn apply_attribute_file(&mut self, file: u8) {
+        let file = file as usize;
+        if file >= ATTR_FILES {
+            return;
+        }
+        let start = file * ATTR_FILE_SIZE;
+        for i in 0..ATTR_W * ATTR_H {
+            let byte = self.attribute_files[start + i / 4];
+            self.attributes[i] = (byte >> (6 - (i % 4) * 2)) & 0x03;
+        }
+    }
+
+    // Frame completo (tons do DMG, 160x144) -> quadro RGBA de 256x224 com a moldura
+    pub fn render(&mut self, shades: &[u8], out: &mut [u8]) {
+        if self.mask != Mask::Freeze {
+            self.screen.copy_from_slice(shades);
+        }
+
+        let backdrop = rgb(self.palettes[0][0]);
+        for y in 0..SGB_H {
+            for x in 0..SGB_W {
+                let color = self.border_pixel(x, y).unwrap_or(backdrop);
+                out[(y * SGB_W + x) * 4..][..4]
+                    .copy_from_slice(&[color[0], color[1], color[2], 255]);
+            }
+        }
+
+        for y in 0..GB_H {
+            for x in 0..GB_W {
+                let color = match self.mask {
+                    Mask::Black => [0, 0, 0],
+                    Mask::Color0 => backdrop,
+                    Mask::None | Mask::Freeze => {
+                        let palette = self.attributes[(y / 8) * ATTR_W + x / 8] as usize;
+                        rgb(self.palettes[palette][(self.screen[y * GB_W + x] & 0x03) as usize])
+                    }
+                };
+                let offset = ((SCREEN_Y + y) * SGB_W + SCREEN_X + x) * 4;
+                out[offset..offset + 4].copy_from_slice(&[color[0], color[1], color[2], 255]);
+            }
+        }
+    }
+
+    // Pixel da moldura; None = transparente (cor 0)
+    fn border_pixel(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        let entry = self.border_map[(y / 8) * BORDER_MAP_W + x / 8];
+        let tile = (entry & 0xFF) as usize;
+        let palette = ((entry >> 10) & 0x07) as usize;
+        let (mut px, mut py) = (x % 8, y % 8);
+        if entry & 0x4000 != 0 {
+            px = 7 - px;
+        }
+        if entry & 0x8000 != 0 {
+            py = 7 - py;
+        }
+
+        // 4bpp do SNES: planos 0/1 nos 16 primeiros bytes, 2/3 nos 16 seguintes
+        let data = &self.border_tiles[tile * BORDER_TILE_SIZE..][..BORDER_TILE_SIZE];
+        let bit = 7 - px;
+        let color = ((data[py * 2] >> bit) & 1)
+            | (((data[py * 2 + 1] >> bit) & 1) << 1)
+            | (((data[16 + py * 2] >> bit) & 1) << 2)
+            | (((data[16 + py * 2 + 1] >> bit) & 1) << 3);
+        if color == 0 || !(4..8).contains(&palette) {
+            return None;
+        }
+        Some(rgb(self.border_palettes[palette - 4][color as usize]))
+    }
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.receiving);
+        w.write_u8(self.bits as u8);
+        w.write_bytes(&self.packet);
+        w.write_u8(self.packets.len() as u8);
+        for packet in &self.packets {
+            w.write_bytes(packet);
+        }
+        w.write_u8(self.previous_p1);
+        w.write_u8(self.players);
+        w.write_u8(self.player);
+        for color in self.palettes.iter().flatten() {
+            w.write_u16(*color);
+        }
+        for color in self.system_palettes.iter().flatten() {
+            w.write_u16(*color);
+        }
+        w.write_bytes(&self.attributes);
+        w.write_bytes(&self.attribute_files);
+        w.write_bytes(&self.border_tiles);
+        for entry in &self.border_map {
+            w.write_u16(*entry);
+        }
+        for color in self.border_palettes.iter().flatten() {
+            w.write_u16(*color);
+        }
+        w.write_u8(self.mask.bits());
+        w.write_bytes(&self.screen);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.receiving = r.read_bool()?;
+        self.bits = (r.read_u8()? as usize).min(PACKET_BITS);
+        r.read_bytes(&mut self.packet)?;
+        let packets = (r.read_u8()? as usize).min(MAX_PACKETS);
+        self.packets = vec![[0; PACKET_SIZE]; packets];
+        for packet in self.packets.iter_mut() {
+            r.read_bytes(packet)?;
+        }
+        self.previous_p1 = r.read_u8()?;
+        self.players = r.read_u8()?;
+        self.player = r.read_u8()? % self.players.max(1);
+        for color in self.palettes.iter_mut().flatten() {
+            *color = r.read_u16()?;
+        }
+        for color in self.system_palettes.iter_mut().flatten() {
+            *color = r.read_u16()?;
+        }
+        r.read_bytes(&mut self.attributes)?;
+        r.read_bytes(&mut self.attribute_files)?;
+        r.read_bytes(&mut self.border_tiles)?;
+        for entry in self.border_map.iter_mut() {
+            *entry = r.read_u16()?;
+        }
+        for color in self.border_palettes.iter_mut().flatten() {
+            *color = r.read_u16()?;
+        }
+        self.mask = Mask::from(r.read_u8()?);
+        r.read_bytes(&mut self.screen)
+    }
+}
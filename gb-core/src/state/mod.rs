@@ -0,0 +1,5 @@
+pub mod state;
+#[cfg(test)]
+mod tests;
+
+pub use state::*;
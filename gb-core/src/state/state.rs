@@ -0,0 +1,260 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+// Formato binário dos save states: cabeçalho + um bloco por componente (tag, versão do
+// layout do componente, tamanho, dados). Até a versão 7 os componentes iam em sequência,
+// sem blocos
+pub const STATE_MAGIC: &[u8; 4] = b"GBST";
+pub const STATE_VERSION: u16 = 8;
+// Mais antiga que ainda carrega (a 7, sem blocos, é lida componente por componente)
+pub const MIN_STATE_VERSION: u16 = 7;
+// Primeira com blocos
+pub const BLOCK_STATE_VERSION: u16 = 8;
+
+pub trait Savestate {
+    fn save_state(&self, w: &mut StateWriter);
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String>;
+}
+
+// Tag de um bloco e a versão do layout dele: o STATE_VERSION em que o save_state do
+// componente mudou por último. Quem muda um save_state sobe a versão do bloco junto
+pub type BlockId = (&'static [u8; 4], u16);
+
+pub struct StateWriter {
+    buf: Vec<u8>,
+}
+
+impl StateWriter {
+    pub fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.buf.push(value as u8);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    // Bloco de tamanho fixo (o leitor já sabe o tamanho)
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    pub fn write_block(&mut self, (tag, version): BlockId, save: impl FnOnce(&mut Self)) {
+        let mut block = StateWriter::new();
+        save(&mut block);
+        self.write_bytes(tag);
+        self.write_u16(version);
+        self.write_u32(block.buf.len() as u32);
+        self.write_bytes(&block.buf);
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+pub struct StateReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    // Versão com que os dados foram gravados, pros load_state lerem campos que não existiam
+    // antes como o valor padrão
+    version: u16,
+}
+
+impl<'a> StateReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::with_version(data, STATE_VERSION)
+    }
+
+    pub fn with_version(data: &'a [u8], version: u16) -> Self {
+        Self {
+            data,
+            pos: 0,
+            version,
+        }
+    }
+
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos + len;
+        if end > self.data.len() {
+            return Err("save state truncado".to_string());
+        }
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, String> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn read_bytes(&mut self, out: &mut [u8]) -> Result<(), String> {
+        out.copy_from_slice(self.take(out.len())?);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.data.len()
+    }
+}
+
+struct StateBlock<'a> {
+    tag: [u8; 4],
+    version: u16,
+    data: &'a [u8],
+    used: bool,
+}
+
+fn tag_name(tag: &[u8; 4]) -> String {
+    String::from_utf8_lossy(tag).trim_end().to_string()
+}
+
+// Blocos de um state, lidos pela tag em qualquer ordem. Bloco que falta ou sobra (state de
+// antes ou de depois de um componente existir) vira erro ou aviso com o nome dele em vez
+// de desalinhar a leitura do resto
+pub struct StateBlocks<'a> {
+    blocks: Vec<StateBlock<'a>>,
+    missing: Vec<String>,
+    defaulted: Vec<String>,
+}
+
+impl<'a> StateBlocks<'a> {
+    pub fn parse(r: &mut StateReader<'a>) -> Result<Self, String> {
+        let mut blocks = Vec::new();
+        while !r.is_empty() {
+            let mut tag = [0; 4];
+            r.read_bytes(&mut tag)?;
+            let version = r.read_u16()?;
+            let len = r.read_u32()? as usize;
+            let data = r.take(len)?;
+            blocks.push(StateBlock {
+                tag,
+                version,
+                data,
+                used: false,
+            });
+        }
+        Ok(Self {
+            blocks,
+            missing: Vec::new(),
+            defaulted: Vec::new(),
+        })
+    }
+
+    // false se o state não tem o bloco
+    fn load(
+        &mut self,
+        (tag, version): BlockId,
+        load: impl FnOnce(&mut StateReader) -> Result<(), String>,
+    ) -> Result<bool, String> {
+        let Some(block) = self.blocks.iter_mut().find(|block| &block.tag == tag) else {
+            return Ok(false);
+        };
+        if block.version > version {
+            return Err(format!(
+                "bloco {} do save state na versão {}; esta versão do emulador lê até a {}",
+                tag_name(tag),
+                block.version,
+                version
+            ));
+        }
+        block.used = true;
+        let mut r = StateReader::with_version(block.data, block.version);
+        load(&mut r).map_err(|erro| format!("bloco {}: {}", tag_name(tag), erro))?;
+        if !r.is_empty() {
+            return Err(format!("bloco {} com dados sobrando", tag_name(tag)));
+        }
+        Ok(true)
+    }
+
+    // Sem esse bloco o state não carrega; a falta só é informada no finish, junto com as
+    // outras
+    pub fn require(
+        &mut self,
+        block: BlockId,
+        load: impl FnOnce(&mut StateReader) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let (tag, _) = block;
+        if !self.load(block, load)? {
+            self.missing.push(tag_name(tag));
+        }
+        Ok(())
+    }
+
+    // false se o bloco falta: quem chama põe o componente no estado de power-on
+    pub fn optional(
+        &mut self,
+        block: BlockId,
+        load: impl FnOnce(&mut StateReader) -> Result<(), String>,
+    ) -> Result<bool, String> {
+        let (tag, _) = block;
+        let found = self.load(block, load)?;
+        if !found {
+            self.defaulted.push(tag_name(tag));
+        }
+        Ok(found)
+    }
+
+    // Avisos (blocos que faltavam e foram reiniciados, blocos ignorados), ou o erro com
+    // os que faltam
+    pub fn finish(self) -> Result<Vec<String>, String> {
+        let unknown: Vec<String> = self
+            .blocks
+            .iter()
+            .filter(|block| !block.used)
+            .map(|block| tag_name(&block.tag))
+            .collect();
+        if !self.missing.is_empty() {
+            let mut message = format!("save state sem os blocos: {}", self.missing.join(", "));
+            if !unknown.is_empty() {
+                message += &format!(" (blocos desconhecidos: {})", unknown.join(", "));
+            }
+            return Err(message);
+        }
+
+        let mut warnings = Vec::new();
+        if !self.defaulted.is_empty() {
+            warnings.push(format!(
+                "aviso: save state sem os blocos {}; ficaram como no power-on",
+                self.defaulted.join(", ")
+            ));
+        }
+        if !unknown.is_empty() {
+            warnings.push(format!(
+                "aviso: blocos do save state ignorados: {}",
+                unknown.join(", ")
+            ));
+        }
+        Ok(warnings)
+    }
+}
@@ -0,0 +1,118 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::cartridge::Cartridge;
+use crate::machine::{Emulator, EmulatorEvent, ResetKind};
+use crate::state::{STATE_MAGIC, Savestate, StateWriter};
+
+fn emulator() -> Emulator {
+    let mut emulator = Emulator::new(Cartridge::load(vec![0; 0x8000]).unwrap());
+    emulator.seed = Some(1);
+    emulator.reset(ResetKind::Hard);
+    for _ in 0..3 {
+        emulator.step_frame();
+    }
+    emulator.drain_events();
+    emulator
+}
+
+// (tag, versão, dados) de cada bloco de um state
+fn split(state: &[u8]) -> Vec<([u8; 4], u16, Vec<u8>)> {
+    let mut blocks = Vec::new();
+    let mut rest = &state[6..];
+    while !rest.is_empty() {
+        let tag = rest[..4].try_into().unwrap();
+        let version = u16::from_le_bytes([rest[4], rest[5]]);
+        let len = u32::from_le_bytes(rest[6..10].try_into().unwrap()) as usize;
+        blocks.push((tag, version, rest[10..10 + len].to_vec()));
+        rest = &rest[10 + len..];
+    }
+    blocks
+}
+
+fn join(version: u16, blocks: &[([u8; 4], u16, Vec<u8>)]) -> Vec<u8> {
+    let mut state = STATE_MAGIC.to_vec();
+    state.extend_from_slice(&version.to_le_bytes());
+    for (tag, version, data) in blocks {
+        state.extend_from_slice(tag);
+        state.extend_from_slice(&version.to_le_bytes());
+        state.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        state.extend_from_slice(data);
+    }
+    state
+}
+
+fn warnings(emulator: &mut Emulator) -> Vec<String> {
+    emulator
+        .drain_events()
+        .filter_map(|event| match event {
+            EmulatorEvent::Error(message) => Some(message),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn state_sem_bloco_opcional_carrega_com_aviso() {
+    let mut emulator = emulator();
+    let state = emulator.save_state();
+    let mut blocks = split(&state);
+    blocks.retain(|(tag, _, _)| tag != b"APU ");
+    blocks.push((*b"XTRA", 1, vec![1, 2, 3]));
+
+    emulator.load_state(&join(8, &blocks)).unwrap();
+    let warnings = warnings(&mut emulator);
+    assert_eq!(warnings.len(), 2, "{:?}", warnings);
+    assert!(warnings[0].contains("APU"));
+    assert!(warnings[1].contains("XTRA"));
+}
+
+#[test]
+fn state_sem_bloco_obrigatorio_lista_os_que_faltam() {
+    let mut emulator = emulator();
+    let mut blocks = split(&emulator.save_state());
+    blocks.retain(|(tag, _, _)| tag != b"CPU " && tag != b"PPU ");
+
+    let erro = emulator.load_state(&join(8, &blocks)).unwrap_err();
+    assert!(erro.contains("CPU, PPU"), "{}", erro);
+}
+
+#[test]
+fn state_mais_novo_carrega_se_os_blocos_conhecidos_nao_mudaram() {
+    let mut emulator = emulator();
+    let state = emulator.save_state();
+    let blocks = split(&state);
+    emulator.load_state(&join(9, &blocks)).unwrap();
+    assert_eq!(emulator.save_state(), state);
+
+    let mut changed = blocks;
+    for (tag, version, _) in &mut changed {
+        if tag == b"PPU " {
+            *version = 9;
+        }
+    }
+    let erro = emulator.load_state(&join(9, &changed)).unwrap_err();
+    assert!(erro.contains("PPU"), "{}", erro);
+}
+
+#[test]
+fn state_da_versao_7_sem_blocos() {
+    let mut emulator = emulator();
+    let hash = emulator.state_hash();
+
+    let mut w = StateWriter::new();
+    w.write_bytes(STATE_MAGIC);
+    w.write_u16(7);
+    emulator.cpu.save_state(&mut w);
+    emulator.bus.save_state(&mut w);
+    let flat = w.into_bytes();
+
+    emulator.step_frame();
+    emulator.load_state(&flat).unwrap();
+    assert_eq!(emulator.state_hash(), hash);
+
+    let mut old = flat;
+    old[4] = 6;
+    assert!(emulator.load_state(&old).is_err());
+}
@@ -0,0 +1,192 @@
+use alloc::string::String;
+
+use crate::bus::{ClockDomain, Clocked, InterruptFlags};
+use crate::state::{Savestate, StateReader, StateWriter};
+
+// Registros do timer
+pub const DIV: u16 = 0xFF04;
+pub const TIMA: u16 = 0xFF05;
+pub const TMA: u16 = 0xFF06;
+pub const TAC: u16 = 0xFF07;
+
+const TAC_ENABLE: u8 = 1 << 2;
+
+pub struct Timer {
+    // Contador interno de 16 bits; DIV é o byte alto
+    div_counter: u16,
+    tima: u8,
+    tma: u8,
+    tac: u8,
+    // TIMA estourou e está em 0x00 esperando o reload (dura 1 M-cycle)
+    overflow_pending: bool,
+    // M-cycle em que o TMA acabou de ser copiado pro TIMA
+    reloading: bool,
+    // T-cycles que ainda não fecharam um M-cycle; com overclock o bus manda menos de 4
+    pending: u64,
+}
+
+impl Timer {
+    pub fn new() -> Self {
+        Self {
+            div_counter: 0,
+            tima: 0,
+            tma: 0,
+            tac: 0,
+            overflow_pending: false,
+            reloading: false,
+            pending: 0,
+        }
+    }
+
+    // Valores pós-bootrom (DMG)
+    pub fn reset(&mut self) {
+        self.div_counter = 0xABCC;
+        self.tima = 0;
+        self.tma = 0;
+        self.tac = 0;
+        self.overflow_pending = false;
+        self.reloading = false;
+        self.pending = 0;
+    }
+
+    fn step_m_cycle(&mut self) -> bool {
+        let mut interrupt = false;
+
+        self.reloading = false;
+
+        // O reload (e a interrupção) só acontece 1 M-cycle depois do overflow
+        if self.overflow_pending {
+            self.overflow_pending = false;
+            self.tima = self.tma;
+            self.reloading = true;
+            interrupt = true;
+        }
+
+        let old_signal = self.signal();
+        self.div_counter = self.div_counter.wrapping_add(4);
+        self.detect_falling_edge(old_signal);
+
+        interrupt
+    }
+
+    fn detect_falling_edge(&mut self, old_signal: bool) {
+        if old_signal && !self.signal() {
+            self.increment_tima();
+        }
+    }
+
+    // Bit do divisor selecionado pelo TAC AND enable; o TIMA incrementa na borda de descida
+    fn signal(&self) -> bool {
+        if (self.tac & TAC_ENABLE) == 0 {
+            return false;
+        }
+
+        let bit = match self.tac & 0b11 {
+            0b00 => 9, // 4096 Hz
+            0b01 => 3, // 262144 Hz
+            0b10 => 5, // 65536 Hz
+            _ => 7,    // 16384 Hz
+        };
+
+        (self.div_counter >> bit) & 1 != 0
+    }
+
+    fn increment_tima(&mut self) {
+        let (result, overflow) = self.tima.overflowing_add(1);
+        self.tima = result;
+
+        if overflow {
+            self.overflow_pending = true;
+        }
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            DIV => (self.div_counter >> 8) as u8,
+            TIMA => self.tima,
+            TMA => self.tma,
+            TAC => self.tac | 0xF8,
+            _ => 0xFF,
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, data: u8) {
+        match addr {
+            DIV => {
+                // Zerar o divisor pode derrubar o bit selecionado e gerar um incremento espúrio
+                let old_signal = self.signal();
+                self.div_counter = 0;
+                self.detect_falling_edge(old_signal);
+            }
+            TIMA => {
+                // No ciclo do reload o valor do TMA vence
+                if self.reloading {
+                    return;
+                }
+                // Escrever durante a janela de overflow cancela reload e interrupção
+                self.overflow_pending = false;
+                self.tima = data;
+            }
+            TMA => {
+                self.tma = data;
+                // No ciclo do reload o novo TMA também vai pro TIMA
+                if self.reloading {
+                    self.tima = data;
+                }
+            }
+            TAC => {
+                // Trocar a frequência ou desligar o timer passa pelo mesmo detector de borda
+                let old_signal = self.signal();
+                self.tac = data & 0x07;
+                self.detect_falling_edge(old_signal);
+            }
+            _ => {}
+        }
+    }
+}
+
+// O DIV anda no clock da CPU, então o timer dobra junto em double speed
+impl Clocked for Timer {
+    fn domain(&self) -> ClockDomain {
+        ClockDomain::Cpu
+    }
+
+    fn tick(&mut self, t_cycles: u64) -> InterruptFlags {
+        let mut interrupt = false;
+
+        let t_cycles = self.pending + t_cycles;
+        self.pending = t_cycles % 4;
+        for _ in 0..(t_cycles / 4) {
+            interrupt |= self.step_m_cycle();
+        }
+
+        if interrupt {
+            InterruptFlags::TIMER
+        } else {
+            InterruptFlags::empty()
+        }
+    }
+}
+
+impl Savestate for Timer {
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u16(self.div_counter);
+        w.write_u8(self.tima);
+        w.write_u8(self.tma);
+        w.write_u8(self.tac);
+        w.write_bool(self.overflow_pending);
+        w.write_bool(self.reloading);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.div_counter = r.read_u16()?;
+        self.tima = r.read_u8()?;
+        self.tma = r.read_u8()?;
+        self.tac = r.read_u8()?;
+        self.overflow_pending = r.read_bool()?;
+        self.reloading = r.read_bool()?;
+        // Fica fora do state, como o resto do overclock no bus
+        self.pending = 0;
+        Ok(())
+    }
+}
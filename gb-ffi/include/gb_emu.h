@@ -0,0 +1,59 @@
+/* API em C do core do gb-emu-rust (cdylib do gb-ffi: libgb_emu.so / gb_emu.dll) */
+#ifndef GB_EMU_H
+#define GB_EMU_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Instância opaca; todas as funções esperam um ponteiro vindo de gb_create */
+typedef struct GbCore GbCore;
+
+/* Bits de gb_set_input */
+#define GB_RIGHT  (1 << 0)
+#define GB_LEFT   (1 << 1)
+#define GB_UP     (1 << 2)
+#define GB_DOWN   (1 << 3)
+#define GB_A      (1 << 4)
+#define GB_B      (1 << 5)
+#define GB_SELECT (1 << 6)
+#define GB_START  (1 << 7)
+
+GbCore *gb_create(void);
+void gb_destroy(GbCore *core);
+
+/* Mensagem do último erro ("" se nenhum), válida até a próxima falha */
+const char *gb_last_error(const GbCore *core);
+
+/* Copia a ROM e liga a máquina. 0 = ok, -1 = erro */
+int gb_load_rom(GbCore *core, const uint8_t *data, size_t len);
+
+/* Roda um frame (70224 ciclos). 0 = ok, -1 = sem ROM */
+int gb_run_frame(GbCore *core);
+
+/* Roda pelo menos `cycles` ciclos (o frame tem 70224), pra intercalar com o laço de quem
+   chama. Devolve quantos rodou, ou -1 sem ROM */
+int64_t gb_step_cycles(GbCore *core, uint64_t cycles);
+
+/* Último frame completo em RGBA (160x144, ou 256x224 com a moldura do SGB).
+   NULL sem ROM; o ponteiro vale até a próxima chamada com o mesmo core */
+const uint8_t *gb_get_framebuffer(GbCore *core, size_t *width, size_t *height);
+
+/* Botões seguros a partir do próximo frame (GB_RIGHT | GB_A ...) */
+void gb_set_input(GbCore *core, uint8_t buttons);
+
+/* Devolve o tamanho do state; só escreve em out se couber em capacity.
+   out = NULL pergunta o tamanho. 0 = sem ROM */
+size_t gb_save_state(GbCore *core, uint8_t *out, size_t capacity);
+
+/* 0 = ok, -1 = erro (a máquina fica como estava) */
+int gb_load_state(GbCore *core, const uint8_t *data, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
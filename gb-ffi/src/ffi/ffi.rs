@@ -0,0 +1,179 @@
+// Os contratos de ponteiro de cada função estão em include/gb_emu.h
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::{CString, c_char, c_int};
+use std::ptr;
+use std::slice;
+
+use gb_core::cartridge::Cartridge;
+use gb_core::joypad::{Buttons, JoypadInput};
+use gb_core::machine::{Emulator, EmulatorEvent, ResetKind};
+
+// Instância opaca do lado do C: o emulador só existe depois do gb_load_rom
+pub struct GbCore {
+    emulator: Option<Emulator>,
+    // Último frame em RGBA, válido até a próxima chamada
+    rgba: Vec<u8>,
+    error: CString,
+}
+
+impl GbCore {
+    fn fail(&mut self, erro: String) -> c_int {
+        self.error = CString::new(erro).unwrap_or_default();
+        -1
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn gb_create() -> *mut GbCore {
+    Box::into_raw(Box::new(GbCore {
+        emulator: None,
+        rgba: Vec::new(),
+        error: CString::default(),
+    }))
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gb_destroy(core: *mut GbCore) {
+    if !core.is_null() {
+        drop(unsafe { Box::from_raw(core) });
+    }
+}
+
+// Mensagem do último erro (string vazia se nenhum), válida até a próxima falha
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gb_last_error(core: *const GbCore) -> *const c_char {
+    unsafe { &*core }.error.as_ptr()
+}
+
+// Troca o cartucho e liga a máquina; 0 = ok, -1 = erro (ver gb_last_error)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gb_load_rom(core: *mut GbCore, data: *const u8, len: usize) -> c_int {
+    let core = unsafe { &mut *core };
+    if data.is_null() {
+        return core.fail("ROM nula".to_string());
+    }
+    let rom = unsafe { slice::from_raw_parts(data, len) }.to_vec();
+    match Cartridge::load(rom) {
+        Ok(cartridge) => {
+            let mut emulator = Emulator::new(cartridge);
+            emulator.reset(ResetKind::Hard);
+            core.emulator = Some(emulator);
+            0
+        }
+        Err(erro) => core.fail(erro),
+    }
+}
+
+// Roda um frame (70224 ciclos); 0 = ok, -1 = sem ROM
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gb_run_frame(core: *mut GbCore) -> c_int {
+    let core = unsafe { &mut *core };
+    let Some(emulator) = &mut core.emulator else {
+        return core.fail("nenhuma ROM carregada".to_string());
+    };
+    emulator.step_frame();
+
+    // Sem thread de frontend ninguém consome os eventos; só o último erro fica
+    let mut erro = None;
+    for event in emulator.drain_events() {
+        if let EmulatorEvent::Error(message) = event {
+            erro = Some(message);
+        }
+    }
+    if let Some(erro) = erro {
+        core.fail(erro);
+    }
+    0
+}
+
+// Roda pelo menos `cycles` ciclos, pra intercalar com o laço de quem chama; devolve
+// quantos rodou (menos num breakpoint) ou -1 sem ROM
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gb_step_cycles(core: *mut GbCore, cycles: u64) -> i64 {
+    let core = unsafe { &mut *core };
+    let Some(emulator) = &mut core.emulator else {
+        return core.fail("nenhuma ROM carregada".to_string()) as i64;
+    };
+    let done = emulator.step_cycles(cycles);
+
+    let mut erro = None;
+    for event in emulator.drain_events() {
+        if let EmulatorEvent::Error(message) = event {
+            erro = Some(message);
+        }
+    }
+    if let Some(erro) = erro {
+        core.fail(erro);
+    }
+    done as i64
+}
+
+// Último frame completo em RGBA 8 bits (160x144, ou 256x224 com a moldura do SGB).
+// NULL sem ROM; o ponteiro vale até a próxima chamada com o mesmo core
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gb_get_framebuffer(
+    core: *mut GbCore,
+    width: *mut usize,
+    height: *mut usize,
+) -> *const u8 {
+    let core = unsafe { &mut *core };
+    let Some(emulator) = &mut core.emulator else {
+        return ptr::null();
+    };
+    let (w, h) = emulator.render_frame(&mut core.rgba);
+    if !width.is_null() {
+        unsafe { *width = w };
+    }
+    if !height.is_null() {
+        unsafe { *height = h };
+    }
+    core.rgba.as_ptr()
+}
+
+// Botões seguros, um bit cada: 0 direita, 1 esquerda, 2 cima, 3 baixo, 4 A, 5 B,
+// 6 select, 7 start
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gb_set_input(core: *mut GbCore, buttons: u8) {
+    if let Some(emulator) = &mut unsafe { &mut *core }.emulator {
+        emulator
+            .bus
+            .set_input(JoypadInput::held(Buttons::from_bits_retain(buttons)));
+    }
+}
+
+// Tamanho do save state; só escreve em `out` se couber em `capacity` (out pode ser NULL
+// pra perguntar o tamanho). 0 = sem ROM
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gb_save_state(core: *mut GbCore, out: *mut u8, capacity: usize) -> usize {
+    let Some(emulator) = &unsafe { &*core }.emulator else {
+        return 0;
+    };
+    let state = emulator.save_state();
+    if !out.is_null() && state.len() <= capacity {
+        unsafe { ptr::copy_nonoverlapping(state.as_ptr(), out, state.len()) };
+    }
+    state.len()
+}
+
+// 0 = ok, -1 = erro (ver gb_last_error)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gb_load_state(core: *mut GbCore, data: *const u8, len: usize) -> c_int {
+    let core = unsafe { &mut *core };
+    if data.is_null() {
+        return core.fail("state nulo".to_string());
+    }
+    let data = unsafe { slice::from_raw_parts(data, len) };
+    let Some(emulator) = &mut core.emulator else {
+        return core.fail("nenhuma ROM carregada".to_string());
+    };
+    // State inválido pode ter sobrescrito parte da máquina: volta pro de antes
+    let backup = emulator.save_state();
+    match emulator.load_state(data) {
+        Ok(()) => 0,
+        Err(erro) => {
+            emulator.load_state(&backup).unwrap();
+            core.fail(erro)
+        }
+    }
+}
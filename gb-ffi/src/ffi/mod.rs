@@ -0,0 +1,3 @@
+pub mod ffi;
+
+pub use ffi::*;
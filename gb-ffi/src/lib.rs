@@ -0,0 +1,5 @@
+// Bindings do gb-core pra fora do Rust: a API em C (include/gb_emu.h), exportada pelo
+// cdylib libgb_emu.so / gb_emu.dll, e o módulo Python com a feature python
+pub mod ffi;
+#[cfg(feature = "python")]
+pub mod python;
@@ -0,0 +1,3 @@
+pub mod python;
+
+pub use python::*;
@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use gb_core::archive;
+use gb_core::cartridge::Cartridge;
+use gb_core::joypad::{BUTTON_NAMES, Buttons, JoypadInput};
+use gb_core::machine::{DETERMINISTIC_SEED, Emulator, Model, ResetKind};
+
+// Emulador sem janela pro Python, um frame por vez na thread de quem chama. O frame sai
+// em bytes RGBA: numpy.frombuffer(emu.frame(), numpy.uint8).reshape(emu.height, emu.width, 4)
+#[pyclass(unsendable, name = "Emulator")]
+pub struct PyEmulator {
+    emulator: Emulator,
+    buttons: Buttons,
+    rgba: Vec<u8>,
+    size: (usize, usize),
+}
+
+impl PyEmulator {
+    fn power_on(cartridge: Cartridge, model: &str, seed: u64) -> PyResult<Self> {
+        let model = Model::parse(model)
+            .ok_or_else(|| PyValueError::new_err(format!("modelo inválido: '{}'", model)))?;
+        let mut emulator = Emulator::new(cartridge);
+        emulator.bus.model = model;
+        emulator.seed = Some(seed);
+        emulator.reset(ResetKind::Hard);
+
+        let mut rgba = Vec::new();
+        let size = emulator.render_frame(&mut rgba);
+        Ok(Self {
+            emulator,
+            buttons: Buttons::empty(),
+            rgba,
+            size,
+        })
+    }
+
+    fn set_buttons_mask(&mut self, buttons: Buttons) {
+        self.buttons = buttons;
+        self.emulator.bus.set_input(JoypadInput::held(buttons));
+    }
+}
+
+fn button(name: &str) -> PyResult<Buttons> {
+    BUTTON_NAMES
+        .iter()
+        .find(|(button, _)| button.eq_ignore_ascii_case(name))
+        .map(|&(_, button)| button)
+        .ok_or_else(|| PyValueError::new_err(format!("botão desconhecido: '{}'", name)))
+}
+
+fn value_error(erro: String) -> PyErr {
+    PyValueError::new_err(erro)
+}
+
+#[pymethods]
+impl PyEmulator {
+    // .gb/.gbc ou .zip; a RAM inicial vem da seed fixa pra episódios reproduzíveis
+    #[new]
+    #[pyo3(signature = (rom, model = "dmg", seed = DETERMINISTIC_SEED))]
+    fn new(rom: PathBuf, model: &str, seed: u64) -> PyResult<Self> {
+        let cartridge = archive::read_rom(&rom)
+            .and_then(Cartridge::load)
+            .map_err(value_error)?;
+        Self::power_on(cartridge, model, seed)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (data, model = "dmg", seed = DETERMINISTIC_SEED))]
+    fn from_bytes(data: &[u8], model: &str, seed: u64) -> PyResult<Self> {
+        let cartridge = Cartridge::load(data.to_vec()).map_err(value_error)?;
+        Self::power_on(cartridge, model, seed)
+    }
+
+    // Roda `frames` frames com os botões atuais; os eventos do core são descartados
+    #[pyo3(signature = (frames = 1))]
+    fn step(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.emulator.step_frame();
+            self.emulator.drain_events().for_each(drop);
+        }
+        self.size = self.emulator.render_frame(&mut self.rgba);
+    }
+
+    // Roda pelo menos `cycles` ciclos e devolve quantos rodou; frame() fica com o último
+    // frame completo
+    fn step_cycles(&mut self, cycles: u64) -> u64 {
+        let done = self.emulator.step_cycles(cycles);
+        self.emulator.drain_events().for_each(drop);
+        self.size = self.emulator.render_frame(&mut self.rgba);
+        done
+    }
+
+    fn frame<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.rgba)
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.size.0
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.size.1
+    }
+
+    // Leitura sem efeito colateral (como o emu.read do Lua)
+    fn peek(&self, addr: u16) -> u8 {
+        self.emulator.bus.peek(addr)
+    }
+
+    fn poke(&mut self, addr: u16, value: u8) {
+        self.emulator.bus.write(addr, value);
+    }
+
+    // Nomes como no Lua: "a", "b", "start", "select", "up", "down", "left", "right"
+    fn press(&mut self, name: &str) -> PyResult<()> {
+        self.set_buttons_mask(self.buttons | button(name)?);
+        Ok(())
+    }
+
+    fn release(&mut self, name: &str) -> PyResult<()> {
+        self.set_buttons_mask(self.buttons - button(name)?);
+        Ok(())
+    }
+
+    // Substitui todos os botões seguros de uma vez
+    fn set_buttons(&mut self, names: Vec<String>) -> PyResult<()> {
+        let mut buttons = Buttons::empty();
+        for name in &names {
+            buttons |= button(name)?;
+        }
+        self.set_buttons_mask(buttons);
+        Ok(())
+    }
+
+    fn buttons(&self) -> Vec<&'static str> {
+        BUTTON_NAMES
+            .iter()
+            .filter(|(_, button)| self.buttons.contains(*button))
+            .map(|&(name, _)| name)
+            .collect()
+    }
+
+    fn save_state<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.emulator.save_state())
+    }
+
+    // State inválido deixa a máquina como estava
+    fn load_state(&mut self, data: &[u8]) -> PyResult<()> {
+        let backup = self.emulator.save_state();
+        if let Err(erro) = self.emulator.load_state(data) {
+            self.emulator.load_state(&backup).unwrap();
+            return Err(value_error(erro));
+        }
+        self.size = self.emulator.render_frame(&mut self.rgba);
+        Ok(())
+    }
+
+    fn state_hash(&self) -> u32 {
+        self.emulator.state_hash()
+    }
+
+    fn frame_hash(&self) -> u32 {
+        self.emulator.bus.ppu.frame_hash()
+    }
+}
+
+#[pymodule]
+fn gb_emu_rust(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyEmulator>()
+}
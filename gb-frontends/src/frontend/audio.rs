@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+
+use raylib::prelude::*;
+
+use crate::apu::SAMPLE_RATE;
+
+// Latência padrão e limites do [audio] latency, em ms: os dois sub-buffers do stream
+pub const DEFAULT_LATENCY_MS: u32 = 45;
+pub const MIN_LATENCY_MS: u32 = 10;
+pub const MAX_LATENCY_MS: u32 = 500;
+// Fila máxima, em sub-buffers; acima disso (fast-forward, frontend atrasado) as amostras
+// mais antigas são descartadas
+const MAX_PENDING_BUFFERS: usize = 4;
+
+// Contadores mostrados no overlay de estatísticas
+#[derive(Copy, Clone, Default, Debug)]
+pub struct AudioStats {
+    // Sub-buffers completados esticando o que havia (ou repetindo a última amostra)
+    pub underruns: u32,
+    // Vezes que a fila passou do limite e perdeu amostras
+    pub overruns: u32,
+    // Amostras na fila esperando o stream
+    pub buffered_ms: u32,
+}
+
+// Stream da raylib alimentado com as amostras que chegam da thread de emulação
+pub struct AudioOutput<'aud> {
+    stream: AudioStream<'aud>,
+    pending: VecDeque<i16>,
+    // Frames estéreo por sub-buffer
+    buffer_frames: usize,
+    last: [i16; 2],
+    stats: AudioStats,
+}
+
+impl<'aud> AudioOutput<'aud> {
+    pub fn new(device: &'aud RaylibAudio, latency_ms: u32) -> Self {
+        let buffer_frames = (SAMPLE_RATE * latency_ms / 1000 / 2) as usize;
+        device.set_audio_stream_buffer_size_default(buffer_frames as i32);
+        let stream = device.new_audio_stream(SAMPLE_RATE, 16, 2);
+        stream.play();
+        Self {
+            stream,
+            pending: VecDeque::with_capacity(buffer_frames * 2 * MAX_PENDING_BUFFERS),
+            buffer_frames,
+            last: [0; 2],
+            stats: AudioStats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> AudioStats {
+        AudioStats {
+            buffered_ms: (self.pending.len() / 2) as u32 * 1000 / SAMPLE_RATE,
+            ..self.stats
+        }
+    }
+
+    pub fn push(&mut self, samples: &[i16]) {
+        self.pending.extend(samples);
+        let max = self.buffer_frames * 2 * MAX_PENDING_BUFFERS;
+        if self.pending.len() > max {
+            let excess = self.pending.len() - max;
+            // Descarta frames inteiros pra não trocar esquerda com direita
+            self.pending.drain(..excess + excess % 2);
+            self.stats.overruns += 1;
+        }
+    }
+
+    // Preenche os sub-buffers que a raylib já tocou. Faltando amostras, o que houver é
+    // esticado até o tamanho do sub-buffer em vez de deixar um buraco de silêncio
+    pub fn update(&mut self) {
+        let size = self.buffer_frames * 2;
+        while self.stream.is_processed() {
+            let chunk: Vec<i16> = if self.pending.len() >= size {
+                self.pending.drain(..size).collect()
+            } else {
+                self.stats.underruns += 1;
+                self.stretch()
+            };
+            if let [.., left, right] = chunk[..] {
+                self.last = [left, right];
+            }
+            // O update da raylib-rs passa o tamanho em bytes onde a raylib espera frames:
+            // com 4 bytes por frame estéreo, um quarto do slice dá o número certo
+            self.stream.update(&chunk[..chunk.len() / 4]);
+        }
+    }
+
+    fn stretch(&mut self) -> Vec<i16> {
+        let available: Vec<i16> = self.pending.drain(..self.pending.len() & !1).collect();
+        let frames = available.len() / 2;
+        let mut chunk = Vec::with_capacity(self.buffer_frames * 2);
+        for frame in 0..self.buffer_frames {
+            if frames == 0 {
+                chunk.extend_from_slice(&self.last);
+            } else {
+                let source = frame * frames / self.buffer_frames;
+                chunk.extend_from_slice(&available[source * 2..source * 2 + 2]);
+            }
+        }
+        chunk
+    }
+}
+
+// [audio] mute = 1,3: canais que já começam silenciados ("none" ou vazio = nenhum)
+pub fn parse_channels(value: &str) -> Option<[bool; 4]> {
+    let mut channels = [false; 4];
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("none") {
+        return Some(channels);
+    }
+    for item in value.split(',') {
+        match item.trim().parse::<usize>() {
+            Ok(channel @ 1..=4) => channels[channel - 1] = true,
+            _ => return None,
+        }
+    }
+    Some(channels)
+}
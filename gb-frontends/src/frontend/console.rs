@@ -0,0 +1,215 @@
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use crate::cheats::{Freeze, SearchFilter};
+use crate::debug::{
+    CallFrame, FrameKind, INTERRUPT_NAMES, Location, Symbols, Trigger, TriggerHit, Watch,
+};
+use crate::machine::{EmulatorCommand, MovieAnchor, StepKind};
+
+// Comandos de texto lidos do stdin (busca de cheats, cheats) sem precisar de UI
+pub struct Console {
+    lines: Receiver<String>,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        let (sender, lines) = mpsc::channel();
+
+        // Thread solta: bloqueia no stdin e morre junto com o processo
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { lines }
+    }
+
+    pub fn poll(&self) -> Vec<String> {
+        self.lines.try_iter().collect()
+    }
+}
+
+// Aceita decimal, 0x.. ou $..
+fn parse_number(text: &str) -> Result<i32, String> {
+    let parsed = if let Some(hex) = text.strip_prefix("0x").or(text.strip_prefix('$')) {
+        i32::from_str_radix(hex, 16)
+    } else {
+        text.parse()
+    };
+    parsed.map_err(|_| format!("número inválido: '{}'", text))
+}
+
+fn parse_byte(text: &str) -> Result<u8, String> {
+    u8::try_from(parse_number(text)?).map_err(|_| format!("valor fora de 0-255: '{}'", text))
+}
+
+fn parse_addr(text: &str) -> Result<u16, String> {
+    u16::try_from(parse_number(text)?)
+        .map_err(|_| format!("endereço fora de 0-FFFF: '{}'", text))
+}
+
+// Endereço ou label do .sym da ROM
+pub fn parse_target(text: &str, symbols: &Symbols) -> Result<u16, String> {
+    match symbols.resolve(text) {
+        Some((_, addr)) => Ok(addr),
+        None if text.starts_with(|c: char| c.is_ascii_digit() || c == '$') => parse_addr(text),
+        None => Err(format!("símbolo desconhecido: '{}'", text)),
+    }
+}
+
+fn parse_trigger(text: &str) -> Result<Trigger, String> {
+    Trigger::parse(text).ok_or(format!("trigger desconhecido: '{}'", text))
+}
+
+// Instruções mostradas pelo `history` sem número
+const HISTORY_LINES: usize = 32;
+
+pub const CONSOLE_HELP: &str = "\
+search start | eq <n> | gt | lt | changed | unchanged | delta <n> | freeze <i> [valor]
+cheat add <código> | remove <código> | on <código> | off <código>
+freeze <endereço|label> <valor> | unfreeze <endereço|label>
+movie record <arquivo> | record-here <arquivo> | play <arquivo> | stop
+break add <endereço|label> | remove <endereço|label>
+trigger add | remove <interrupt|vblank|stat|timer|serial|joypad|rom-bank|ram-bank>
+watch add <expressão> | remove <n> | list  (ex.: [wPlayerHP], w[hl+2], a & $0F)
+step [over|out] | backtrace | history [n]
+profile start | stop <arquivo>
+coverage start | stop <arquivo.json|arquivo.cdl>
+trace start <arquivo> | stop
+ppulog on | off | show
+serial <none|console|printer[:pasta]|tcp-host:<porta>|tcp:<endereço>:<porta>|lua>";
+
+pub fn parse_command(line: &str, symbols: &Symbols) -> Result<EmulatorCommand, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    let filter = |filter| Ok(EmulatorCommand::SearchFilter(filter));
+    match words.as_slice() {
+        ["search", "start"] => Ok(EmulatorCommand::SearchStart),
+        ["search", "eq", value] => filter(SearchFilter::Equal(parse_byte(value)?)),
+        ["search", "gt"] => filter(SearchFilter::Greater),
+        ["search", "lt"] => filter(SearchFilter::Less),
+        ["search", "changed"] => filter(SearchFilter::Changed),
+        ["search", "unchanged"] => filter(SearchFilter::Unchanged),
+        ["search", "delta", delta] => {
+            let delta = parse_number(delta)?;
+            if !(-255..=255).contains(&delta) {
+                return Err(format!("delta fora de -255..255: {}", delta));
+            }
+            filter(SearchFilter::ChangedBy(delta as i16))
+        }
+        ["search", "freeze", index, rest @ ..] if rest.len() <= 1 => {
+            let index = parse_number(index)?;
+            let value = rest.first().map(|value| parse_byte(value)).transpose()?;
+            Ok(EmulatorCommand::SearchFreeze {
+                index: index.max(0) as usize,
+                value,
+            })
+        }
+        ["cheat", "add", code] => Ok(EmulatorCommand::AddCheat(code.to_string())),
+        ["cheat", "remove", code] => Ok(EmulatorCommand::RemoveCheat(code.to_string())),
+        ["cheat", "on", code] => Ok(EmulatorCommand::SetCheatEnabled(code.to_string(), true)),
+        ["cheat", "off", code] => Ok(EmulatorCommand::SetCheatEnabled(code.to_string(), false)),
+        ["freeze", addr, value] => {
+            let freeze = Freeze::new(parse_target(addr, symbols)?, parse_byte(value)?)?;
+            Ok(EmulatorCommand::Freeze(freeze))
+        }
+        ["unfreeze", addr] => {
+            let freeze = Freeze::new(parse_target(addr, symbols)?, 0)?;
+            Ok(EmulatorCommand::Unfreeze(freeze.address))
+        }
+        ["movie", "record", path] => {
+            Ok(EmulatorCommand::RecordMovie(PathBuf::from(path), MovieAnchor::PowerOn))
+        }
+        ["movie", "record-here", path] => {
+            Ok(EmulatorCommand::RecordMovie(PathBuf::from(path), MovieAnchor::SaveState))
+        }
+        ["movie", "play", path] => Ok(EmulatorCommand::PlayMovie(PathBuf::from(path))),
+        ["movie", "stop"] => Ok(EmulatorCommand::StopMovie),
+        ["break", "add", addr] => {
+            Ok(EmulatorCommand::AddBreakpoint(parse_target(addr, symbols)?))
+        }
+        ["break", "remove", addr] => {
+            Ok(EmulatorCommand::RemoveBreakpoint(parse_target(addr, symbols)?))
+        }
+        ["trigger", "add", name] => Ok(EmulatorCommand::AddTrigger(parse_trigger(name)?)),
+        ["trigger", "remove", name] => Ok(EmulatorCommand::RemoveTrigger(parse_trigger(name)?)),
+        ["watch", "add", expr @ ..] if !expr.is_empty() => {
+            Ok(EmulatorCommand::AddWatch(Watch::parse(&expr.join(" "), symbols)?))
+        }
+        ["watch", "remove", index] => {
+            Ok(EmulatorCommand::RemoveWatch(parse_number(index)?.max(0) as usize))
+        }
+        ["watch", "list"] => Ok(EmulatorCommand::ListWatches),
+        ["step"] => Ok(EmulatorCommand::Step(StepKind::Into)),
+        ["step", "over"] => Ok(EmulatorCommand::Step(StepKind::Over)),
+        ["step", "out"] => Ok(EmulatorCommand::Step(StepKind::Out)),
+        ["backtrace"] => Ok(EmulatorCommand::Backtrace),
+        ["history"] => Ok(EmulatorCommand::History(HISTORY_LINES)),
+        ["history", count] => Ok(EmulatorCommand::History(parse_number(count)?.max(0) as usize)),
+        ["profile", "start"] => Ok(EmulatorCommand::StartProfile),
+        ["profile", "stop", path] => Ok(EmulatorCommand::StopProfile(PathBuf::from(path))),
+        ["coverage", "start"] => Ok(EmulatorCommand::StartCoverage),
+        ["coverage", "stop", path] => Ok(EmulatorCommand::StopCoverage(PathBuf::from(path))),
+        ["trace", "start", path] => Ok(EmulatorCommand::StartTrace(PathBuf::from(path))),
+        ["trace", "stop"] => Ok(EmulatorCommand::StopTrace),
+        ["ppulog", "on"] => Ok(EmulatorCommand::SetPpuLog(true)),
+        ["ppulog", "off"] => Ok(EmulatorCommand::SetPpuLog(false)),
+        ["ppulog", "show"] => Ok(EmulatorCommand::PpuLog),
+        ["serial", device] => Ok(EmulatorCommand::AttachSerial(device.to_string())),
+        _ => Err(format!(
+            "comando desconhecido: '{}'\n{}",
+            line.trim(),
+            CONSOLE_HELP
+        )),
+    }
+}
+
+// Mensagem de um trigger do debugger, com o label de onde disparou
+pub fn describe_trigger(hit: TriggerHit, symbols: &Symbols) -> String {
+    match hit {
+        TriggerHit::Interrupt { bit, pc } => format!(
+            "Interrupção {} em {}",
+            INTERRUPT_NAMES[bit as usize],
+            symbols.locate(pc)
+        ),
+        TriggerHit::RomBank { old, new, pc } => format!(
+            "Banco da ROM {:02X} -> {:02X} em {}",
+            old,
+            new,
+            symbols.locate(pc)
+        ),
+        TriggerHit::RamBank { old, new, pc } => format!(
+            "Banco da RAM {:02X} -> {:02X} em {}",
+            old,
+            new,
+            symbols.locate(pc)
+        ),
+    }
+}
+
+// Resposta do backtrace: o PC atual e os quadros do mais recente pro mais antigo
+pub fn backtrace_lines(pc: Location, frames: &[CallFrame], symbols: &Symbols) -> Vec<String> {
+    let mut lines = vec![format!("#0  {}", symbols.locate(pc))];
+    for (depth, frame) in frames.iter().rev().enumerate() {
+        let kind = match frame.kind {
+            FrameKind::Call => "CALL",
+            FrameKind::Rst => "RST",
+            FrameKind::Interrupt => "interrupção",
+        };
+        lines.push(format!(
+            "#{:<2} {}  ({} {})",
+            depth + 1,
+            symbols.locate(frame.return_to),
+            kind,
+            symbols.locate(frame.entry)
+        ));
+    }
+    lines
+}
@@ -0,0 +1,784 @@
+use std::mem;
+
+use raylib::core::texture::RaylibTexture2D;
+use raylib::prelude::*;
+
+use crate::debug::{
+    CANVAS_SIZE, Inspection, OAM_COLUMNS, OAM_SHEET_H, OAM_SHEET_W, Symbols, TILE_SHEET_H,
+    TILE_SHEET_W, VideoInspection, Watch, palette_rgb,
+};
+use crate::frontend::{SCOPE_STRIP_HEIGHT, draw_scope, parse_target};
+use crate::machine::{EmulatorCommand, StepKind};
+
+// Largura dos painéis; a barra de título é a do GuiWindowBox
+const PANEL_WIDTH: f32 = 300.0;
+const TITLE_HEIGHT: f32 = 24.0;
+// Uma linha da barra de botões e o espaço de cada botão nela
+const TOOLBAR_HEIGHT: f32 = 30.0;
+const TOOLBAR_STEP: f32 = 84.0;
+const LINE_HEIGHT: f32 = 16.0;
+const BUTTON_HEIGHT: f32 = 22.0;
+const PADDING: f32 = 8.0;
+// Soltar a janela com a borda a essa distância da direita encaixa ela de volta na coluna
+const DOCK_MARGIN: f32 = 48.0;
+const TILE_SCALE: f32 = 2.0;
+const OAM_SCALE: f32 = 3.0;
+// Amostra de cada cor no painel de paletas
+const SWATCH_WIDTH: f32 = 44.0;
+const SWATCH_HEIGHT: f32 = 24.0;
+// Instruções mostradas a partir do PC no painel da CPU
+const CODE_LINES: usize = 8;
+// Linhas do IoRegisters::describe
+const IO_LINES: usize = 14;
+// O Inspect sempre lê um pedaço da memória; os painéis não mostram, então tanto faz onde
+const INSPECT_MEMORY: u16 = 0xC000;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Panel {
+    Cpu,
+    Io,
+    Breakpoints,
+    Watches,
+    Tiles,
+    Oam,
+    Palettes,
+    Canvas,
+    Scope,
+    Cheats,
+}
+
+impl Panel {
+    const ALL: [Panel; 10] = [
+        Panel::Cpu,
+        Panel::Io,
+        Panel::Breakpoints,
+        Panel::Watches,
+        Panel::Tiles,
+        Panel::Oam,
+        Panel::Palettes,
+        Panel::Canvas,
+        Panel::Scope,
+        Panel::Cheats,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Panel::Cpu => "CPU",
+            Panel::Io => "IO",
+            Panel::Breakpoints => "Breakpoints",
+            Panel::Watches => "Watches",
+            Panel::Tiles => "Tiles",
+            Panel::Oam => "OAM",
+            Panel::Palettes => "Paletas",
+            Panel::Canvas => "Canvas",
+            Panel::Scope => "Scope",
+            Panel::Cheats => "Cheats",
+        }
+    }
+
+    // Altura da janela abaixo da barra de título
+    fn height(self) -> f32 {
+        let content = match self {
+            Panel::Cpu => LINE_HEIGHT * (4 + CODE_LINES) as f32 + BUTTON_HEIGHT + PADDING,
+            Panel::Io => LINE_HEIGHT * IO_LINES as f32,
+            Panel::Breakpoints | Panel::Watches | Panel::Cheats => 180.0,
+            Panel::Tiles => TILE_SHEET_H as f32 * TILE_SCALE + LINE_HEIGHT,
+            Panel::Oam => OAM_SHEET_H as f32 * OAM_SCALE + LINE_HEIGHT,
+            Panel::Palettes => SWATCH_HEIGHT * 3.0 + PADDING * 2.0,
+            Panel::Canvas => CANVAS_SIZE as f32 + LINE_HEIGHT,
+            Panel::Scope => (SCOPE_STRIP_HEIGHT * 4) as f32,
+        };
+        content + PADDING * 2.0
+    }
+}
+
+struct PanelWindow {
+    panel: Panel,
+    open: bool,
+    // Na coluna da direita, um embaixo do outro; solto, fica em `position`
+    docked: bool,
+    position: Vector2,
+}
+
+// Campo de uma linha desenhado à mão: o gui_text_box do raylib-rs não aumenta a String
+#[derive(Default)]
+struct TextField {
+    text: String,
+    focused: bool,
+}
+
+// Janelas do raygui por cima da tela: CPU, breakpoints, watches, tiles, OAM, scope e cheats. O
+// estado vem do core pelos comandos Inspect*, pedidos a cada frame com o painel aberto
+pub struct DebugPanels {
+    pub visible: bool,
+    windows: Vec<PanelWindow>,
+    // Janela sendo arrastada pela barra de título e onde o mouse pegou nela
+    dragging: Option<(usize, Vector2)>,
+    inspection: Option<Box<Inspection>>,
+    video: Option<Box<VideoInspection>>,
+    // Chegou VRAM nova: as texturas são refeitas antes do próximo desenho
+    video_changed: bool,
+    cheats: Vec<(String, bool)>,
+    tiles: Option<Texture2D>,
+    sprites: Option<Texture2D>,
+    canvas: Option<Texture2D>,
+    breakpoint_field: TextField,
+    watch_field: TextField,
+    cheat_field: TextField,
+    // Saída dos botões e campos, que o Frontend manda/mostra depois do desenho
+    commands: Vec<EmulatorCommand>,
+    messages: Vec<String>,
+}
+
+impl DebugPanels {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            windows: Panel::ALL
+                .iter()
+                .map(|&panel| PanelWindow {
+                    panel,
+                    open: panel == Panel::Cpu,
+                    docked: true,
+                    position: Vector2::new(PADDING, TOOLBAR_HEIGHT + PADDING),
+                })
+                .collect(),
+            dragging: None,
+            inspection: None,
+            video: None,
+            video_changed: false,
+            cheats: Vec::new(),
+            tiles: None,
+            sprites: None,
+            canvas: None,
+            breakpoint_field: TextField::default(),
+            watch_field: TextField::default(),
+            cheat_field: TextField::default(),
+            commands: Vec::new(),
+            messages: Vec::new(),
+        }
+    }
+
+    pub fn shows(&self, panel: Panel) -> bool {
+        self.visible
+            && self
+                .windows
+                .iter()
+                .any(|window| window.panel == panel && window.open)
+    }
+
+    // Com um campo em foco o teclado não vai pro jogo nem pros atalhos
+    pub fn typing(&self) -> bool {
+        self.visible
+            && (self.breakpoint_field.focused
+                || self.watch_field.focused
+                || self.cheat_field.focused)
+    }
+
+    // Pedidos de estado pro core, um de cada por frame da janela
+    pub fn requests(&self) -> Vec<EmulatorCommand> {
+        let mut requests = Vec::new();
+        let inspected = [Panel::Cpu, Panel::Io, Panel::Breakpoints, Panel::Watches];
+        if inspected.iter().any(|&panel| self.shows(panel)) {
+            requests.push(EmulatorCommand::Inspect(INSPECT_MEMORY));
+        }
+        let video = [Panel::Tiles, Panel::Oam, Panel::Palettes, Panel::Canvas];
+        if video.iter().any(|&panel| self.shows(panel)) {
+            requests.push(EmulatorCommand::InspectVideo);
+        }
+        if self.shows(Panel::Cheats) {
+            requests.push(EmulatorCommand::ListCheats);
+        }
+        requests
+    }
+
+    pub fn set_inspection(&mut self, inspection: Box<Inspection>) {
+        self.inspection = Some(inspection);
+    }
+
+    pub fn set_video(&mut self, video: Box<VideoInspection>) {
+        self.video = Some(video);
+        self.video_changed = true;
+    }
+
+    pub fn set_cheats(&mut self, cheats: Vec<(String, bool)>) {
+        self.cheats = cheats;
+    }
+
+    pub fn take_commands(&mut self) -> Vec<EmulatorCommand> {
+        mem::take(&mut self.commands)
+    }
+
+    pub fn take_messages(&mut self) -> Vec<String> {
+        mem::take(&mut self.messages)
+    }
+
+    // Antes do begin_drawing: texturas, teclado dos campos e arrastar/encaixar as janelas
+    pub fn update(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread, symbols: &Symbols) {
+        if !self.visible {
+            return;
+        }
+
+        // O canvas é o mais caro de montar: só com o painel aberto
+        let canvas = self.shows(Panel::Canvas);
+        if self.video_changed
+            && let Some(video) = &self.video
+        {
+            let tiles = video.tile_sheet_rgba();
+            let size = (TILE_SHEET_W, TILE_SHEET_H);
+            update_texture(rl, thread, &mut self.tiles, size, &tiles);
+            let sprites = video.oam_sheet_rgba();
+            let size = (OAM_SHEET_W, OAM_SHEET_H);
+            update_texture(rl, thread, &mut self.sprites, size, &sprites);
+            if canvas {
+                let size = (CANVAS_SIZE, CANVAS_SIZE);
+                update_texture(rl, thread, &mut self.canvas, size, &video.canvas_rgba());
+            }
+            self.video_changed = false;
+        }
+
+        let screen_w = rl.get_screen_width() as f32;
+        let mouse = rl.get_mouse_position();
+        let layout = self.layout(screen_w);
+
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            // Clique fora dos campos tira o foco
+            let clicked = |panel: Panel| {
+                layout.iter().any(|&(index, bounds)| {
+                    self.windows[index].panel == panel
+                        && field_bounds(content_bounds(bounds)).check_collision_point_rec(mouse)
+                })
+            };
+            self.breakpoint_field.focused = clicked(Panel::Breakpoints);
+            self.watch_field.focused = clicked(Panel::Watches);
+            self.cheat_field.focused = clicked(Panel::Cheats);
+
+            // A de cima pega o clique
+            self.dragging = layout
+                .iter()
+                .rev()
+                .find(|(_, bounds)| title_bar(*bounds).check_collision_point_rec(mouse))
+                .map(|&(index, bounds)| {
+                    (index, Vector2::new(mouse.x - bounds.x, mouse.y - bounds.y))
+                });
+        }
+
+        if let Some((index, grab)) = self.dragging {
+            let window = &mut self.windows[index];
+            if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+                window.docked = false;
+                window.position = Vector2::new(mouse.x - grab.x, mouse.y - grab.y);
+            } else {
+                window.docked = window.position.x + PANEL_WIDTH >= screen_w - DOCK_MARGIN;
+                self.dragging = None;
+            }
+        }
+
+        let panel = if self.breakpoint_field.focused {
+            Panel::Breakpoints
+        } else if self.watch_field.focused {
+            Panel::Watches
+        } else if self.cheat_field.focused {
+            Panel::Cheats
+        } else {
+            return;
+        };
+        let field = match panel {
+            Panel::Breakpoints => &mut self.breakpoint_field,
+            Panel::Watches => &mut self.watch_field,
+            _ => &mut self.cheat_field,
+        };
+        while let Some(c) = rl.get_char_pressed() {
+            field.text.push(c);
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+            field.text.pop();
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            self.submit(panel, symbols);
+        }
+    }
+
+    // Retângulo de cada janela aberta, barra de título inclusa, na ordem de desenho: as
+    // encaixadas primeiro, as soltas por cima
+    fn layout(&self, screen_w: f32) -> Vec<(usize, Rectangle)> {
+        let mut docked_y = toolbar_rows(screen_w) as f32 * TOOLBAR_HEIGHT + PADDING;
+        let mut layout = Vec::new();
+        for (index, window) in self.windows.iter().enumerate() {
+            if !window.open {
+                continue;
+            }
+            let height = TITLE_HEIGHT + window.panel.height();
+            let (x, y) = if window.docked {
+                let y = docked_y;
+                docked_y += height + PADDING;
+                (screen_w - PANEL_WIDTH - PADDING, y)
+            } else {
+                (window.position.x, window.position.y)
+            };
+            layout.push((index, Rectangle::new(x, y, PANEL_WIDTH, height)));
+        }
+        layout.sort_by_key(|&(index, _)| !self.windows[index].docked);
+        layout
+    }
+
+    // Enter ou o botão "+" do campo
+    fn submit(&mut self, panel: Panel, symbols: &Symbols) {
+        match panel {
+            Panel::Breakpoints => {
+                let text = mem::take(&mut self.breakpoint_field.text);
+                if text.trim().is_empty() {
+                    return;
+                }
+                match parse_target(text.trim(), symbols) {
+                    Ok(addr) => self.commands.push(EmulatorCommand::AddBreakpoint(addr)),
+                    Err(erro) => self.messages.push(erro),
+                }
+            }
+            Panel::Watches => {
+                let text = mem::take(&mut self.watch_field.text);
+                if text.trim().is_empty() {
+                    return;
+                }
+                match Watch::parse(&text, symbols) {
+                    Ok(watch) => self.commands.push(EmulatorCommand::AddWatch(watch)),
+                    Err(erro) => self.messages.push(erro),
+                }
+            }
+            Panel::Cheats => {
+                let text = mem::take(&mut self.cheat_field.text);
+                if !text.trim().is_empty() {
+                    let code = text.trim().to_string();
+                    self.commands.push(EmulatorCommand::AddCheat(code));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        d: &mut RaylibDrawHandle,
+        symbols: &Symbols,
+        paused: bool,
+        scope: Option<&[Vec<u8>; 4]>,
+        silenced: [bool; 4],
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        // Barra com um botão por painel no topo da janela
+        let screen_w = d.get_screen_width() as f32;
+        let columns = toolbar_columns(screen_w);
+        for (index, window) in self.windows.iter_mut().enumerate() {
+            let (row, column) = (index / columns, index % columns);
+            let bounds = Rectangle::new(
+                PADDING + column as f32 * TOOLBAR_STEP,
+                4.0 + row as f32 * TOOLBAR_HEIGHT,
+                TOOLBAR_STEP - 4.0,
+                TOOLBAR_HEIGHT - 8.0,
+            );
+            d.gui_toggle(bounds, window.panel.title(), &mut window.open);
+        }
+
+        for (index, bounds) in self.layout(screen_w) {
+            let panel = self.windows[index].panel;
+            // O "x" da barra de título fecha
+            if d.gui_window_box(bounds, panel.title()) {
+                self.windows[index].open = false;
+                continue;
+            }
+
+            let area = content_bounds(bounds);
+            match panel {
+                Panel::Cpu => self.draw_cpu(d, area, symbols, paused),
+                Panel::Io => {
+                    if let Some(inspection) = &self.inspection {
+                        for (row, line) in inspection.io.describe().iter().enumerate() {
+                            d.gui_label(row_bounds(area, row), line);
+                        }
+                    }
+                }
+                Panel::Breakpoints => self.draw_breakpoints(d, area, symbols),
+                Panel::Watches => self.draw_watches(d, area, symbols),
+                Panel::Tiles => self.draw_tiles(d, area),
+                Panel::Oam => self.draw_oam(d, area),
+                Panel::Palettes => self.draw_palettes(d, area),
+                Panel::Canvas => self.draw_canvas(d, area),
+                Panel::Scope => {
+                    if let Some(scope) = scope {
+                        let (x, y, width) = (area.x as i32, area.y as i32, area.width as i32);
+                        draw_scope(d, scope, silenced, x, y, width);
+                    }
+                }
+                Panel::Cheats => self.draw_cheats(d, area, symbols),
+            }
+        }
+    }
+
+    // Registradores, botões de execução e as próximas instruções
+    fn draw_cpu(
+        &mut self,
+        d: &mut RaylibDrawHandle,
+        area: Rectangle,
+        symbols: &Symbols,
+        paused: bool,
+    ) {
+        let Some(inspection) = &self.inspection else {
+            return;
+        };
+        let cpu = &inspection.cpu;
+        let flags: String = [(7, 'Z'), (6, 'N'), (5, 'H'), (4, 'C')]
+            .iter()
+            .map(|&(bit, name)| if cpu.af & (1 << bit) != 0 { name } else { '-' })
+            .collect();
+        let state = if inspection.locked {
+            "LOCKED"
+        } else if inspection.halt {
+            "HALT"
+        } else {
+            ""
+        };
+        let lines = [
+            format!("AF={:04X}  BC={:04X}  DE={:04X}", cpu.af, cpu.bc, cpu.de),
+            format!("HL={:04X}  SP={:04X}  PC={:04X}", cpu.hl, cpu.sp, cpu.pc),
+            format!(
+                "{}  IME={}  ROM {:02X}  {}",
+                flags, inspection.ime as u8, cpu.rom_bank, state
+            ),
+            symbols.locate(cpu.location()),
+        ];
+        for (row, line) in lines.iter().enumerate() {
+            d.gui_label(row_bounds(area, row), line);
+        }
+
+        let top = area.y + lines.len() as f32 * LINE_HEIGHT + PADDING / 2.0;
+        let buttons = [
+            if paused { "Continuar" } else { "Pausar" },
+            "Step",
+            "Over",
+            "Out",
+        ];
+        let width = (area.width - PADDING * (buttons.len() - 1) as f32) / buttons.len() as f32;
+        for (index, label) in buttons.iter().enumerate() {
+            let x = area.x + index as f32 * (width + PADDING);
+            if !d.gui_button(Rectangle::new(x, top, width, BUTTON_HEIGHT), label) {
+                continue;
+            }
+            self.commands.push(match index {
+                0 => EmulatorCommand::SetPaused(!paused),
+                1 => EmulatorCommand::Step(StepKind::Into),
+                2 => EmulatorCommand::Step(StepKind::Over),
+                _ => EmulatorCommand::Step(StepKind::Out),
+            });
+        }
+
+        let code = Rectangle::new(
+            area.x,
+            top + BUTTON_HEIGHT + PADDING / 2.0,
+            area.width,
+            LINE_HEIGHT * CODE_LINES as f32,
+        );
+        let upcoming = inspection.upcoming(symbols);
+        for (row, (addr, text)) in upcoming.iter().take(CODE_LINES).enumerate() {
+            let marker = if *addr == cpu.pc {
+                ">"
+            } else if inspection.breakpoints.contains(addr) {
+                "*"
+            } else {
+                " "
+            };
+            let line = format!("{} {:04X}  {}", marker, addr, text);
+            d.gui_label(row_bounds(code, row), &line);
+        }
+    }
+
+    fn draw_breakpoints(&mut self, d: &mut RaylibDrawHandle, area: Rectangle, symbols: &Symbols) {
+        if let Some(inspection) = &self.inspection {
+            let rows = ((area.height - BUTTON_HEIGHT - PADDING) / LINE_HEIGHT) as usize;
+            for (row, &addr) in inspection.breakpoints.iter().take(rows).enumerate() {
+                let bounds = row_bounds(area, row);
+                // Sem banco no breakpoint: o label é o do banco mapeado agora
+                let label = symbols
+                    .describe(inspection.cpu.rom_bank as usize, addr)
+                    .unwrap_or_default();
+                d.gui_label(bounds, &format!("${:04X} {}", addr, label));
+                if d.gui_button(remove_button(bounds), "x") {
+                    self.commands.push(EmulatorCommand::RemoveBreakpoint(addr));
+                }
+            }
+        }
+        self.draw_field(d, area, Panel::Breakpoints, symbols);
+    }
+
+    // Valor de cada watch no último Inspect, ou seja, a cada frame ou step
+    fn draw_watches(&mut self, d: &mut RaylibDrawHandle, area: Rectangle, symbols: &Symbols) {
+        if let Some(inspection) = &self.inspection {
+            let rows = ((area.height - BUTTON_HEIGHT - PADDING) / LINE_HEIGHT) as usize;
+            for (row, watch) in inspection.watches.iter().take(rows).enumerate() {
+                let bounds = row_bounds(area, row);
+                d.gui_label(bounds, &watch.describe());
+                if d.gui_button(remove_button(bounds), "x") {
+                    self.commands.push(EmulatorCommand::RemoveWatch(row));
+                }
+            }
+        }
+        self.draw_field(d, area, Panel::Watches, symbols);
+    }
+
+    fn draw_tiles(&self, d: &mut RaylibDrawHandle, area: Rectangle) {
+        let Some(texture) = &self.tiles else {
+            return;
+        };
+        let position = Vector2::new(area.x, area.y);
+        d.draw_texture_ex(texture, position, 0.0, TILE_SCALE, Color::WHITE);
+
+        // Tile sob o mouse e onde ele fica na VRAM
+        let sheet = Rectangle::new(
+            area.x,
+            area.y,
+            TILE_SHEET_W as f32 * TILE_SCALE,
+            TILE_SHEET_H as f32 * TILE_SCALE,
+        );
+        let mouse = d.get_mouse_position();
+        if sheet.check_collision_point_rec(mouse) {
+            let column = ((mouse.x - sheet.x) / (8.0 * TILE_SCALE)) as usize;
+            let row = ((mouse.y - sheet.y) / (8.0 * TILE_SCALE)) as usize;
+            let tile = row * (TILE_SHEET_W / 8) + column;
+            let bounds = Rectangle::new(area.x, sheet.y + sheet.height, area.width, LINE_HEIGHT);
+            let text = format!("Tile {} em ${:04X}", tile, 0x8000 + tile * 16);
+            d.gui_label(bounds, &text);
+        }
+    }
+
+    fn draw_oam(&self, d: &mut RaylibDrawHandle, area: Rectangle) {
+        let Some(texture) = &self.sprites else {
+            return;
+        };
+        let sheet = Rectangle::new(
+            area.x,
+            area.y,
+            OAM_SHEET_W as f32 * OAM_SCALE,
+            OAM_SHEET_H as f32 * OAM_SCALE,
+        );
+        // Fundo escuro pra cor 0, que é transparente
+        d.draw_rectangle_rec(sheet, Color::new(40, 40, 40, 255));
+        let position = Vector2::new(sheet.x, sheet.y);
+        d.draw_texture_ex(texture, position, 0.0, OAM_SCALE, Color::WHITE);
+
+        let mouse = d.get_mouse_position();
+        if let Some(video) = &self.video
+            && sheet.check_collision_point_rec(mouse)
+        {
+            let column = ((mouse.x - sheet.x) / (8.0 * OAM_SCALE)) as usize;
+            let row = ((mouse.y - sheet.y) / (16.0 * OAM_SCALE)) as usize;
+            let index = row * OAM_COLUMNS + column;
+            if let Some(sprite) = video.sprites().get(index) {
+                let bounds =
+                    Rectangle::new(area.x, sheet.y + sheet.height, area.width, LINE_HEIGHT);
+                let text = format!(
+                    "#{} X={} Y={} tile=${:02X} attr=${:02X}",
+                    index, sprite.x, sprite.y, sprite.tile, sprite.attributes
+                );
+                d.gui_label(bounds, &text);
+            }
+        }
+    }
+
+    // Plano do BG inteiro com a área visível de cada linha (vermelho), a borda da janela
+    // (azul) e os objetos (verde)
+    fn draw_canvas(&self, d: &mut RaylibDrawHandle, area: Rectangle) {
+        let (Some(texture), Some(video)) = (&self.canvas, &self.video) else {
+            return;
+        };
+        let position = Vector2::new(area.x, area.y);
+        d.draw_texture_ex(texture, position, 0.0, 1.0, Color::WHITE);
+        if let Some(line) = video.lines.first() {
+            let bounds =
+                Rectangle::new(area.x, area.y + CANVAS_SIZE as f32, area.width, LINE_HEIGHT);
+            let text = format!(
+                "SCX={} SCY={} WX={} WY={}",
+                line.scx, line.scy, line.wx, line.wy
+            );
+            d.gui_label(bounds, &text);
+        }
+    }
+
+    // BGP, OBP0 e OBP1 com o valor cru e a cor que cada índice dá. Não tem CGB no core, então
+    // não há paleta de cor pra mostrar
+    fn draw_palettes(&self, d: &mut RaylibDrawHandle, area: Rectangle) {
+        let Some(video) = &self.video else {
+            return;
+        };
+        let palettes = [
+            ("BGP", video.bgp),
+            ("OBP0", video.obp0),
+            ("OBP1", video.obp1),
+        ];
+        for (row, (name, value)) in palettes.iter().enumerate() {
+            let top = area.y + row as f32 * (SWATCH_HEIGHT + PADDING);
+            let label = Rectangle::new(area.x, top, SWATCH_WIDTH * 1.5, SWATCH_HEIGHT);
+            d.gui_label(label, &format!("{} ${:02X}", name, value));
+
+            for (color, [r, g, b]) in palette_rgb(*value).iter().enumerate() {
+                let x = label.x + label.width + color as f32 * (SWATCH_WIDTH + PADDING / 2.0);
+                let swatch = Rectangle::new(x, top, SWATCH_WIDTH, SWATCH_HEIGHT);
+                d.draw_rectangle_rec(swatch, Color::new(*r, *g, *b, 255));
+                d.draw_rectangle_lines_ex(swatch, 1.0, Color::GRAY);
+                // Índice e o tom escolhido pra ele (0 = mais claro)
+                let shade = (value >> (color * 2)) & 0b11;
+                let text = if shade < 2 {
+                    Color::BLACK
+                } else {
+                    Color::WHITE
+                };
+                let (x, y) = (swatch.x as i32 + 4, swatch.y as i32 + 7);
+                d.draw_text(&format!("{}={}", color, shade), x, y, 10, text);
+            }
+        }
+    }
+
+    fn draw_cheats(&mut self, d: &mut RaylibDrawHandle, area: Rectangle, symbols: &Symbols) {
+        let rows = ((area.height - BUTTON_HEIGHT - PADDING) / LINE_HEIGHT) as usize;
+        for (row, (code, enabled)) in self.cheats.iter().take(rows).enumerate() {
+            let bounds = row_bounds(area, row);
+            let check = Rectangle::new(
+                bounds.x,
+                bounds.y + 2.0,
+                LINE_HEIGHT - 4.0,
+                LINE_HEIGHT - 4.0,
+            );
+            let mut checked = *enabled;
+            d.gui_check_box(check, code, &mut checked);
+            if checked != *enabled {
+                let command = EmulatorCommand::SetCheatEnabled(code.clone(), checked);
+                self.commands.push(command);
+            }
+            if d.gui_button(remove_button(bounds), "x") {
+                self.commands
+                    .push(EmulatorCommand::RemoveCheat(code.clone()));
+            }
+        }
+        self.draw_field(d, area, Panel::Cheats, symbols);
+    }
+
+    // Campo de texto e botão "+" no rodapé do painel
+    fn draw_field(
+        &mut self,
+        d: &mut RaylibDrawHandle,
+        area: Rectangle,
+        panel: Panel,
+        symbols: &Symbols,
+    ) {
+        let (field, placeholder) = match panel {
+            Panel::Breakpoints => (&self.breakpoint_field, "endereço ou label"),
+            Panel::Watches => (&self.watch_field, "expressão: [wPlayerHP], hl+2, a & $0F"),
+            _ => (&self.cheat_field, "código GameShark ou Game Genie"),
+        };
+        let bounds = field_bounds(area);
+        d.draw_rectangle_rec(bounds, Color::WHITE);
+        let border = if field.focused {
+            Color::SKYBLUE
+        } else {
+            Color::GRAY
+        };
+        d.draw_rectangle_lines_ex(bounds, 1.0, border);
+
+        let text = Rectangle::new(bounds.x + 4.0, bounds.y, bounds.width - 8.0, bounds.height);
+        if field.focused {
+            d.gui_label(text, &format!("{}_", field.text));
+        } else if field.text.is_empty() {
+            d.draw_text(
+                placeholder,
+                text.x as i32,
+                (text.y + 6.0) as i32,
+                10,
+                Color::GRAY,
+            );
+        } else {
+            d.gui_label(text, &field.text);
+        }
+
+        let add = Rectangle::new(
+            bounds.x + bounds.width + PADDING / 2.0,
+            bounds.y,
+            BUTTON_HEIGHT,
+            BUTTON_HEIGHT,
+        );
+        if d.gui_button(add, "+") {
+            self.submit(panel, symbols);
+        }
+    }
+}
+
+// Cria a textura no primeiro uso e copia os pixels
+fn update_texture(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    texture: &mut Option<Texture2D>,
+    (width, height): (usize, usize),
+    rgba: &[u8],
+) {
+    let texture = texture.get_or_insert_with(|| {
+        let image = Image::gen_image_color(width as i32, height as i32, Color::BLANK);
+        rl.load_texture_from_image(thread, &image).unwrap()
+    });
+    texture.update_texture(rgba).unwrap();
+}
+
+// Botões da barra por linha: quebra quando a janela é estreita
+fn toolbar_columns(screen_w: f32) -> usize {
+    (((screen_w - PADDING) / TOOLBAR_STEP) as usize).max(1)
+}
+
+fn toolbar_rows(screen_w: f32) -> usize {
+    Panel::ALL.len().div_ceil(toolbar_columns(screen_w))
+}
+
+fn content_bounds(window: Rectangle) -> Rectangle {
+    Rectangle::new(
+        window.x + PADDING,
+        window.y + TITLE_HEIGHT + PADDING,
+        window.width - PADDING * 2.0,
+        window.height - TITLE_HEIGHT - PADDING * 2.0,
+    )
+}
+
+// A barra de título sem o botão de fechar
+fn title_bar(window: Rectangle) -> Rectangle {
+    Rectangle::new(
+        window.x,
+        window.y,
+        window.width - TITLE_HEIGHT,
+        TITLE_HEIGHT,
+    )
+}
+
+fn row_bounds(area: Rectangle, row: usize) -> Rectangle {
+    Rectangle::new(
+        area.x,
+        area.y + row as f32 * LINE_HEIGHT,
+        area.width,
+        LINE_HEIGHT,
+    )
+}
+
+fn remove_button(row: Rectangle) -> Rectangle {
+    Rectangle::new(
+        row.x + row.width - LINE_HEIGHT,
+        row.y + 1.0,
+        LINE_HEIGHT,
+        LINE_HEIGHT - 2.0,
+    )
+}
+
+// O campo de texto ocupa o rodapé, deixando espaço pro "+"
+fn field_bounds(area: Rectangle) -> Rectangle {
+    Rectangle::new(
+        area.x,
+        area.y + area.height - BUTTON_HEIGHT,
+        area.width - BUTTON_HEIGHT - PADDING / 2.0,
+        BUTTON_HEIGHT,
+    )
+}
@@ -0,0 +1,110 @@
+use raylib::prelude::*;
+
+// Pós-processamento da imagem escalada ([video] filter / shader no config). O filter =
+// ghosting não passa por aqui: vira o [video] ghosting do core, que mistura antes de escalar
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Filter {
+    None,
+    // Grade entre os pixels, como a tela do DMG
+    LcdGrid,
+    // Scanlines e vinheta
+    Crt,
+    // Fragment shader GLSL do usuário
+    Custom(String),
+}
+
+impl Filter {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "none" => Some(Filter::None),
+            "lcd" => Some(Filter::LcdGrid),
+            "crt" => Some(Filter::Crt),
+            _ => None,
+        }
+    }
+}
+
+// Shaders no formato padrão da raylib (GLSL 330): texture0, colDiffuse, fragTexCoord
+const LCD_GRID_FS: &str = r#"#version 330
+in vec2 fragTexCoord;
+in vec4 fragColor;
+uniform sampler2D texture0;
+uniform vec4 colDiffuse;
+uniform vec2 textureSize;
+uniform float scale;
+out vec4 finalColor;
+
+void main() {
+    vec4 color = texture(texture0, fragTexCoord) * colDiffuse * fragColor;
+    vec2 cell = fract(fragTexCoord * textureSize);
+    float edge = 1.0 / max(scale, 1.0);
+    float grid = (cell.x < edge || cell.y < edge) ? 0.75 : 1.0;
+    finalColor = vec4(color.rgb * grid, color.a);
+}
+"#;
+
+const CRT_FS: &str = r#"#version 330
+in vec2 fragTexCoord;
+in vec4 fragColor;
+uniform sampler2D texture0;
+uniform vec4 colDiffuse;
+uniform vec2 textureSize;
+uniform float scale;
+out vec4 finalColor;
+
+void main() {
+    vec4 color = texture(texture0, fragTexCoord) * colDiffuse * fragColor;
+    float line = sin(fragTexCoord.y * textureSize.y * 6.2831853) * 0.5 + 0.5;
+    float scan = mix(0.65, 1.0, line);
+    vec2 center = fragTexCoord - 0.5;
+    float vignette = 1.0 - dot(center, center) * 0.8;
+    finalColor = vec4(color.rgb * scan * vignette, color.a);
+}
+"#;
+
+pub struct DisplayFilter {
+    shader: Option<Shader>,
+    size_location: i32,
+    scale_location: i32,
+}
+
+impl DisplayFilter {
+    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread, filter: &Filter) -> Self {
+        let shader = match filter {
+            Filter::LcdGrid => Some(rl.load_shader_from_memory(thread, None, Some(LCD_GRID_FS))),
+            Filter::Crt => Some(rl.load_shader_from_memory(thread, None, Some(CRT_FS))),
+            Filter::Custom(path) => Some(rl.load_shader(thread, None, Some(path))),
+            Filter::None => None,
+        };
+
+        // Shader que não compilou: desenha sem filtro em vez de tela preta
+        let shader = shader.filter(|shader| {
+            let valid = shader.is_shader_valid();
+            if !valid {
+                eprintln!("shader inválido, desenhando sem filtro");
+            }
+            valid
+        });
+
+        let (size_location, scale_location) = match &shader {
+            Some(shader) => (
+                shader.get_shader_location("textureSize"),
+                shader.get_shader_location("scale"),
+            ),
+            None => (-1, -1),
+        };
+
+        Self {
+            shader,
+            size_location,
+            scale_location,
+        }
+    }
+
+    pub fn shader(&mut self, scale: f32, (width, height): (usize, usize)) -> Option<&mut Shader> {
+        let shader = self.shader.as_mut()?;
+        shader.set_shader_value(self.size_location, [width as f32, height as f32]);
+        shader.set_shader_value(self.scale_location, scale);
+        Some(shader)
+    }
+}
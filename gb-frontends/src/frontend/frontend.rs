@@ -0,0 +1,1220 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+use raylib::core::texture::RaylibTexture2D;
+use raylib::prelude::*;
+
+use crate::frontend::{
+    AudioOutput, AudioStats, Console, DEFAULT_LATENCY_MS, DebugPanels, DisplayFilter, Filter,
+    GamePalettes, GameProfiles, Hotkey, HotkeyEvent, HotkeyMap, InputMapping, MAX_GAMEPADS,
+    MacroKeys, Osd, Panel, PresenceReporter, RecentRoms, SessionState, backtrace_lines,
+    describe_trigger, parse_command,
+};
+use crate::archive;
+use crate::cartridge::Cartridge;
+use crate::debug::Symbols;
+use crate::joypad::{Buttons, InputMacro, JoypadInput, Tilt};
+use crate::machine::{
+    EmulationStats, EmulatorCommand, EmulatorEvent, EmulatorHandle, GB_H, GB_W, MovieAnchor,
+    ResetKind,
+};
+use crate::png;
+use crate::script::OverlayItem;
+use crate::sgb::{SGB_H, SGB_W};
+
+const STATE_SLOTS: u8 = 10;
+const INITIAL_SCALE: i32 = 3;
+// Duração de cada pulso de vibração, em segundos
+const RUMBLE_PULSE: f32 = 0.1;
+// Em pixels do Game Boy: 8 = altura de um tile
+const OVERLAY_FONT_SIZE: f32 = 8.0;
+// Painel do scope, em pixels da janela: uma faixa por canal
+const SCOPE_WIDTH: i32 = 256;
+pub const SCOPE_STRIP_HEIGHT: i32 = 40;
+const SCOPE_CHANNELS: [&str; 4] = ["SQ1", "SQ2", "WAVE", "NOISE"];
+// Botões do input display, na ordem da tela
+const INPUT_LABELS: [(&str, Buttons); 8] = [
+    ("<", Buttons::LEFT),
+    ("^", Buttons::UP),
+    ("v", Buttons::DOWN),
+    (">", Buttons::RIGHT),
+    ("SEL", Buttons::SELECT),
+    ("START", Buttons::START),
+    ("B", Buttons::B),
+    ("A", Buttons::A),
+];
+const INPUT_FONT_SIZE: i32 = 20;
+
+// Como a imagem de 160x144 é ampliada pra caber na janela
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Scaling {
+    // Maior múltiplo inteiro que cabe (pixels todos do mesmo tamanho)
+    Integer,
+    // Ocupa o máximo possível mantendo a proporção
+    Fractional,
+}
+
+impl Scaling {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "integer" => Some(Scaling::Integer),
+            "fractional" => Some(Scaling::Fractional),
+            _ => None,
+        }
+    }
+}
+
+// Retângulo do jogo dentro da janela (x, y, escala), centralizado com letterbox
+fn viewport(
+    screen_w: i32,
+    screen_h: i32,
+    (frame_w, frame_h): (usize, usize),
+    scaling: Scaling,
+) -> (f32, f32, f32) {
+    let fit = (screen_w as f32 / frame_w as f32).min(screen_h as f32 / frame_h as f32);
+    let scale = match scaling {
+        // Janela menor que 1x: deixa cortar em vez de sumir
+        Scaling::Integer => fit.floor().max(1.0),
+        Scaling::Fractional => fit,
+    };
+
+    let x = ((screen_w as f32 - frame_w as f32 * scale) * 0.5).floor();
+    let y = ((screen_h as f32 - frame_h as f32 * scale) * 0.5).floor();
+    (x, y, scale)
+}
+
+// Tamanho do frame pelo número de bytes: muda quando o SGB liga ou desliga
+fn frame_size_of(frame: &[u8]) -> (usize, usize) {
+    if frame.len() == SGB_W * SGB_H * 4 {
+        (SGB_W, SGB_H)
+    } else {
+        (GB_W, GB_H)
+    }
+}
+
+// Valor digital de cada canal no último frame, uma faixa por canal; em cinza os que estão
+// fora da mixagem
+pub fn draw_scope(
+    d: &mut impl RaylibDraw,
+    scope: &[Vec<u8>; 4],
+    silenced: [bool; 4],
+    left: i32,
+    top: i32,
+    width: i32,
+) {
+    d.draw_rectangle(
+        left,
+        top,
+        width,
+        SCOPE_STRIP_HEIGHT * 4,
+        Color::new(0, 0, 0, 180),
+    );
+    for (channel, samples) in scope.iter().enumerate() {
+        let top = top + channel as i32 * SCOPE_STRIP_HEIGHT;
+        let color = if silenced[channel] {
+            Color::GRAY
+        } else {
+            Color::GREEN
+        };
+        d.draw_text(SCOPE_CHANNELS[channel], left + 4, top + 2, 10, color);
+        if samples.is_empty() {
+            continue;
+        }
+
+        let y = |x: i32| {
+            let index = x as usize * samples.len() / width as usize;
+            let height = samples[index] as i32 * (SCOPE_STRIP_HEIGHT - 8) / 15;
+            top + SCOPE_STRIP_HEIGHT - 4 - height
+        };
+        for x in 1..width {
+            d.draw_line(left + x - 1, y(x - 1), left + x, y(x), color);
+        }
+    }
+}
+
+// Uma caixa por botão, acesa quando apertado; o frame do movie vai na linha de cima
+fn draw_input_display(d: &mut impl RaylibDraw, display: &InputDisplay, right: i32, bottom: i32) {
+    let widths = INPUT_LABELS.map(|(label, _)| label.len() as i32 * 12 + 10);
+    let height = INPUT_FONT_SIZE + 8;
+    let top = bottom - height;
+    let mut left = right - widths.iter().sum::<i32>() - 4 * (INPUT_LABELS.len() as i32 - 1);
+
+    if let Some(frame) = display.movie_frame {
+        let text = format!("Movie: frame {}", frame);
+        d.draw_text(&text, left, top - INPUT_FONT_SIZE - 4, INPUT_FONT_SIZE, Color::WHITE);
+    }
+    for ((label, button), width) in INPUT_LABELS.iter().zip(widths) {
+        let pressed = display.buttons.contains(*button);
+        let (fill, text) = if pressed {
+            (Color::YELLOW, Color::BLACK)
+        } else {
+            (Color::new(0, 0, 0, 160), Color::GRAY)
+        };
+        d.draw_rectangle(left, top, width, height, fill);
+        d.draw_text(label, left + 5, top + 4, INPUT_FONT_SIZE, text);
+        left += width + 4;
+    }
+}
+
+pub fn black_texture(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    (width, height): (usize, usize),
+) -> Texture2D {
+    let image = Image::gen_image_color(width as i32, height as i32, Color::BLACK);
+    rl.load_texture_from_image(thread, &image).unwrap()
+}
+
+// Macro que o core terminou de gravar: vai pro config e vira a do play_macro
+fn save_macro(macros: &mut Option<MacroKeys>, osd: &mut Osd, input_macro: InputMacro) {
+    let Some(macros) = macros else {
+        return;
+    };
+    match macros.save(input_macro) {
+        Ok(name) => osd.push(format!("Macro gravada: {}", name)),
+        Err(erro) => {
+            eprintln!("{}", erro);
+            osd.push(erro);
+        }
+    }
+}
+
+// Segunda instância do --link, desenhada à direita da primeira
+struct LinkedView {
+    emulator: EmulatorHandle,
+    texture: Texture2D,
+    frame_size: (usize, usize),
+}
+
+// Overlay de estatísticas: o áudio é medido aqui, o resto vem do core a cada segundo
+#[derive(Default)]
+struct StatsOverlay {
+    audio: AudioStats,
+    emulation: EmulationStats,
+}
+
+// Input display: o que o core mandou no último frame rodado (parado, fica o último)
+#[derive(Default)]
+struct InputDisplay {
+    buttons: Buttons,
+    movie_frame: Option<usize>,
+}
+
+// Janela raylib: desenha os frames que chegam da thread de emulação
+pub struct Frontend {
+    rl: RaylibHandle,
+    thread: RaylibThread,
+    texture: Texture2D,
+    lock_message: Option<String>,
+    vsync: bool,
+    scaling: Scaling,
+    filter: DisplayFilter,
+    input: InputMapping,
+    hotkeys: HotkeyMap,
+    joypad: JoypadInput,
+    tilt: Tilt,
+    // Save states e screenshots ficam ao lado da ROM
+    rom_path: PathBuf,
+    slot: u8,
+    paused: bool,
+    cheats: bool,
+    // Códigos vindos do <rom>.cht, pra remover os que saírem do arquivo no reload
+    file_cheats: Vec<String>,
+    // Último frame recebido, pra screenshot
+    frame: Vec<u8>,
+    // 160x144, ou 256x224 com a moldura do SGB
+    frame_size: (usize, usize),
+    osd: Osd,
+    console: Console,
+    overlay: Vec<OverlayItem>,
+    // Movie gravando ou tocando (a mesma tecla encerra)
+    movie: bool,
+    macros: Option<MacroKeys>,
+    // Gravando uma macro (a mesma tecla termina)
+    recording_macro: bool,
+    // Intensidade do rumble do cartucho, em %
+    rumble: u8,
+    linked: Option<LinkedView>,
+    // Teclado e controle indo pra instância da direita
+    linked_focus: bool,
+    // Mute por canal do APU; o solo, quando ligado, vale por cima
+    muted: [bool; 4],
+    solo: Option<usize>,
+    // Overlay do scope aberto (o painel de debug também mostra o scope)
+    scope_overlay: bool,
+    // Formas de onda do último frame, enquanto o core manda
+    scope: Option<[Vec<u8>; 4]>,
+    // Tamanho do buffer do stream de áudio ([audio] latency)
+    audio_latency: u32,
+    // Overlay de estatísticas aberto, com os últimos contadores
+    stats: Option<StatsOverlay>,
+    input_display: Option<InputDisplay>,
+    // Labels do .sym da ROM, pro console e as mensagens de breakpoint
+    symbols: Symbols,
+    debug: DebugPanels,
+    // .pal do jogo atual, vigiado pra recarregar
+    palettes: Option<GamePalettes>,
+    // Perfis por jogo do gb-emu.ini, aplicados a cada ROM carregada
+    profiles: Option<GameProfiles>,
+    // Discord e arquivo de status do [presence]
+    presence: Option<PresenceReporter>,
+    // Lista do launcher, atualizada com as ROMs arrastadas pra janela
+    recent: Option<RecentRoms>,
+}
+
+impl Frontend {
+    pub fn new(
+        title: &str,
+        rom_path: &Path,
+        vsync: bool,
+        scaling: Scaling,
+        filter: &Filter,
+        input: InputMapping,
+        hotkeys: HotkeyMap,
+    ) -> Self {
+        let mut builder = raylib::init();
+        builder
+            .size(GB_W as i32 * INITIAL_SCALE, GB_H as i32 * INITIAL_SCALE)
+            .title(title)
+            .resizable();
+        if vsync {
+            builder.vsync();
+        }
+        let (mut rl, thread) = builder.build();
+
+        let texture = black_texture(&mut rl, &thread, (GB_W, GB_H));
+        let filter = DisplayFilter::new(&mut rl, &thread, filter);
+
+        Self {
+            rl,
+            thread,
+            texture,
+            lock_message: None,
+            vsync,
+            scaling,
+            filter,
+            input,
+            hotkeys,
+            joypad: JoypadInput::default(),
+            tilt: Tilt::default(),
+            rom_path: rom_path.to_path_buf(),
+            slot: 0,
+            paused: false,
+            cheats: true,
+            file_cheats: Vec::new(),
+            frame: vec![0; GB_W * GB_H * 4],
+            frame_size: (GB_W, GB_H),
+            osd: Osd::new(),
+            console: Console::new(),
+            overlay: Vec::new(),
+            movie: false,
+            macros: None,
+            recording_macro: false,
+            rumble: 0,
+            linked: None,
+            linked_focus: false,
+            muted: [false; 4],
+            solo: None,
+            scope_overlay: false,
+            scope: None,
+            audio_latency: DEFAULT_LATENCY_MS,
+            stats: None,
+            input_display: None,
+            symbols: Symbols::new(),
+            debug: DebugPanels::new(),
+            palettes: None,
+            profiles: None,
+            presence: None,
+            recent: None,
+        }
+    }
+
+    // Canais silenciados desde o início ([audio] mute)
+    pub fn set_muted_channels(&mut self, muted: [bool; 4]) {
+        self.muted = muted;
+    }
+
+    pub fn set_audio_latency(&mut self, latency_ms: u32) {
+        self.audio_latency = latency_ms;
+    }
+
+    pub fn set_symbols(&mut self, symbols: Symbols) {
+        self.symbols = symbols;
+    }
+
+    pub fn set_macros(&mut self, macros: MacroKeys) {
+        self.macros = Some(macros);
+    }
+
+    pub fn set_recent(&mut self, recent: RecentRoms) {
+        self.recent = Some(recent);
+    }
+
+    // Já com o .pal do jogo inicial escolhido
+    pub fn set_palettes(&mut self, palettes: GamePalettes) {
+        self.palettes = Some(palettes);
+    }
+
+    // Já aplicados ao jogo inicial; valem pras ROMs trocadas depois
+    pub fn set_profiles(&mut self, profiles: GameProfiles) {
+        self.profiles = Some(profiles);
+    }
+
+    // Já com a sessão do jogo inicial começada
+    pub fn set_presence(&mut self, presence: PresenceReporter) {
+        self.presence = Some(presence);
+    }
+
+    // Com `linked`, as duas instâncias dividem a janela; hotkeys e console ficam na primeira.
+    // Devolve o código de saída quando uma condição de parada da automação fecha a janela
+    pub fn run(
+        &mut self,
+        mut emulator: EmulatorHandle,
+        linked: Option<EmulatorHandle>,
+    ) -> Option<i32> {
+        if self.rom_path.with_extension("cht").exists() {
+            self.reload_cheats(&emulator);
+        }
+        emulator.send(EmulatorCommand::SetMutedChannels(self.silenced()));
+
+        if let Some(linked) = linked {
+            let texture = black_texture(&mut self.rl, &self.thread, (GB_W, GB_H));
+            self.linked = Some(LinkedView {
+                emulator: linked,
+                texture,
+                frame_size: (GB_W, GB_H),
+            });
+            self.rl
+                .set_window_size(GB_W as i32 * INITIAL_SCALE * 2, GB_H as i32 * INITIAL_SCALE);
+        }
+
+        // O stream empresta o dispositivo, então os dois vivem só dentro do run. O áudio
+        // da instância do --link não toca
+        let device = match RaylibAudio::init_audio_device() {
+            Ok(device) => Some(device),
+            Err(erro) => {
+                eprintln!("sem áudio: {}", erro);
+                None
+            }
+        };
+        let mut audio = device
+            .as_ref()
+            .map(|device| AudioOutput::new(device, self.audio_latency));
+
+        let mut exit_code = None;
+        while exit_code.is_none() && !self.rl.window_should_close() {
+            // Digitando num campo dos painéis de debug, o teclado não vai pro jogo
+            let typing = self.debug.typing();
+            let joypad = if typing {
+                JoypadInput::default()
+            } else {
+                self.input.poll(&self.rl)
+            };
+            if joypad != self.joypad {
+                self.joypad = joypad;
+                self.focused(&emulator).send(EmulatorCommand::SetInput(joypad));
+            }
+
+            let tilt = self.input.poll_tilt(&self.rl);
+            if tilt != self.tilt {
+                self.tilt = tilt;
+                self.focused(&emulator).send(EmulatorCommand::SetTilt(tilt));
+            }
+
+            if !typing {
+                for event in self.hotkeys.poll(&self.rl) {
+                    self.handle_hotkey(event, &emulator);
+                }
+                let pressed = self.macros.as_mut().map(|macros| macros.poll(&self.rl));
+                for input_macro in pressed.unwrap_or_default() {
+                    self.focused(&emulator)
+                        .send(EmulatorCommand::PlayMacro(input_macro));
+                }
+            }
+
+            if self.rl.is_file_dropped() {
+                let dropped = self.rl.load_dropped_files();
+                if let Some(path) = dropped.paths().first() {
+                    self.load_rom(Path::new(path), &emulator);
+                }
+            }
+
+            for line in self.console.poll() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match parse_command(&line, &self.symbols) {
+                    Ok(command) => emulator.send(command),
+                    Err(erro) => eprintln!("{}", erro),
+                }
+            }
+
+            // Sem vsync quem dita o ritmo da tela é o pacer da emulação: espera o próximo frame
+            if !self.vsync && !self.paused {
+                match emulator.frames.recv_timeout(Duration::from_millis(100)) {
+                    Ok(frame) => self.present(frame),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+
+            // Só o frame mais recente interessa
+            if let Some(frame) = emulator.frames.try_iter().last() {
+                self.present(frame);
+            }
+
+            if let Some(linked) = &mut self.linked {
+                if let Some(frame) = linked.emulator.frames.try_iter().last() {
+                    let size = frame_size_of(&frame);
+                    if size != linked.frame_size {
+                        linked.frame_size = size;
+                        linked.texture = black_texture(&mut self.rl, &self.thread, size);
+                    }
+                    linked.texture.update_texture(&frame).unwrap();
+                }
+
+                // Da segunda instância só interessam os erros (e a macro gravada com o
+                // controle nela)
+                for event in linked.emulator.events.try_iter() {
+                    match event {
+                        EmulatorEvent::IllegalOpcode {
+                            pc,
+                            opcode,
+                            locked: true,
+                        } => self.osd.push(format!(
+                            "P2: CPU locked at ${:04X} (opcode {:02X})",
+                            pc, opcode
+                        )),
+                        EmulatorEvent::Error(erro) => {
+                            eprintln!("P2: {}", erro);
+                            self.osd.push(format!("P2: {}", erro));
+                        }
+                        EmulatorEvent::MacroRecorded(input_macro) => {
+                            save_macro(&mut self.macros, &mut self.osd, input_macro);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            for event in emulator.events.try_iter() {
+                match event {
+                    EmulatorEvent::IllegalOpcode {
+                        pc,
+                        opcode,
+                        locked: true,
+                    } => {
+                        self.lock_message =
+                            Some(format!("CPU locked at ${:04X} (opcode {:02X})", pc, opcode));
+                    }
+                    EmulatorEvent::IllegalOpcode { .. } => {}
+                    // O frame em si chega pelo canal de frames; o serial já sai no stdout
+                    EmulatorEvent::FrameReady | EmulatorEvent::SerialByte(_) => {}
+                    EmulatorEvent::AudioSamples(samples) => {
+                        if let Some(audio) = &mut audio {
+                            audio.push(&samples);
+                        }
+                    }
+                    EmulatorEvent::Breakpoint { pc, bank } => {
+                        self.paused = true;
+                        self.osd.push(match self.symbols.describe(bank, pc) {
+                            Some(label) => format!("Breakpoint em ${:04X} ({})", pc, label),
+                            None => format!("Breakpoint em ${:04X}", pc),
+                        });
+                    }
+                    EmulatorEvent::Triggered(hit) => {
+                        self.paused = true;
+                        let message = describe_trigger(hit, &self.symbols);
+                        println!("{}", message);
+                        self.osd.push(message);
+                    }
+                    EmulatorEvent::Stepped { pc, bank } => {
+                        self.paused = true;
+                        println!("{}", self.symbols.locate((bank, pc)));
+                    }
+                    EmulatorEvent::Backtrace { pc, frames } => {
+                        for line in backtrace_lines(pc, &frames, &self.symbols) {
+                            println!("{}", line);
+                        }
+                    }
+                    EmulatorEvent::History(entries) => {
+                        for entry in &entries {
+                            println!("{}", entry.format(&self.symbols));
+                        }
+                    }
+                    EmulatorEvent::Watches(watches) => {
+                        for (index, watch) in watches.iter().enumerate() {
+                            println!("{}: {}", index, watch.describe());
+                        }
+                    }
+                    EmulatorEvent::PpuLog(events) => {
+                        for event in &events {
+                            println!("{}", event.describe());
+                        }
+                    }
+                    EmulatorEvent::StateSaved(_) => {
+                        self.osd.push(format!("State {} salvo", self.slot));
+                    }
+                    EmulatorEvent::StateLoaded(_) => {
+                        self.lock_message = None;
+                        self.osd.push(format!("State {} carregado", self.slot));
+                    }
+                    EmulatorEvent::SearchResults { total, results } => {
+                        println!("{} candidatos", total);
+                        for (index, result) in results.iter().enumerate() {
+                            println!("  {:3}: {:?} = {}", index, result.address, result.value);
+                        }
+                    }
+                    EmulatorEvent::CheatAdded(code) => {
+                        self.osd.push(format!("Cheat {} adicionado", code));
+                    }
+                    EmulatorEvent::MovieRecording(path) => {
+                        self.movie = true;
+                        self.osd.push(format!("Gravando movie: {}", path.display()));
+                    }
+                    EmulatorEvent::MoviePlaying(path) => {
+                        self.movie = true;
+                        self.osd.push(format!("Tocando movie: {}", path.display()));
+                    }
+                    EmulatorEvent::MovieStopped { frames } => {
+                        self.movie = false;
+                        self.osd.push(format!("Movie encerrado ({} frames)", frames));
+                    }
+                    EmulatorEvent::MovieRerecorded { frame, rerecords } => {
+                        self.osd.push(format!("Re-record {} (frame {})", rerecords, frame));
+                    }
+                    EmulatorEvent::Rumble(level) => {
+                        self.rumble = level;
+                        if level == 0 {
+                            self.set_vibration(0.0, 0.0);
+                        }
+                    }
+                    // Sem áudio ainda: a melodia do piezo só aparece na tela
+                    EmulatorEvent::Melody(tone) => {
+                        self.osd.push(format!("HuC3: melodia {}", tone));
+                    }
+                    EmulatorEvent::NetplaySynced { frame } => {
+                        self.osd
+                            .push(format!("Netplay sincronizado no frame {}", frame));
+                    }
+                    EmulatorEvent::Resumed => {
+                        self.lock_message = None;
+                        self.osd.push("Continuando de onde parou");
+                    }
+                    EmulatorEvent::BatterySaved => self.osd.push("Save gravado"),
+                    EmulatorEvent::RomLoaded { path, title, crc } => {
+                        self.rl.set_window_title(&self.thread, &title);
+                        self.symbols = Symbols::for_rom(&path).unwrap_or_else(|erro| {
+                            eprintln!("{}", erro);
+                            Symbols::new()
+                        });
+                        self.rom_path = path;
+                        self.lock_message = None;
+                        self.overlay.clear();
+                        self.osd.push(format!("ROM carregada: {}", title));
+                        if let Some(presence) = &mut self.presence {
+                            presence.start(&title);
+                        }
+                        self.load_rom_cheats(&emulator);
+                        if let Some(recent) = &mut self.recent
+                            && let Err(erro) = recent.add(&self.rom_path)
+                        {
+                            self.osd.push(erro);
+                        }
+                        if let Some(profiles) = &self.profiles {
+                            let profile = profiles.resolve(&title, crc);
+                            emulator.send(EmulatorCommand::SetOverclock(profile.overclock));
+                            emulator.send(EmulatorCommand::SetRunAhead(profile.run_ahead));
+                            self.input = profile.input;
+                            if let Some(name) = profile.name {
+                                self.osd.push(format!("Perfil: {}", name));
+                            }
+                            if let Some(palettes) = &mut self.palettes {
+                                match palettes.select(profile.palette) {
+                                    Ok(mode) => {
+                                        emulator.send(EmulatorCommand::SetColorization(mode))
+                                    }
+                                    Err(erro) => self.osd.push(erro),
+                                }
+                            }
+                        }
+                    }
+                    EmulatorEvent::Overlay(overlay) => self.overlay = overlay,
+                    EmulatorEvent::InputDisplay {
+                        buttons,
+                        movie_frame,
+                    } => {
+                        if let Some(display) = &mut self.input_display {
+                            *display = InputDisplay {
+                                buttons,
+                                movie_frame,
+                            };
+                        }
+                    }
+                    EmulatorEvent::Stats(emulation) => {
+                        if let Some(stats) = &mut self.stats {
+                            stats.emulation = emulation;
+                        }
+                    }
+                    EmulatorEvent::Scope(scope) => {
+                        if self.scope.is_some() {
+                            self.scope = Some(scope);
+                        }
+                    }
+                    EmulatorEvent::Inspection(inspection) => self.debug.set_inspection(inspection),
+                    EmulatorEvent::VideoInspection(video) => self.debug.set_video(video),
+                    EmulatorEvent::CheatList(cheats) => self.debug.set_cheats(cheats),
+                    EmulatorEvent::MacroRecorded(input_macro) => {
+                        save_macro(&mut self.macros, &mut self.osd, input_macro);
+                    }
+                    EmulatorEvent::AchievementUnlocked {
+                        title,
+                        description,
+                        points,
+                    } => {
+                        self.osd.push(format!("Conquista: {} ({} pontos)", title, points));
+                        if !description.is_empty() {
+                            self.osd.push(description);
+                        }
+                    }
+                    EmulatorEvent::ProfileSaved(path) => {
+                        self.osd.push(format!("Profile: {}", path.display()));
+                    }
+                    EmulatorEvent::CoverageSaved(path) => {
+                        self.osd.push(format!("Cobertura: {}", path.display()));
+                    }
+                    EmulatorEvent::TraceSaved(path) => {
+                        self.osd.push(format!("Trace: {}", path.display()));
+                    }
+                    EmulatorEvent::CrashReport(path) => {
+                        eprintln!("relatório de crash: {}", path.display());
+                        self.osd
+                            .push(format!("Relatório de crash: {}", path.display()));
+                    }
+                    EmulatorEvent::ScreenshotSaved(path) => {
+                        self.osd.push(format!("Screenshot: {}", path.display()));
+                    }
+                    EmulatorEvent::SerialMessage(message) => {
+                        eprintln!("{}", message);
+                        self.osd.push(message);
+                    }
+                    EmulatorEvent::Exit(code) => exit_code = Some(code),
+                    EmulatorEvent::Error(erro) => {
+                        eprintln!("{}", erro);
+                        self.osd.push(erro);
+                    }
+                }
+            }
+
+            // Pausado não chega amostra: completar os buffers só contaria underrun à toa
+            if let Some(audio) = &mut audio
+                && !self.paused
+            {
+                audio.update();
+            }
+            if let (Some(stats), Some(audio)) = (&mut self.stats, &audio) {
+                stats.audio = audio.stats();
+            }
+
+            // A vibração da raylib tem duração: com o motor ligado é renovada todo frame
+            if self.rumble > 0 {
+                self.set_vibration(self.rumble as f32 / 100.0, RUMBLE_PULSE);
+            }
+
+            for request in self.debug.requests() {
+                emulator.send(request);
+            }
+            self.debug.update(&mut self.rl, &self.thread, &self.symbols);
+
+            self.draw();
+
+            // Botões dos painéis: o pause passa pelo frontend pra ele saber se está pausado
+            for command in self.debug.take_commands() {
+                if let EmulatorCommand::SetPaused(paused) = command {
+                    self.paused = paused;
+                    self.send_linked(EmulatorCommand::SetPaused(paused));
+                }
+                emulator.send(command);
+            }
+            for message in self.debug.take_messages() {
+                self.osd.push(message);
+            }
+            self.update_scope(&emulator);
+
+            // .pal do jogo editado com o emulador aberto
+            match self.palettes.as_mut().and_then(GamePalettes::poll) {
+                Some(Ok(mode)) => {
+                    emulator.send(EmulatorCommand::SetColorization(mode));
+                    self.osd.push("Paleta recarregada");
+                }
+                Some(Err(erro)) => self.osd.push(erro),
+                None => {}
+            }
+
+            if let Some(presence) = &mut self.presence {
+                presence.set_state(if self.paused {
+                    SessionState::Paused
+                } else {
+                    SessionState::Playing
+                });
+                for erro in presence.poll() {
+                    self.osd.push(erro);
+                }
+            }
+        }
+
+        self.set_vibration(0.0, 0.0);
+        emulator.stop();
+        if let Some(linked) = &mut self.linked {
+            linked.emulator.stop();
+        }
+        exit_code
+    }
+
+    // Canais fora da mixagem: com solo, todos menos ele
+    fn silenced(&self) -> [bool; 4] {
+        match self.solo {
+            Some(solo) => std::array::from_fn(|channel| channel != solo),
+            None => self.muted,
+        }
+    }
+
+    fn focused<'a>(&'a self, emulator: &'a EmulatorHandle) -> &'a EmulatorHandle {
+        match &self.linked {
+            Some(linked) if self.linked_focus => &linked.emulator,
+            _ => emulator,
+        }
+    }
+
+    // Pausa e fast-forward valem pras duas instâncias, senão o cabo fica esperando
+    fn send_linked(&self, command: EmulatorCommand) {
+        if let Some(linked) = &self.linked {
+            linked.emulator.send(command);
+        }
+    }
+
+    fn gamepad_connected(&self) -> bool {
+        (0..MAX_GAMEPADS).any(|gamepad| self.rl.is_gamepad_available(gamepad))
+    }
+
+    fn set_vibration(&mut self, intensity: f32, duration: f32) {
+        for gamepad in 0..MAX_GAMEPADS {
+            if self.rl.is_gamepad_available(gamepad) {
+                self.rl
+                    .set_gamepad_vibration(gamepad, intensity, intensity, duration);
+            }
+        }
+    }
+
+    fn present(&mut self, frame: Vec<u8>) {
+        // O tamanho muda quando o SGB liga ou desliga (troca de ROM)
+        let size = frame_size_of(&frame);
+        if size != self.frame_size {
+            self.frame_size = size;
+            self.texture = black_texture(&mut self.rl, &self.thread, size);
+        }
+        self.texture.update_texture(&frame).unwrap();
+        self.frame = frame;
+    }
+
+    fn state_path(&self) -> PathBuf {
+        self.rom_path.with_extension(format!("ss{}", self.slot))
+    }
+
+    fn movie_path(&self) -> PathBuf {
+        self.rom_path.with_extension("gbm")
+    }
+
+    // Primeiro <rom>-N.png que ainda não existe
+    fn screenshot_path(&self) -> PathBuf {
+        let stem = self
+            .rom_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+        (1..)
+            .map(|n| self.rom_path.with_file_name(format!("{}-{}.png", stem, n)))
+            .find(|path| !path.exists())
+            .unwrap()
+    }
+
+    // <rom>.cht: um código por linha, '!' na frente deixa desligado, '#' é comentário
+    fn reload_cheats(&mut self, emulator: &EmulatorHandle) {
+        let path = self.rom_path.with_extension("cht");
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(erro) => {
+                self.osd
+                    .push(format!("erro ao ler '{}': {}", path.display(), erro));
+                return;
+            }
+        };
+
+        let mut codes = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (code, enabled) = match line.strip_prefix('!') {
+                Some(code) => (code.trim(), false),
+                None => (line, true),
+            };
+            emulator.send(EmulatorCommand::AddCheat(code.to_string()));
+            emulator.send(EmulatorCommand::SetCheatEnabled(code.to_string(), enabled));
+            codes.push(code.to_uppercase());
+        }
+
+        for old in &self.file_cheats {
+            if !codes.contains(old) {
+                emulator.send(EmulatorCommand::RemoveCheat(old.clone()));
+            }
+        }
+
+        self.osd.push(format!("{} cheats carregados", codes.len()));
+        self.file_cheats = codes;
+    }
+
+    // ROM arrastada pra janela (.gb, .gbc ou .zip)
+    fn load_rom(&mut self, path: &Path, emulator: &EmulatorHandle) {
+        match archive::read_rom(path).and_then(Cartridge::load) {
+            Ok(cartridge) => emulator.send(EmulatorCommand::LoadRom {
+                cartridge: Box::new(cartridge),
+                path: path.to_path_buf(),
+            }),
+            Err(erro) => {
+                eprintln!("{}", erro);
+                self.osd.push(erro);
+            }
+        }
+    }
+
+    // Os cheats do <rom>.cht antigo saem; os do novo entram
+    // banco:endereço como no RGBDS, com o label do .sym quando tiver
+    fn load_rom_cheats(&mut self, emulator: &EmulatorHandle) {
+        for old in self.file_cheats.drain(..) {
+            emulator.send(EmulatorCommand::RemoveCheat(old));
+        }
+        if self.rom_path.with_extension("cht").exists() {
+            self.reload_cheats(emulator);
+        }
+    }
+
+    fn handle_hotkey(&mut self, event: HotkeyEvent, emulator: &EmulatorHandle) {
+        match event {
+            HotkeyEvent::Pressed(Hotkey::SaveState) => {
+                emulator.send(EmulatorCommand::SaveState(self.state_path()));
+            }
+            HotkeyEvent::Pressed(Hotkey::LoadState) => {
+                emulator.send(EmulatorCommand::LoadState(self.state_path()));
+            }
+            HotkeyEvent::Pressed(Hotkey::NextSlot) => {
+                self.slot = (self.slot + 1) % STATE_SLOTS;
+                self.osd.push(format!("Slot {}", self.slot));
+            }
+            HotkeyEvent::Pressed(Hotkey::Rewind) => {
+                emulator.send(EmulatorCommand::SetRewind(true));
+                self.osd.push("Rewind");
+            }
+            HotkeyEvent::Released(Hotkey::Rewind) => {
+                emulator.send(EmulatorCommand::SetRewind(false));
+            }
+            HotkeyEvent::Pressed(Hotkey::FastForward) => {
+                emulator.send(EmulatorCommand::SetFastForward(true));
+                self.send_linked(EmulatorCommand::SetFastForward(true));
+                self.osd.push("Fast-forward ON");
+            }
+            HotkeyEvent::Released(Hotkey::FastForward) => {
+                emulator.send(EmulatorCommand::SetFastForward(false));
+                self.send_linked(EmulatorCommand::SetFastForward(false));
+                self.osd.push("Fast-forward OFF");
+            }
+            HotkeyEvent::Pressed(Hotkey::Screenshot) => {
+                let path = self.screenshot_path();
+                let (width, height) = self.frame_size;
+                match png::write_png(&path, width, height, &self.frame) {
+                    Ok(()) => self.osd.push(format!("Screenshot: {}", path.display())),
+                    Err(erro) => {
+                        let message = format!("erro ao salvar '{}': {}", path.display(), erro);
+                        eprintln!("{}", message);
+                        self.osd.push(message);
+                    }
+                }
+            }
+            HotkeyEvent::Pressed(Hotkey::Pause) => {
+                self.paused = !self.paused;
+                emulator.send(EmulatorCommand::SetPaused(self.paused));
+                self.send_linked(EmulatorCommand::SetPaused(self.paused));
+                self.osd.push(if self.paused {
+                    "Pausado"
+                } else {
+                    "Continuando"
+                });
+            }
+            HotkeyEvent::Pressed(Hotkey::Reset) => {
+                self.lock_message = None;
+                emulator.send(EmulatorCommand::Reset(ResetKind::Soft));
+                self.osd.push("Reset");
+            }
+            HotkeyEvent::Pressed(Hotkey::HardReset) => {
+                self.lock_message = None;
+                emulator.send(EmulatorCommand::Reset(ResetKind::Hard));
+                self.osd.push("Power cycle");
+            }
+            HotkeyEvent::Pressed(Hotkey::Fullscreen) => {
+                // Borderless: não troca a resolução do monitor
+                self.rl.toggle_borderless_windowed();
+            }
+            HotkeyEvent::Pressed(Hotkey::ToggleCheats) => {
+                self.cheats = !self.cheats;
+                emulator.send(EmulatorCommand::SetCheatsEnabled(self.cheats));
+                self.osd.push(if self.cheats {
+                    "Cheats ON"
+                } else {
+                    "Cheats OFF"
+                });
+            }
+            HotkeyEvent::Pressed(Hotkey::ReloadCheats) => self.reload_cheats(emulator),
+            HotkeyEvent::Pressed(Hotkey::RecordMovie | Hotkey::PlayMovie) if self.movie => {
+                emulator.send(EmulatorCommand::StopMovie);
+            }
+            HotkeyEvent::Pressed(Hotkey::RecordMovie) => {
+                self.lock_message = None;
+                let path = self.movie_path();
+                emulator.send(EmulatorCommand::RecordMovie(path, MovieAnchor::PowerOn));
+            }
+            HotkeyEvent::Pressed(Hotkey::PlayMovie) => {
+                self.lock_message = None;
+                emulator.send(EmulatorCommand::PlayMovie(self.movie_path()));
+            }
+            HotkeyEvent::Pressed(Hotkey::RecordMacro) => {
+                self.recording_macro = !self.recording_macro;
+                let focused = self.focused(emulator);
+                focused.send(EmulatorCommand::RecordMacro(self.recording_macro));
+                if self.recording_macro {
+                    self.osd.push("Gravando macro");
+                }
+            }
+            HotkeyEvent::Pressed(Hotkey::PlayMacro) => {
+                match self.macros.as_ref().and_then(MacroKeys::last) {
+                    Some(input_macro) => {
+                        let command = EmulatorCommand::PlayMacro(input_macro.clone());
+                        self.focused(emulator).send(command);
+                    }
+                    None => self.osd.push("Nenhuma macro"),
+                }
+            }
+            HotkeyEvent::Pressed(Hotkey::SwitchPlayer) if self.linked.is_some() => {
+                // Solta os botões na instância que perde o controle
+                let previous = self.focused(emulator);
+                previous.send(EmulatorCommand::SetInput(JoypadInput::default()));
+                previous.send(EmulatorCommand::SetTilt(Tilt::default()));
+                self.linked_focus = !self.linked_focus;
+                let current = self.focused(emulator);
+                current.send(EmulatorCommand::SetInput(self.joypad));
+                current.send(EmulatorCommand::SetTilt(self.tilt));
+                let player = if self.linked_focus { 2 } else { 1 };
+                self.osd.push(format!("Controle no jogador {}", player));
+            }
+            HotkeyEvent::Pressed(Hotkey::SwitchPlayer) => {}
+            HotkeyEvent::Pressed(Hotkey::Mute(channel)) => {
+                self.muted[channel] = !self.muted[channel];
+                self.solo = None;
+                emulator.send(EmulatorCommand::SetMutedChannels(self.silenced()));
+                let state = if self.muted[channel] { "mudo" } else { "ligado" };
+                self.osd
+                    .push(format!("Canal {} {}", SCOPE_CHANNELS[channel], state));
+            }
+            HotkeyEvent::Pressed(Hotkey::Solo(channel)) => {
+                self.solo = if self.solo == Some(channel) {
+                    None
+                } else {
+                    Some(channel)
+                };
+                emulator.send(EmulatorCommand::SetMutedChannels(self.silenced()));
+                self.osd.push(match self.solo {
+                    Some(_) => format!("Solo: {}", SCOPE_CHANNELS[channel]),
+                    None => "Solo desligado".to_string(),
+                });
+            }
+            HotkeyEvent::Pressed(Hotkey::Scope) => {
+                self.scope_overlay = !self.scope_overlay;
+                self.update_scope(emulator);
+            }
+            HotkeyEvent::Pressed(Hotkey::Stats) => {
+                let open = self.stats.is_none();
+                self.stats = open.then(StatsOverlay::default);
+                emulator.send(EmulatorCommand::SetStats(open));
+            }
+            HotkeyEvent::Pressed(Hotkey::InputDisplay) => {
+                let open = self.input_display.is_none();
+                self.input_display = open.then(InputDisplay::default);
+                emulator.send(EmulatorCommand::SetInputDisplay(open));
+            }
+            HotkeyEvent::Pressed(Hotkey::Debug) => {
+                self.debug.visible = !self.debug.visible;
+                self.update_scope(emulator);
+            }
+            HotkeyEvent::Released(_) => {}
+        }
+    }
+
+    // O core só manda o scope enquanto o overlay ou o painel estiver aberto
+    fn update_scope(&mut self, emulator: &EmulatorHandle) {
+        let open = self.scope_overlay || self.debug.shows(Panel::Scope);
+        if open != self.scope.is_some() {
+            self.scope = open.then(Default::default);
+            emulator.send(EmulatorCommand::SetScope(open));
+        }
+    }
+
+    fn draw(&mut self) {
+        let screen_w = self.rl.get_screen_width();
+        let screen_h = self.rl.get_screen_height();
+        // Com --link as duas telas dividem o viewport lado a lado
+        let area = match &self.linked {
+            Some(linked) => (
+                self.frame_size.0 + linked.frame_size.0,
+                self.frame_size.1.max(linked.frame_size.1),
+            ),
+            None => self.frame_size,
+        };
+        let (x, top, scale) = viewport(screen_w, screen_h, area, self.scaling);
+        let y = top + ((area.1 - self.frame_size.1) / 2) as f32 * scale;
+        let draw_h = self.frame_size.1 as f32 * scale;
+        // O overlay do script fica sobre a tela do Game Boy, não sobre a moldura do SGB
+        let (screen_x, screen_y) = (
+            x + ((self.frame_size.0 - GB_W) / 2) as f32 * scale,
+            y + ((self.frame_size.1 - GB_H) / 2) as f32 * scale,
+        );
+        let rumble_indicator = self.rumble > 0 && !self.gamepad_connected();
+        let silenced = self.silenced();
+
+        let mut d = self.rl.begin_drawing(&self.thread);
+        d.clear_background(Color::BLACK);
+
+        match self.filter.shader(scale, self.frame_size) {
+            Some(shader) => {
+                let mut s = d.begin_shader_mode(shader);
+                s.draw_texture_ex(&self.texture, Vector2::new(x, y), 0.0, scale, Color::WHITE);
+            }
+            None => {
+                d.draw_texture_ex(&self.texture, Vector2::new(x, y), 0.0, scale, Color::WHITE);
+            }
+        }
+
+        // A moldura marca a instância que recebe teclado e controle
+        if let Some(linked) = &self.linked {
+            let position = Vector2::new(
+                x + self.frame_size.0 as f32 * scale,
+                top + ((area.1 - linked.frame_size.1) / 2) as f32 * scale,
+            );
+            match self.filter.shader(scale, linked.frame_size) {
+                Some(shader) => {
+                    let mut s = d.begin_shader_mode(shader);
+                    s.draw_texture_ex(&linked.texture, position, 0.0, scale, Color::WHITE);
+                }
+                None => d.draw_texture_ex(&linked.texture, position, 0.0, scale, Color::WHITE),
+            }
+
+            let (focus, (focus_w, focus_h)) = if self.linked_focus {
+                (position, linked.frame_size)
+            } else {
+                (Vector2::new(x, y), self.frame_size)
+            };
+            d.draw_rectangle_lines(
+                focus.x as i32,
+                focus.y as i32,
+                (focus_w as f32 * scale) as i32,
+                (focus_h as f32 * scale) as i32,
+                Color::YELLOW,
+            );
+        }
+
+        // Overlay do script em coordenadas do Game Boy, escalado junto com a imagem
+        for item in &self.overlay {
+            match item {
+                OverlayItem::Text {
+                    x: text_x,
+                    y: text_y,
+                    text,
+                    color,
+                } => d.draw_text(
+                    text,
+                    (screen_x + *text_x as f32 * scale) as i32,
+                    (screen_y + *text_y as f32 * scale) as i32,
+                    (OVERLAY_FONT_SIZE * scale) as i32,
+                    Color::get_color(*color),
+                ),
+                OverlayItem::Rect {
+                    x: rect_x,
+                    y: rect_y,
+                    w,
+                    h,
+                    color,
+                } => d.draw_rectangle(
+                    (screen_x + *rect_x as f32 * scale) as i32,
+                    (screen_y + *rect_y as f32 * scale) as i32,
+                    (*w as f32 * scale) as i32,
+                    (*h as f32 * scale) as i32,
+                    Color::get_color(*color),
+                ),
+            }
+        }
+
+        d.draw_fps(10, 10);
+
+        if let Some(stats) = &self.stats {
+            let emulation = &stats.emulation;
+            let ms = |us: u64| us as f64 / 1000.0;
+            let lines = [
+                format!("Velocidade: {}%", emulation.speed),
+                format!("Instruções/s: {:.2}M", emulation.instructions_per_second as f64 / 1e6),
+                format!("Ciclos/s: {:.2}M", emulation.cycles_per_second as f64 / 1e6),
+                format!("Frame: {:.2} ms", ms(emulation.frame_us)),
+                format!("  CPU: {:.2} ms", ms(emulation.cpu_us)),
+                format!("  PPU: {:.2} ms", ms(emulation.ppu_us)),
+                format!("  APU: {:.2} ms", ms(emulation.apu_us)),
+                format!("Buffer de áudio: {} ms", stats.audio.buffered_ms),
+                format!("Latência: {} ms", self.audio_latency),
+                format!("Underruns: {}", stats.audio.underruns),
+                format!("Overruns: {}", stats.audio.overruns),
+            ];
+            d.draw_rectangle(10, 34, 230, 8 + lines.len() as i32 * 20, Color::new(0, 0, 0, 180));
+            for (index, line) in lines.iter().enumerate() {
+                d.draw_text(line, 16, 38 + index as i32 * 20, 20, Color::WHITE);
+            }
+        }
+
+        // Input display no canto inferior direito do jogo, acima do OSD
+        if let Some(display) = &self.input_display {
+            let right = (x + self.frame_size.0 as f32 * scale) as i32 - 8;
+            draw_input_display(&mut d, display, right, (y + draw_h) as i32 - 8);
+        }
+
+        // OSD por cima da imagem escalada, no canto inferior esquerdo do jogo
+        self.osd.draw(&mut d, x as i32 + 8, (y + draw_h) as i32 - 8);
+
+        // Sem controle pra vibrar, o rumble aparece na tela
+        if rumble_indicator {
+            d.draw_text("RUMBLE", screen_w - 90, 10, 20, Color::ORANGE);
+        }
+
+        if let Some(message) = &self.lock_message {
+            d.draw_text(message, 10, screen_h - 30, 20, Color::RED);
+        }
+
+        // Scope no canto superior direito
+        if self.scope_overlay
+            && let Some(scope) = &self.scope
+        {
+            draw_scope(&mut d, scope, silenced, screen_w - SCOPE_WIDTH - 10, 40, SCOPE_WIDTH);
+        }
+
+        // Painéis de debug por cima de tudo
+        let scope = self.scope.as_ref();
+        self.debug.draw(&mut d, &self.symbols, self.paused, scope, silenced);
+    }
+}
+
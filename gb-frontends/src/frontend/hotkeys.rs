@@ -0,0 +1,193 @@
+use raylib::prelude::*;
+
+use crate::config::Config;
+use crate::frontend::parse_key;
+
+// Atalhos do emulador (não chegam no jogo)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Hotkey {
+    SaveState,
+    LoadState,
+    NextSlot,
+    Rewind,
+    FastForward,
+    Screenshot,
+    Pause,
+    Reset,
+    HardReset,
+    Fullscreen,
+    ToggleCheats,
+    ReloadCheats,
+    RecordMovie,
+    PlayMovie,
+    // Grava o input numa macro (a mesma tecla termina) e toca a última
+    RecordMacro,
+    PlayMacro,
+    // Com --link: troca a instância que recebe teclado e controle
+    SwitchPlayer,
+    // Canal do APU (0-3): liga/desliga o mute, ou toca só ele
+    Mute(usize),
+    Solo(usize),
+    Scope,
+    // Overlay com contadores de áudio
+    Stats,
+    // Painéis de debug por cima da tela
+    Debug,
+    // Botões que o jogo está vendo, frame a frame
+    InputDisplay,
+}
+
+const MUTE_NAMES: [&str; 4] = ["mute_1", "mute_2", "mute_3", "mute_4"];
+const SOLO_NAMES: [&str; 4] = ["solo_1", "solo_2", "solo_3", "solo_4"];
+
+impl Hotkey {
+    const ALL: [Hotkey; 29] = [
+        Hotkey::SaveState,
+        Hotkey::LoadState,
+        Hotkey::NextSlot,
+        Hotkey::Rewind,
+        Hotkey::FastForward,
+        Hotkey::Screenshot,
+        Hotkey::Pause,
+        Hotkey::Reset,
+        Hotkey::HardReset,
+        Hotkey::Fullscreen,
+        Hotkey::ToggleCheats,
+        Hotkey::ReloadCheats,
+        Hotkey::RecordMovie,
+        Hotkey::PlayMovie,
+        Hotkey::RecordMacro,
+        Hotkey::PlayMacro,
+        Hotkey::SwitchPlayer,
+        Hotkey::Mute(0),
+        Hotkey::Mute(1),
+        Hotkey::Mute(2),
+        Hotkey::Mute(3),
+        Hotkey::Solo(0),
+        Hotkey::Solo(1),
+        Hotkey::Solo(2),
+        Hotkey::Solo(3),
+        Hotkey::Scope,
+        Hotkey::Stats,
+        Hotkey::Debug,
+        Hotkey::InputDisplay,
+    ];
+
+    // Nome usado na seção [hotkeys] do config
+    pub fn name(self) -> &'static str {
+        match self {
+            Hotkey::SaveState => "save_state",
+            Hotkey::LoadState => "load_state",
+            Hotkey::NextSlot => "next_slot",
+            Hotkey::Rewind => "rewind",
+            Hotkey::FastForward => "fast_forward",
+            Hotkey::Screenshot => "screenshot",
+            Hotkey::Pause => "pause",
+            Hotkey::Reset => "reset",
+            Hotkey::HardReset => "hard_reset",
+            Hotkey::Fullscreen => "fullscreen",
+            Hotkey::ToggleCheats => "toggle_cheats",
+            Hotkey::ReloadCheats => "reload_cheats",
+            Hotkey::RecordMovie => "record_movie",
+            Hotkey::PlayMovie => "play_movie",
+            Hotkey::RecordMacro => "record_macro",
+            Hotkey::PlayMacro => "play_macro",
+            Hotkey::SwitchPlayer => "switch_player",
+            Hotkey::Mute(channel) => MUTE_NAMES[channel],
+            Hotkey::Solo(channel) => SOLO_NAMES[channel],
+            Hotkey::Scope => "scope",
+            Hotkey::Stats => "stats",
+            Hotkey::Debug => "debug",
+            Hotkey::InputDisplay => "input_display",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|hotkey| hotkey.name().eq_ignore_ascii_case(name))
+    }
+}
+
+// Rewind e fast-forward valem enquanto a tecla está apertada; o resto dispara no press
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HotkeyEvent {
+    Pressed(Hotkey),
+    Released(Hotkey),
+}
+
+pub struct HotkeyMap {
+    bindings: Vec<(KeyboardKey, Hotkey)>,
+}
+
+impl HotkeyMap {
+    pub fn new() -> Self {
+        Self {
+            bindings: vec![
+                (KeyboardKey::KEY_F5, Hotkey::SaveState),
+                (KeyboardKey::KEY_F8, Hotkey::LoadState),
+                (KeyboardKey::KEY_F6, Hotkey::NextSlot),
+                (KeyboardKey::KEY_R, Hotkey::Rewind),
+                (KeyboardKey::KEY_TAB, Hotkey::FastForward),
+                (KeyboardKey::KEY_F12, Hotkey::Screenshot),
+                (KeyboardKey::KEY_P, Hotkey::Pause),
+                (KeyboardKey::KEY_F1, Hotkey::Reset),
+                (KeyboardKey::KEY_F2, Hotkey::HardReset),
+                (KeyboardKey::KEY_F11, Hotkey::Fullscreen),
+                (KeyboardKey::KEY_F4, Hotkey::ToggleCheats),
+                (KeyboardKey::KEY_F3, Hotkey::ReloadCheats),
+                (KeyboardKey::KEY_F7, Hotkey::RecordMovie),
+                (KeyboardKey::KEY_F9, Hotkey::PlayMovie),
+                (KeyboardKey::KEY_M, Hotkey::RecordMacro),
+                (KeyboardKey::KEY_N, Hotkey::PlayMacro),
+                (KeyboardKey::KEY_F10, Hotkey::SwitchPlayer),
+                (KeyboardKey::KEY_ONE, Hotkey::Mute(0)),
+                (KeyboardKey::KEY_TWO, Hotkey::Mute(1)),
+                (KeyboardKey::KEY_THREE, Hotkey::Mute(2)),
+                (KeyboardKey::KEY_FOUR, Hotkey::Mute(3)),
+                (KeyboardKey::KEY_FIVE, Hotkey::Solo(0)),
+                (KeyboardKey::KEY_SIX, Hotkey::Solo(1)),
+                (KeyboardKey::KEY_SEVEN, Hotkey::Solo(2)),
+                (KeyboardKey::KEY_EIGHT, Hotkey::Solo(3)),
+                (KeyboardKey::KEY_O, Hotkey::Scope),
+                (KeyboardKey::KEY_I, Hotkey::Stats),
+                (KeyboardKey::KEY_GRAVE, Hotkey::Debug),
+                (KeyboardKey::KEY_U, Hotkey::InputDisplay),
+            ],
+        }
+    }
+
+    // [hotkeys] nome = tecla; "none" desativa o atalho
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let mut map = Self::new();
+
+        for (name, value) in config.section("hotkeys") {
+            let hotkey = Hotkey::from_name(name)
+                .ok_or(format!("[hotkeys]: atalho desconhecido '{}'", name))?;
+            map.bindings.retain(|(_, h)| *h != hotkey);
+
+            if value.eq_ignore_ascii_case("none") {
+                continue;
+            }
+            let key =
+                parse_key(value).ok_or(format!("[hotkeys]: tecla desconhecida '{}'", value))?;
+            map.bindings.push((key, hotkey));
+        }
+
+        Ok(map)
+    }
+
+    pub fn poll(&self, rl: &RaylibHandle) -> Vec<HotkeyEvent> {
+        let mut events = Vec::new();
+
+        for &(key, hotkey) in &self.bindings {
+            if rl.is_key_pressed(key) {
+                events.push(HotkeyEvent::Pressed(hotkey));
+            } else if rl.is_key_released(key) {
+                events.push(HotkeyEvent::Released(hotkey));
+            }
+        }
+
+        events
+    }
+}
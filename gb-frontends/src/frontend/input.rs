@@ -0,0 +1,398 @@
+use raylib::prelude::*;
+
+use crate::config::Config;
+use crate::joypad::{Buttons, JoypadInput, Tilt};
+
+pub const MAX_GAMEPADS: i32 = 4;
+const DEFAULT_DEADZONE: f32 = 0.25;
+
+// Mapeamento de um controle; `name` é procurado (sem diferenciar maiúsculas) no nome do device
+#[derive(Clone)]
+pub struct GamepadProfile {
+    pub name: String,
+    pub buttons: Vec<(GamepadButton, JoypadInput)>,
+}
+
+#[derive(Clone)]
+pub struct InputMapping {
+    pub keyboard: Vec<(KeyboardKey, JoypadInput)>,
+    // Perfis específicos primeiro; o último (nome vazio) serve pra qualquer controle
+    pub gamepads: Vec<GamepadProfile>,
+    pub deadzone: f32,
+}
+
+fn dpad() -> Vec<(GamepadButton, JoypadInput)> {
+    vec![
+        (
+            GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP,
+            JoypadInput::held(Buttons::UP),
+        ),
+        (
+            GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN,
+            JoypadInput::held(Buttons::DOWN),
+        ),
+        (
+            GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT,
+            JoypadInput::held(Buttons::LEFT),
+        ),
+        (
+            GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT,
+            JoypadInput::held(Buttons::RIGHT),
+        ),
+        (
+            GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT,
+            JoypadInput::held(Buttons::SELECT),
+        ),
+        (
+            GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT,
+            JoypadInput::held(Buttons::START),
+        ),
+    ]
+}
+
+// A raylib nomeia os botões pela posição; cada layout põe A/B do GB onde o rótulo bate
+fn profile(name: &str, a: GamepadButton, b: GamepadButton) -> GamepadProfile {
+    let mut buttons = dpad();
+    buttons.push((a, JoypadInput::held(Buttons::A)));
+    buttons.push((b, JoypadInput::held(Buttons::B)));
+    GamepadProfile {
+        name: name.to_string(),
+        buttons,
+    }
+}
+
+impl InputMapping {
+    pub fn new() -> Self {
+        Self {
+            keyboard: vec![
+                (KeyboardKey::KEY_UP, JoypadInput::held(Buttons::UP)),
+                (KeyboardKey::KEY_DOWN, JoypadInput::held(Buttons::DOWN)),
+                (KeyboardKey::KEY_LEFT, JoypadInput::held(Buttons::LEFT)),
+                (KeyboardKey::KEY_RIGHT, JoypadInput::held(Buttons::RIGHT)),
+                (KeyboardKey::KEY_X, JoypadInput::held(Buttons::A)),
+                (KeyboardKey::KEY_Z, JoypadInput::held(Buttons::B)),
+                (
+                    KeyboardKey::KEY_BACKSPACE,
+                    JoypadInput::held(Buttons::SELECT),
+                ),
+                (KeyboardKey::KEY_ENTER, JoypadInput::held(Buttons::START)),
+                (KeyboardKey::KEY_S, JoypadInput::turbo(Buttons::A)),
+                (KeyboardKey::KEY_A, JoypadInput::turbo(Buttons::B)),
+            ],
+            gamepads: vec![
+                // Xbox e PlayStation: A/Cruz embaixo, B/Quadrado à esquerda
+                profile(
+                    "xbox",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN,
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT,
+                ),
+                profile(
+                    "playstation",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN,
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT,
+                ),
+                profile(
+                    "dualshock",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN,
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT,
+                ),
+                // Layout Nintendo: A à direita, B embaixo, igual ao GB
+                profile(
+                    "",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT,
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN,
+                ),
+            ],
+            deadzone: DEFAULT_DEADZONE,
+        }
+    }
+
+    // [keyboard] e [gamepad] trocam o mapeamento padrão; [gamepad.<nome>] cria um perfil por device
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let mut mapping = Self::new();
+        mapping.apply(config, "")?;
+        Ok(mapping)
+    }
+
+    // As mesmas seções com um prefixo na frente ("profile.<nome>."), por cima deste mapeamento
+    pub fn apply(&mut self, config: &Config, prefix: &str) -> Result<(), String> {
+        let keyboard = format!("{}keyboard", prefix);
+        let gamepad = format!("{}gamepad", prefix);
+        let device_prefix = format!("{}gamepad.", prefix);
+
+        let bindings = parse_bindings(config, &keyboard, parse_key)?;
+        bind(&mut self.keyboard, bindings);
+
+        if let Some(value) = config.get(&gamepad, "deadzone") {
+            self.deadzone = value
+                .parse::<f32>()
+                .ok()
+                .filter(|d| (0.0..1.0).contains(d))
+                .ok_or(format!("deadzone inválida: '{}'", value))?;
+        }
+
+        let last = self.gamepads.len() - 1;
+        let bindings = parse_bindings(config, &gamepad, parse_gamepad_button)?;
+        bind(&mut self.gamepads[last].buttons, bindings);
+
+        // Perfis do usuário têm prioridade sobre os padrões, partindo do d-pad/start/select
+        for name in config.section_names() {
+            let Some(device) = name.strip_prefix(device_prefix.as_str()) else {
+                continue;
+            };
+
+            let mut profile = GamepadProfile {
+                name: device.to_lowercase(),
+                buttons: dpad(),
+            };
+            bind(
+                &mut profile.buttons,
+                parse_bindings(config, name, parse_gamepad_button)?,
+            );
+            self.gamepads.insert(0, profile);
+        }
+
+        Ok(())
+    }
+
+    fn profile_for(&self, name: &str) -> &GamepadProfile {
+        let name = name.to_lowercase();
+        self.gamepads
+            .iter()
+            .find(|profile| name.contains(&profile.name))
+            .unwrap_or(&self.gamepads[self.gamepads.len() - 1])
+    }
+
+    pub fn poll(&self, rl: &RaylibHandle) -> JoypadInput {
+        let mut input = JoypadInput::default();
+
+        for &(key, target) in &self.keyboard {
+            if rl.is_key_down(key) {
+                input = input | target;
+            }
+        }
+
+        for gamepad in 0..MAX_GAMEPADS {
+            if !rl.is_gamepad_available(gamepad) {
+                continue;
+            }
+
+            let name = rl.get_gamepad_name(gamepad).unwrap_or_default();
+            for &(button, target) in &self.profile_for(&name).buttons {
+                if rl.is_gamepad_button_down(gamepad, button) {
+                    input = input | target;
+                }
+            }
+
+            // Analógico esquerdo vira d-pad fora da zona morta
+            let x = rl.get_gamepad_axis_movement(gamepad, GamepadAxis::GAMEPAD_AXIS_LEFT_X);
+            let y = rl.get_gamepad_axis_movement(gamepad, GamepadAxis::GAMEPAD_AXIS_LEFT_Y);
+            if x < -self.deadzone {
+                input.buttons |= Buttons::LEFT;
+            } else if x > self.deadzone {
+                input.buttons |= Buttons::RIGHT;
+            }
+            if y < -self.deadzone {
+                input.buttons |= Buttons::UP;
+            } else if y > self.deadzone {
+                input.buttons |= Buttons::DOWN;
+            }
+        }
+
+        input
+    }
+
+    // Inclinação pro acelerômetro do MBC7: setas no teclado ou analógico esquerdo
+    pub fn poll_tilt(&self, rl: &RaylibHandle) -> Tilt {
+        let key = |key| if rl.is_key_down(key) { 1.0 } else { 0.0 };
+        let mut x = key(KeyboardKey::KEY_RIGHT) - key(KeyboardKey::KEY_LEFT);
+        let mut y = key(KeyboardKey::KEY_DOWN) - key(KeyboardKey::KEY_UP);
+
+        for gamepad in 0..MAX_GAMEPADS {
+            if !rl.is_gamepad_available(gamepad) {
+                continue;
+            }
+            let stick_x = rl.get_gamepad_axis_movement(gamepad, GamepadAxis::GAMEPAD_AXIS_LEFT_X);
+            let stick_y = rl.get_gamepad_axis_movement(gamepad, GamepadAxis::GAMEPAD_AXIS_LEFT_Y);
+            if stick_x.abs() > self.deadzone {
+                x += stick_x;
+            }
+            if stick_y.abs() > self.deadzone {
+                y += stick_y;
+            }
+        }
+
+        Tilt::new(x, y)
+    }
+}
+
+// Cada botão do GB (normal ou turbo) fica com uma entrada só: a nova substitui a padrão
+fn bind<T>(buttons: &mut Vec<(T, JoypadInput)>, bindings: Vec<(T, JoypadInput)>) {
+    for (input, target) in bindings {
+        buttons.retain(|(_, b)| *b != target);
+        buttons.push((input, target));
+    }
+}
+
+fn parse_bindings<T>(
+    config: &Config,
+    section: &str,
+    parse: fn(&str) -> Option<T>,
+) -> Result<Vec<(T, JoypadInput)>, String> {
+    let mut bindings = Vec::new();
+
+    for (key, value) in config.section(section) {
+        if key == "deadzone" {
+            continue;
+        }
+
+        let target =
+            parse_binding(key).ok_or(format!("[{}]: botão desconhecido '{}'", section, key))?;
+        let input =
+            parse(value).ok_or(format!("[{}]: entrada desconhecida '{}'", section, value))?;
+        bindings.push((input, target));
+    }
+
+    Ok(bindings)
+}
+
+// "a", "start"... ou "turbo_a", "turbo_b"... pra auto-fire
+fn parse_binding(name: &str) -> Option<JoypadInput> {
+    let name = name.to_lowercase();
+    match name.strip_prefix("turbo_") {
+        Some(button) => parse_gb_button(button).map(JoypadInput::turbo),
+        None => parse_gb_button(&name).map(JoypadInput::held),
+    }
+}
+
+pub fn parse_gb_button(name: &str) -> Option<Buttons> {
+    match name.to_lowercase().as_str() {
+        "up" => Some(Buttons::UP),
+        "down" => Some(Buttons::DOWN),
+        "left" => Some(Buttons::LEFT),
+        "right" => Some(Buttons::RIGHT),
+        "a" => Some(Buttons::A),
+        "b" => Some(Buttons::B),
+        "select" => Some(Buttons::SELECT),
+        "start" => Some(Buttons::START),
+        _ => None,
+    }
+}
+
+// Aceita o nome com ou sem o prefixo KEY_ (ex: "X", "KEY_ENTER", "left_shift")
+pub fn parse_key(name: &str) -> Option<KeyboardKey> {
+    use KeyboardKey::*;
+
+    let name = name.to_uppercase();
+    let name = name.strip_prefix("KEY_").unwrap_or(&name);
+
+    let key = match name {
+        "A" => KEY_A,
+        "B" => KEY_B,
+        "C" => KEY_C,
+        "D" => KEY_D,
+        "E" => KEY_E,
+        "F" => KEY_F,
+        "G" => KEY_G,
+        "H" => KEY_H,
+        "I" => KEY_I,
+        "J" => KEY_J,
+        "K" => KEY_K,
+        "L" => KEY_L,
+        "M" => KEY_M,
+        "N" => KEY_N,
+        "O" => KEY_O,
+        "P" => KEY_P,
+        "Q" => KEY_Q,
+        "R" => KEY_R,
+        "S" => KEY_S,
+        "T" => KEY_T,
+        "U" => KEY_U,
+        "V" => KEY_V,
+        "W" => KEY_W,
+        "X" => KEY_X,
+        "Y" => KEY_Y,
+        "Z" => KEY_Z,
+        "0" | "ZERO" => KEY_ZERO,
+        "1" | "ONE" => KEY_ONE,
+        "2" | "TWO" => KEY_TWO,
+        "3" | "THREE" => KEY_THREE,
+        "4" | "FOUR" => KEY_FOUR,
+        "5" | "FIVE" => KEY_FIVE,
+        "6" | "SIX" => KEY_SIX,
+        "7" | "SEVEN" => KEY_SEVEN,
+        "8" | "EIGHT" => KEY_EIGHT,
+        "9" | "NINE" => KEY_NINE,
+        "F1" => KEY_F1,
+        "F2" => KEY_F2,
+        "F3" => KEY_F3,
+        "F4" => KEY_F4,
+        "F5" => KEY_F5,
+        "F6" => KEY_F6,
+        "F7" => KEY_F7,
+        "F8" => KEY_F8,
+        "F9" => KEY_F9,
+        "F10" => KEY_F10,
+        "F11" => KEY_F11,
+        "F12" => KEY_F12,
+        "UP" => KEY_UP,
+        "DOWN" => KEY_DOWN,
+        "LEFT" => KEY_LEFT,
+        "RIGHT" => KEY_RIGHT,
+        "ENTER" => KEY_ENTER,
+        "SPACE" => KEY_SPACE,
+        "BACKSPACE" => KEY_BACKSPACE,
+        "TAB" => KEY_TAB,
+        "ESCAPE" => KEY_ESCAPE,
+        "LEFT_SHIFT" => KEY_LEFT_SHIFT,
+        "RIGHT_SHIFT" => KEY_RIGHT_SHIFT,
+        "LEFT_CONTROL" => KEY_LEFT_CONTROL,
+        "RIGHT_CONTROL" => KEY_RIGHT_CONTROL,
+        "LEFT_ALT" => KEY_LEFT_ALT,
+        "RIGHT_ALT" => KEY_RIGHT_ALT,
+        "GRAVE" => KEY_GRAVE,
+        "MINUS" => KEY_MINUS,
+        "EQUAL" => KEY_EQUAL,
+        "PAUSE" => KEY_PAUSE,
+        "PAGE_UP" => KEY_PAGE_UP,
+        "PAGE_DOWN" => KEY_PAGE_DOWN,
+        "HOME" => KEY_HOME,
+        "END" => KEY_END,
+        "INSERT" => KEY_INSERT,
+        "DELETE" => KEY_DELETE,
+        _ => return None,
+    };
+
+    Some(key)
+}
+
+// Nomes da raylib com ou sem o prefixo GAMEPAD_BUTTON_ (ex: "RIGHT_FACE_DOWN")
+pub fn parse_gamepad_button(name: &str) -> Option<GamepadButton> {
+    use GamepadButton::*;
+
+    let name = name.to_uppercase();
+    let name = name.strip_prefix("GAMEPAD_BUTTON_").unwrap_or(&name);
+
+    let button = match name {
+        "LEFT_FACE_UP" => GAMEPAD_BUTTON_LEFT_FACE_UP,
+        "LEFT_FACE_RIGHT" => GAMEPAD_BUTTON_LEFT_FACE_RIGHT,
+        "LEFT_FACE_DOWN" => GAMEPAD_BUTTON_LEFT_FACE_DOWN,
+        "LEFT_FACE_LEFT" => GAMEPAD_BUTTON_LEFT_FACE_LEFT,
+        "RIGHT_FACE_UP" => GAMEPAD_BUTTON_RIGHT_FACE_UP,
+        "RIGHT_FACE_RIGHT" => GAMEPAD_BUTTON_RIGHT_FACE_RIGHT,
+        "RIGHT_FACE_DOWN" => GAMEPAD_BUTTON_RIGHT_FACE_DOWN,
+        "RIGHT_FACE_LEFT" => GAMEPAD_BUTTON_RIGHT_FACE_LEFT,
+        "LEFT_TRIGGER_1" => GAMEPAD_BUTTON_LEFT_TRIGGER_1,
+        "LEFT_TRIGGER_2" => GAMEPAD_BUTTON_LEFT_TRIGGER_2,
+        "RIGHT_TRIGGER_1" => GAMEPAD_BUTTON_RIGHT_TRIGGER_1,
+        "RIGHT_TRIGGER_2" => GAMEPAD_BUTTON_RIGHT_TRIGGER_2,
+        "MIDDLE_LEFT" => GAMEPAD_BUTTON_MIDDLE_LEFT,
+        "MIDDLE" => GAMEPAD_BUTTON_MIDDLE,
+        "MIDDLE_RIGHT" => GAMEPAD_BUTTON_MIDDLE_RIGHT,
+        "LEFT_THUMB" => GAMEPAD_BUTTON_LEFT_THUMB,
+        "RIGHT_THUMB" => GAMEPAD_BUTTON_RIGHT_THUMB,
+        _ => return None,
+    };
+
+    Some(button)
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use raylib::prelude::*;
+
+const LAUNCHER_W: i32 = 640;
+const LAUNCHER_H: i32 = 400;
+const FONT_SIZE: i32 = 20;
+const LINE_HEIGHT: i32 = 28;
+const LIST_TOP: i32 = 60;
+
+// Tela do emulador aberto sem argumentos: escolhe uma das ROMs recentes com as setas e
+// Enter, ou uma ROM arrastada pra janela. None = janela fechada sem escolher
+pub fn choose_rom(recent: &[String]) -> Option<String> {
+    let (mut rl, thread) = raylib::init()
+        .size(LAUNCHER_W, LAUNCHER_H)
+        .title("gb-emu-rust")
+        .resizable()
+        .vsync()
+        .build();
+
+    let mut selected = 0;
+    while !rl.window_should_close() {
+        if rl.is_file_dropped() {
+            let dropped = rl.load_dropped_files();
+            if let Some(path) = dropped.paths().first() {
+                return Some(path.to_string());
+            }
+        }
+
+        if !recent.is_empty() {
+            if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+                selected = (selected + 1) % recent.len();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+                selected = (selected + recent.len() - 1) % recent.len();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                return Some(recent[selected].clone());
+            }
+        }
+
+        let (screen_w, screen_h) = (rl.get_screen_width(), rl.get_screen_height());
+        let mut d = rl.begin_drawing(&thread);
+        d.clear_background(Color::BLACK);
+        d.draw_text("ROMs recentes", 20, 20, FONT_SIZE, Color::WHITE);
+
+        if recent.is_empty() {
+            d.draw_text("Nenhuma ROM recente", 20, LIST_TOP, FONT_SIZE, Color::GRAY);
+        }
+        // Só as linhas que cabem na janela, rolando junto com a seleção
+        let visible = ((screen_h - LIST_TOP - 40) / LINE_HEIGHT).max(1) as usize;
+        let first = selected.saturating_sub(visible - 1);
+        for (row, (index, path)) in recent
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(visible)
+            .enumerate()
+        {
+            let y = LIST_TOP + row as i32 * LINE_HEIGHT;
+            let name = Path::new(path)
+                .file_name()
+                .map_or(path.clone(), |name| name.to_string_lossy().to_string());
+            let color = if Path::new(path).exists() {
+                Color::WHITE
+            } else {
+                Color::new(80, 80, 80, 255)
+            };
+            if index == selected {
+                d.draw_rectangle(
+                    12,
+                    y - 4,
+                    screen_w - 24,
+                    LINE_HEIGHT,
+                    Color::new(60, 60, 120, 255),
+                );
+            }
+            d.draw_text(&name, 20, y, FONT_SIZE, color);
+        }
+
+        d.draw_text(
+            "Setas + Enter abrem; arraste uma ROM (.gb, .gbc ou .zip) pra janela",
+            20,
+            screen_h - 30,
+            10,
+            Color::GRAY,
+        );
+    }
+    None
+}
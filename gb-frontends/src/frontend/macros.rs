@@ -0,0 +1,93 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use raylib::prelude::*;
+
+use crate::config::Config;
+use crate::frontend::parse_key;
+use crate::joypad::InputMacro;
+
+const MACROS_SECTION: &str = "macros";
+
+// Macros de input do config: [macros] nome = tecla: sequência ("none" = sem tecla, só
+// pelo play_macro). As gravadas vão pro fim do arquivo numa seção [macros] nova
+pub struct MacroKeys {
+    config_path: PathBuf,
+    macros: Vec<(String, Option<KeyboardKey>, InputMacro)>,
+    // Tocada pelo atalho play_macro: a última gravada ou tocada
+    last: Option<InputMacro>,
+}
+
+impl MacroKeys {
+    pub fn from_config(config: &Config, config_path: &Path) -> Result<Self, String> {
+        let mut macros = Vec::new();
+        for (name, value) in config.section(MACROS_SECTION) {
+            let invalid = |erro: String| format!("[macros] {}: {}", name, erro);
+            let (key, sequence) = value
+                .split_once(':')
+                .ok_or_else(|| invalid("esperado 'tecla: sequência'".to_string()))?;
+            let key = match key.trim() {
+                key if key.eq_ignore_ascii_case("none") => None,
+                key => Some(
+                    parse_key(key)
+                        .ok_or_else(|| invalid(format!("tecla desconhecida '{}'", key)))?,
+                ),
+            };
+            let input_macro = InputMacro::parse(sequence).map_err(invalid)?;
+            macros.push((name.to_string(), key, input_macro));
+        }
+        Ok(Self {
+            config_path: config_path.to_path_buf(),
+            last: macros.last().map(|(_, _, input_macro)| input_macro.clone()),
+            macros,
+        })
+    }
+
+    // Macros com a tecla apertada neste frame
+    pub fn poll(&mut self, rl: &RaylibHandle) -> Vec<InputMacro> {
+        let pressed: Vec<InputMacro> = self
+            .macros
+            .iter()
+            .filter(|(_, key, _)| key.is_some_and(|key| rl.is_key_pressed(key)))
+            .map(|(_, _, input_macro)| input_macro.clone())
+            .collect();
+        if let Some(input_macro) = pressed.last() {
+            self.last = Some(input_macro.clone());
+        }
+        pressed
+    }
+
+    pub fn last(&self) -> Option<&InputMacro> {
+        self.last.as_ref()
+    }
+
+    // Grava no config como "macro_<n> = none: ..." e devolve o nome; a tecla fica pra
+    // quem editar o arquivo
+    pub fn save(&mut self, input_macro: InputMacro) -> Result<String, String> {
+        let number = (1..)
+            .find(|n| {
+                let name = format!("macro_{}", n);
+                !self.macros.iter().any(|(other, _, _)| *other == name)
+            })
+            .unwrap();
+        let name = format!("macro_{}", number);
+
+        let line = format!(
+            "\n[{}]\n{} = none: {}\n",
+            MACROS_SECTION,
+            name,
+            input_macro.format()
+        );
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config_path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|erro| format!("erro ao gravar '{}': {}", self.config_path.display(), erro))?;
+
+        self.last = Some(input_macro.clone());
+        self.macros.push((name.clone(), None, input_macro));
+        Ok(name)
+    }
+}
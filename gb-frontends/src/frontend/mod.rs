@@ -0,0 +1,29 @@
+pub mod audio;
+pub mod console;
+pub mod debug_panels;
+pub mod filters;
+pub mod frontend;
+pub mod hotkeys;
+pub mod input;
+pub mod launcher;
+pub mod macros;
+pub mod osd;
+pub mod palettes;
+pub mod presence;
+pub mod profiles;
+pub mod recent;
+
+pub use audio::*;
+pub use console::*;
+pub use debug_panels::*;
+pub use filters::*;
+pub use frontend::*;
+pub use hotkeys::*;
+pub use input::*;
+pub use launcher::*;
+pub use macros::*;
+pub use osd::*;
+pub use palettes::*;
+pub use presence::*;
+pub use profiles::*;
+pub use recent::*;
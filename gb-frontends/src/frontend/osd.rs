@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use raylib::prelude::*;
+
+const MESSAGE_DURATION: Duration = Duration::from_millis(2500);
+// Últimos instantes da mensagem são de fade-out
+const FADE_DURATION: Duration = Duration::from_millis(500);
+const MAX_MESSAGES: usize = 4;
+const FONT_SIZE: i32 = 20;
+const LINE_HEIGHT: i32 = 24;
+
+struct OsdMessage {
+    text: String,
+    created: Instant,
+}
+
+// Mensagens temporárias desenhadas por cima do jogo ("State 3 salvo", "Fast-forward ON"...)
+pub struct Osd {
+    messages: VecDeque<OsdMessage>,
+}
+
+impl Osd {
+    pub fn new() -> Self {
+        Self {
+            messages: VecDeque::with_capacity(MAX_MESSAGES),
+        }
+    }
+
+    pub fn push(&mut self, text: impl Into<String>) {
+        if self.messages.len() == MAX_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(OsdMessage {
+            text: text.into(),
+            created: Instant::now(),
+        });
+    }
+
+    // Desenha de baixo pra cima a partir de (x, bottom); a mais nova fica embaixo
+    pub fn draw(&mut self, d: &mut impl RaylibDraw, x: i32, bottom: i32) {
+        let now = Instant::now();
+        self.messages
+            .retain(|message| now - message.created < MESSAGE_DURATION);
+
+        for (index, message) in self.messages.iter().rev().enumerate() {
+            let remaining = MESSAGE_DURATION - (now - message.created);
+            let alpha = if remaining < FADE_DURATION {
+                remaining.as_secs_f32() / FADE_DURATION.as_secs_f32()
+            } else {
+                1.0
+            };
+
+            let y = bottom - (index as i32 + 1) * LINE_HEIGHT;
+            // Sombra pra ler em cima de qualquer cor
+            d.draw_text(
+                &message.text,
+                x + 2,
+                y + 2,
+                FONT_SIZE,
+                Color::BLACK.fade(alpha),
+            );
+            d.draw_text(&message.text, x, y, FONT_SIZE, Color::WHITE.fade(alpha));
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::ppu::{Colorization, ColorizationMode};
+
+// De quanto em quanto tempo o .pal do jogo é conferido no disco
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Paleta .pal do jogo atual (vinda do perfil dele), recarregada quando o arquivo muda.
+// Jogos sem paleta própria ficam com o [video] colorization
+pub struct GamePalettes {
+    default: ColorizationMode,
+    // Arquivo do jogo atual e a data de modificação da última leitura
+    current: Option<(PathBuf, Option<SystemTime>)>,
+    last_poll: Instant,
+}
+
+impl GamePalettes {
+    pub fn new(default: ColorizationMode) -> Self {
+        Self {
+            default,
+            current: None,
+            last_poll: Instant::now(),
+        }
+    }
+
+    // Jogo novo: lê o .pal dele, se tiver, e passa a vigiar o arquivo
+    pub fn select(&mut self, path: Option<PathBuf>) -> Result<ColorizationMode, String> {
+        self.current = path.map(|path| (path, None));
+        self.load()
+    }
+
+    // Cores novas quando o .pal do jogo atual mudou desde a última leitura
+    pub fn poll(&mut self) -> Option<Result<ColorizationMode, String>> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+
+        let (path, loaded) = self.current.as_ref()?;
+        let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        if modified.is_none() || modified == *loaded {
+            return None;
+        }
+        Some(self.load())
+    }
+
+    fn load(&mut self) -> Result<ColorizationMode, String> {
+        let Some((path, loaded)) = &mut self.current else {
+            return Ok(self.default);
+        };
+        *loaded = fs::metadata(&*path).and_then(|meta| meta.modified()).ok();
+        let data = fs::read(&*path)
+            .map_err(|erro| format!("erro ao ler '{}': {}", path.display(), erro))?;
+        let colors = Colorization::parse_pal(&data)
+            .map_err(|erro| format!("{}: {}", path.display(), erro))?;
+        Ok(ColorizationMode::Preset(colors))
+    }
+}
@@ -0,0 +1,229 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "discord")]
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient, activity};
+
+use crate::config::Config;
+use crate::machine::unix_time;
+
+// O Discord ignora atualizações mais próximas que isso
+const MIN_INTERVAL: Duration = Duration::from_secs(15);
+// Sem mudança nenhuma, reenvia mesmo assim pro tempo de jogo andar no arquivo de status
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SessionState {
+    Playing,
+    Paused,
+}
+
+impl SessionState {
+    fn describe(self) -> &'static str {
+        match self {
+            SessionState::Playing => "Jogando",
+            SessionState::Paused => "Pausado",
+        }
+    }
+}
+
+// O que os serviços de presença mostram: o jogo (título do cabeçalho), desde quando e
+// se está rodando
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Session {
+    pub title: String,
+    // Segundos Unix de quando a ROM carregou
+    pub started: u64,
+    pub state: SessionState,
+}
+
+impl Session {
+    pub fn play_time(&self) -> Duration {
+        Duration::from_secs(unix_time().saturating_sub(self.started))
+    }
+}
+
+pub trait Presence {
+    fn name(&self) -> &str;
+    fn update(&mut self, session: &Session) -> Result<(), String>;
+    // Janela fechando: tira o jogo do perfil
+    fn clear(&mut self) {}
+}
+
+// Arquivo de texto com título, estado e tempo de jogo, pra overlays de stream e scripts
+pub struct StatusFile {
+    path: PathBuf,
+}
+
+impl StatusFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl Presence for StatusFile {
+    fn name(&self) -> &str {
+        "status_file"
+    }
+
+    fn update(&mut self, session: &Session) -> Result<(), String> {
+        let minutes = session.play_time().as_secs() / 60;
+        let text = format!(
+            "{}\n{}\n{}:{:02}\n",
+            session.title,
+            session.state.describe(),
+            minutes / 60,
+            minutes % 60
+        );
+        fs::write(&self.path, text)
+            .map_err(|erro| format!("erro ao gravar '{}': {}", self.path.display(), erro))
+    }
+
+    fn clear(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// Rich Presence pelo IPC local do Discord; `client_id` é o da aplicação criada no portal
+// de desenvolvedores, que dá o nome mostrado no perfil
+#[cfg(feature = "discord")]
+pub struct DiscordPresence {
+    client: DiscordIpcClient,
+    connected: bool,
+}
+
+#[cfg(feature = "discord")]
+impl DiscordPresence {
+    pub fn new(client_id: &str) -> Self {
+        Self {
+            client: DiscordIpcClient::new(client_id),
+            connected: false,
+        }
+    }
+}
+
+#[cfg(feature = "discord")]
+impl Presence for DiscordPresence {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    fn update(&mut self, session: &Session) -> Result<(), String> {
+        if !self.connected {
+            self.client
+                .connect()
+                .map_err(|erro| format!("erro ao conectar no Discord: {}", erro))?;
+            self.connected = true;
+        }
+        let activity = activity::Activity::new()
+            .details(session.title.as_str())
+            .state(session.state.describe())
+            .timestamps(activity::Timestamps::new().start(session.started as i64));
+        self.client.set_activity(activity).map_err(|erro| {
+            self.connected = false;
+            format!("erro ao atualizar o Discord: {}", erro)
+        })
+    }
+
+    fn clear(&mut self) {
+        if self.connected {
+            let _ = self.client.clear_activity();
+            let _ = self.client.close();
+        }
+    }
+}
+
+// Serviços do [presence] do config: discord = <client id> (com a feature discord) e
+// status_file = caminho. Serviço que dá erro sai da lista, com um aviso só
+pub struct PresenceReporter {
+    services: Vec<Box<dyn Presence>>,
+    session: Option<Session>,
+    // O que foi mandado por último e quando
+    sent: Option<(Session, Instant)>,
+}
+
+impl PresenceReporter {
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let mut reporter = Self {
+            services: Vec::new(),
+            session: None,
+            sent: None,
+        };
+        if let Some(path) = config.get("presence", "status_file") {
+            reporter.add(Box::new(StatusFile::new(PathBuf::from(path))));
+        }
+        if let Some(client_id) = config.get("presence", "discord") {
+            #[cfg(feature = "discord")]
+            reporter.add(Box::new(DiscordPresence::new(client_id)));
+            #[cfg(not(feature = "discord"))]
+            return Err(format!(
+                "[presence] discord = {}: compilado sem a feature discord",
+                client_id
+            ));
+        }
+        Ok(reporter)
+    }
+
+    // Pra outros serviços além dos do config
+    pub fn add(&mut self, service: Box<dyn Presence>) {
+        self.services.push(service);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.services.is_empty()
+    }
+
+    // ROM nova: o tempo de jogo recomeça
+    pub fn start(&mut self, title: &str) {
+        self.session = Some(Session {
+            title: title.trim().to_string(),
+            started: unix_time(),
+            state: SessionState::Playing,
+        });
+    }
+
+    pub fn set_state(&mut self, state: SessionState) {
+        if let Some(session) = &mut self.session {
+            session.state = state;
+        }
+    }
+
+    // Chamado todo frame; só fala com os serviços quando a sessão mudou (ou pra atualizar
+    // o tempo), sem passar do limite do Discord. Devolve os erros, pro OSD
+    pub fn poll(&mut self) -> Vec<String> {
+        let Some(session) = &self.session else {
+            return Vec::new();
+        };
+        let due = match &self.sent {
+            None => true,
+            Some((sent, at)) => {
+                let elapsed = at.elapsed();
+                elapsed >= REFRESH_INTERVAL || (elapsed >= MIN_INTERVAL && sent != session)
+            }
+        };
+        if !due {
+            return Vec::new();
+        }
+
+        let mut errors = Vec::new();
+        self.services
+            .retain_mut(|service| match service.update(session) {
+                Ok(()) => true,
+                Err(erro) => {
+                    errors.push(format!("{} desligado: {}", service.name(), erro));
+                    false
+                }
+            });
+        self.sent = Some((session.clone(), Instant::now()));
+        errors
+    }
+}
+
+impl Drop for PresenceReporter {
+    fn drop(&mut self) {
+        for service in &mut self.services {
+            service.clear();
+        }
+    }
+}
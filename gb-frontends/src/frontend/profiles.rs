@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::frontend::InputMapping;
+use crate::options::{MAX_OVERCLOCK, MAX_RUN_AHEAD};
+
+// O que um perfil ou um jogo pode trocar; None = fica o de fora
+#[derive(Clone, Default)]
+struct Settings {
+    palette: Option<PathBuf>,
+    overclock: Option<u8>,
+    run_ahead: Option<u8>,
+}
+
+impl Settings {
+    fn from_section(config: &Config, section: &str) -> Result<Self, String> {
+        let number = |key: &str, range: std::ops::RangeInclusive<u8>| {
+            config
+                .get(section, key)
+                .map(|value| {
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|n| range.contains(n))
+                        .ok_or(format!(
+                            "[{}] {} inválido: '{}' ({} a {})",
+                            section,
+                            key,
+                            value,
+                            range.start(),
+                            range.end()
+                        ))
+                })
+                .transpose()
+        };
+        Ok(Self {
+            palette: config.get(section, "palette").map(PathBuf::from),
+            overclock: number("overclock", 1..=MAX_OVERCLOCK)?,
+            run_ahead: number("run_ahead", 0..=MAX_RUN_AHEAD)?,
+        })
+    }
+
+    // Os valores de `other` ganham
+    fn merge(&self, other: &Settings) -> Settings {
+        Settings {
+            palette: other.palette.clone().or(self.palette.clone()),
+            overclock: other.overclock.or(self.overclock),
+            run_ahead: other.run_ahead.or(self.run_ahead),
+        }
+    }
+}
+
+enum GameKey {
+    Title(String),
+    Crc(u32),
+}
+
+struct Profile {
+    name: String,
+    settings: Settings,
+    input: InputMapping,
+}
+
+struct Game {
+    key: GameKey,
+    profile: Option<String>,
+    settings: Settings,
+}
+
+// Configuração aplicada a um jogo quando ele carrega
+#[derive(Clone)]
+pub struct GameProfile {
+    // Nome do [profile.<nome>] usado, se algum
+    pub name: Option<String>,
+    pub palette: Option<PathBuf>,
+    pub overclock: u8,
+    pub run_ahead: u8,
+    pub input: InputMapping,
+}
+
+// Perfis nomeados ([profile.<nome>] com palette, overclock e run_ahead, mais
+// [profile.<nome>.keyboard] e [profile.<nome>.gamepad...] por cima do mapeamento normal) e
+// os jogos que usam cada um: [game.<título>] ou [game.crc32:XXXXXXXX] com profile = nome.
+// O que estiver na seção do jogo vale por cima do perfil
+pub struct GameProfiles {
+    profiles: Vec<Profile>,
+    games: Vec<Game>,
+    default: GameProfile,
+}
+
+impl GameProfiles {
+    // `default` é o que vale pra jogos sem perfil: linha de comando e seções normais
+    pub fn from_config(config: &Config, default: GameProfile) -> Result<Self, String> {
+        let mut profiles: Vec<Profile> = Vec::new();
+        let mut games = Vec::new();
+
+        for section in config.section_names() {
+            if let Some(name) = section.strip_prefix("profile.") {
+                // As seções de botões ([profile.x.keyboard]...) entram pelo InputMapping
+                if name.contains('.') || profiles.iter().any(|p| p.name.eq_ignore_ascii_case(name))
+                {
+                    continue;
+                }
+                let mut input = default.input.clone();
+                input.apply(config, &format!("{}.", section))?;
+                profiles.push(Profile {
+                    name: name.to_string(),
+                    settings: Settings::from_section(config, section)?,
+                    input,
+                });
+            } else if let Some(game) = section.strip_prefix("game.") {
+                let key = match game.strip_prefix("crc32:") {
+                    Some(crc) => GameKey::Crc(
+                        u32::from_str_radix(crc.trim(), 16)
+                            .map_err(|_| format!("[{}]: crc32 inválido: '{}'", section, crc))?,
+                    ),
+                    None => GameKey::Title(game.trim().to_string()),
+                };
+                games.push(Game {
+                    key,
+                    profile: config.get(section, "profile").map(str::to_string),
+                    settings: Settings::from_section(config, section)?,
+                });
+            }
+        }
+
+        for game in &games {
+            if let Some(name) = &game.profile
+                && !profiles.iter().any(|p| p.name.eq_ignore_ascii_case(name))
+            {
+                return Err(format!("perfil desconhecido: '{}'", name));
+            }
+        }
+
+        Ok(Self {
+            profiles,
+            games,
+            default,
+        })
+    }
+
+    // O CRC identifica a ROM exata e ganha do título, que se repete entre versões
+    pub fn resolve(&self, title: &str, crc: u32) -> GameProfile {
+        let game = self
+            .games
+            .iter()
+            .find(|game| matches!(game.key, GameKey::Crc(game_crc) if game_crc == crc))
+            .or_else(|| {
+                self.games.iter().find(|game| match &game.key {
+                    GameKey::Title(game_title) => game_title.eq_ignore_ascii_case(title.trim()),
+                    GameKey::Crc(_) => false,
+                })
+            });
+        let Some(game) = game else {
+            return self.default.clone();
+        };
+
+        let profile = game.profile.as_ref().and_then(|name| {
+            self.profiles
+                .iter()
+                .find(|profile| profile.name.eq_ignore_ascii_case(name))
+        });
+        let settings = match profile {
+            Some(profile) => profile.settings.merge(&game.settings),
+            None => game.settings.clone(),
+        };
+
+        GameProfile {
+            name: profile.map(|profile| profile.name.clone()),
+            palette: settings.palette.or(self.default.palette.clone()),
+            overclock: settings.overclock.unwrap_or(self.default.overclock),
+            run_ahead: settings.run_ahead.unwrap_or(self.default.run_ahead),
+            input: profile.map_or(self.default.input.clone(), |profile| profile.input.clone()),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+// Seção do config com as ROMs abertas por último, uma linha "rom = caminho" cada
+const RECENT_SECTION: &str = "recent";
+const MAX_RECENT: usize = 10;
+
+// ROMs recentes pro launcher, gravadas de volta no próprio arquivo de config
+pub struct RecentRoms {
+    config_path: PathBuf,
+    paths: Vec<String>,
+}
+
+impl RecentRoms {
+    pub fn from_config(config: &Config, config_path: &Path) -> Self {
+        let paths = config
+            .section(RECENT_SECTION)
+            .filter(|(key, _)| *key == "rom")
+            .map(|(_, path)| path.to_string())
+            .collect();
+        Self {
+            config_path: config_path.to_path_buf(),
+            paths,
+        }
+    }
+
+    // Mais recente primeiro
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    // Caminho absoluto, pra lista valer de qualquer diretório
+    pub fn add(&mut self, path: &Path) -> Result<(), String> {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let path = path.to_string_lossy().to_string();
+        self.paths.retain(|recent| *recent != path);
+        self.paths.insert(0, path);
+        self.paths.truncate(MAX_RECENT);
+        self.save()
+    }
+
+    // Reescreve só a seção [recent] (no fim do arquivo); o resto do config fica como está
+    fn save(&self) -> Result<(), String> {
+        let text = match fs::read_to_string(&self.config_path) {
+            Ok(text) => text,
+            Err(erro) if erro.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(erro) => {
+                return Err(format!(
+                    "erro ao ler '{}': {}",
+                    self.config_path.display(),
+                    erro
+                ));
+            }
+        };
+
+        let mut lines = Vec::new();
+        let mut in_recent = false;
+        for line in text.lines() {
+            if let Some(name) = line
+                .trim()
+                .strip_prefix('[')
+                .and_then(|l| l.strip_suffix(']'))
+            {
+                in_recent = name.trim().eq_ignore_ascii_case(RECENT_SECTION);
+            }
+            if !in_recent {
+                lines.push(line);
+            }
+        }
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+
+        let mut text = lines.join("\n");
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(&format!("[{}]\n", RECENT_SECTION));
+        for path in &self.paths {
+            text.push_str(&format!("rom = {}\n", path));
+        }
+        fs::write(&self.config_path, text)
+            .map_err(|erro| format!("erro ao gravar '{}': {}", self.config_path.display(), erro))
+    }
+}
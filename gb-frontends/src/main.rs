@@ -0,0 +1,506 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod frontend;
+mod options;
+#[cfg(feature = "tui")]
+mod tui;
+
+// O frontend e as opções usam o core pelos caminhos crate::...
+use gb_core::{
+    achievements, apu, archive, cartridge, cheats, config, cpu, debug, joypad, machine, netplay,
+    patch, png, ppu, script, sgb,
+};
+
+use crate::achievements::{Achievements, rom_hash};
+use crate::cartridge::{Cartridge, IrSocket, SaveLayout, sensor_image};
+use crate::config::{Config, DEFAULT_CONFIG_PATH};
+use crate::debug::Symbols;
+use crate::frontend::{
+    DEFAULT_LATENCY_MS, Filter, Frontend, GameProfile, GameProfiles, HotkeyMap, InputMapping,
+    MAX_LATENCY_MS, MIN_LATENCY_MS, GamePalettes, MacroKeys, PresenceReporter, RecentRoms, Scaling,
+    choose_rom, parse_channels,
+};
+use crate::machine::{
+    CYCLES_PER_FRAME, DEFAULT_BACKUPS, DETERMINISTIC_SEED, Emulator, overclock_warning, unix_time,
+    write_rotated,
+};
+use crate::netplay::Netplay;
+use crate::options::{Options, SramAction, SramCommand};
+use crate::ppu::{
+    ColorCorrection, ColorTransform, ColorizationMode, DEFAULT_PERSISTENCE, LcdGhosting, MAX_GAMMA,
+    MAX_PERSISTENCE, MIN_GAMMA,
+};
+
+// States do --resume, relativos ao diretório atual como o gb-emu.ini
+const RESUME_DIR: &str = "resume";
+
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    // gb-emu-rust sram ...: só converte o save, sem janela
+    if args.get(1).is_some_and(|arg| arg == "sram") {
+        let result = SramCommand::parse(&args[2..]).and_then(|command| run_sram(&command));
+        if let Err(erro) = result {
+            eprintln!("{}", erro);
+        }
+        return;
+    }
+    // Sem argumentos: a ROM sai do launcher, com as recentes do config padrão
+    if args.len() == 1 {
+        let config = if Path::new(DEFAULT_CONFIG_PATH).exists() {
+            Config::load(Path::new(DEFAULT_CONFIG_PATH))
+        } else {
+            Ok(Config::new())
+        };
+        let recent = match config {
+            Ok(config) => RecentRoms::from_config(&config, Path::new(DEFAULT_CONFIG_PATH)),
+            Err(erro) => {
+                eprintln!("{}", erro);
+                return;
+            }
+        };
+        match choose_rom(recent.paths()) {
+            Some(path) => args.push(path),
+            None => return,
+        }
+    }
+
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    };
+
+    // Sem --config, o arquivo padrão é opcional
+    let config = match &options.config_path {
+        Some(path) => Config::load(Path::new(path)),
+        None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+            Config::load(Path::new(DEFAULT_CONFIG_PATH))
+        }
+        None => Ok(Config::new()),
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    };
+
+    let config_path = options.config_path.as_deref().unwrap_or(DEFAULT_CONFIG_PATH);
+    let mut recent = RecentRoms::from_config(&config, Path::new(config_path));
+
+    let input = match InputMapping::from_config(&config) {
+        Ok(input) => input,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    };
+
+    let mut presence = match PresenceReporter::from_config(&config) {
+        Ok(presence) => presence,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    };
+
+    let hotkeys = match HotkeyMap::from_config(&config) {
+        Ok(hotkeys) => hotkeys,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    };
+
+    let macros = match MacroKeys::from_config(&config, Path::new(config_path)) {
+        Ok(macros) => macros,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    };
+
+    let scaling = match config.get("video", "scaling") {
+        None => Scaling::Integer,
+        Some(value) => match Scaling::parse(value) {
+            Some(scaling) => scaling,
+            None => {
+                eprintln!("[video] scaling inválido: '{}'", value);
+                return;
+            }
+        },
+    };
+
+    // Um shader próprio tem prioridade sobre os filtros embutidos. O filter = ghosting não
+    // é shader: liga o rastro do core ([video] ghosting) com a persistência padrão
+    let ghosting_filter = config
+        .get("video", "filter")
+        .is_some_and(|value| value.eq_ignore_ascii_case("ghosting"));
+    let filter = match (config.get("video", "shader"), config.get("video", "filter")) {
+        (Some(path), _) => Filter::Custom(path.to_string()),
+        (None, None) => Filter::None,
+        (None, Some(_)) if ghosting_filter => Filter::None,
+        (None, Some(value)) => match Filter::parse(value) {
+            Some(filter) => filter,
+            None => {
+                eprintln!("[video] filter inválido: '{}'", value);
+                return;
+            }
+        },
+    };
+
+    // Jogos de DMG em cores: auto (só no modelo CGB), title, off ou uma das combinações
+    let colorization = match config.get("video", "colorization") {
+        None => ColorizationMode::Auto,
+        Some(value) => match ColorizationMode::parse(value) {
+            Ok(mode) => mode,
+            Err(erro) => {
+                eprintln!("[video] {}", erro);
+                return;
+            }
+        },
+    };
+
+    let correction = match config.get("video", "color_correction") {
+        None => ColorCorrection::Off,
+        Some(value) => match ColorCorrection::parse(value) {
+            Ok(correction) => correction,
+            Err(erro) => {
+                eprintln!("[video] {}", erro);
+                return;
+            }
+        },
+    };
+
+    let gamma = match config.get("video", "gamma") {
+        None => 1.0,
+        Some(value) => match value.trim().parse::<f32>() {
+            Ok(gamma) if (MIN_GAMMA..=MAX_GAMMA).contains(&gamma) => gamma,
+            _ => {
+                eprintln!(
+                    "[video] gamma inválido: '{}' (de {} a {})",
+                    value, MIN_GAMMA, MAX_GAMMA
+                );
+                return;
+            }
+        },
+    };
+    // Sem correção nem gamma a conversão fica no caminho direto
+    let color_transform = (correction != ColorCorrection::Off || gamma != 1.0)
+        .then(|| ColorTransform::new(correction, gamma));
+
+    // Quanto do frame anterior fica na tela, em %, imitando o rastro do LCD do DMG. Vale
+    // pras duas telas do --link e entra também nos screenshots
+    let ghosting = match config.get("video", "ghosting") {
+        None if ghosting_filter => DEFAULT_PERSISTENCE,
+        None => 0,
+        Some(value) => match value.trim().parse::<u8>() {
+            Ok(percent) if percent <= MAX_PERSISTENCE => percent,
+            _ => {
+                eprintln!(
+                    "[video] ghosting inválido: '{}' (de 0 a {}%)",
+                    value, MAX_PERSISTENCE
+                );
+                return;
+            }
+        },
+    };
+
+    let muted = match config.get("audio", "mute") {
+        None => [false; 4],
+        Some(value) => match parse_channels(value) {
+            Some(muted) => muted,
+            None => {
+                eprintln!("[audio] mute inválido: '{}' (canais de 1 a 4)", value);
+                return;
+            }
+        },
+    };
+
+    let latency = match config.get("audio", "latency") {
+        None => DEFAULT_LATENCY_MS,
+        Some(value) => match value.trim().parse::<u32>() {
+            Ok(ms) if (MIN_LATENCY_MS..=MAX_LATENCY_MS).contains(&ms) => ms,
+            _ => {
+                eprintln!(
+                    "[audio] latency inválida: '{}' (de {} a {} ms)",
+                    value, MIN_LATENCY_MS, MAX_LATENCY_MS
+                );
+                return;
+            }
+        },
+    };
+
+    let mut rom = archive::read_rom(Path::new(&options.rom_path));
+    if let Some(patch) = &options.patch_path {
+        rom = rom.and_then(|rom| patch::apply_patch(rom, Path::new(patch)));
+    }
+    let cartridge = match rom.and_then(Cartridge::load) {
+        Ok(cartridge) => cartridge,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    };
+    // <rom>.sym do rgblink, se tiver: labels no console, nos breakpoints e nos relatórios
+    let symbols = match Symbols::for_rom(Path::new(&options.rom_path)) {
+        Ok(symbols) => symbols,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    };
+
+    let mut emulator = Emulator::new(cartridge);
+    emulator.symbols = symbols.clone();
+    emulator.cpu.illegal_opcode_policy = options.illegal_opcode;
+    emulator.bus.model = options.model;
+    emulator.colorization = colorization;
+    emulator.color_transform = color_transform.clone();
+    emulator.ghosting = (ghosting > 0).then(|| LcdGhosting::new(ghosting));
+    emulator.script_path = options.script_path.map(PathBuf::from);
+    if let Some(path) = &options.achievements_path {
+        match Achievements::load(Path::new(path)) {
+            Ok(achievements) => {
+                // O arquivo não diz de que ROM é: o hash ajuda a conferir no site
+                eprintln!(
+                    "RetroAchievements: {} conquistas, hash da ROM {}",
+                    achievements.list().len(),
+                    rom_hash(emulator.bus.cartridge.rom())
+                );
+                emulator.achievements = Some(achievements);
+            }
+            Err(erro) => {
+                eprintln!("{}", erro);
+                return;
+            }
+        }
+    }
+    emulator.sav_path = Some(Path::new(&options.rom_path).with_extension("sav"));
+    emulator.crash_path = Some(Path::new(&options.rom_path).with_extension("crash.txt"));
+    if options.resume {
+        emulator.resume_dir = Some(PathBuf::from(RESUME_DIR));
+    }
+    emulator.backups = options.backups;
+    emulator.run_ahead = options.run_ahead;
+    emulator.bus.overclock = options.overclock;
+    emulator.bus.serial.external_timeout = options
+        .serial_timeout
+        .map(|frames| frames * CYCLES_PER_FRAME);
+    // No TUI o stdout é a tela: o serial aparece só no log
+    emulator.serial_device = options
+        .serial_device
+        .or_else(|| options.tui.then(|| "none".to_string()));
+    emulator.seed = match (options.seed, options.deterministic) {
+        (Some(seed), _) => Some(seed),
+        (None, true) => Some(DETERMINISTIC_SEED),
+        (None, false) => None,
+    };
+    emulator.rtc_epoch = options.rtc_epoch;
+    if let Err(erro) = emulator.bus.cheats.load_config(&config) {
+        eprintln!("{}", erro);
+        return;
+    }
+
+    if let Some((port, peer)) = options.ir_link {
+        match IrSocket::bind(port, peer).and_then(|socket| Ok((socket.try_clone()?, socket))) {
+            Ok((console, cartridge)) => {
+                emulator.bus.infrared = Box::new(console);
+                emulator.set_ir_device(Box::new(cartridge));
+            }
+            Err(erro) => {
+                eprintln!("{}", erro);
+                return;
+            }
+        }
+    }
+
+    if let Some(path) = &options.camera_path {
+        match png::read_png(Path::new(path)) {
+            Ok((width, height, rgba)) => {
+                emulator.set_camera_image(sensor_image(width, height, &rgba));
+            }
+            Err(erro) => {
+                eprintln!("{}", erro);
+                return;
+            }
+        }
+    }
+
+    emulator.screenshots = options.screenshots;
+    if !options.exit.is_empty() {
+        emulator.set_exit_conditions(options.exit.clone());
+    }
+
+    let rom_crc = emulator.bus.cartridge.rom_crc;
+    let netplay = match (options.netplay_host, &options.netplay_connect) {
+        (Some(port), _) => {
+            println!("netplay: esperando conexão na porta {}...", port);
+            Some(Netplay::host(port, options.netplay_delay, rom_crc))
+        }
+        (None, Some(addr)) => Some(Netplay::connect(addr, rom_crc)),
+        (None, None) => None,
+    };
+    match netplay {
+        Some(Ok(netplay)) => emulator.netplay = Some(netplay),
+        Some(Err(erro)) => {
+            eprintln!("{}", erro);
+            return;
+        }
+        None => {}
+    }
+
+    // A segunda instância usa o mesmo modelo e a mesma seed, mas sem script nem resume
+    let linked = match &options.link_rom {
+        Some(path) => match archive::read_rom(Path::new(path)).and_then(Cartridge::load) {
+            Ok(cartridge) => {
+                let mut linked = Emulator::new(cartridge);
+                linked.cpu.illegal_opcode_policy = options.illegal_opcode;
+                linked.bus.model = options.model;
+                linked.bus.overclock = options.overclock;
+                linked.colorization = colorization;
+                linked.color_transform = color_transform;
+                linked.ghosting = (ghosting > 0).then(|| LcdGhosting::new(ghosting));
+                // A mesma ROM nas duas pontas não pode dividir o .sav (nem o relatório de crash)
+                let (sav, crash) = if *path == options.rom_path {
+                    ("2.sav", "2.crash.txt")
+                } else {
+                    ("sav", "crash.txt")
+                };
+                linked.sav_path = Some(Path::new(path).with_extension(sav));
+                linked.crash_path = Some(Path::new(path).with_extension(crash));
+                linked.seed = emulator.seed;
+                linked.rtc_epoch = emulator.rtc_epoch;
+                Some(linked)
+            }
+            Err(erro) => {
+                eprintln!("{}", erro);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // Só ROMs que carregaram entram na lista do launcher
+    if let Err(erro) = recent.add(Path::new(&options.rom_path)) {
+        eprintln!("{}", erro);
+    }
+
+    let title = emulator.title();
+    // O perfil do jogo ([game.<título>] ou [game.crc32:...]) vale por cima da linha de comando
+    let default = GameProfile {
+        name: None,
+        palette: None,
+        overclock: options.overclock,
+        run_ahead: options.run_ahead,
+        input,
+    };
+    let profiles = match GameProfiles::from_config(&config, default) {
+        Ok(profiles) => profiles,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    };
+    let profile = profiles.resolve(&title, emulator.bus.cartridge.rom_crc);
+    emulator.bus.overclock = profile.overclock;
+    emulator.run_ahead = profile.run_ahead;
+    if emulator.bus.overclock > 1 {
+        eprintln!("{}", overclock_warning(emulator.bus.overclock));
+    }
+    let mut palettes = GamePalettes::new(colorization);
+    match palettes.select(profile.palette) {
+        Ok(mode) => emulator.colorization = mode,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            return;
+        }
+    }
+    #[cfg(feature = "tui")]
+    if options.tui {
+        match tui::Tui::new(&title, symbols).run(emulator.start()) {
+            Ok(Some(code)) => std::process::exit(code),
+            Ok(None) => {}
+            Err(erro) => eprintln!("erro no terminal: {}", erro),
+        }
+        return;
+    }
+
+    let (handle, linked) = match linked {
+        Some(linked) => {
+            let (handle, linked) = emulator.start_linked(linked);
+            (handle, Some(linked))
+        }
+        None => (emulator.start(), None),
+    };
+
+    let mut frontend = Frontend::new(
+        &title,
+        Path::new(&options.rom_path),
+        options.vsync,
+        scaling,
+        &filter,
+        profile.input,
+        hotkeys,
+    );
+    frontend.set_muted_channels(muted);
+    frontend.set_audio_latency(latency);
+    frontend.set_symbols(symbols);
+    frontend.set_palettes(palettes);
+    frontend.set_profiles(profiles);
+    if !presence.is_empty() {
+        presence.start(&title);
+        frontend.set_presence(presence);
+    }
+    frontend.set_recent(recent);
+    frontend.set_macros(macros);
+    if let Some(code) = frontend.run(handle, linked) {
+        std::process::exit(code);
+    }
+}
+
+// Exporta o .sav ao lado da ROM pra um arquivo, ou importa um arquivo no lugar dele (o
+// anterior entra na rotação de cópias, como .sav.1)
+fn run_sram(command: &SramCommand) -> Result<(), String> {
+    let rom_path = Path::new(&command.rom_path);
+    let mut cartridge = archive::read_rom(rom_path).and_then(Cartridge::load)?;
+    if !cartridge.has_battery() {
+        return Err(format!("'{}' não tem RAM com bateria", command.rom_path));
+    }
+
+    // O .sav atual é o que se exporta; na importação em formato bruto o relógio vem dele
+    let sav_path = rom_path.with_extension("sav");
+    let now = unix_time();
+    if sav_path.exists() {
+        fs::read(&sav_path)
+            .map_err(|erro| erro.to_string())
+            .and_then(|data| cartridge.load_battery_data(&data, Some(now)))
+            .map_err(|erro| format!("erro ao carregar '{}': {}", sav_path.display(), erro))?;
+    } else if command.action == SramAction::Export {
+        return Err(format!("'{}' não existe", sav_path.display()));
+    }
+
+    match command.action {
+        SramAction::Export => {
+            let data = cartridge.export_battery(command.layout.unwrap_or(SaveLayout::Vba), now);
+            fs::write(&command.file_path, data)
+                .map_err(|erro| format!("erro ao gravar '{}': {}", command.file_path, erro))?;
+            println!("{} -> {}", sav_path.display(), command.file_path);
+        }
+        SramAction::Import => {
+            fs::read(&command.file_path)
+                .map_err(|erro| erro.to_string())
+                .and_then(|data| cartridge.import_battery(&data, command.layout, Some(now)))
+                .map_err(|erro| format!("erro ao importar '{}': {}", command.file_path, erro))?;
+            write_rotated(&sav_path, &cartridge.battery_data(now), DEFAULT_BACKUPS)
+                .map_err(|erro| format!("erro ao gravar '{}': {}", sav_path.display(), erro))?;
+            println!("{} -> {}", command.file_path, sav_path.display());
+        }
+    }
+    Ok(())
+}
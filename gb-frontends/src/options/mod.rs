@@ -0,0 +1,5 @@
+pub mod options;
+pub mod sram;
+
+pub use options::*;
+pub use sram::*;
@@ -0,0 +1,328 @@
+use crate::cpu::IllegalOpcodePolicy;
+use crate::machine::{DEFAULT_BACKUPS, ExitConditions, Model, ScheduledScreenshot};
+
+// Cada frame de run-ahead custa um frame inteiro de emulação a mais
+pub const MAX_RUN_AHEAD: u8 = 4;
+
+// Acima disso a emulação fica pesada demais pra rodar em tempo real
+pub const MAX_OVERCLOCK: u8 = 4;
+
+const DEFAULT_NETPLAY_DELAY: u8 = 2;
+const MAX_NETPLAY_DELAY: u8 = 10;
+
+pub struct Options {
+    pub rom_path: String,
+    pub illegal_opcode: IllegalOpcodePolicy,
+    pub model: Model,
+    pub vsync: bool,
+    pub config_path: Option<String>,
+    pub script_path: Option<String>,
+    // Conquistas do RetroAchievements no formato do RAIntegration (<id>-User.txt)
+    pub achievements_path: Option<String>,
+    pub run_ahead: u8,
+    // Ciclos de CPU por ciclo da PPU/APU (1 = normal)
+    pub overclock: u8,
+    // Runs reproduzíveis (test runner, movies, netplay): RAM inicial vem de uma seed fixa
+    pub deterministic: bool,
+    pub seed: Option<u64>,
+    // Hora (UNIX) que o RTC do cartucho usa no lugar do relógio; 0 com --deterministic/--seed
+    pub rtc_epoch: Option<u64>,
+    pub netplay_host: Option<u16>,
+    pub netplay_connect: Option<String>,
+    // Frames entre ler o input e ele valer nos dois lados
+    pub netplay_delay: u8,
+    // IPS ou BPS aplicado na ROM antes de carregar o cartucho
+    pub patch_path: Option<String>,
+    // Save state automático ao fechar, carregado de novo na próxima vez com a mesma ROM
+    pub resume: bool,
+    // Cópias antigas de cada slot de state e do .sav
+    pub backups: usize,
+    // Porta IR (CGB e cartuchos HuC) ligada em outra instância local: (porta local, porta
+    // do outro)
+    pub ir_link: Option<(u16, u16)>,
+    // PNG que a Pocket Camera enxerga
+    pub camera_path: Option<String>,
+    // Segunda ROM na mesma janela, com os seriais ligados por um cabo de link
+    pub link_rom: Option<String>,
+    // Frames até uma transferência com clock externo sem cabo terminar com 0xFF (None =
+    // espera pra sempre, como no hardware)
+    pub serial_timeout: Option<u64>,
+    // Dispositivo na porta serial, pelo nome no registro (None = saída no stdout)
+    pub serial_device: Option<String>,
+    pub exit: ExitConditions,
+    pub screenshots: Vec<ScheduledScreenshot>,
+    // Debugger no terminal no lugar da janela (só com a feature tui)
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub tui: bool,
+}
+
+impl Options {
+    // args[0] é o nome do binário
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut rom_path: Option<String> = None;
+        let mut illegal_opcode = IllegalOpcodePolicy::Lock;
+        let mut model = Model::Dmg;
+        let mut vsync = true;
+        let mut config_path: Option<String> = None;
+        let mut script_path: Option<String> = None;
+        let mut achievements_path: Option<String> = None;
+        let mut run_ahead = 0;
+        let mut overclock = 1;
+        let mut deterministic = false;
+        let mut seed: Option<u64> = None;
+        let mut rtc_epoch: Option<u64> = None;
+        let mut netplay_host: Option<u16> = None;
+        let mut netplay_connect: Option<String> = None;
+        let mut netplay_delay = DEFAULT_NETPLAY_DELAY;
+        let mut patch_path: Option<String> = None;
+        let mut resume = false;
+        let mut backups = DEFAULT_BACKUPS;
+        let mut ir_link: Option<(u16, u16)> = None;
+        let mut camera_path: Option<String> = None;
+        let mut link_rom: Option<String> = None;
+        let mut serial_timeout: Option<u64> = None;
+        let mut serial_device: Option<String> = None;
+        let mut exit = ExitConditions::default();
+        let mut screenshots = Vec::new();
+        let mut tui = false;
+
+        let mut iter = args.iter().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--illegal-opcode" => {
+                    let value = iter
+                        .next()
+                        .ok_or("--illegal-opcode espera 'lock' ou 'skip'")?;
+                    illegal_opcode = match value.as_str() {
+                        "lock" => IllegalOpcodePolicy::Lock,
+                        "skip" => IllegalOpcodePolicy::Skip,
+                        other => {
+                            return Err(format!(
+                                "valor inválido pra --illegal-opcode: '{}'",
+                                other
+                            ));
+                        }
+                    };
+                }
+                "--model" => {
+                    let value = iter.next().ok_or("--model espera 'dmg', 'cgb' ou 'sgb'")?;
+                    model = Model::parse(value)
+                        .ok_or_else(|| format!("valor inválido pra --model: '{}'", value))?;
+                }
+                "--no-vsync" => vsync = false,
+                "--config" => {
+                    let value = iter.next().ok_or("--config espera o caminho do arquivo")?;
+                    config_path = Some(value.to_string());
+                }
+                "--script" => {
+                    let value = iter.next().ok_or("--script espera o caminho do script Lua")?;
+                    script_path = Some(value.to_string());
+                }
+                "--achievements" => {
+                    let value = iter
+                        .next()
+                        .ok_or("--achievements espera o arquivo de conquistas")?;
+                    achievements_path = Some(value.to_string());
+                }
+                "--run-ahead" => {
+                    let value = iter.next().ok_or("--run-ahead espera o número de frames")?;
+                    run_ahead = match value.parse() {
+                        Ok(frames) if frames <= MAX_RUN_AHEAD => frames,
+                        _ => {
+                            return Err(format!(
+                                "valor inválido pra --run-ahead: '{}' (0 a {})",
+                                value, MAX_RUN_AHEAD
+                            ));
+                        }
+                    };
+                }
+                "--overclock" => {
+                    let value = iter.next().ok_or("--overclock espera o fator (1 a 4)")?;
+                    overclock = match value.parse() {
+                        Ok(factor) if (1..=MAX_OVERCLOCK).contains(&factor) => factor,
+                        _ => {
+                            return Err(format!(
+                                "valor inválido pra --overclock: '{}' (1 a {})",
+                                value, MAX_OVERCLOCK
+                            ));
+                        }
+                    };
+                }
+                "--deterministic" => deterministic = true,
+                "--seed" => {
+                    let value = iter.next().ok_or("--seed espera um número")?;
+                    let parsed = value
+                        .parse()
+                        .map_err(|_| format!("valor inválido pra --seed: '{}'", value))?;
+                    seed = Some(parsed);
+                }
+                "--rtc-epoch" => {
+                    let value = iter.next().ok_or("--rtc-epoch espera os segundos UNIX")?;
+                    let parsed = value
+                        .parse()
+                        .map_err(|_| format!("valor inválido pra --rtc-epoch: '{}'", value))?;
+                    rtc_epoch = Some(parsed);
+                }
+                "--netplay-host" => {
+                    let value = iter.next().ok_or("--netplay-host espera a porta")?;
+                    let port = value
+                        .parse()
+                        .map_err(|_| format!("porta inválida: '{}'", value))?;
+                    netplay_host = Some(port);
+                }
+                "--netplay-connect" => {
+                    let value = iter
+                        .next()
+                        .ok_or("--netplay-connect espera <endereço>:<porta>")?;
+                    netplay_connect = Some(value.to_string());
+                }
+                "--netplay-delay" => {
+                    let value = iter.next().ok_or("--netplay-delay espera o número de frames")?;
+                    netplay_delay = match value.parse() {
+                        Ok(frames) if (1..=MAX_NETPLAY_DELAY).contains(&frames) => frames,
+                        _ => {
+                            return Err(format!(
+                                "valor inválido pra --netplay-delay: '{}' (1 a {})",
+                                value, MAX_NETPLAY_DELAY
+                            ));
+                        }
+                    };
+                }
+                "--patch" => {
+                    let value = iter.next().ok_or("--patch espera o caminho do .ips/.bps")?;
+                    patch_path = Some(value.to_string());
+                }
+                "--resume" => resume = true,
+                "--backups" => {
+                    let value = iter.next().ok_or("--backups espera o número de cópias")?;
+                    backups = value
+                        .parse()
+                        .map_err(|_| format!("valor inválido pra --backups: '{}'", value))?;
+                }
+                "--ir-link" => {
+                    let value = iter
+                        .next()
+                        .ok_or("--ir-link espera <porta local>:<porta do outro>")?;
+                    let ports = value
+                        .split_once(':')
+                        .and_then(|(local, peer)| Some((local.parse().ok()?, peer.parse().ok()?)))
+                        .ok_or_else(|| format!("valor inválido pra --ir-link: '{}'", value))?;
+                    ir_link = Some(ports);
+                }
+                "--camera" => {
+                    let value = iter.next().ok_or("--camera espera o caminho do .png")?;
+                    camera_path = Some(value.to_string());
+                }
+                "--link" => {
+                    let value = iter.next().ok_or("--link espera o caminho da segunda ROM")?;
+                    link_rom = Some(value.to_string());
+                }
+                "--serial" => {
+                    let value = iter.next().ok_or(
+                        "--serial espera o dispositivo (none, console, printer[:pasta], \
+                         tcp-host:<porta>, tcp:<endereço>:<porta>, lua)",
+                    )?;
+                    serial_device = Some(value.to_string());
+                }
+                "--serial-timeout" => {
+                    let value = iter
+                        .next()
+                        .ok_or("--serial-timeout espera o número de frames")?;
+                    let frames = value.parse().ok().filter(|&frames| frames > 0).ok_or_else(
+                        || format!("valor inválido pra --serial-timeout: '{}'", value),
+                    )?;
+                    serial_timeout = Some(frames);
+                }
+                "--frames" => {
+                    let value = iter.next().ok_or("--frames espera o número de frames")?;
+                    let frames = value
+                        .parse()
+                        .ok()
+                        .filter(|&frames| frames > 0)
+                        .ok_or_else(|| format!("valor inválido pra --frames: '{}'", value))?;
+                    exit.frames = Some(frames);
+                }
+                "--until-pc" => {
+                    let value = iter.next().ok_or("--until-pc espera um endereço em hexa")?;
+                    let hex = value
+                        .strip_prefix("0x")
+                        .or(value.strip_prefix('$'))
+                        .unwrap_or(value);
+                    let addr = u16::from_str_radix(hex, 16)
+                        .map_err(|_| format!("valor inválido pra --until-pc: '{}'", value))?;
+                    exit.until_pc = Some(addr);
+                }
+                "--until-serial" => {
+                    let value = iter.next().ok_or("--until-serial espera o texto procurado")?;
+                    if value.is_empty() {
+                        return Err("--until-serial espera o texto procurado".to_string());
+                    }
+                    exit.until_serial = Some(value.to_string());
+                }
+                "--screenshot-at" => {
+                    let value = iter
+                        .next()
+                        .ok_or("--screenshot-at espera <frame>:<arquivo.png>")?;
+                    let shot = ScheduledScreenshot::parse(value).ok_or_else(|| {
+                        format!("valor inválido pra --screenshot-at: '{}'", value)
+                    })?;
+                    screenshots.push(shot);
+                }
+                "--tui" if cfg!(feature = "tui") => tui = true,
+                flag if flag.starts_with("--") => {
+                    return Err(format!("opção desconhecida: {}", flag));
+                }
+                path => {
+                    rom_path = Some(path.to_string());
+                }
+            }
+        }
+
+        let rom_path = rom_path.ok_or("uso: gb-emu-rust [--illegal-opcode lock|skip] [--model dmg|cgb|sgb] [--no-vsync] [--config <arquivo>] [--script <arquivo.lua>] [--achievements <arquivo>] [--run-ahead <frames>] [--overclock <fator>] [--deterministic] [--seed <n>] [--rtc-epoch <segundos>] [--netplay-host <porta> | --netplay-connect <endereço>] [--netplay-delay <frames>] [--patch <arquivo.ips|.bps>] [--resume] [--backups <n>] [--ir-link <porta>:<porta>] [--camera <imagem.png>] [--link <rom>] [--serial <dispositivo>] [--serial-timeout <frames>] [--frames <n>] [--until-pc <endereço>] [--until-serial <texto>] [--screenshot-at <frame>:<arquivo.png>]... [--tui] <rom>")?;
+
+        if netplay_host.is_some() && netplay_connect.is_some() {
+            return Err("--netplay-host e --netplay-connect não podem ser usados juntos".to_string());
+        }
+
+        if link_rom.is_some() && (netplay_host.is_some() || netplay_connect.is_some()) {
+            return Err("--link não pode ser usado junto com netplay".to_string());
+        }
+
+        if link_rom.is_some() && serial_device.is_some() {
+            return Err("--serial não pode ser usado junto com --link".to_string());
+        }
+
+        if tui && link_rom.is_some() {
+            return Err("--tui não pode ser usado junto com --link".to_string());
+        }
+
+        Ok(Self {
+            rom_path,
+            illegal_opcode,
+            model,
+            vsync,
+            config_path,
+            script_path,
+            achievements_path,
+            run_ahead,
+            overclock,
+            deterministic,
+            seed,
+            rtc_epoch: rtc_epoch.or((deterministic || seed.is_some()).then_some(0)),
+            netplay_host,
+            netplay_connect,
+            netplay_delay,
+            patch_path,
+            resume,
+            backups,
+            ir_link,
+            camera_path,
+            link_rom,
+            serial_timeout,
+            serial_device,
+            exit,
+            screenshots,
+            tui,
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use crate::cartridge::SaveLayout;
+
+const USAGE: &str = "uso: gb-emu-rust sram export|import [--format raw|vba] <rom> <arquivo>";
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum SramAction {
+    // .sav da ROM -> arquivo
+    Export,
+    // arquivo -> .sav da ROM
+    Import,
+}
+
+// Subcomando `sram`: troca o .sav ao lado da ROM com saves de flashcarts e de outros
+// emuladores, sem abrir a janela
+pub struct SramCommand {
+    pub action: SramAction,
+    pub rom_path: String,
+    pub file_path: String,
+    // None = exporta no formato do VBA e detecta o formato na importação
+    pub layout: Option<SaveLayout>,
+}
+
+impl SramCommand {
+    // `args` é o que vem depois de "sram"
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut iter = args.iter();
+        let action = match iter.next().map(String::as_str) {
+            Some("export") => SramAction::Export,
+            Some("import") => SramAction::Import,
+            _ => return Err(USAGE.to_string()),
+        };
+
+        let mut layout = None;
+        let mut paths = Vec::new();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--format" => {
+                    let value = iter.next().ok_or("--format espera 'raw' ou 'vba'")?;
+                    layout = Some(
+                        SaveLayout::parse(value)
+                            .ok_or_else(|| format!("valor inválido pra --format: '{}'", value))?,
+                    );
+                }
+                flag if flag.starts_with("--") => {
+                    return Err(format!("opção desconhecida: {}", flag));
+                }
+                path => paths.push(path.to_string()),
+            }
+        }
+
+        let [rom_path, file_path]: [String; 2] = paths.try_into().map_err(|_| USAGE.to_string())?;
+        Ok(Self {
+            action,
+            rom_path,
+            file_path,
+            layout,
+        })
+    }
+}
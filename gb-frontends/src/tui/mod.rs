@@ -0,0 +1,3 @@
+pub mod tui;
+
+pub use tui::*;
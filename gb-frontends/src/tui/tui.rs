@@ -0,0 +1,414 @@
+use std::io;
+use std::mem;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::debug::{Inspection, Location, Symbols};
+use crate::frontend::{
+    CONSOLE_HELP, backtrace_lines, describe_trigger, parse_command, parse_target,
+};
+use crate::machine::{EmulatorCommand, EmulatorEvent, EmulatorHandle, StepKind};
+
+// Intervalo entre redesenhos (e pedidos de Inspect) esperando tecla
+const REFRESH: Duration = Duration::from_millis(33);
+
+// Linhas guardadas no painel de log
+const LOG_LINES: usize = 500;
+
+// Altura dos painéis de memória e de log, bordas inclusas
+const MEMORY_HEIGHT: u16 = 10;
+const LOG_HEIGHT: u16 = 10;
+
+const TUI_HELP: &str = "\
+F5 continua/pausa | F10 step over | F11 step | F12 step out | Ctrl-C sai
+c(ontinue) | pause | s | n | finish | mem <endereço|label> | PageUp/PageDown/setas: memória
+quit";
+
+// Debugger no terminal: disassembly em volta do PC, registradores, memória, log e uma linha
+// de comando com os comandos do console. A emulação roda sem janela, então serve por SSH
+pub struct Tui {
+    symbols: Symbols,
+    title: String,
+    paused: bool,
+    inspection: Option<Box<Inspection>>,
+    memory_start: u16,
+    command: String,
+    log: Vec<String>,
+    // Linha do serial ainda sem \n
+    serial: String,
+    exit_code: Option<i32>,
+    quit: bool,
+}
+
+impl Tui {
+    pub fn new(title: &str, symbols: Symbols) -> Self {
+        Self {
+            symbols,
+            title: title.to_string(),
+            paused: false,
+            inspection: None,
+            memory_start: 0xC000,
+            command: String::new(),
+            log: TUI_HELP.lines().map(str::to_string).collect(),
+            serial: String::new(),
+            exit_code: None,
+            quit: false,
+        }
+    }
+
+    // Toma conta do terminal até o Ctrl-C/quit (ou uma condição de parada) e devolve ele
+    // como estava. O código de saída vem do --frames/--until-*
+    pub fn run(mut self, emulator: EmulatorHandle) -> io::Result<Option<i32>> {
+        let mut terminal = ratatui::init();
+        let result = self.main_loop(&mut terminal, &emulator);
+        ratatui::restore();
+        result.map(|()| self.exit_code)
+    }
+
+    fn main_loop(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        emulator: &EmulatorHandle,
+    ) -> io::Result<()> {
+        while !self.quit {
+            emulator.send(EmulatorCommand::Inspect(self.memory_start));
+            // Sem tela pros frames: só não deixa o canal encher
+            while emulator.frames.try_recv().is_ok() {}
+            let events: Vec<EmulatorEvent> = emulator.events.try_iter().collect();
+            for event in events {
+                self.handle_event(event);
+            }
+
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(REFRESH)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key, emulator);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: EmulatorEvent) {
+        match event {
+            EmulatorEvent::Inspection(inspection) => self.inspection = Some(inspection),
+            EmulatorEvent::Breakpoint { pc, bank } => {
+                self.paused = true;
+                self.push_log(format!("Breakpoint em {}", self.symbols.locate((bank, pc))));
+            }
+            EmulatorEvent::Stepped { .. } => self.paused = true,
+            EmulatorEvent::Triggered(hit) => {
+                self.paused = true;
+                self.push_log(describe_trigger(hit, &self.symbols));
+            }
+            EmulatorEvent::Backtrace { pc, frames } => {
+                for line in backtrace_lines(pc, &frames, &self.symbols) {
+                    self.push_log(line);
+                }
+            }
+            EmulatorEvent::History(entries) => {
+                for entry in &entries {
+                    self.push_log(entry.format(&self.symbols));
+                }
+            }
+            EmulatorEvent::Watches(watches) => {
+                for (index, watch) in watches.iter().enumerate() {
+                    self.push_log(format!("{}: {}", index, watch.describe()));
+                }
+            }
+            EmulatorEvent::PpuLog(events) => {
+                for event in &events {
+                    self.push_log(event.describe());
+                }
+            }
+            EmulatorEvent::IllegalOpcode { pc, opcode, locked } => {
+                let action = if locked { "CPU travada" } else { "pulado" };
+                self.push_log(format!(
+                    "Opcode ilegal {:02X} em ${:04X} ({})",
+                    opcode, pc, action
+                ));
+            }
+            EmulatorEvent::SerialByte(byte) => match byte {
+                b'\n' => {
+                    let line = mem::take(&mut self.serial);
+                    self.push_log(format!("serial: {}", line));
+                }
+                _ => self.serial.push(byte as char),
+            },
+            EmulatorEvent::StateSaved(path)
+            | EmulatorEvent::StateLoaded(path)
+            | EmulatorEvent::ProfileSaved(path)
+            | EmulatorEvent::CoverageSaved(path)
+            | EmulatorEvent::TraceSaved(path)
+            | EmulatorEvent::MovieRecording(path)
+            | EmulatorEvent::MoviePlaying(path) => {
+                self.push_log(format!("{}", path.display()));
+            }
+            EmulatorEvent::CrashReport(path) => {
+                self.push_log(format!("Relatório de crash: {}", path.display()));
+            }
+            EmulatorEvent::AchievementUnlocked { title, points, .. } => {
+                self.push_log(format!("Conquista: {} ({} pontos)", title, points));
+            }
+            EmulatorEvent::Exit(code) => {
+                self.exit_code = Some(code);
+                self.quit = true;
+            }
+            EmulatorEvent::Error(erro) | EmulatorEvent::SerialMessage(erro) => self.push_log(erro),
+            _ => {}
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, emulator: &EmulatorHandle) {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.quit = true;
+            }
+            KeyCode::Char(c) => self.command.push(c),
+            KeyCode::Backspace => {
+                self.command.pop();
+            }
+            KeyCode::Esc => self.command.clear(),
+            KeyCode::Enter => {
+                let line = mem::take(&mut self.command);
+                self.execute(&line, emulator);
+            }
+            KeyCode::F(5) => self.set_paused(!self.paused, emulator),
+            KeyCode::F(10) => self.send(EmulatorCommand::Step(StepKind::Over), emulator),
+            KeyCode::F(11) => self.send(EmulatorCommand::Step(StepKind::Into), emulator),
+            KeyCode::F(12) => self.send(EmulatorCommand::Step(StepKind::Out), emulator),
+            KeyCode::Up => self.memory_start = self.memory_start.wrapping_sub(0x10),
+            KeyCode::Down => self.memory_start = self.memory_start.wrapping_add(0x10),
+            KeyCode::PageUp => self.memory_start = self.memory_start.wrapping_sub(0x100),
+            KeyCode::PageDown => self.memory_start = self.memory_start.wrapping_add(0x100),
+            _ => {}
+        }
+    }
+
+    // Atalhos do TUI; o resto vai pro parser do console
+    fn execute(&mut self, line: &str, emulator: &EmulatorHandle) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if !words.is_empty() {
+            self.push_log(format!("> {}", words.join(" ")));
+        }
+
+        match words.as_slice() {
+            [] => {}
+            ["quit"] | ["q"] => self.quit = true,
+            ["continue"] | ["c"] => self.set_paused(false, emulator),
+            ["pause"] => self.set_paused(true, emulator),
+            ["s"] => self.send(EmulatorCommand::Step(StepKind::Into), emulator),
+            ["n"] => self.send(EmulatorCommand::Step(StepKind::Over), emulator),
+            ["finish"] => self.send(EmulatorCommand::Step(StepKind::Out), emulator),
+            ["mem", target] => match parse_target(target, &self.symbols) {
+                Ok(addr) => self.memory_start = addr,
+                Err(erro) => self.push_log(erro),
+            },
+            ["help"] => {
+                for line in TUI_HELP.lines().chain(CONSOLE_HELP.lines()) {
+                    self.push_log(line.to_string());
+                }
+            }
+            _ => match parse_command(line, &self.symbols) {
+                Ok(command) => self.send(command, emulator),
+                Err(erro) => {
+                    for line in erro.lines() {
+                        self.push_log(line.to_string());
+                    }
+                }
+            },
+        }
+    }
+
+    fn send(&mut self, command: EmulatorCommand, emulator: &EmulatorHandle) {
+        // O core despausa pra dar o passo e avisa com Stepped
+        if let EmulatorCommand::Step(_) = command {
+            self.paused = false;
+        }
+        emulator.send(command);
+    }
+
+    fn set_paused(&mut self, paused: bool, emulator: &EmulatorHandle) {
+        self.paused = paused;
+        emulator.send(EmulatorCommand::SetPaused(paused));
+    }
+
+    fn push_log(&mut self, line: String) {
+        if self.log.len() == LOG_LINES {
+            self.log.remove(0);
+        }
+        self.log.push(line);
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [top, memory, log, command] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(MEMORY_HEIGHT),
+            Constraint::Length(LOG_HEIGHT),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+        let [code, cpu] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(32)]).areas(top);
+
+        frame.render_widget(self.code_pane(code.height.saturating_sub(2)), code);
+        frame.render_widget(self.cpu_pane(), cpu);
+        frame.render_widget(self.memory_pane(memory.height.saturating_sub(2)), memory);
+        frame.render_widget(self.log_pane(log.height.saturating_sub(2)), log);
+        frame.render_widget(
+            Paragraph::new(format!("> {}", self.command)).block(Block::bordered().title("Comando")),
+            command,
+        );
+        frame.set_cursor_position(cursor(command, self.command.chars().count()));
+    }
+
+    // Instruções já executadas (em cinza), a do PC destacada e as próximas
+    fn code_pane(&self, height: u16) -> Paragraph<'_> {
+        let block = Block::bordered().title("Código");
+        let Some(inspection) = &self.inspection else {
+            return Paragraph::new("").block(block);
+        };
+
+        let recent = Style::new().fg(Color::DarkGray);
+        let mut lines: Vec<Line> = inspection
+            .recent
+            .iter()
+            .map(|entry| {
+                let text = self.code_line(
+                    inspection,
+                    entry.location(),
+                    &entry.disassemble(&self.symbols),
+                );
+                Line::styled(text, recent)
+            })
+            .collect();
+
+        let bank = inspection.cpu.bank as usize;
+        for (addr, text) in inspection.upcoming(&self.symbols) {
+            if lines.len() >= height as usize {
+                break;
+            }
+            if let Some(name) = self.symbols.name(bank, addr) {
+                lines.push(Line::from(format!("{}:", name)));
+            }
+            let line = self.code_line(inspection, (bank, addr), &text);
+            lines.push(if addr == inspection.cpu.pc {
+                Line::styled(line, Style::new().add_modifier(Modifier::REVERSED))
+            } else {
+                Line::from(line)
+            });
+        }
+        Paragraph::new(lines).block(block)
+    }
+
+    fn code_line(&self, inspection: &Inspection, (bank, addr): Location, text: &str) -> String {
+        let marker = if inspection.breakpoints.contains(&addr) {
+            '*'
+        } else {
+            ' '
+        };
+        format!("{} {:02X}:{:04X}  {}", marker, bank, addr, text)
+    }
+
+    fn cpu_pane(&self) -> Paragraph<'_> {
+        let block = Block::bordered().title(self.title.as_str());
+        let Some(inspection) = &self.inspection else {
+            return Paragraph::new("").block(block);
+        };
+        let cpu = &inspection.cpu;
+
+        let flags: String = ['Z', 'N', 'H', 'C']
+            .iter()
+            .enumerate()
+            .map(|(index, &name)| {
+                if cpu.af & (0x80 >> index) != 0 {
+                    name
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let status = if inspection.locked {
+            "travada"
+        } else if self.paused {
+            "pausado"
+        } else if inspection.halt {
+            "rodando (HALT)"
+        } else {
+            "rodando"
+        };
+
+        let mut lines = vec![
+            Line::from(format!("AF={:04X}  BC={:04X}", cpu.af, cpu.bc)),
+            Line::from(format!("DE={:04X}  HL={:04X}", cpu.de, cpu.hl)),
+            Line::from(format!("SP={:04X}  PC={:04X}", cpu.sp, cpu.pc)),
+            Line::from(format!("{}  IME={}", flags, inspection.ime as u8)),
+            Line::from(format!("ROM {:02X}", cpu.rom_bank)),
+            Line::from(""),
+            Line::from(status),
+            Line::from(self.symbols.locate(cpu.location())),
+        ];
+        // Watches embaixo, atualizados a cada redesenho
+        if !inspection.watches.is_empty() {
+            lines.push(Line::from(""));
+        }
+        for (index, watch) in inspection.watches.iter().enumerate() {
+            lines.push(Line::from(format!("{}: {}", index, watch.describe())));
+        }
+        Paragraph::new(lines).block(block)
+    }
+
+    // 16 bytes por linha, com os caracteres imprimíveis do lado
+    fn memory_pane(&self, height: u16) -> Paragraph<'_> {
+        let block = Block::bordered().title("Memória");
+        let Some(inspection) = &self.inspection else {
+            return Paragraph::new("").block(block);
+        };
+
+        let lines: Vec<Line> = inspection
+            .memory
+            .chunks(16)
+            .take(height as usize)
+            .enumerate()
+            .map(|(row, bytes)| {
+                let addr = inspection.memory_start.wrapping_add(row as u16 * 16);
+                let hex: Vec<String> = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+                let text: String = bytes
+                    .iter()
+                    .map(|&byte| {
+                        if byte.is_ascii_graphic() || byte == b' ' {
+                            byte as char
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect();
+                Line::from(format!("{:04X}  {}  {}", addr, hex.join(" "), text))
+            })
+            .collect();
+        Paragraph::new(lines).block(block)
+    }
+
+    fn log_pane(&self, height: u16) -> Paragraph<'_> {
+        let skip = self.log.len().saturating_sub(height as usize);
+        let lines: Vec<Line> = self.log[skip..]
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        Paragraph::new(lines).block(Block::bordered().title("Log"))
+    }
+}
+
+// Depois do "> " dentro da borda
+fn cursor(area: Rect, length: usize) -> (u16, u16) {
+    let x = area.x + 3 + length as u16;
+    (x.min(area.right().saturating_sub(2)), area.y + 1)
+}
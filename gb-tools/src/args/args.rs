@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use gb_core::archive;
+use gb_core::cartridge::Cartridge;
+
+// Endereço em hexa, com ou sem prefixo (0x150, $150, 150)
+pub fn parse_addr(value: &str) -> Option<u16> {
+    let hex = value
+        .strip_prefix("0x")
+        .or(value.strip_prefix('$'))
+        .unwrap_or(value);
+    u16::from_str_radix(hex, 16).ok()
+}
+
+// Valor de uma opção que espera argumento, com a mensagem de erro se faltar
+pub fn next_value<'a>(
+    iter: &mut impl Iterator<Item = &'a String>,
+    expected: &str,
+) -> Result<&'a str, String> {
+    iter.next()
+        .map(String::as_str)
+        .ok_or_else(|| expected.to_string())
+}
+
+// .gb/.gbc ou .zip
+pub fn load_cartridge(path: &str) -> Result<Cartridge, String> {
+    archive::read_rom(Path::new(path)).and_then(Cartridge::load)
+}
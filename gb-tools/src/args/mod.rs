@@ -0,0 +1,3 @@
+pub mod args;
+
+pub use args::*;
@@ -0,0 +1,114 @@
+use std::env;
+use std::path::Path;
+
+use gb_core::archive;
+use gb_core::debug::Symbols;
+use gb_tools::args::{next_value, parse_addr};
+
+const USAGE: &str = "uso: gb-disasm [--bank <n>] [--from <endereço|label>] [--count <instruções>] [--symbols <arquivo.sym>] <rom>";
+
+const BANK_SIZE: usize = 0x4000;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if let Err(erro) = run(&args) {
+        eprintln!("{}", erro);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut rom_path: Option<&str> = None;
+    let mut bank = 1;
+    let mut from = "0100";
+    let mut count = 32;
+    let mut symbols_path: Option<&str> = None;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--bank" => {
+                let value = next_value(&mut iter, "--bank espera o número do banco")?;
+                bank = value
+                    .parse()
+                    .map_err(|_| format!("valor inválido pra --bank: '{}'", value))?;
+            }
+            "--from" => {
+                from = next_value(&mut iter, "--from espera um endereço em hexa ou um label")?;
+            }
+            "--count" => {
+                let value = next_value(&mut iter, "--count espera o número de instruções")?;
+                count = value
+                    .parse()
+                    .map_err(|_| format!("valor inválido pra --count: '{}'", value))?;
+            }
+            "--symbols" => {
+                symbols_path = Some(next_value(&mut iter, "--symbols espera o arquivo .sym")?);
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("opção desconhecida: {}", flag));
+            }
+            path => rom_path = Some(path),
+        }
+    }
+
+    let rom_path = Path::new(rom_path.ok_or(USAGE)?);
+    let rom = archive::read_rom(rom_path)?;
+    // Sem --symbols, o <rom>.sym do lado da ROM (se existir)
+    let symbols = match symbols_path {
+        Some(path) => Symbols::load(Path::new(path))?,
+        None => Symbols::for_rom(rom_path)?,
+    };
+
+    // Um label em 4000-7FFF já diz o banco
+    let from = match symbols.resolve(from) {
+        Some((label_bank, addr)) if addr < 0x8000 => {
+            if addr >= 0x4000 {
+                bank = label_bank;
+            }
+            addr
+        }
+        _ => parse_addr(from)
+            .filter(|&addr| addr < 0x8000)
+            .ok_or_else(|| format!("valor inválido pra --from: '{}'", from))?,
+    };
+    if bank >= rom.len().div_ceil(BANK_SIZE).max(2) {
+        return Err(format!("a ROM não tem o banco {}", bank));
+    }
+
+    // 0000-3FFF é sempre o banco 0; 4000-7FFF mostra o --bank. Fora da ROM lê 0xFF como o
+    // barramento aberto
+    let offset = |addr: u16| match addr {
+        0x0000..=0x3FFF => addr as usize,
+        _ => bank * BANK_SIZE + (addr as usize - BANK_SIZE),
+    };
+    let read = |addr: u16| match addr {
+        0x0000..=0x7FFF => rom.get(offset(addr)).copied().unwrap_or(0xFF),
+        _ => 0xFF,
+    };
+
+    let mut addr = from;
+    for _ in 0..count {
+        let (text, length) = symbols.disassemble(addr, bank, read);
+        let bytes: Vec<String> = (0..length)
+            .map(|index| format!("{:02X}", read(addr.wrapping_add(index))))
+            .collect();
+        let shown_bank = if addr < 0x4000 { 0 } else { bank };
+        if let Some(name) = symbols.name(bank, addr) {
+            println!("{}:", name);
+        }
+        println!(
+            "{:02X}:{:04X}  {:<9} {}",
+            shown_bank,
+            addr,
+            bytes.join(" "),
+            text
+        );
+
+        addr = addr.wrapping_add(length);
+        if addr >= 0x8000 {
+            break;
+        }
+    }
+    Ok(())
+}
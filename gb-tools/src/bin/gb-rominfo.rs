@@ -0,0 +1,41 @@
+use std::env;
+
+use gb_core::achievements::rom_hash;
+use gb_tools::args::load_cartridge;
+
+// Só o header do cartucho
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let result = match args.get(1) {
+        Some(path) => print_info(path),
+        None => Err("uso: gb-rominfo <rom>".to_string()),
+    };
+    if let Err(erro) = result {
+        eprintln!("{}", erro);
+        std::process::exit(1);
+    }
+}
+
+fn print_info(path: &str) -> Result<(), String> {
+    let cartridge = load_cartridge(path)?;
+    let valid = |ok: bool| if ok { "ok" } else { "inválido" };
+
+    print!("{}", cartridge);
+    match cartridge.rom_size_bytes() {
+        Some(bytes) => println!("ROM Size (bytes):    {}", bytes),
+        None => println!("ROM Size (bytes):    desconhecido"),
+    }
+    println!("RAM Size (bytes):    {}", cartridge.ram_size_bytes());
+    println!(
+        "Header Checksum OK:  {}",
+        valid(cartridge.header_checksum_valid)
+    );
+    println!(
+        "Global Checksum OK:  {}",
+        valid(cartridge.global_checksum_valid)
+    );
+    println!("Mapper:              {}", cartridge.mapper());
+    println!("ROM CRC32:           {:08X}", cartridge.rom_crc);
+    println!("ROM MD5 (RA):        {}", rom_hash(cartridge.rom()));
+    Ok(())
+}
@@ -0,0 +1,437 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use gb_core::debug::{History, HistoryEntry, Symbols};
+use gb_core::machine::{CYCLES_PER_FRAME, DETERMINISTIC_SEED, Emulator, Model, ResetKind};
+use gb_tools::args::{load_cartridge, next_value, parse_addr};
+
+const USAGE: &str = "uso: gb-statediff [--a <config>] [--b <config>] [--trace <arquivo>] [--frames <n>] [--ignore-io <endereço>]... <rom>
+  config: model=dmg|cgb|sgb,seed=<n>,overclock=<1-4> (separados por vírgula)
+  --trace: compara a config A com um log de execução (o --trace do emulador ou o formato do Gameboy Doctor)";
+
+// Código de saída: 0 = terminou sem divergir, 1 = divergiu, 2 = erro nos argumentos/arquivos
+const EXIT_SAME: i32 = 0;
+const EXIT_DIVERGED: i32 = 1;
+const EXIT_ERROR: i32 = 2;
+
+const DEFAULT_FRAMES: u32 = 600;
+// Instruções em comum mostradas antes da divergência
+const CONTEXT: usize = 8;
+
+// Roda a mesma ROM com duas configurações (ou uma contra um trace importado), instrução
+// por instrução, e para na primeira diferença em registradores, IO ou hash do frame
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let code = match setup(&args) {
+        Ok(diff) => diff.run(),
+        Err(erro) => {
+            eprintln!("{}", erro);
+            EXIT_ERROR
+        }
+    };
+    std::process::exit(code);
+}
+
+// Um lado da comparação
+#[derive(Copy, Clone)]
+struct Config {
+    model: Model,
+    seed: u64,
+    overclock: u8,
+}
+
+impl Config {
+    fn parse(value: &str) -> Result<Self, String> {
+        let mut config = Config::default();
+        for item in value.split(',').filter(|item| !item.trim().is_empty()) {
+            let (key, value) = item
+                .split_once('=')
+                .ok_or_else(|| format!("item de config sem '=': '{}'", item))?;
+            let invalid = || format!("valor inválido pra {}: '{}'", key.trim(), value.trim());
+            match key.trim() {
+                "model" => config.model = Model::parse(value.trim()).ok_or_else(invalid)?,
+                "seed" => config.seed = value.trim().parse().map_err(|_| invalid())?,
+                "overclock" => {
+                    config.overclock = value
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|factor| (1..=4).contains(factor))
+                        .ok_or_else(invalid)?;
+                }
+                other => return Err(format!("item de config desconhecido: '{}'", other)),
+            }
+        }
+        Ok(config)
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "model={:?} seed={} overclock={}",
+            self.model, self.seed, self.overclock
+        )
+    }
+
+    fn build(&self, rom_path: &str) -> Result<Emulator, String> {
+        let mut emulator = Emulator::new(load_cartridge(rom_path)?);
+        emulator.bus.model = self.model;
+        emulator.bus.overclock = self.overclock;
+        emulator.seed = Some(self.seed);
+        emulator.reset(ResetKind::Hard);
+        Ok(emulator)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            model: Model::Dmg,
+            seed: DETERMINISTIC_SEED,
+            overclock: 1,
+        }
+    }
+}
+
+// Linha de um trace importado. O banco só vem no formato do emulador
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct TraceLine {
+    bank: Option<u16>,
+    pc: u16,
+    af: u16,
+    bc: u16,
+    de: u16,
+    hl: u16,
+    sp: u16,
+}
+
+impl TraceLine {
+    // "01:4000 label AF=0180 BC=0013 ... SP=FFFE  disasm" (--trace do emulador) ou
+    // "A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:..." (Gameboy Doctor)
+    fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace().peekable();
+        let mut bank = None;
+        let mut pc = None;
+        if let Some((b, p)) = words.peek().and_then(|word| word.split_once(':'))
+            && b.len() == 2
+            && p.len() == 4
+        {
+            bank = Some(u16::from_str_radix(b, 16).ok()?);
+            pc = Some(u16::from_str_radix(p, 16).ok()?);
+            words.next();
+        }
+
+        let mut regs = [None::<u16>; 4];
+        let mut bytes = [None::<u8>; 8];
+        let mut sp = None;
+        for word in words {
+            let Some((key, value)) = word.split_once(['=', ':']) else {
+                continue;
+            };
+            let wide = || u16::from_str_radix(value, 16).ok();
+            let byte = || u8::from_str_radix(value, 16).ok();
+            match key {
+                "AF" => regs[0] = wide(),
+                "BC" => regs[1] = wide(),
+                "DE" => regs[2] = wide(),
+                "HL" => regs[3] = wide(),
+                "SP" => sp = wide(),
+                "PC" => pc = pc.or(wide()),
+                "A" => bytes[0] = byte(),
+                "F" => bytes[1] = byte(),
+                "B" => bytes[2] = byte(),
+                "C" => bytes[3] = byte(),
+                "D" => bytes[4] = byte(),
+                "E" => bytes[5] = byte(),
+                "H" => bytes[6] = byte(),
+                "L" => bytes[7] = byte(),
+                _ => {}
+            }
+        }
+        // Registradores de 8 bits (Gameboy Doctor) viram os pares
+        for (pair, halves) in regs.iter_mut().zip(bytes.chunks_exact(2)) {
+            if pair.is_none()
+                && let [Some(high), Some(low)] = halves
+            {
+                *pair = Some(u16::from_be_bytes([*high, *low]));
+            }
+        }
+
+        Some(Self {
+            bank,
+            pc: pc?,
+            af: regs[0]?,
+            bc: regs[1]?,
+            de: regs[2]?,
+            hl: regs[3]?,
+            sp: sp?,
+        })
+    }
+
+    fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.bank.is_none_or(|bank| bank == entry.bank)
+            && (self.pc, self.af, self.bc, self.de, self.hl, self.sp)
+                == (entry.pc, entry.af, entry.bc, entry.de, entry.hl, entry.sp)
+    }
+
+    fn describe(&self) -> String {
+        let bank = self
+            .bank
+            .map_or("--".to_string(), |bank| format!("{:02X}", bank));
+        format!(
+            "{}:{:04X} AF={:04X} BC={:04X} DE={:04X} HL={:04X} SP={:04X}",
+            bank, self.pc, self.af, self.bc, self.de, self.hl, self.sp
+        )
+    }
+}
+
+// O outro lado: uma segunda configuração ou o trace
+enum Reference {
+    Emulator(Box<Emulator>, Config),
+    Trace { lines: Vec<TraceLine>, next: usize },
+}
+
+// Resultado de um passo da CPU
+struct Step {
+    entry: HistoryEntry,
+    // Em HALT/STOP (ou travada): não executou instrução
+    idle: bool,
+    // Ciclos do clock normal que passaram (metade em double speed)
+    clock: u64,
+    vblank: bool,
+}
+
+fn step(emulator: &mut Emulator) -> Step {
+    let cpu = &emulator.cpu;
+    let entry = HistoryEntry::capture(cpu, &emulator.bus);
+    let idle = cpu.halt || cpu.stop || cpu.locked;
+    let cycles = emulator.cpu.step(&mut emulator.bus) as u64;
+    let clock = emulator.bus.tick(cycles, emulator.cpu.stop);
+    let vblank = emulator.bus.ppu.take_vblank();
+    Step {
+        entry,
+        idle,
+        clock,
+        vblank,
+    }
+}
+
+// Registradores de IO comparados depois de cada passo: FF00-FF7F e IE
+fn io_addrs() -> impl Iterator<Item = u16> {
+    (0xFF00..=0xFF7F).chain([0xFFFF])
+}
+
+struct StateDiff {
+    emulator: Emulator,
+    config: Config,
+    reference: Reference,
+    frames: u32,
+    ignore_io: Vec<u16>,
+    symbols: Symbols,
+}
+
+impl StateDiff {
+    fn run(mut self) -> i32 {
+        match &self.reference {
+            Reference::Emulator(_, other) => {
+                println!("A: {}", self.config.describe());
+                println!("B: {}", other.describe());
+            }
+            Reference::Trace { lines, .. } => {
+                println!("A: {}", self.config.describe());
+                println!("B: trace com {} instruções", lines.len());
+            }
+        }
+
+        let mut context = History::new(CONTEXT);
+        // Frames contados pelo clock, como no run_frame: com o LCD desligado não tem vblank
+        let mut frame = 0;
+        let mut cycles = 0;
+        let mut steps: u64 = 0;
+        while frame < self.frames {
+            let a = step(&mut self.emulator);
+            steps += 1;
+            let divergence = match &mut self.reference {
+                Reference::Emulator(other, _) => {
+                    let b = step(other);
+                    compare(
+                        &self.emulator,
+                        other,
+                        &a,
+                        &b,
+                        &self.ignore_io,
+                        &self.symbols,
+                    )
+                }
+                Reference::Trace { lines, next } if !a.idle => match lines.get(*next) {
+                    Some(line) if line.matches(&a.entry) => {
+                        *next += 1;
+                        None
+                    }
+                    Some(line) => Some(format!(
+                        "registradores (linha {} do trace)\n  A: {}\n  B: {}",
+                        *next + 1,
+                        a.entry.format(&self.symbols),
+                        line.describe()
+                    )),
+                    None => {
+                        println!("trace acabou depois de {} instruções sem divergência", next);
+                        return EXIT_SAME;
+                    }
+                },
+                Reference::Trace { .. } => None,
+            };
+
+            if let Some(divergence) = divergence {
+                println!(
+                    "divergência no frame {}, passo {}: {}",
+                    frame, steps, divergence
+                );
+                if !context.is_empty() {
+                    println!("instruções anteriores (A):");
+                    for entry in context.iter() {
+                        println!("  {}", entry.format(&self.symbols));
+                    }
+                }
+                return EXIT_DIVERGED;
+            }
+            if !a.idle {
+                context.record(a.entry);
+            }
+            cycles += a.clock;
+            if cycles >= CYCLES_PER_FRAME {
+                cycles -= CYCLES_PER_FRAME;
+                frame += 1;
+            }
+        }
+
+        println!(
+            "nenhuma divergência em {} frames ({} passos)",
+            self.frames, steps
+        );
+        EXIT_SAME
+    }
+}
+
+// Primeira diferença entre os dois lados depois do mesmo passo: CPU, depois IO, depois o
+// frame (só quando os dois acabaram de entrar em vblank)
+fn compare(
+    a: &Emulator,
+    b: &Emulator,
+    step_a: &Step,
+    step_b: &Step,
+    ignore_io: &[u16],
+    symbols: &Symbols,
+) -> Option<String> {
+    if step_a.entry != step_b.entry || step_a.idle != step_b.idle {
+        let describe = |step: &Step| {
+            let idle = if step.idle { " (parada)" } else { "" };
+            format!("{}{}", step.entry.format(symbols), idle)
+        };
+        return Some(format!(
+            "registradores\n  A: {}\n  B: {}",
+            describe(step_a),
+            describe(step_b)
+        ));
+    }
+
+    let differing = io_addrs()
+        .filter(|addr| !ignore_io.contains(addr))
+        .map(|addr| (addr, a.bus.peek(addr), b.bus.peek(addr)))
+        .find(|(_, value_a, value_b)| value_a != value_b);
+    if let Some((addr, value_a, value_b)) = differing {
+        return Some(format!(
+            "IO ${:04X} depois de {}\n  A: {:02X}\n  B: {:02X}",
+            addr,
+            step_a.entry.format(symbols),
+            value_a,
+            value_b
+        ));
+    }
+
+    if step_a.vblank != step_b.vblank {
+        let side = if step_a.vblank { "A" } else { "B" };
+        return Some(format!("só {} entrou em vblank", side));
+    }
+    if !step_a.vblank {
+        return None;
+    }
+    let (hash_a, hash_b) = (a.bus.ppu.frame_hash(), b.bus.ppu.frame_hash());
+    (hash_a != hash_b).then(|| format!("hash do frame\n  A: {:08X}\n  B: {:08X}", hash_a, hash_b))
+}
+
+fn setup(args: &[String]) -> Result<StateDiff, String> {
+    let mut rom_path: Option<&str> = None;
+    let mut config_a = Config::default();
+    let mut config_b: Option<Config> = None;
+    let mut trace_path: Option<&str> = None;
+    let mut frames = DEFAULT_FRAMES;
+    let mut ignore_io = Vec::new();
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--a" => config_a = Config::parse(next_value(&mut iter, "--a espera a config")?)?,
+            "--b" => {
+                config_b = Some(Config::parse(next_value(
+                    &mut iter,
+                    "--b espera a config",
+                )?)?);
+            }
+            "--trace" => {
+                trace_path = Some(next_value(&mut iter, "--trace espera o arquivo do log")?);
+            }
+            "--frames" => {
+                let value = next_value(&mut iter, "--frames espera o número de frames")?;
+                frames = value
+                    .parse()
+                    .ok()
+                    .filter(|&frames| frames > 0)
+                    .ok_or_else(|| format!("valor inválido pra --frames: '{}'", value))?;
+            }
+            "--ignore-io" => {
+                let value = next_value(&mut iter, "--ignore-io espera um endereço em hexa")?;
+                let addr = parse_addr(value)
+                    .filter(|&addr| addr >= 0xFF00)
+                    .ok_or_else(|| format!("valor inválido pra --ignore-io: '{}'", value))?;
+                ignore_io.push(addr);
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("opção desconhecida: {}", flag));
+            }
+            path => rom_path = Some(path),
+        }
+    }
+
+    let rom_path = rom_path.ok_or(USAGE)?;
+    let reference = match (config_b, trace_path) {
+        (Some(_), Some(_)) => return Err("use --b ou --trace, não os dois".to_string()),
+        (None, Some(path)) => {
+            let text = fs::read_to_string(path)
+                .map_err(|erro| format!("erro ao ler '{}': {}", path, erro))?;
+            let lines = text
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(number, line)| {
+                    TraceLine::parse(line)
+                        .ok_or_else(|| format!("{}: linha {} não reconhecida", path, number + 1))
+                })
+                .collect::<Result<_, _>>()?;
+            Reference::Trace { lines, next: 0 }
+        }
+        (config_b, None) => {
+            let config_b = config_b.unwrap_or_default();
+            Reference::Emulator(Box::new(config_b.build(rom_path)?), config_b)
+        }
+    };
+
+    Ok(StateDiff {
+        emulator: config_a.build(rom_path)?,
+        config: config_a,
+        reference,
+        frames,
+        ignore_io,
+        symbols: Symbols::for_rom(Path::new(rom_path))?,
+    })
+}
@@ -0,0 +1,164 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use gb_core::cpu::IllegalOpcodePolicy;
+use gb_core::debug::Symbols;
+use gb_core::machine::{
+    DETERMINISTIC_SEED, EXIT_ERROR, Emulator, ExitConditions, Model, ScheduledScreenshot,
+};
+use gb_tools::args::{load_cartridge, next_value, parse_addr};
+
+const USAGE: &str = "uso: gb-testrunner [--model dmg|cgb|sgb] [--seed <n>] [--illegal-opcode lock|skip] [--script <arquivo.lua>] [--frames <n>] [--until-pc <endereço|label>] [--until-serial <texto>] [--screenshot-at <frame>:<arquivo.png>]... [--profile <arquivo>] [--coverage <arquivo.json|arquivo.cdl>] [--trace <arquivo>] [--crash-report <arquivo>] [--symbols <arquivo.sym>] <rom>";
+
+// Roda a ROM sem janela, áudio nem pacer até uma condição de parada; o código de saída
+// diz qual (ver EXIT_* em machine::automation)
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let emulator = match setup(&args) {
+        Ok(emulator) => emulator,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            std::process::exit(EXIT_ERROR);
+        }
+    };
+    std::process::exit(emulator.run_headless());
+}
+
+fn setup(args: &[String]) -> Result<Emulator, String> {
+    let mut rom_path: Option<&str> = None;
+    let mut model = Model::Dmg;
+    // Sempre reproduzível: a RAM inicial vem de uma seed fixa
+    let mut seed = DETERMINISTIC_SEED;
+    let mut illegal_opcode = IllegalOpcodePolicy::Lock;
+    let mut script_path: Option<PathBuf> = None;
+    let mut exit = ExitConditions::default();
+    let mut screenshots = Vec::new();
+    let mut profile_path: Option<PathBuf> = None;
+    let mut coverage_path: Option<PathBuf> = None;
+    let mut trace_path: Option<PathBuf> = None;
+    let mut crash_path: Option<PathBuf> = None;
+    let mut symbols_path: Option<&str> = None;
+    // Resolvido depois de carregar os símbolos
+    let mut until_pc: Option<&str> = None;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--model" => {
+                let value = next_value(&mut iter, "--model espera 'dmg', 'cgb' ou 'sgb'")?;
+                model = Model::parse(value)
+                    .ok_or_else(|| format!("valor inválido pra --model: '{}'", value))?;
+            }
+            "--seed" => {
+                let value = next_value(&mut iter, "--seed espera um número")?;
+                seed = value
+                    .parse()
+                    .map_err(|_| format!("valor inválido pra --seed: '{}'", value))?;
+            }
+            "--illegal-opcode" => {
+                let value = next_value(&mut iter, "--illegal-opcode espera 'lock' ou 'skip'")?;
+                illegal_opcode = match value {
+                    "lock" => IllegalOpcodePolicy::Lock,
+                    "skip" => IllegalOpcodePolicy::Skip,
+                    other => {
+                        return Err(format!("valor inválido pra --illegal-opcode: '{}'", other));
+                    }
+                };
+            }
+            "--script" => {
+                let value = next_value(&mut iter, "--script espera o caminho do script Lua")?;
+                script_path = Some(PathBuf::from(value));
+            }
+            "--frames" => {
+                let value = next_value(&mut iter, "--frames espera o número de frames")?;
+                let frames = value
+                    .parse()
+                    .ok()
+                    .filter(|&frames| frames > 0)
+                    .ok_or_else(|| format!("valor inválido pra --frames: '{}'", value))?;
+                exit.frames = Some(frames);
+            }
+            "--until-pc" => {
+                until_pc = Some(next_value(
+                    &mut iter,
+                    "--until-pc espera um endereço em hexa ou um label",
+                )?);
+            }
+            "--until-serial" => {
+                let value = next_value(&mut iter, "--until-serial espera o texto procurado")?;
+                if value.is_empty() {
+                    return Err("--until-serial espera o texto procurado".to_string());
+                }
+                exit.until_serial = Some(value.to_string());
+            }
+            "--screenshot-at" => {
+                let value = next_value(&mut iter, "--screenshot-at espera <frame>:<arquivo.png>")?;
+                let shot = ScheduledScreenshot::parse(value)
+                    .ok_or_else(|| format!("valor inválido pra --screenshot-at: '{}'", value))?;
+                screenshots.push(shot);
+            }
+            "--profile" => {
+                let value = next_value(&mut iter, "--profile espera o caminho do relatório")?;
+                profile_path = Some(PathBuf::from(value));
+            }
+            "--coverage" => {
+                let value = next_value(&mut iter, "--coverage espera o caminho do mapa")?;
+                coverage_path = Some(PathBuf::from(value));
+            }
+            "--trace" => {
+                let value = next_value(&mut iter, "--trace espera o caminho do log")?;
+                trace_path = Some(PathBuf::from(value));
+            }
+            "--crash-report" => {
+                let value = next_value(&mut iter, "--crash-report espera o caminho do relatório")?;
+                crash_path = Some(PathBuf::from(value));
+            }
+            "--symbols" => {
+                symbols_path = Some(next_value(&mut iter, "--symbols espera o arquivo .sym")?);
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("opção desconhecida: {}", flag));
+            }
+            path => rom_path = Some(path),
+        }
+    }
+
+    let rom_path = rom_path.ok_or(USAGE)?;
+    // Sem --symbols, o <rom>.sym do lado da ROM (se existir)
+    let symbols = match symbols_path {
+        Some(path) => Symbols::load(Path::new(path))?,
+        None => Symbols::for_rom(Path::new(rom_path))?,
+    };
+    if let Some(value) = until_pc {
+        let addr = symbols
+            .resolve(value)
+            .map(|(_, addr)| addr)
+            .or_else(|| parse_addr(value))
+            .ok_or_else(|| format!("valor inválido pra --until-pc: '{}'", value))?;
+        exit.until_pc = Some(addr);
+    }
+
+    // Só com screenshots, para depois do último
+    if exit.is_empty() {
+        exit.frames = screenshots.iter().map(|shot| shot.frame).max();
+    }
+    if exit.is_empty() {
+        return Err(
+            "precisa de --frames, --until-pc, --until-serial ou --screenshot-at".to_string(),
+        );
+    }
+
+    let mut emulator = Emulator::new(load_cartridge(rom_path)?);
+    emulator.bus.model = model;
+    emulator.seed = Some(seed);
+    emulator.cpu.illegal_opcode_policy = illegal_opcode;
+    emulator.script_path = script_path;
+    emulator.screenshots = screenshots;
+    emulator.profile_path = profile_path;
+    emulator.coverage_path = coverage_path;
+    emulator.trace_path = trace_path;
+    emulator.crash_path = crash_path;
+    emulator.symbols = symbols;
+    emulator.set_exit_conditions(exit);
+    Ok(emulator)
+}
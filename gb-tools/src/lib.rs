@@ -0,0 +1,3 @@
+// Ferramentas de linha de comando em cima do gb-core, sem janela nem raylib: cada uma é
+// um binário em src/bin; aqui fica o que elas dividem
+pub mod args;
@@ -1,3 +0,0 @@
-pub mod memory_bus;
-
-pub use memory_bus::{MemoryBus, InterruptFlags};
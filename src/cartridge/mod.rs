@@ -1,6 +0,0 @@
-pub mod cartridge;
-pub mod cartridge_type;
-pub mod destination;
-mod mbc;
-
-pub use cartridge::*;
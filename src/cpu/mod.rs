@@ -1,3 +0,0 @@
-pub mod cpu;
-
-pub use cpu::*;
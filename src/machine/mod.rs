@@ -1,3 +0,0 @@
-pub mod machine;
-
-pub use machine::*;
@@ -1,4 +0,0 @@
-pub mod framebuffer;
-pub mod ppu;
-
-pub use ppu::*;
@@ -7,4 +7,4 @@ FILE="./cpu_instrs.gb"
 
 cargo build
 
-cargo run -- "$FILE"
+cargo run -p gb-frontends -- "$FILE"